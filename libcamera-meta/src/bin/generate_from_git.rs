@@ -1,7 +1,7 @@
 use std::{collections::BTreeMap, path::Path};
 
 use git2::{build::CheckoutBuilder, ObjectType, Repository};
-use libcamera_meta::{ControlEnumValue, ControlSize, ControlType};
+use libcamera_meta::{ControlDirection, ControlEnumValue, ControlSize, ControlType};
 use semver::Version;
 use yaml_rust::{Yaml, YamlLoader};
 
@@ -10,6 +10,8 @@ use crate::generate_rust::ControlsType;
 struct ByVersionData {
     pub controls: BTreeMap<String, String>,
     pub properties: BTreeMap<String, String>,
+    /// `formats.yaml` and libcamera's copy of `drm_fourcc.h`, which defines the fourcc codes it refers to.
+    pub formats: (String, String),
 }
 
 #[derive(Debug)]
@@ -18,6 +20,7 @@ pub struct Control {
     pub vendor: String,
 
     pub typ: ControlType,
+    pub direction: ControlDirection,
     pub description: String,
     pub size: Option<Vec<ControlSize>>,
     pub enumeration: Option<Vec<ControlEnumValue>>,
@@ -108,8 +111,20 @@ fn main() {
         };
         let controls = extract_controls("control_ids");
         let properties = extract_controls("property_ids");
+        // `libcamera` build script requires a pixel format table for every version
+        let formats = (
+            std::fs::read_to_string(git_dir.join("src/libcamera/formats.yaml")).expect("formats.yaml is missing"),
+            std::fs::read_to_string(git_dir.join("include/linux/drm_fourcc.h")).expect("drm_fourcc.h is missing"),
+        );
 
-        by_version.insert(version, ByVersionData { controls, properties });
+        by_version.insert(
+            version,
+            ByVersionData {
+                controls,
+                properties,
+                formats,
+            },
+        );
 
         true
     })
@@ -154,6 +169,10 @@ fn main() {
                 });
 
                 let typ = val["type"].as_str().unwrap().try_into().unwrap();
+                let direction = val["direction"]
+                    .as_str()
+                    .map(|d| d.try_into().unwrap())
+                    .unwrap_or_else(|| ControlDirection::undeclared(&name));
                 let description = val["description"].as_str().unwrap().to_string();
                 let size = val["size"]
                     .as_vec()
@@ -172,6 +191,7 @@ fn main() {
                     name,
                     vendor: vendor.to_string(),
                     typ,
+                    direction,
                     description,
                     size,
                     enumeration,
@@ -193,6 +213,7 @@ fn main() {
 
         println!("Parsing controls for version {version}");
         let controls = parse_control_files(&data.controls);
+        check_vendor_features(&controls);
         std::fs::write(
             output_dir.join("controls.rs"),
             generate_rust::generate_controls_file(&controls, ControlsType::Control),
@@ -201,18 +222,237 @@ fn main() {
 
         println!("Parsing properties for version {version}");
         let properties = parse_control_files(&data.properties);
+        check_vendor_features(&properties);
         std::fs::write(
             output_dir.join("properties.rs"),
             generate_rust::generate_controls_file(&properties, ControlsType::Property),
         )
         .unwrap();
+
+        let (formats, drm_fourcc) = &data.formats;
+        std::fs::write(output_dir.join("formats.yaml"), formats).unwrap();
+        std::fs::write(output_dir.join("drm_fourcc.h"), drm_fourcc).unwrap();
+
+        println!("Parsing pixel formats for version {version}");
+        std::fs::write(
+            output_dir.join("formats.rs"),
+            generate_formats::generate_formats_file(formats, drm_fourcc),
+        )
+        .unwrap();
+    }
+}
+
+mod generate_formats {
+    use std::collections::BTreeMap;
+
+    use yaml_rust::YamlLoader;
+
+    /// Memory layout of a pixel format, mirroring `libcamera::PixelFormatInfo`.
+    struct Layout {
+        bits_per_pixel: u32,
+        colour_encoding: &'static str,
+        packed: bool,
+        pixels_per_group: u32,
+        /// `(bytes_per_group, vertical_sub_sampling)` of each plane.
+        planes: &'static [(u32, u32)],
+    }
+
+    /// Layout of a format by its libcamera name.
+    ///
+    /// `formats.yaml` only maps names to fourcc codes, the layout is described by `PixelFormatInfo` within
+    /// `formats.cpp` and is reproduced here.
+    fn layout(name: &str) -> Option<Layout> {
+        let layout = |bits_per_pixel, colour_encoding, packed, pixels_per_group, planes| Layout {
+            bits_per_pixel,
+            colour_encoding,
+            packed,
+            pixels_per_group,
+            planes,
+        };
+
+        let layout = match name {
+            "RGB565" | "RGB565_BE" => layout(16, "Rgb", false, 1, &[(2, 1)]),
+            "RGB888" | "BGR888" => layout(24, "Rgb", false, 1, &[(3, 1)]),
+            "XRGB8888" | "XBGR8888" | "RGBX8888" | "BGRX8888" | "ARGB8888" | "ABGR8888" | "RGBA8888" | "BGRA8888" => {
+                layout(32, "Rgb", false, 1, &[(4, 1)])
+            }
+            "RGB161616" | "BGR161616" => layout(48, "Rgb", false, 1, &[(6, 1)]),
+            "YUYV" | "YVYU" | "UYVY" | "VYUY" => layout(16, "Yuv", false, 2, &[(4, 1)]),
+            "AVUY8888" | "XVUY8888" => layout(32, "Yuv", false, 1, &[(4, 1)]),
+            "NV12" | "NV21" => layout(12, "Yuv", false, 2, &[(2, 1), (2, 2)]),
+            "NV16" | "NV61" => layout(16, "Yuv", false, 2, &[(2, 1), (2, 1)]),
+            "NV24" | "NV42" => layout(24, "Yuv", false, 1, &[(1, 1), (2, 1)]),
+            "YUV420" | "YVU420" => layout(12, "Yuv", false, 2, &[(2, 1), (1, 2), (1, 2)]),
+            "YUV422" | "YVU422" => layout(16, "Yuv", false, 2, &[(2, 1), (1, 1), (1, 1)]),
+            "YUV444" | "YVU444" => layout(24, "Yuv", false, 1, &[(1, 1), (1, 1), (1, 1)]),
+            "R8" => layout(8, "Yuv", false, 1, &[(1, 1)]),
+            "R10" => layout(10, "Yuv", false, 1, &[(2, 1)]),
+            "R12" => layout(12, "Yuv", false, 1, &[(2, 1)]),
+            "R16" => layout(16, "Yuv", false, 1, &[(2, 1)]),
+            "R10_CSI2P" => layout(10, "Yuv", true, 4, &[(5, 1)]),
+            "R12_CSI2P" => layout(12, "Yuv", true, 2, &[(3, 1)]),
+            "MJPEG" => layout(0, "Yuv", false, 1, &[(1, 1)]),
+            "MONO_PISP_COMP1" => layout(8, "Yuv", true, 2, &[(2, 1)]),
+            _ => {
+                // Bayer formats, i.e. SRGGB10_CSI2P
+                let suffix = ["SBGGR", "SGBRG", "SGRBG", "SRGGB"]
+                    .iter()
+                    .find_map(|order| name.strip_prefix(order))?;
+                match suffix {
+                    "8" => layout(8, "Raw", false, 2, &[(2, 1)]),
+                    "10" => layout(10, "Raw", false, 2, &[(4, 1)]),
+                    "12" => layout(12, "Raw", false, 2, &[(4, 1)]),
+                    "14" => layout(14, "Raw", false, 2, &[(4, 1)]),
+                    "16" => layout(16, "Raw", false, 2, &[(4, 1)]),
+                    "10_CSI2P" => layout(10, "Raw", true, 4, &[(5, 1)]),
+                    "12_CSI2P" => layout(12, "Raw", true, 2, &[(3, 1)]),
+                    "14_CSI2P" => layout(14, "Raw", true, 4, &[(7, 1)]),
+                    "10_IPU3" => layout(10, "Raw", true, 25, &[(32, 1)]),
+                    "_PISP_COMP1" => layout(8, "Raw", true, 2, &[(2, 1)]),
+                    _ => return None,
+                }
+            }
+        };
+        Some(layout)
+    }
+
+    fn parse_int(value: &str) -> Option<u64> {
+        let value = value.trim_end_matches(['U', 'L', 'u', 'l']);
+        match value.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => value.parse().ok(),
+        }
+    }
+
+    /// Returns arguments of a macro call `name(...)` within `value`.
+    fn call_args<'a>(value: &'a str, name: &str) -> Option<Vec<&'a str>> {
+        let args = value.split_once(&format!("{name}("))?.1.split_once(')')?.0;
+        Some(args.split(',').map(str::trim).collect())
+    }
+
+    /// Parses `fourcc_code()` and `fourcc_mod_code()` defines of `drm_fourcc.h`.
+    fn parse_drm_defines(header: &str) -> (BTreeMap<String, u32>, BTreeMap<String, u64>) {
+        let mut vendors = BTreeMap::new();
+        let mut fourccs = BTreeMap::new();
+        let mut modifiers = BTreeMap::new();
+
+        for line in header.lines() {
+            let Some(define) = line.trim_start().strip_prefix("#define") else {
+                continue;
+            };
+            let Some((name, value)) = define.trim_start().split_once(char::is_whitespace) else {
+                continue;
+            };
+
+            if let Some(vendor) = name.strip_prefix("DRM_FORMAT_MOD_VENDOR_") {
+                if let Some(code) = value.split_whitespace().next().and_then(parse_int) {
+                    vendors.insert(vendor.to_string(), code);
+                }
+            } else if let Some(args) = call_args(value, "fourcc_mod_code") {
+                if let [vendor, code] = args[..] {
+                    let code = parse_int(code);
+                    modifiers.insert(name.to_string(), (vendor.to_string(), code));
+                }
+            } else if let Some(args) = call_args(value, "fourcc_code") {
+                let chars = args
+                    .iter()
+                    .filter_map(|c| c.strip_prefix('\'')?.strip_suffix('\'')?.bytes().next())
+                    .collect::<Vec<_>>();
+                if let [a, b, c, d] = chars[..] {
+                    fourccs.insert(name.to_string(), u32::from_le_bytes([a, b, c, d]));
+                }
+            }
+        }
+
+        let modifiers = modifiers
+            .into_iter()
+            .filter_map(|(name, (vendor, code))| {
+                let vendor = vendors.get(&vendor)?;
+                Some((name, (vendor << 56) | (code? & 0x00ff_ffff_ffff_ffff)))
+            })
+            .collect();
+
+        (fourccs, modifiers)
+    }
+
+    pub fn generate_formats_file(formats: &str, drm_fourcc: &str) -> String {
+        let (fourccs, modifiers) = parse_drm_defines(drm_fourcc);
+        let root = &YamlLoader::load_from_str(formats).unwrap()[0];
+
+        let mut entries = String::new();
+        for (name, val) in root["formats"]
+            .as_vec()
+            .unwrap()
+            .iter()
+            .flat_map(|format| format.as_hash().unwrap().iter())
+        {
+            let name = name.as_str().unwrap();
+
+            let fourcc_name = val["fourcc"].as_str().unwrap();
+            let Some(mut fourcc) = fourccs.get(fourcc_name).copied() else {
+                println!("WARNING: fourcc {fourcc_name} of format {name} is not defined, skipping");
+                continue;
+            };
+            if val["big_endian"].as_bool().unwrap_or(false) {
+                fourcc |= 1 << 31;
+            }
+
+            let modifier = match val["mod"].as_str() {
+                Some(mod_name) => match modifiers.get(mod_name) {
+                    Some(modifier) => *modifier,
+                    None => {
+                        println!("WARNING: modifier {mod_name} of format {name} is not defined, skipping");
+                        continue;
+                    }
+                },
+                None => 0,
+            };
+
+            let Some(layout) = layout(name) else {
+                println!("WARNING: layout of format {name} is unknown, skipping");
+                continue;
+            };
+
+            let planes = layout
+                .planes
+                .iter()
+                .map(|(bytes, sub_sampling)| {
+                    format!("PlaneInfo {{ bytes_per_group: {bytes}, vertical_sub_sampling: {sub_sampling} }}")
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            entries.push_str(&format!(
+                r#"
+                PixelFormatInfo {{
+                    name: "{name}",
+                    format: PixelFormat::new({fourcc:#010x}, {modifier:#x}),
+                    bits_per_pixel: {},
+                    colour_encoding: ColourEncoding::{},
+                    packed: {},
+                    pixels_per_group: {},
+                    planes: &[{planes}],
+                }},
+                "#,
+                layout.bits_per_pixel, layout.colour_encoding, layout.packed, layout.pixels_per_group,
+            ));
+        }
+
+        let file = format!(
+            r#"
+            use crate::pixel_format::{{ColourEncoding, PixelFormat, PixelFormatInfo, PlaneInfo}};
+
+            pub const FORMATS: &[PixelFormatInfo] = &[{entries}];
+            "#
+        );
+        prettyplease::unparse(&syn::parse_file(&file).unwrap())
     }
 }
 
 mod generate_rust {
-    use libcamera_meta::{ControlSize, ControlType};
+    use libcamera_meta::{ControlDirection, ControlSize, ControlType};
 
-    use crate::{to_c_type_name, Control};
+    use crate::{to_c_type_name, vendor_feature, Control};
 
     fn format_docstring(desc: &str, indent: usize) -> String {
         let mut out = String::new();
@@ -266,6 +506,51 @@ mod generate_rust {
         }
     }
 
+    /// First paragraph of a description, joined into a single line.
+    fn summary(desc: &str) -> String {
+        desc.trim()
+            .split("\n\n")
+            .next()
+            .unwrap_or("")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn to_c_type_id(t: ControlType) -> &'static str {
+        match t {
+            ControlType::Bool => "LIBCAMERA_CONTROL_TYPE_BOOL",
+            ControlType::Byte => "LIBCAMERA_CONTROL_TYPE_BYTE",
+            ControlType::Int32 => "LIBCAMERA_CONTROL_TYPE_INT32",
+            ControlType::Int64 => "LIBCAMERA_CONTROL_TYPE_INT64",
+            ControlType::Float => "LIBCAMERA_CONTROL_TYPE_FLOAT",
+            ControlType::String => "LIBCAMERA_CONTROL_TYPE_STRING",
+            ControlType::Rectangle => "LIBCAMERA_CONTROL_TYPE_RECTANGLE",
+            ControlType::Size => "LIBCAMERA_CONTROL_TYPE_SIZE",
+        }
+    }
+
+    fn to_yaml_type_name(t: ControlType) -> &'static str {
+        match t {
+            ControlType::Bool => "bool",
+            ControlType::Byte => "uint8_t",
+            ControlType::Int32 => "int32_t",
+            ControlType::Int64 => "int64_t",
+            ControlType::Float => "float",
+            ControlType::String => "string",
+            ControlType::Rectangle => "Rectangle",
+            ControlType::Size => "Size",
+        }
+    }
+
+    fn to_rust_direction(direction: ControlDirection) -> &'static str {
+        match direction {
+            ControlDirection::In => "ControlDirection::In",
+            ControlDirection::Out => "ControlDirection::Out",
+            ControlDirection::InOut => "ControlDirection::InOut",
+        }
+    }
+
     pub enum ControlsType {
         Control,
         Property,
@@ -274,7 +559,7 @@ mod generate_rust {
     fn generate_controls(controls: &[Control], ty: ControlsType) -> String {
         fn vendor_feature_gate(control: &Control) -> String {
             if control.vendor != "libcamera" {
-                format!(r#"#[cfg(feature="vendor_{}")]"#, control.vendor)
+                format!(r#"#[cfg(feature="{}")]"#, vendor_feature(&control.vendor))
             } else {
                 "".to_string()
             }
@@ -301,6 +586,116 @@ mod generate_rust {
         }
         out += "}\n";
 
+        let mut all_variants = String::new();
+        let mut name_variants = String::new();
+        let mut from_name_variants = String::new();
+        let mut direction_variants = String::new();
+        let mut descriptors = String::new();
+        for ctrl in controls.iter() {
+            let gate = vendor_feature_gate(ctrl);
+            let ctrl_name = &ctrl.name;
+            let direction = to_rust_direction(ctrl.direction);
+
+            let size = ctrl
+                .size
+                .iter()
+                .flatten()
+                .map(|s| match s {
+                    ControlSize::Dynamic => "None".to_string(),
+                    ControlSize::Fixed(n) => format!("Some({n})"),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let enumeration = ctrl
+                .enumeration
+                .iter()
+                .flatten()
+                .map(|val| {
+                    format!(
+                        "EnumValueDescriptor {{ name: {:?}, value: {}, summary: {:?} }}",
+                        val.name.replace(&ctrl.name, ""),
+                        val.value,
+                        summary(&val.description),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            descriptors += &format!(
+                r#"{gate} ControlDescriptor {{
+                    id: {name}::{ctrl_name} as u32,
+                    name: "{ctrl_name}",
+                    vendor: "{}",
+                    ty: libcamera_control_type::{},
+                    type_name: "{}",
+                    direction: {direction},
+                    size: &[{size}],
+                    enumeration: &[{enumeration}],
+                    summary: {:?},
+                }},
+                "#,
+                ctrl.vendor,
+                to_c_type_id(ctrl.typ),
+                to_yaml_type_name(ctrl.typ),
+                summary(&ctrl.description),
+            );
+
+            all_variants += &format!("{gate} {name}::{ctrl_name},\n");
+            name_variants += &format!("{gate} {name}::{ctrl_name} => \"{ctrl_name}\",\n");
+            from_name_variants += &format!("{gate} \"{ctrl_name}\" => Some({name}::{ctrl_name}),\n");
+            direction_variants += &format!("{gate} {name}::{ctrl_name} => {direction},\n");
+        }
+
+        let direction_fn = match ty {
+            ControlsType::Control => format!(
+                r#"
+            /// Returns whether the control can be set on requests, is reported in metadata, or both.
+            pub fn direction(&self) -> ControlDirection {{
+                match self {{
+                    {direction_variants}
+                }}
+            }}
+            "#
+            ),
+            ControlsType::Property => String::new(),
+        };
+
+        out += &format!(
+            r#"
+        impl {name} {{
+            /// Returns all known ids.
+            pub fn all() -> &'static [{name}] {{
+                &[
+                    {all_variants}
+                ]
+            }}
+
+            /// Returns name of the id as used in libcamera YAML definitions, i.e. `ExposureTime`.
+            pub fn name(&self) -> &'static str {{
+                match self {{
+                    {name_variants}
+                }}
+            }}
+
+            /// Looks up id by its name as used in libcamera YAML definitions, i.e. `ExposureTime`.
+            ///
+            /// Returns [None] if name is unknown or the corresponding vendor feature is disabled.
+            pub fn from_name(name: &str) -> Option<{name}> {{
+                match name {{
+                    {from_name_variants}
+                    _ => None,
+                }}
+            }}
+
+            /// Iterates over `(name, id)` pairs of all known ids.
+            pub fn names() -> impl Iterator<Item = (&'static str, {name})> {{
+                Self::all().iter().map(|id| (id.name(), *id))
+            }}
+
+            {direction_fn}
+        }}
+    "#
+        );
+
         let mut dyn_variants = String::new();
 
         for ctrl in controls.iter() {
@@ -311,6 +706,7 @@ mod generate_rust {
             if let Some(enumeration) = &ctrl.enumeration {
                 out += &vendor_feature_gate(ctrl);
                 out += "#[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]";
+                out += r#"#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]"#;
                 out += &format!("#[repr({ctrl_type})]");
                 out += &format!("pub enum {ctrl_name} {{");
                 for val in enumeration {
@@ -347,6 +743,7 @@ mod generate_rust {
                     r#"
                 {0}
                 #[derive(Debug, Clone)]
+                #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
                 pub struct {ctrl_name}(pub {ctrl_type});
 
                 {0}
@@ -397,7 +794,10 @@ mod generate_rust {
 
             out += &vendor_feature_gate(ctrl);
             out += &match ty {
-                ControlsType::Control => format!("impl Control for {ctrl_name} {{}}\n"),
+                ControlsType::Control => format!(
+                    "impl Control for {ctrl_name} {{ const DIRECTION: ControlDirection = {}; }}\n",
+                    to_rust_direction(ctrl.direction)
+                ),
                 ControlsType::Property => format!("impl Property for {ctrl_name} {{}}\n"),
             };
 
@@ -414,6 +814,13 @@ mod generate_rust {
                 {dyn_variants}
             }}
         }}
+
+        /// Returns descriptions of all known ids as data, i.e. to render control panels or validate remote requests.
+        pub fn descriptor_table() -> &'static [ControlDescriptor] {{
+            &[
+                {descriptors}
+            ]
+        }}
     "#
         );
 
@@ -425,7 +832,10 @@ mod generate_rust {
                 use std::ops::{{Deref, DerefMut}};
                 use num_enum::{{IntoPrimitive, TryFromPrimitive}};
                 #[allow(unused_imports)]
-                use crate::control::{{Control, Property, ControlEntry, DynControlEntry}};
+                use crate::control::{{
+                    Control, ControlDescriptor, ControlDirection, Property, ControlEntry, DynControlEntry,
+                    EnumValueDescriptor,
+                }};
                 use crate::control_value::{{ControlValue, ControlValueError}};
                 #[allow(unused_imports)]
                 use crate::geometry::{{Rectangle, Size}};
@@ -440,6 +850,33 @@ mod generate_rust {
     }
 }
 
+/// Name of the `libcamera` crate feature, which enables controls of the given vendor namespace.
+pub fn vendor_feature(vendor: &str) -> String {
+    format!("vendor_{}", vendor.replace('-', "_"))
+}
+
+/// Warns about vendor namespaces, which do not have a corresponding feature declared in `libcamera/Cargo.toml`.
+///
+/// Controls of such vendors would be generated, but could never be enabled.
+fn check_vendor_features(controls: &[Control]) {
+    let manifest = std::fs::read_to_string("libcamera/Cargo.toml").unwrap();
+    let vendors = controls
+        .iter()
+        .map(|c| c.vendor.as_str())
+        .filter(|v| *v != "libcamera")
+        .collect::<std::collections::BTreeSet<_>>();
+
+    for vendor in vendors {
+        let feature = vendor_feature(vendor);
+        if !manifest
+            .lines()
+            .any(|l| l.trim_start().starts_with(&format!("{feature} =")))
+        {
+            println!("WARNING: vendor `{vendor}` has no `{feature}` feature in libcamera/Cargo.toml, please add it");
+        }
+    }
+}
+
 pub fn to_c_type_name(str: &str) -> String {
     let mut out = String::new();
     let chars = str.chars().collect::<Vec<_>>();
@@ -30,6 +30,65 @@ impl TryFrom<&str> for ControlType {
     }
 }
 
+/// Direction in which a control is passed, i.e. whether it can be set on requests or is only reported in metadata.
+///
+/// Controls without a `direction` field in the YAML definitions are bidirectional, unless listed in
+/// [METADATA_ONLY_CONTROLS].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ControlDirection {
+    In,
+    Out,
+    #[default]
+    InOut,
+}
+
+/// Controls documented as only being reported in metadata by libcamera versions that do not declare a `direction`.
+pub const METADATA_ONLY_CONTROLS: &[&str] = &[
+    "AeFlickerDetected",
+    "AeLocked",
+    "AeState",
+    "AfPauseState",
+    "AfState",
+    "AwbLocked",
+    "AwbState",
+    "Bcm2835StatsOutput",
+    "ColourTemperature",
+    "FocusFoM",
+    "FrameDuration",
+    "HdrChannel",
+    "Lux",
+    "PipelineDepth",
+    "SceneFlicker",
+    "SensorBlackLevels",
+    "SensorRollingShutterSkew",
+    "SensorTemperature",
+    "SensorTimestamp",
+];
+
+impl ControlDirection {
+    /// Direction of a control without a `direction` field in the YAML definitions.
+    pub fn undeclared(name: &str) -> Self {
+        if METADATA_ONLY_CONTROLS.contains(&name) {
+            ControlDirection::Out
+        } else {
+            ControlDirection::InOut
+        }
+    }
+}
+
+impl TryFrom<&str> for ControlDirection {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "in" => Ok(ControlDirection::In),
+            "out" => Ok(ControlDirection::Out),
+            "inout" => Ok(ControlDirection::InOut),
+            _ => Err(format!("Unknown control direction {}", value)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum ControlSize {
     Dynamic,
@@ -1,4 +1,7 @@
-use std::{env, fs, path::PathBuf};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
 fn main() {
     let libcamera = match pkg_config::probe_library("libcamera") {
@@ -19,8 +22,6 @@ fn main() {
         .first()
         .expect("Unable to get libcamera include path");
 
-    println!("cargo:rustc-link-lib=camera");
-
     let mut c_api_headers: Vec<PathBuf> = Vec::new();
     let mut cpp_api_headers: Vec<PathBuf> = Vec::new();
     let mut c_api_sources: Vec<PathBuf> = Vec::new();
@@ -48,31 +49,54 @@ fn main() {
         println!("cargo:rerun-if-changed={}", file.display());
     }
 
-    cc::Build::new()
-        .cpp(true)
-        .flag("-std=c++17")
-        .files(c_api_sources)
-        .include(libcamera_include_path)
-        .compile("camera_c_api");
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let dlopen = env::var_os("CARGO_FEATURE_DLOPEN").is_some();
 
-    // C bindings
-    let mut builder = bindgen::Builder::default()
-        .clang_arg(format!("-I{}", libcamera_include_path.display()))
-        .constified_enum_module("libcamera_.*")
-        .allowlist_function("libcamera_.*")
-        .allowlist_var("LIBCAMERA_.*")
-        .allowlist_var(".*LIBCAMERA_VERSION.*")
-        .allowlist_type("libcamera_.*");
-    for header in c_api_headers {
-        builder = builder.header(header.to_str().unwrap());
+    if dlopen {
+        let shim = build_shared_c_api(&c_api_sources, &libcamera, &out_path);
+        println!("cargo:rustc-env=LIBCAMERA_SYS_C_API_BUILD_PATH={}", shim.display());
+    } else {
+        println!("cargo:rustc-link-lib=camera");
+        cc::Build::new()
+            .cpp(true)
+            .flag("-std=c++17")
+            .files(c_api_sources)
+            .include(libcamera_include_path)
+            .compile("camera_c_api");
     }
 
-    let bindings = builder.generate().expect("Unable to generate bindings");
+    // C bindings
+    let c_builder = || {
+        let mut builder = bindgen::Builder::default()
+            .clang_arg(format!("-I{}", libcamera_include_path.display()))
+            .constified_enum_module("libcamera_.*")
+            .allowlist_function("libcamera_.*")
+            .allowlist_var("LIBCAMERA_.*")
+            .allowlist_var(".*LIBCAMERA_VERSION.*")
+            .allowlist_type("libcamera_.*");
+        for header in c_api_headers.iter() {
+            builder = builder.header(header.to_str().unwrap());
+        }
+        builder
+    };
 
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
-    bindings
-        .write_to_file(out_path.join("bindings.rs"))
-        .expect("Couldn't write bindings!");
+    if dlopen {
+        // Functions become members of a runtime loaded library struct, forward free functions with the same
+        // signatures to it so that the API of the crate stays the same.
+        let linked = c_builder().generate().expect("Unable to generate bindings");
+        let forwarders = generate_forwarders(&linked.to_string());
+        let bindings = c_builder()
+            .dynamic_library_name("LibcameraCApi")
+            .dynamic_link_require_all(true)
+            .generate()
+            .expect("Unable to generate bindings");
+        fs::write(out_path.join("bindings.rs"), bindings.to_string() + &forwarders).expect("Couldn't write bindings!");
+    } else {
+        let bindings = c_builder().generate().expect("Unable to generate bindings");
+        bindings
+            .write_to_file(out_path.join("bindings.rs"))
+            .expect("Couldn't write bindings!");
+    }
 
     // CPP bindings
     let mut builder = bindgen::Builder::default()
@@ -89,3 +113,135 @@ fn main() {
         .write_to_file(out_path.join("bindings_cpp.rs"))
         .expect("Couldn't write bindings!");
 }
+
+/// Builds the C API as a shared library linked against libcamera, which is loaded at runtime by the `dlopen` feature.
+fn build_shared_c_api(sources: &[PathBuf], libcamera: &pkg_config::Library, out_path: &Path) -> PathBuf {
+    let mut build = cc::Build::new();
+    build.cpp(true).pic(true).flag("-std=c++17");
+    for path in libcamera.include_paths.iter() {
+        build.include(path);
+    }
+
+    let shim = out_path.join("libcamera_c_api.so");
+    let mut cmd = build.get_compiler().to_command();
+    cmd.arg("-shared").args(sources).arg("-o").arg(&shim);
+    for path in libcamera.link_paths.iter() {
+        cmd.arg(format!("-L{}", path.display()));
+    }
+    for lib in libcamera.libs.iter() {
+        cmd.arg(format!("-l{lib}"));
+    }
+
+    let status = cmd.status().expect("Unable to run C++ compiler");
+    assert!(status.success(), "Unable to build shared C API library");
+    shim
+}
+
+/// Generates a free function calling the runtime loaded library for every function declared in `extern "C"` blocks of
+/// `bindings`.
+fn generate_forwarders(bindings: &str) -> String {
+    let mut out = String::new();
+    let mut rest = bindings;
+    while let Some(pos) = find_outside_strings(rest, "extern \"C\"") {
+        rest = rest[pos + "extern \"C\"".len()..].trim_start();
+        // skips function pointer types
+        let Some(block) = rest.strip_prefix('{') else {
+            continue;
+        };
+        let end = find_outside_strings(block, "}").expect("Unterminated extern block");
+        forward_declarations(&block[..end], &mut out);
+        rest = &block[end..];
+    }
+    out
+}
+
+fn forward_declarations(block: &str, out: &mut String) {
+    let mut rest = block;
+    while let Some(pos) = find_outside_strings(rest, "pub fn ") {
+        rest = &rest[pos + "pub fn ".len()..];
+        let name_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let name = &rest[..name_len];
+        let args_start = rest.find('(').expect("Invalid function declaration");
+        let args_len = matching_paren(&rest[args_start..]).expect("Unbalanced function declaration");
+        let args = &rest[args_start + 1..args_start + args_len];
+        let ret_end = rest[args_start + args_len..]
+            .find(';')
+            .expect("Unterminated function declaration");
+        let ret = &rest[args_start + args_len + 1..args_start + args_len + ret_end];
+
+        let names = split_top_level(args)
+            .map(|arg| arg.split(':').next().unwrap().trim())
+            .collect::<Vec<_>>()
+            .join(", ");
+        *out += &format!(
+            "pub unsafe fn {name}({args}){ret} {{ crate::dlopen::library().{name}({names}) }}\n",
+            ret = ret.trim_end(),
+        );
+        rest = &rest[args_start + args_len + ret_end..];
+    }
+}
+
+/// Finds `pat` in `s`, skipping string literals, i.e. of doc attributes.
+fn find_outside_strings(s: &str, pat: &str) -> Option<usize> {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if s[i..].starts_with(pat) {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Returns offset of the parenthesis closing the one `s` starts with.
+fn matching_paren(s: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits function arguments at commas outside of nested types, i.e. function pointers.
+fn split_top_level(args: &str) -> impl Iterator<Item = &str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut prev = ' ';
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            // `->` of function pointer return types
+            '>' if prev == '-' => {}
+            ')' | '>' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    parts.push(&args[start..]);
+    parts.into_iter().filter(|arg| !arg.trim().is_empty())
+}
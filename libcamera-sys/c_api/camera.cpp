@@ -1,5 +1,8 @@
 #include "camera.h"
 
+#include <errno.h>
+#include <libcamera/version.h>
+
 extern "C" {
 
 void libcamera_camera_configuration_destroy(libcamera_camera_configuration_t* config) {
@@ -22,6 +25,98 @@ libcamera_camera_configuration_status_t libcamera_camera_configuration_validate(
     return config->validate();
 }
 
+// SensorConfiguration was introduced in libcamera v0.3.0
+#if LIBCAMERA_VERSION_MAJOR > 0 || LIBCAMERA_VERSION_MINOR >= 3
+#define LIBCAMERA_HAS_SENSOR_CONFIGURATION
+#endif
+
+int libcamera_camera_configuration_set_sensor_configuration(libcamera_camera_configuration_t* config, const libcamera_sensor_configuration_t *sensor_config) {
+#ifdef LIBCAMERA_HAS_SENSOR_CONFIGURATION
+    libcamera::SensorConfiguration cfg;
+    cfg.bitDepth = sensor_config->bit_depth;
+    cfg.analogCrop = libcamera::Rectangle(sensor_config->analog_crop.x, sensor_config->analog_crop.y, sensor_config->analog_crop.width, sensor_config->analog_crop.height);
+    cfg.binning.binX = sensor_config->bin_x;
+    cfg.binning.binY = sensor_config->bin_y;
+    cfg.skipping.xOddInc = sensor_config->x_odd_inc;
+    cfg.skipping.xEvenInc = sensor_config->x_even_inc;
+    cfg.skipping.yOddInc = sensor_config->y_odd_inc;
+    cfg.skipping.yEvenInc = sensor_config->y_even_inc;
+    cfg.outputSize = libcamera::Size(sensor_config->output_size.width, sensor_config->output_size.height);
+
+    if (!cfg.isValid())
+        return -EINVAL;
+
+    config->sensorConfig = cfg;
+    return 0;
+#else
+    return -ENOTSUP;
+#endif
+}
+
+bool libcamera_camera_configuration_get_sensor_configuration(const libcamera_camera_configuration_t* config, libcamera_sensor_configuration_t *sensor_config) {
+#ifdef LIBCAMERA_HAS_SENSOR_CONFIGURATION
+    if (!config->sensorConfig)
+        return false;
+
+    const libcamera::SensorConfiguration &cfg = *config->sensorConfig;
+    sensor_config->bit_depth = cfg.bitDepth;
+    sensor_config->analog_crop = { cfg.analogCrop.x, cfg.analogCrop.y, cfg.analogCrop.width, cfg.analogCrop.height };
+    sensor_config->bin_x = cfg.binning.binX;
+    sensor_config->bin_y = cfg.binning.binY;
+    sensor_config->x_odd_inc = cfg.skipping.xOddInc;
+    sensor_config->x_even_inc = cfg.skipping.xEvenInc;
+    sensor_config->y_odd_inc = cfg.skipping.yOddInc;
+    sensor_config->y_even_inc = cfg.skipping.yEvenInc;
+    sensor_config->output_size = { cfg.outputSize.width, cfg.outputSize.height };
+    return true;
+#else
+    return false;
+#endif
+}
+
+void libcamera_camera_configuration_clear_sensor_configuration(libcamera_camera_configuration_t* config) {
+#ifdef LIBCAMERA_HAS_SENSOR_CONFIGURATION
+    config->sensorConfig.reset();
+#endif
+}
+
+// CameraConfiguration::orientation replaced CameraConfiguration::transform in libcamera v0.2.0
+#if LIBCAMERA_VERSION_MAJOR > 0 || LIBCAMERA_VERSION_MINOR >= 2
+#define LIBCAMERA_HAS_ORIENTATION
+#else
+// Transforms corresponding to each orientation of the image, indexed by orientation - 1
+static const libcamera::Transform orientation_transforms[] = {
+    libcamera::Transform::Identity,
+    libcamera::Transform::HFlip,
+    libcamera::Transform::Rot180,
+    libcamera::Transform::VFlip,
+    libcamera::Transform::Transpose,
+    libcamera::Transform::Rot270,
+    libcamera::Transform::Rot180Transpose,
+    libcamera::Transform::Rot90,
+};
+#endif
+
+enum libcamera_orientation libcamera_camera_configuration_get_orientation(const libcamera_camera_configuration_t* config) {
+#ifdef LIBCAMERA_HAS_ORIENTATION
+    return static_cast<enum libcamera_orientation>(config->orientation);
+#else
+    for (size_t i = 0; i < 8; i++) {
+        if (orientation_transforms[i] == config->transform)
+            return static_cast<enum libcamera_orientation>(i + 1);
+    }
+    return LIBCAMERA_ORIENTATION_ROTATE_0;
+#endif
+}
+
+void libcamera_camera_configuration_set_orientation(libcamera_camera_configuration_t* config, enum libcamera_orientation orientation) {
+#ifdef LIBCAMERA_HAS_ORIENTATION
+    config->orientation = static_cast<libcamera::Orientation>(orientation);
+#else
+    config->transform = orientation_transforms[orientation - 1];
+#endif
+}
+
 libcamera_camera_t* libcamera_camera_copy(libcamera_camera_t *cam) {
     const libcamera_camera_t& ptr = *cam;
     return new libcamera_camera_t(ptr);
@@ -50,6 +145,36 @@ void libcamera_camera_request_completed_disconnect(libcamera_camera_t *cam, libc
     delete handle;
 }
 
+libcamera_callback_handle_t *libcamera_camera_buffer_completed_connect(libcamera_camera_t *cam, libcamera_buffer_completed_cb_t *callback, void *data) {
+    libcamera_callback_handle_t *handle = new libcamera_callback_handle_t {};
+
+    cam->get()->bufferCompleted.connect(handle, [=](libcamera::Request *request, libcamera::FrameBuffer *buffer) {
+        callback(data, request, buffer);
+    });
+
+    return handle;
+}
+
+void libcamera_camera_buffer_completed_disconnect(libcamera_camera_t *cam, libcamera_callback_handle_t *handle) {
+    cam->get()->bufferCompleted.disconnect(handle);
+    delete handle;
+}
+
+libcamera_callback_handle_t *libcamera_camera_disconnected_connect(libcamera_camera_t *cam, libcamera_camera_disconnected_cb_t *callback, void *data) {
+    libcamera_callback_handle_t *handle = new libcamera_callback_handle_t {};
+
+    cam->get()->disconnected.connect(handle, [=]() {
+        callback(data);
+    });
+
+    return handle;
+}
+
+void libcamera_camera_disconnected_disconnect(libcamera_camera_t *cam, libcamera_callback_handle_t *handle) {
+    cam->get()->disconnected.disconnect(handle);
+    delete handle;
+}
+
 int libcamera_camera_acquire(libcamera_camera_t *cam) {
     return cam->get()->acquire();
 }
@@ -14,7 +14,34 @@ enum libcamera_camera_configuration_status {
     LIBCAMERA_CAMERA_CONFIGURATION_STATUS_INVALID,
 };
 
+enum libcamera_orientation {
+    LIBCAMERA_ORIENTATION_ROTATE_0 = 1,
+    LIBCAMERA_ORIENTATION_ROTATE_0_MIRROR = 2,
+    LIBCAMERA_ORIENTATION_ROTATE_180 = 3,
+    LIBCAMERA_ORIENTATION_ROTATE_180_MIRROR = 4,
+    LIBCAMERA_ORIENTATION_ROTATE_90_MIRROR = 5,
+    LIBCAMERA_ORIENTATION_ROTATE_270 = 6,
+    LIBCAMERA_ORIENTATION_ROTATE_270_MIRROR = 7,
+    LIBCAMERA_ORIENTATION_ROTATE_90 = 8,
+};
+
 typedef void libcamera_request_completed_cb_t(void*, libcamera_request_t*);
+typedef void libcamera_buffer_completed_cb_t(void*, libcamera_request_t*, libcamera_framebuffer_t*);
+typedef void libcamera_camera_disconnected_cb_t(void*);
+
+struct libcamera_sensor_configuration {
+    unsigned int bit_depth;
+    struct libcamera_rectangle analog_crop;
+    unsigned int bin_x;
+    unsigned int bin_y;
+    unsigned int x_odd_inc;
+    unsigned int x_even_inc;
+    unsigned int y_odd_inc;
+    unsigned int y_even_inc;
+    struct libcamera_size output_size;
+};
+
+typedef struct libcamera_sensor_configuration libcamera_sensor_configuration_t;
 
 #ifdef __cplusplus
 #include <libcamera/camera.h>
@@ -34,12 +61,21 @@ void libcamera_camera_configuration_destroy(libcamera_camera_configuration_t* co
 size_t libcamera_camera_configuration_size(const libcamera_camera_configuration_t* config);
 libcamera_stream_configuration_t *libcamera_camera_configuration_at(libcamera_camera_configuration_t* config, size_t index);
 libcamera_camera_configuration_status_t libcamera_camera_configuration_validate(libcamera_camera_configuration_t* config);
+int libcamera_camera_configuration_set_sensor_configuration(libcamera_camera_configuration_t* config, const libcamera_sensor_configuration_t *sensor_config);
+bool libcamera_camera_configuration_get_sensor_configuration(const libcamera_camera_configuration_t* config, libcamera_sensor_configuration_t *sensor_config);
+void libcamera_camera_configuration_clear_sensor_configuration(libcamera_camera_configuration_t* config);
+enum libcamera_orientation libcamera_camera_configuration_get_orientation(const libcamera_camera_configuration_t* config);
+void libcamera_camera_configuration_set_orientation(libcamera_camera_configuration_t* config, enum libcamera_orientation orientation);
 
 libcamera_camera_t *libcamera_camera_copy(libcamera_camera_t *cam);
 void libcamera_camera_destroy(libcamera_camera_t *cam);
 const char *libcamera_camera_id(const libcamera_camera_t *cam);
 libcamera_callback_handle_t *libcamera_camera_request_completed_connect(libcamera_camera_t *cam, libcamera_request_completed_cb_t *callback, void *data);
 void libcamera_camera_request_completed_disconnect(libcamera_camera_t *cam, libcamera_callback_handle_t *handle);
+libcamera_callback_handle_t *libcamera_camera_buffer_completed_connect(libcamera_camera_t *cam, libcamera_buffer_completed_cb_t *callback, void *data);
+void libcamera_camera_buffer_completed_disconnect(libcamera_camera_t *cam, libcamera_callback_handle_t *handle);
+libcamera_callback_handle_t *libcamera_camera_disconnected_connect(libcamera_camera_t *cam, libcamera_camera_disconnected_cb_t *callback, void *data);
+void libcamera_camera_disconnected_disconnect(libcamera_camera_t *cam, libcamera_callback_handle_t *handle);
 int libcamera_camera_acquire(libcamera_camera_t *cam);
 int libcamera_camera_release(libcamera_camera_t *cam);
 const libcamera_control_info_map_t *libcamera_camera_controls(const libcamera_camera_t *cam);
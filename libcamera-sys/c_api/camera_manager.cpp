@@ -37,6 +37,36 @@ const char *libcamera_camera_manager_version(libcamera_camera_manager_t *mgr) {
     return mgr->version().c_str();
 }
 
+libcamera_callback_handle_t *libcamera_camera_manager_camera_added_connect(libcamera_camera_manager_t *mgr, libcamera_camera_hotplug_cb_t *callback, void *data) {
+    libcamera_callback_handle_t *handle = new libcamera_callback_handle_t {};
+
+    mgr->cameraAdded.connect(handle, [=](std::shared_ptr<libcamera::Camera> camera) {
+        callback(data, new libcamera_camera_t(camera));
+    });
+
+    return handle;
+}
+
+void libcamera_camera_manager_camera_added_disconnect(libcamera_camera_manager_t *mgr, libcamera_callback_handle_t *handle) {
+    mgr->cameraAdded.disconnect(handle);
+    delete handle;
+}
+
+libcamera_callback_handle_t *libcamera_camera_manager_camera_removed_connect(libcamera_camera_manager_t *mgr, libcamera_camera_hotplug_cb_t *callback, void *data) {
+    libcamera_callback_handle_t *handle = new libcamera_callback_handle_t {};
+
+    mgr->cameraRemoved.connect(handle, [=](std::shared_ptr<libcamera::Camera> camera) {
+        callback(data, new libcamera_camera_t(camera));
+    });
+
+    return handle;
+}
+
+void libcamera_camera_manager_camera_removed_disconnect(libcamera_camera_manager_t *mgr, libcamera_callback_handle_t *handle) {
+    mgr->cameraRemoved.disconnect(handle);
+    delete handle;
+}
+
 size_t libcamera_camera_list_size(libcamera_camera_list_t *list) {
     return list->size();
 }
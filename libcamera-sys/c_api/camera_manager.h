@@ -6,6 +6,8 @@
 #include <stddef.h>
 #include <sys/types.h>
 
+typedef void libcamera_camera_hotplug_cb_t(void*, libcamera_camera_t*);
+
 #ifdef __cplusplus
 #include <libcamera/camera_manager.h>
 
@@ -25,6 +27,10 @@ void libcamera_camera_manager_stop(libcamera_camera_manager_t *mgr);
 libcamera_camera_list_t *libcamera_camera_manager_cameras(const libcamera_camera_manager_t *mgr);
 libcamera_camera_t *libcamera_camera_manager_get_id(libcamera_camera_manager_t *mgr, const char *id);
 const char *libcamera_camera_manager_version(libcamera_camera_manager_t *mgr);
+libcamera_callback_handle_t *libcamera_camera_manager_camera_added_connect(libcamera_camera_manager_t *mgr, libcamera_camera_hotplug_cb_t *callback, void *data);
+void libcamera_camera_manager_camera_added_disconnect(libcamera_camera_manager_t *mgr, libcamera_callback_handle_t *handle);
+libcamera_callback_handle_t *libcamera_camera_manager_camera_removed_connect(libcamera_camera_manager_t *mgr, libcamera_camera_hotplug_cb_t *callback, void *data);
+void libcamera_camera_manager_camera_removed_disconnect(libcamera_camera_manager_t *mgr, libcamera_callback_handle_t *handle);
 
 void libcamera_camera_list_destroy(libcamera_camera_list_t *list);
 size_t libcamera_camera_list_size(libcamera_camera_list_t *list);
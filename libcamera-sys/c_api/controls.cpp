@@ -59,6 +59,34 @@ void libcamera_control_list_set(libcamera_control_list_t *list, enum libcamera_p
     list->set(id, *val);
 }
 
+bool libcamera_control_list_contains(const libcamera_control_list_t *list, enum libcamera_property_id id) {
+    return list->contains(id);
+}
+
+bool libcamera_control_list_remove(libcamera_control_list_t *list, enum libcamera_property_id id) {
+    if (!list->contains(id))
+        return false;
+
+    // ControlList does not provide a way to erase a single entry, so rebuild the list without it.
+    // clear() keeps the id map and validator of the list intact.
+    libcamera::ControlList copy = *list;
+    list->clear();
+    for (const auto &[key, value] : copy) {
+        if (key != id)
+            list->set(key, value);
+    }
+
+    return true;
+}
+
+size_t libcamera_control_list_size(const libcamera_control_list_t *list) {
+    return list->size();
+}
+
+void libcamera_control_list_clear(libcamera_control_list_t *list) {
+    list->clear();
+}
+
 libcamera_control_list_iter_t *libcamera_control_list_iter(libcamera_control_list_t *list) {
     auto it = list->begin();
     return new libcamera_control_list_iter_t { list, it };
@@ -86,6 +114,68 @@ const libcamera_control_value_t *libcamera_control_list_iter_value(libcamera_con
     return &iter->it->second;
 }
 
+const libcamera_control_info_t *libcamera_control_info_map_at(const libcamera_control_info_map_t *map, unsigned int id) {
+    auto it = map->find(id);
+    if (it != map->end())
+        return &it->second;
+    else
+        return nullptr;
+}
+
+size_t libcamera_control_info_map_size(const libcamera_control_info_map_t *map) {
+    return map->size();
+}
+
+libcamera_control_info_map_iter_t *libcamera_control_info_map_iter(const libcamera_control_info_map_t *map) {
+    auto it = map->begin();
+    return new libcamera_control_info_map_iter_t { map, it };
+}
+
+void libcamera_control_info_map_iter_destroy(libcamera_control_info_map_iter_t *iter) {
+    delete iter;
+}
+
+bool libcamera_control_info_map_iter_end(const libcamera_control_info_map_iter_t *iter) {
+    return iter->it == iter->map->end();
+}
+
+void libcamera_control_info_map_iter_next(libcamera_control_info_map_iter_t *iter) {
+    if (iter->it != iter->map->end()) {
+        ++(iter->it);
+    }
+}
+
+unsigned int libcamera_control_info_map_iter_id(const libcamera_control_info_map_iter_t *iter) {
+    return iter->it->first->id();
+}
+
+const libcamera_control_info_t *libcamera_control_info_map_iter_info(const libcamera_control_info_map_iter_t *iter) {
+    return &iter->it->second;
+}
+
+const libcamera_control_value_t *libcamera_control_info_min(const libcamera_control_info_t *info) {
+    return &info->min();
+}
+
+const libcamera_control_value_t *libcamera_control_info_max(const libcamera_control_info_t *info) {
+    return &info->max();
+}
+
+const libcamera_control_value_t *libcamera_control_info_def(const libcamera_control_info_t *info) {
+    return &info->def();
+}
+
+size_t libcamera_control_info_values_size(const libcamera_control_info_t *info) {
+    return info->values().size();
+}
+
+const libcamera_control_value_t *libcamera_control_info_values_at(const libcamera_control_info_t *info, size_t index) {
+    if (index < info->values().size())
+        return &info->values()[index];
+    else
+        return nullptr;
+}
+
 libcamera_control_value_t *libcamera_control_value_create() {
     return new libcamera::ControlValue();
 }
@@ -13,10 +13,17 @@ struct libcamera_control_list_iter {
 	libcamera::ControlList::iterator it;
 };
 
+struct libcamera_control_info_map_iter {
+	const libcamera::ControlInfoMap *map;
+	libcamera::ControlInfoMap::const_iterator it;
+};
+
 typedef libcamera::ControlValue libcamera_control_value_t;
 typedef libcamera::ControlList libcamera_control_list_t;
 typedef struct libcamera_control_list_iter libcamera_control_list_iter_t;
 typedef libcamera::ControlInfoMap libcamera_control_info_map_t;
+typedef libcamera::ControlInfo libcamera_control_info_t;
+typedef struct libcamera_control_info_map_iter libcamera_control_info_map_iter_t;
 
 extern "C" {
 #else
@@ -24,6 +31,8 @@ typedef struct libcamera_control_value libcamera_control_value_t;
 typedef struct libcamera_control_list libcamera_control_list_t;
 typedef struct libcamera_control_list_iter libcamera_control_list_iter_t;
 typedef struct libcamera_control_info_map libcamera_control_info_map_t;
+typedef struct libcamera_control_info libcamera_control_info_t;
+typedef struct libcamera_control_info_map_iter libcamera_control_info_map_iter_t;
 #endif
 
 enum libcamera_control_id { libcamera_control_id_DUMMY };
@@ -54,6 +63,10 @@ libcamera_control_list_t *libcamera_control_list_create();
 void libcamera_control_list_destroy(libcamera_control_list_t *list);
 const libcamera_control_value_t *libcamera_control_list_get(libcamera_control_list_t *list, enum libcamera_property_id id);
 void libcamera_control_list_set(libcamera_control_list_t *list, enum libcamera_property_id id, const libcamera_control_value_t *val);
+bool libcamera_control_list_contains(const libcamera_control_list_t *list, enum libcamera_property_id id);
+bool libcamera_control_list_remove(libcamera_control_list_t *list, enum libcamera_property_id id);
+size_t libcamera_control_list_size(const libcamera_control_list_t *list);
+void libcamera_control_list_clear(libcamera_control_list_t *list);
 libcamera_control_list_iter_t *libcamera_control_list_iter(libcamera_control_list_t *list);
 
 // --- libcamera_control_list_iter_t ---
@@ -63,6 +76,25 @@ void libcamera_control_list_iter_next(libcamera_control_list_iter_t *iter);
 unsigned int libcamera_control_list_iter_id(libcamera_control_list_iter_t *iter);
 const libcamera_control_value_t *libcamera_control_list_iter_value(libcamera_control_list_iter_t *iter);
 
+// --- libcamera_control_info_map_t ---
+const libcamera_control_info_t *libcamera_control_info_map_at(const libcamera_control_info_map_t *map, unsigned int id);
+size_t libcamera_control_info_map_size(const libcamera_control_info_map_t *map);
+libcamera_control_info_map_iter_t *libcamera_control_info_map_iter(const libcamera_control_info_map_t *map);
+
+// --- libcamera_control_info_map_iter_t ---
+void libcamera_control_info_map_iter_destroy(libcamera_control_info_map_iter_t *iter);
+bool libcamera_control_info_map_iter_end(const libcamera_control_info_map_iter_t *iter);
+void libcamera_control_info_map_iter_next(libcamera_control_info_map_iter_t *iter);
+unsigned int libcamera_control_info_map_iter_id(const libcamera_control_info_map_iter_t *iter);
+const libcamera_control_info_t *libcamera_control_info_map_iter_info(const libcamera_control_info_map_iter_t *iter);
+
+// --- libcamera_control_info_t ---
+const libcamera_control_value_t *libcamera_control_info_min(const libcamera_control_info_t *info);
+const libcamera_control_value_t *libcamera_control_info_max(const libcamera_control_info_t *info);
+const libcamera_control_value_t *libcamera_control_info_def(const libcamera_control_info_t *info);
+size_t libcamera_control_info_values_size(const libcamera_control_info_t *info);
+const libcamera_control_value_t *libcamera_control_info_values_at(const libcamera_control_info_t *info, size_t index);
+
 // --- libcamera_control_value_t ---
 libcamera_control_value_t *libcamera_control_value_create();
 void libcamera_control_value_destroy(libcamera_control_value_t *val);
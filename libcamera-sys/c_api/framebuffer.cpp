@@ -41,6 +41,25 @@ const libcamera_frame_metadata_t *libcamera_framebuffer_metadata(const libcamera
     return &framebuffer->metadata();
 }
 
+libcamera_framebuffer_t *libcamera_framebuffer_create(const struct libcamera_framebuffer_plane_info *planes, size_t num_planes, uint64_t cookie) {
+    std::vector<libcamera::FrameBuffer::Plane> fb_planes;
+    for (size_t i = 0; i < num_planes; i++) {
+        libcamera::FrameBuffer::Plane plane;
+        // SharedFD duplicates the file descriptor, caller keeps ownership of the original one
+        plane.fd = libcamera::SharedFD(planes[i].fd);
+        if (!plane.fd.isValid())
+            return nullptr;
+        plane.offset = planes[i].offset;
+        plane.length = planes[i].length;
+        fb_planes.push_back(std::move(plane));
+    }
+    return new libcamera::FrameBuffer(fb_planes, cookie);
+}
+
+void libcamera_framebuffer_destroy(libcamera_framebuffer_t *framebuffer) {
+    delete framebuffer;
+}
+
 uint64_t libcamera_framebuffer_cookie(const libcamera_framebuffer_t *framebuffer) {
     return framebuffer->cookie();
 }
@@ -15,6 +15,12 @@ struct libcamera_frame_metadata_plane {
     unsigned int bytes_used;
 };
 
+struct libcamera_framebuffer_plane_info {
+    int fd;
+    size_t offset;
+    size_t length;
+};
+
 #ifdef __cplusplus
 #include <libcamera/camera.h>
 
@@ -52,6 +58,8 @@ size_t libcamera_frame_metadata_planes_size(const libcamera_frame_metadata_plane
 libcamera_frame_metadata_plane_t *libcamera_frame_metadata_planes_at(libcamera_frame_metadata_planes_t *planes, size_t index);
 
 // --- libcamera_framebuffer_t ---
+libcamera_framebuffer_t *libcamera_framebuffer_create(const struct libcamera_framebuffer_plane_info *planes, size_t num_planes, uint64_t cookie);
+void libcamera_framebuffer_destroy(libcamera_framebuffer_t *framebuffer);
 const libcamera_framebuffer_planes_t *libcamera_framebuffer_planes(const libcamera_framebuffer_t *framebuffer);
 const libcamera_frame_metadata_t *libcamera_framebuffer_metadata(const libcamera_framebuffer_t *framebuffer);
 uint64_t libcamera_framebuffer_cookie(const libcamera_framebuffer_t *framebuffer);
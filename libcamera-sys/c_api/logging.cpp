@@ -1,6 +1,55 @@
 #include <iostream>
+#include <mutex>
+#include <streambuf>
+#include <string>
 #include "logging.h"
 
+// Collects lines written by libcamera and passes each of them to the callback
+class CallbackStreambuf : public std::streambuf {
+public:
+    void set_callback(libcamera_log_cb_t *callback, void *data) {
+        std::lock_guard<std::mutex> lock(mutex_);
+        callback_ = callback;
+        data_ = data;
+        line_.clear();
+    }
+
+protected:
+    int_type overflow(int_type ch) override {
+        if (ch != traits_type::eof()) {
+            std::lock_guard<std::mutex> lock(mutex_);
+            push(traits_type::to_char_type(ch));
+        }
+        return ch;
+    }
+
+    std::streamsize xsputn(const char *s, std::streamsize count) override {
+        std::lock_guard<std::mutex> lock(mutex_);
+        for (std::streamsize i = 0; i < count; i++)
+            push(s[i]);
+        return count;
+    }
+
+private:
+    void push(char ch) {
+        if (ch != '\n') {
+            line_.push_back(ch);
+            return;
+        }
+        if (callback_)
+            callback_(data_, line_.data(), line_.size());
+        line_.clear();
+    }
+
+    std::mutex mutex_;
+    libcamera_log_cb_t *callback_ = nullptr;
+    void *data_ = nullptr;
+    std::string line_;
+};
+
+static CallbackStreambuf callback_streambuf;
+static std::ostream callback_stream(&callback_streambuf);
+
 int libcamera_log_set_file(const char *path, bool color) {
     return libcamera::logSetFile(path, color);
 }
@@ -23,6 +72,11 @@ int libcamera_log_set_target(libcamera_logging_target_t target) {
     return libcamera::logSetTarget(target);
 }
 
+int libcamera_log_set_callback(libcamera_log_cb_t *callback, void *data) {
+    callback_streambuf.set_callback(callback, data);
+    return libcamera::logSetStream(&callback_stream, false);
+}
+
 void libcamera_log_set_level(const char *category, const char *level) {
     libcamera::logSetLevel(category, level);
 }
@@ -2,6 +2,7 @@
 #define __LIBCAMERA_C_LOGGING__
 
 #include <stdbool.h>
+#include <stddef.h>
 
 enum libcamera_logging_target {
     LIBCAMERA_LOGGING_TARGET_NONE,
@@ -14,6 +15,7 @@ enum libcamera_logging_stream {
 };
 
 typedef enum libcamera_logging_stream libcamera_logging_stream_t;
+typedef void libcamera_log_cb_t(void*, const char*, size_t);
 
 #ifdef __cplusplus
 
@@ -30,6 +32,7 @@ typedef enum libcamera_logging_target libcamera_logging_target_t;
 int libcamera_log_set_file(const char *path, bool color);
 int libcamera_log_set_stream(libcamera_logging_stream_t stream, bool color);
 int libcamera_log_set_target(libcamera_logging_target_t target);
+int libcamera_log_set_callback(libcamera_log_cb_t *callback, void *data);
 void libcamera_log_set_level(const char *category, const char *level);
 
 #ifdef __cplusplus
@@ -9,6 +9,10 @@ char *libcamera_pixel_format_str(const libcamera_pixel_format_t *format) {
     return strdup(format->toString().c_str());
 }
 
+libcamera_pixel_format_t libcamera_pixel_format_from_str(const char *name) {
+    return libcamera::PixelFormat::fromString(name);
+}
+
 void libcamera_pixel_formats_destroy(libcamera_pixel_formats_t *formats) {
     delete formats;
 }
@@ -30,6 +30,9 @@ typedef struct libcamera_pixel_formats libcamera_pixel_formats_t;
 /// @param format libcamera PixelFormat structure
 /// @return A heap allocated null-terminated string, that has to be deallocated with free()
 char *libcamera_pixel_format_str(const libcamera_pixel_format_t *format);
+/// @brief Parses pixel format from its libcamera name, i.e. "NV12" or "SRGGB10_CSI2P"
+/// @return Pixel format with zero fourcc if the name is unknown
+libcamera_pixel_format_t libcamera_pixel_format_from_str(const char *name);
 
 void libcamera_pixel_formats_destroy(libcamera_pixel_formats_t *formats);
 size_t libcamera_pixel_formats_size(const libcamera_pixel_formats_t *formats);
@@ -2,6 +2,11 @@
 
 #include <libcamera/libcamera.h>
 
+static_assert(static_cast<int>(libcamera::ColorSpace::Primaries::Rec2020) == LIBCAMERA_COLOR_SPACE_PRIMARIES_REC2020);
+static_assert(static_cast<int>(libcamera::ColorSpace::TransferFunction::Rec709) == LIBCAMERA_COLOR_SPACE_TRANSFER_FUNCTION_REC709);
+static_assert(static_cast<int>(libcamera::ColorSpace::YcbcrEncoding::Rec2020) == LIBCAMERA_COLOR_SPACE_YCBCR_ENCODING_REC2020);
+static_assert(static_cast<int>(libcamera::ColorSpace::Range::Limited) == LIBCAMERA_COLOR_SPACE_RANGE_LIMITED);
+
 extern "C" {
 
 libcamera_pixel_formats_t *libcamera_stream_formats_pixel_formats(const libcamera_stream_formats_t* formats) {
@@ -24,4 +29,27 @@ libcamera_stream_t *libcamera_stream_configuration_stream(const libcamera_stream
     return config->stream();
 }
 
+bool libcamera_stream_configuration_get_color_space(const libcamera_stream_configuration_t *config, libcamera_color_space_t *color_space) {
+    if (!config->colorSpace)
+        return false;
+
+    color_space->primaries = static_cast<enum libcamera_color_space_primaries>(config->colorSpace->primaries);
+    color_space->transfer_function = static_cast<enum libcamera_color_space_transfer_function>(config->colorSpace->transferFunction);
+    color_space->ycbcr_encoding = static_cast<enum libcamera_color_space_ycbcr_encoding>(config->colorSpace->ycbcrEncoding);
+    color_space->range = static_cast<enum libcamera_color_space_range>(config->colorSpace->range);
+    return true;
+}
+
+void libcamera_stream_configuration_set_color_space(libcamera_stream_configuration_t *config, const libcamera_color_space_t *color_space) {
+    config->colorSpace = libcamera::ColorSpace(
+        static_cast<libcamera::ColorSpace::Primaries>(color_space->primaries),
+        static_cast<libcamera::ColorSpace::TransferFunction>(color_space->transfer_function),
+        static_cast<libcamera::ColorSpace::YcbcrEncoding>(color_space->ycbcr_encoding),
+        static_cast<libcamera::ColorSpace::Range>(color_space->range));
+}
+
+void libcamera_stream_configuration_clear_color_space(libcamera_stream_configuration_t *config) {
+    config->colorSpace.reset();
+}
+
 }
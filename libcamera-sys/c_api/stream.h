@@ -4,8 +4,43 @@
 #include "geometry.h"
 #include "pixel_format.h"
 
+#include <stdbool.h>
 #include <stddef.h>
 
+enum libcamera_color_space_primaries {
+    LIBCAMERA_COLOR_SPACE_PRIMARIES_RAW,
+    LIBCAMERA_COLOR_SPACE_PRIMARIES_SMPTE170M,
+    LIBCAMERA_COLOR_SPACE_PRIMARIES_REC709,
+    LIBCAMERA_COLOR_SPACE_PRIMARIES_REC2020,
+};
+
+enum libcamera_color_space_transfer_function {
+    LIBCAMERA_COLOR_SPACE_TRANSFER_FUNCTION_LINEAR,
+    LIBCAMERA_COLOR_SPACE_TRANSFER_FUNCTION_SRGB,
+    LIBCAMERA_COLOR_SPACE_TRANSFER_FUNCTION_REC709,
+};
+
+enum libcamera_color_space_ycbcr_encoding {
+    LIBCAMERA_COLOR_SPACE_YCBCR_ENCODING_NONE,
+    LIBCAMERA_COLOR_SPACE_YCBCR_ENCODING_REC601,
+    LIBCAMERA_COLOR_SPACE_YCBCR_ENCODING_REC709,
+    LIBCAMERA_COLOR_SPACE_YCBCR_ENCODING_REC2020,
+};
+
+enum libcamera_color_space_range {
+    LIBCAMERA_COLOR_SPACE_RANGE_FULL,
+    LIBCAMERA_COLOR_SPACE_RANGE_LIMITED,
+};
+
+struct libcamera_color_space {
+    enum libcamera_color_space_primaries primaries;
+    enum libcamera_color_space_transfer_function transfer_function;
+    enum libcamera_color_space_ycbcr_encoding ycbcr_encoding;
+    enum libcamera_color_space_range range;
+};
+
+typedef struct libcamera_color_space libcamera_color_space_t;
+
 struct libcamera_stream_configuration {
     libcamera_pixel_format_t pixel_format;
     libcamera_size_t size;
@@ -57,6 +92,9 @@ libcamera_size_range_t libcamera_stream_formats_range(const libcamera_stream_for
 
 const libcamera_stream_formats_t *libcamera_stream_configuration_formats(const libcamera_stream_configuration_t *config);
 libcamera_stream_t *libcamera_stream_configuration_stream(const libcamera_stream_configuration_t *config);
+bool libcamera_stream_configuration_get_color_space(const libcamera_stream_configuration_t *config, libcamera_color_space_t *color_space);
+void libcamera_stream_configuration_set_color_space(libcamera_stream_configuration_t *config, const libcamera_color_space_t *color_space);
+void libcamera_stream_configuration_clear_color_space(libcamera_stream_configuration_t *config);
 
 #ifdef __cplusplus
 }
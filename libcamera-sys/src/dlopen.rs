@@ -0,0 +1,71 @@
+//! Runtime loading of libcamera, enabled by the `dlopen` feature.
+//!
+//! The C API is built as a shared library linked against libcamera and loaded on first use, so binaries start on
+//! systems without libcamera installed. Call [load()] before using any other function to detect that case, functions
+//! panic if the library can not be loaded.
+
+use std::{ffi::OsString, fmt, sync::OnceLock};
+
+use crate::LibcameraCApi;
+
+/// File name of the C API library, searched in the directory of the executable and then in the default library paths.
+pub const LIBRARY_NAME: &str = "libcamera_c_api.so";
+
+/// Environment variable with the path of the C API library, which takes precedence over [LIBRARY_NAME].
+pub const LIBRARY_PATH_ENV: &str = "LIBCAMERA_SYS_LIBRARY";
+
+/// Path of the C API library within the build directory, used as the last resort, i.e. when running with `cargo run`.
+const BUILD_PATH: &str = env!("LIBCAMERA_SYS_C_API_BUILD_PATH");
+
+#[derive(Debug, Clone)]
+pub enum LoadError {
+    /// Neither the C API library nor libcamera it links to could be loaded. Contains errors of all attempted paths.
+    LibraryNotFound(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LibraryNotFound(e) => write!(f, "libcamera library not found: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+static LIBRARY: OnceLock<Result<LibcameraCApi, LoadError>> = OnceLock::new();
+
+fn open() -> Result<LibcameraCApi, LoadError> {
+    // a bare name is only searched in the default library paths, not next to the executable
+    let beside_exe = std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(LIBRARY_NAME).into_os_string()));
+    let paths = std::env::var_os(LIBRARY_PATH_ENV)
+        .into_iter()
+        .chain(beside_exe)
+        .chain([OsString::from(LIBRARY_NAME), OsString::from(BUILD_PATH)]);
+
+    let mut errors = Vec::new();
+    for path in paths {
+        match unsafe { LibcameraCApi::new(&path) } {
+            Ok(lib) => return Ok(lib),
+            Err(e) => errors.push(format!("{}: {e}", path.to_string_lossy())),
+        }
+    }
+    Err(LoadError::LibraryNotFound(errors.join("; ")))
+}
+
+/// Loads libcamera unless already loaded.
+///
+/// The result is cached, so a library installed after the first failed attempt is not picked up.
+pub fn load() -> Result<(), LoadError> {
+    LIBRARY.get_or_init(open).as_ref().map(|_| ()).map_err(Clone::clone)
+}
+
+/// Returns the loaded library, used by the generated functions.
+pub(crate) fn library() -> &'static LibcameraCApi {
+    match LIBRARY.get_or_init(open) {
+        Ok(lib) => lib,
+        Err(e) => panic!("{e}, use libcamera_sys::load() to handle this"),
+    }
+}
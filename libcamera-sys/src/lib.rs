@@ -5,6 +5,13 @@
 #![allow(deref_nullptr)]
 // libcamera documentation is incorrectly interpreted as rust code blocks
 #![allow(rustdoc::invalid_rust_codeblocks)]
+// generated functions of the runtime loaded library mirror the undocumented C API
+#![cfg_attr(feature = "dlopen", allow(clippy::missing_safety_doc))]
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 include!(concat!(env!("OUT_DIR"), "/bindings_cpp.rs"));
+
+#[cfg(feature = "dlopen")]
+mod dlopen;
+#[cfg(feature = "dlopen")]
+pub use dlopen::{load, LoadError, LIBRARY_NAME, LIBRARY_PATH_ENV};
@@ -58,7 +58,14 @@ fn main() {
 
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
 
-    for file in ["controls.rs", "properties.rs"] {
+    for file in ["controls.rs", "properties.rs", "formats.rs"] {
         std::fs::copy(selected_version.join(file), out_path.join(file)).unwrap();
     }
+
+    // Controls which helpers depend on, but which only newer libcamera versions define
+    println!("cargo:rustc-check-cfg=cfg(libcamera_has_scaler_crops)");
+    let controls = std::fs::read_to_string(selected_version.join("controls.rs")).unwrap();
+    if controls.contains("ControlId::ScalerCrops") {
+        println!("cargo:rustc-cfg=libcamera_has_scaler_crops");
+    }
 }
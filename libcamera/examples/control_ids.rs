@@ -0,0 +1,130 @@
+//! Sanity checks of generated control and property bindings, which runs without a camera.
+//!
+//! Verifies that ids and names of every control and property round-trip, and that values accepted by `make_dyn()`
+//! convert back unchanged. Exits with a non-zero code on failure.
+
+use libcamera::{
+    control::DynControlEntry,
+    control_value::{ControlValue, ControlValueError},
+    controls::{self, ControlId},
+    geometry::{Rectangle, Size},
+    properties::{self, PropertyId},
+};
+
+/// Values of every type with lengths covering scalars, small arrays and matrices.
+fn samples() -> Vec<ControlValue> {
+    let mut samples = vec![ControlValue::None, ControlValue::String("test".into())];
+    for len in [1, 2, 3, 4, 9] {
+        samples.push(vec![true; len].into());
+        samples.push(vec![1u8; len].into());
+        samples.push(vec![1.5f32; len].into());
+        samples.push(vec![1i64; len].into());
+        samples.push(
+            vec![
+                Rectangle {
+                    x: 1,
+                    y: 2,
+                    width: 3,
+                    height: 4,
+                };
+                len
+            ]
+            .into(),
+        );
+        samples.push(
+            vec![
+                Size {
+                    width: 640,
+                    height: 480,
+                };
+                len
+            ]
+            .into(),
+        );
+        // Enum values
+        for v in 0i32..4 {
+            samples.push(vec![v; len].into());
+        }
+    }
+    samples
+}
+
+fn check_values(
+    kind: &str,
+    name: &str,
+    id: u32,
+    samples: &[ControlValue],
+    make_dyn: impl Fn(ControlValue) -> Result<Box<dyn DynControlEntry>, ControlValueError>,
+    errors: &mut Vec<String>,
+) {
+    let mut accepted = 0;
+    for sample in samples {
+        let Ok(entry) = make_dyn(sample.clone()) else {
+            continue;
+        };
+        accepted += 1;
+        if entry.id() != id {
+            errors.push(format!("{kind} {name}: id {} does not match {id}", entry.id()));
+        }
+        if entry.value() != *sample {
+            errors.push(format!("{kind} {name}: {sample:?} converted to {:?}", entry.value()));
+        }
+    }
+    if accepted == 0 {
+        println!("warning: {kind} {name} did not accept any sample value");
+    }
+}
+
+fn main() {
+    let samples = samples();
+    let mut errors = Vec::new();
+
+    for id in ControlId::all() {
+        let name = id.name();
+        if ControlId::from_name(name) != Some(*id) {
+            errors.push(format!("control {name}: name does not round-trip"));
+        }
+        if ControlId::try_from(*id as u32).ok() != Some(*id) {
+            errors.push(format!("control {name}: id does not round-trip"));
+        }
+        check_values(
+            "control",
+            name,
+            *id as u32,
+            &samples,
+            |v| controls::make_dyn(*id, v),
+            &mut errors,
+        );
+    }
+
+    for id in PropertyId::all() {
+        let name = id.name();
+        if PropertyId::from_name(name) != Some(*id) {
+            errors.push(format!("property {name}: name does not round-trip"));
+        }
+        if PropertyId::try_from(*id as u32).ok() != Some(*id) {
+            errors.push(format!("property {name}: id does not round-trip"));
+        }
+        check_values(
+            "property",
+            name,
+            *id as u32,
+            &samples,
+            |v| properties::make_dyn(*id, v),
+            &mut errors,
+        );
+    }
+
+    println!(
+        "Checked {} controls and {} properties",
+        ControlId::all().len(),
+        PropertyId::all().len()
+    );
+
+    if !errors.is_empty() {
+        for e in errors.iter() {
+            eprintln!("error: {e}");
+        }
+        std::process::exit(1);
+    }
+}
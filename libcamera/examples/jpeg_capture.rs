@@ -3,17 +3,16 @@ use std::time::Duration;
 use libcamera::{
     camera::CameraConfigurationStatus,
     camera_manager::CameraManager,
+    exif::ExifBuilder,
     framebuffer::AsFrameBuffer,
     framebuffer_allocator::{FrameBuffer, FrameBufferAllocator},
     framebuffer_map::MemoryMappedFrameBuffer,
-    pixel_format::PixelFormat,
+    jpeg::JpegEncoder,
+    pixel_format::formats,
     properties,
     stream::StreamRole,
 };
 
-// drm-fourcc does not have MJPEG type yet, construct it from raw fourcc identifier
-const PIXEL_FORMAT_MJPEG: PixelFormat = PixelFormat::new(u32::from_le_bytes([b'M', b'J', b'P', b'G']), 0);
-
 fn main() {
     let filename = std::env::args().nth(1).expect("Usage ./jpeg_capture <filename.jpg>");
 
@@ -33,8 +32,8 @@ fn main() {
     // This will generate default configuration for each specified role
     let mut cfgs = cam.generate_configuration(&[StreamRole::ViewFinder]).unwrap();
 
-    // Use MJPEG format so we can write resulting frame directly into jpeg file
-    cfgs.get_mut(0).unwrap().set_pixel_format(PIXEL_FORMAT_MJPEG);
+    // Prefer MJPEG format so we can write resulting frame directly into jpeg file, other formats are encoded
+    cfgs.get_mut(0).unwrap().set_pixel_format(formats::MJPEG);
 
     println!("Generated config: {:#?}", cfgs);
 
@@ -44,11 +43,11 @@ fn main() {
         CameraConfigurationStatus::Invalid => panic!("Error validating camera configuration"),
     }
 
-    // Ensure that pixel format was unchanged
-    assert_eq!(
-        cfgs.get(0).unwrap().get_pixel_format(),
-        PIXEL_FORMAT_MJPEG,
-        "MJPEG is not supported by the camera"
+    let encoder = JpegEncoder::from_stream_config(&cfgs.get(0).unwrap());
+    assert!(
+        encoder.is_supported(),
+        "Pixel format {:?} can not be encoded",
+        cfgs.get(0).unwrap().get_pixel_format()
     );
 
     cam.configure(&mut cfgs).expect("Unable to configure camera");
@@ -98,14 +97,19 @@ fn main() {
     let framebuffer: &MemoryMappedFrameBuffer<FrameBuffer> = req.buffer(&stream).unwrap();
     println!("FrameBuffer metadata: {:#?}", framebuffer.metadata());
 
-    // MJPEG format has only one data plane containing encoded jpeg data with all the headers
-    let planes = framebuffer.data();
-    let jpeg_data = planes.get(0).unwrap();
-    // Actual JPEG-encoded data will be smalled than framebuffer size, its length can be obtained from metadata.
-    let jpeg_len = framebuffer.metadata().unwrap().planes().get(0).unwrap().bytes_used as usize;
+    // MJPEG frames are written as is, using the number of bytes reported in metadata
+    let jpeg_data = encoder.encode_buffer(framebuffer).unwrap();
+
+    // Add capture parameters, i.e. exposure time and ISO, and orientation as EXIF metadata
+    let jpeg_data = ExifBuilder::new()
+        .properties(cam.properties())
+        .orientation(cfgs.orientation())
+        .metadata(req.metadata())
+        .insert(&jpeg_data)
+        .unwrap();
 
-    std::fs::write(&filename, &jpeg_data[..jpeg_len]).unwrap();
-    println!("Written {} bytes to {}", jpeg_len, &filename);
+    std::fs::write(&filename, &jpeg_data).unwrap();
+    println!("Written {} bytes to {}", jpeg_data.len(), &filename);
 
     // Everything is cleaned up automatically by Drop implementations
 }
@@ -5,7 +5,13 @@ fn main() {
 
     mgr.log_set_level("Camera", LoggingLevel::Error);
 
-    let cameras = mgr.cameras();
+    let cameras = match mgr.cameras_checked() {
+        Ok(cameras) => cameras,
+        Err(e) => {
+            eprintln!("No cameras available: {e}");
+            std::process::exit(1);
+        }
+    };
 
     for i in 0..cameras.len() {
         let cam = cameras.get(i).unwrap();
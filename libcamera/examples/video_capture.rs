@@ -6,15 +6,12 @@ use libcamera::{
     framebuffer::AsFrameBuffer,
     framebuffer_allocator::{FrameBuffer, FrameBufferAllocator},
     framebuffer_map::MemoryMappedFrameBuffer,
-    pixel_format::PixelFormat,
+    pixel_format::formats,
     properties,
     request::ReuseFlag,
     stream::StreamRole,
 };
 
-// drm-fourcc does not have MJPEG type yet, construct it from raw fourcc identifier
-const PIXEL_FORMAT_MJPEG: PixelFormat = PixelFormat::new(u32::from_le_bytes([b'M', b'J', b'P', b'G']), 0);
-
 fn main() {
     let filename = match std::env::args().nth(1) {
         Some(f) => f,
@@ -41,7 +38,7 @@ fn main() {
     // This will generate default configuration for each specified role
     let mut cfgs = cam.generate_configuration(&[StreamRole::VideoRecording]).unwrap();
 
-    cfgs.get_mut(0).unwrap().set_pixel_format(PIXEL_FORMAT_MJPEG);
+    cfgs.get_mut(0).unwrap().set_pixel_format(formats::MJPEG);
 
     println!("Generated config: {:#?}", cfgs);
 
@@ -54,7 +51,7 @@ fn main() {
     // Ensure that pixel format was unchanged
     assert_eq!(
         cfgs.get(0).unwrap().get_pixel_format(),
-        PIXEL_FORMAT_MJPEG,
+        formats::MJPEG,
         "MJPEG is not supported by the camera"
     );
 
@@ -0,0 +1,273 @@
+//! Software auto white balance for pipelines without an AWB algorithm (i.e. UVC or simple pipeline handler).
+//!
+//! [CaptureSession::enable_software_awb()](crate::session::CaptureSession::enable_software_awb) runs it on the frames
+//! of a session. It can also be driven manually:
+//!
+//! ```no_run
+//! use libcamera::{awb::GreyWorldAwb, geometry::Size, pixel_format::PixelFormat, request::Request};
+//!
+//! # fn process(req: &mut Request, planes: &[&[u8]], format: PixelFormat, size: Size, stride: u32) {
+//! let mut awb = GreyWorldAwb::default();
+//!
+//! // For each completed frame: estimate new gains and apply them to the next request
+//! awb.process_frame(planes, format, size, stride).unwrap();
+//! awb.apply(req.controls_mut()).unwrap();
+//! # }
+//! ```
+
+use thiserror::Error;
+
+use crate::{
+    control::{ControlError, ControlList},
+    controls::ColourGains,
+    geometry::Size,
+    pixel_format::PixelFormat,
+    pixels::{self, for_each_pixel, Pixel},
+};
+
+#[derive(Debug, Error)]
+pub enum AwbError {
+    /// Pixel format of the frame is not supported for statistics gathering
+    #[error("Unsupported pixel format {0:?}")]
+    UnsupportedFormat(PixelFormat),
+    /// Frame does not contain the planes required by the pixel format
+    #[error("Expected {expected} planes, found {found}")]
+    MissingPlanes { expected: usize, found: usize },
+    /// Plane data is shorter than implied by the frame size and stride
+    #[error("Plane {index} is {len} bytes long, but at least {expected} bytes are required")]
+    PlaneTooShort { index: usize, len: usize, expected: usize },
+    /// Frame does not contain any usable (non-saturated, non-black) pixels
+    #[error("Frame contains no usable pixels")]
+    NoValidPixels,
+}
+
+/// Colour statistics of a single frame gathered by [FrameStatistics::from_frame()].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameStatistics {
+    /// Sum of the red, green and blue components of all sampled pixels
+    pub sum: [f64; 3],
+    /// Maximum red, green and blue component values among the sampled pixels
+    pub max: [f32; 3],
+    /// Number of pixels accumulated into `sum`
+    pub count: u64,
+}
+
+impl FrameStatistics {
+    /// Gathers per-channel statistics from the frame planes.
+    ///
+    /// Only every `step`-th pixel in each direction is sampled. Pixels with any of the components at or above
+    /// `saturation` or with all components below `black` are skipped as they do not carry colour information.
+    ///
+    /// Supported formats are packed RGB (`RGB888`, `BGR888`, `XRGB8888`, `XBGR8888`, `ARGB8888`, `ABGR8888`), `YUYV`
+    /// and `NV12`, `NV21`, `YUV420` planar YUV formats. YUV data is converted to RGB using BT.601 coefficients.
+    pub fn from_frame(
+        planes: &[&[u8]],
+        format: PixelFormat,
+        size: Size,
+        stride: u32,
+        step: usize,
+        black: f32,
+        saturation: f32,
+    ) -> Result<Self, AwbError> {
+        let mut stats = Self::default();
+        for_each_pixel(planes, format, size, stride, step, |px| {
+            let rgb = match px {
+                Pixel::Rgb([r, g, b]) => [r as f32, g as f32, b as f32],
+                Pixel::Yuv([y, u, v]) => yuv_to_rgb(y, u, v),
+            };
+            if rgb.iter().any(|c| *c >= saturation) || rgb.iter().all(|c| *c < black) {
+                return;
+            }
+            for (i, c) in rgb.iter().enumerate() {
+                stats.sum[i] += *c as f64;
+                stats.max[i] = stats.max[i].max(*c);
+            }
+            stats.count += 1;
+        })?;
+
+        Ok(stats)
+    }
+
+    /// Returns `true` if statistics can be gathered from frames of `format`, see [FrameStatistics::from_frame()].
+    pub fn is_supported(format: PixelFormat) -> bool {
+        pixels::is_supported(format)
+    }
+
+    /// Average value of each of the red, green and blue components.
+    pub fn mean(&self) -> Option<[f64; 3]> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.sum.map(|s| s / self.count as f64))
+        }
+    }
+}
+
+pub(crate) fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [f32; 3] {
+    let (y, u, v) = (y as f32, u as f32 - 128.0, v as f32 - 128.0);
+    [
+        (y + 1.402 * v).clamp(0.0, 255.0),
+        (y - 0.344 * u - 0.714 * v).clamp(0.0, 255.0),
+        (y + 1.772 * u).clamp(0.0, 255.0),
+    ]
+}
+
+/// Tuning parameters of [GreyWorldAwb].
+#[derive(Debug, Clone, Copy)]
+pub struct GreyWorldAwbConfig {
+    /// Fraction of the estimated correction applied per frame, in range `(0.0, 1.0]`.
+    ///
+    /// Gains reach the target value only after several frames, which prevents oscillation caused by the pipeline
+    /// applying new gains a few frames after they were requested.
+    pub damping: f32,
+    /// Corrections smaller than this relative amount are ignored to keep gains stable on static scenes.
+    pub deadband: f32,
+    /// Weight of the white-patch (brightest pixel) estimate mixed into the grey-world estimate, in range
+    /// `[0.0, 1.0]`. `0.0` results in pure grey-world AWB.
+    pub retinex_weight: f32,
+    /// Minimum allowed red/blue gain.
+    pub min_gain: f32,
+    /// Maximum allowed red/blue gain, which takes precedence if it is below `min_gain`.
+    pub max_gain: f32,
+    /// Only every `step`-th pixel in each direction is used for statistics.
+    pub step: usize,
+    /// Pixels with all components below this value are ignored.
+    pub black_level: f32,
+    /// Pixels with any of the components at or above this value are ignored.
+    pub saturation_level: f32,
+}
+
+impl Default for GreyWorldAwbConfig {
+    fn default() -> Self {
+        Self {
+            damping: 0.3,
+            deadband: 0.01,
+            retinex_weight: 0.2,
+            min_gain: 0.25,
+            max_gain: 8.0,
+            step: 8,
+            black_level: 16.0,
+            saturation_level: 250.0,
+        }
+    }
+}
+
+/// Grey-world AWB with an optional retinex (white-patch) component.
+///
+/// Assumes that the average colour of the scene is grey, and adjusts red and blue gains until the average red and
+/// blue components of the produced frames match the green one. Statistics are expected to be gathered from frames
+/// that already have the current gains applied, so the estimated correction is applied on top of them.
+#[derive(Debug, Clone)]
+pub struct GreyWorldAwb {
+    config: GreyWorldAwbConfig,
+    gains: [f32; 2],
+}
+
+impl GreyWorldAwb {
+    /// Creates AWB with the given configuration and unity starting gains.
+    pub fn new(config: GreyWorldAwbConfig) -> Self {
+        Self {
+            config,
+            gains: [1.0, 1.0],
+        }
+    }
+
+    pub fn config(&self) -> &GreyWorldAwbConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: GreyWorldAwbConfig) {
+        self.config = config;
+        self.gains = self.clamp(self.gains);
+    }
+
+    /// Returns the current red and blue gains.
+    pub fn gains(&self) -> ColourGains {
+        ColourGains(self.gains)
+    }
+
+    /// Overrides the current red and blue gains, i.e. to start from a previously converged value.
+    pub fn set_gains(&mut self, gains: ColourGains) {
+        self.gains = self.clamp(gains.0);
+    }
+
+    /// Resets gains to unity.
+    pub fn reset(&mut self) {
+        self.gains = [1.0, 1.0];
+    }
+
+    /// Updates gains from statistics of a frame produced with the current gains and returns the new gains.
+    ///
+    /// Gains are left unchanged if statistics do not contain any usable pixels.
+    pub fn update(&mut self, stats: &FrameStatistics) -> ColourGains {
+        let Some([r, g, b]) = stats.mean() else {
+            return self.gains();
+        };
+        if r <= 0.0 || g <= 0.0 || b <= 0.0 {
+            return self.gains();
+        }
+
+        let mut correction = [(g / r) as f32, (g / b) as f32];
+
+        let weight = self.config.retinex_weight.clamp(0.0, 1.0);
+        let [max_r, max_g, max_b] = stats.max;
+        if weight > 0.0 && max_r > 0.0 && max_g > 0.0 && max_b > 0.0 {
+            let white_patch = [max_g / max_r, max_g / max_b];
+            for (c, wp) in correction.iter_mut().zip(white_patch) {
+                *c = *c * (1.0 - weight) + wp * weight;
+            }
+        }
+
+        let damping = self.config.damping.clamp(f32::EPSILON, 1.0);
+        let mut gains = self.gains;
+        for (gain, c) in gains.iter_mut().zip(correction) {
+            if (c - 1.0).abs() > self.config.deadband {
+                // Damping is applied in the log domain, so that corrections in both directions converge equally fast
+                *gain *= c.powf(damping);
+            }
+        }
+        self.gains = self.clamp(gains);
+
+        self.gains()
+    }
+
+    /// Gathers statistics from the frame planes and updates gains, see [FrameStatistics::from_frame()] and
+    /// [GreyWorldAwb::update()].
+    pub fn process_frame(
+        &mut self,
+        planes: &[&[u8]],
+        format: PixelFormat,
+        size: Size,
+        stride: u32,
+    ) -> Result<ColourGains, AwbError> {
+        let stats = FrameStatistics::from_frame(
+            planes,
+            format,
+            size,
+            stride,
+            self.config.step,
+            self.config.black_level,
+            self.config.saturation_level,
+        )?;
+        if stats.count == 0 {
+            return Err(AwbError::NoValidPixels);
+        }
+        Ok(self.update(&stats))
+    }
+
+    /// Writes the current gains into the request controls.
+    pub fn apply(&self, controls: &mut ControlList) -> Result<(), ControlError> {
+        controls.set(self.gains())
+    }
+
+    /// Limits gains to the configured range, `max_gain` wins if the range is empty.
+    fn clamp(&self, gains: [f32; 2]) -> [f32; 2] {
+        gains.map(|g| g.max(self.config.min_gain).min(self.config.max_gain))
+    }
+}
+
+impl Default for GreyWorldAwb {
+    fn default() -> Self {
+        Self::new(GreyWorldAwbConfig::default())
+    }
+}
@@ -0,0 +1,158 @@
+use std::borrow::Cow;
+
+use drm_fourcc::DrmFourcc;
+
+use crate::{camera::CameraConfiguration, geometry::Size, pixel_format::PixelFormat, stream::StreamConfigurationRef};
+
+/// Estimated data rate of a single configured stream.
+#[derive(Debug, Clone, Copy)]
+pub struct StreamBandwidth {
+    /// Index of the stream within [CameraConfiguration].
+    pub index: usize,
+    pub pixel_format: PixelFormat,
+    pub size: Size,
+    /// Bytes per frame, as reported by libcamera or estimated from the pixel format if not yet known.
+    pub frame_size: u64,
+    pub fps: f64,
+}
+
+impl StreamBandwidth {
+    /// Estimates data rate of a stream running at `fps` frames per second.
+    ///
+    /// Frame size reported by libcamera is only filled in after [CameraConfiguration::validate()]. Before that it is
+    /// estimated from stride or pixel format. For compressed formats (i.e. MJPEG) it is an upper bound.
+    pub fn new(index: usize, cfg: &StreamConfigurationRef<'_>, fps: f64) -> Self {
+        let pixel_format = cfg.get_pixel_format();
+        let size = cfg.get_size();
+        let frame_size = match (cfg.get_frame_size(), cfg.get_stride()) {
+            (0, 0) => (size.width as u64 * size.height as u64 * bits_per_pixel(pixel_format)).div_ceil(8),
+            (0, stride) => stride as u64 * size.height as u64,
+            (frame_size, _) => frame_size as u64,
+        };
+
+        Self {
+            index,
+            pixel_format,
+            size,
+            frame_size,
+            fps,
+        }
+    }
+
+    /// Estimated data rate in bytes per second.
+    pub fn bytes_per_sec(&self) -> f64 {
+        self.frame_size as f64 * self.fps
+    }
+}
+
+/// Average bits per pixel used when frame size is not known yet.
+fn bits_per_pixel(pixel_format: PixelFormat) -> u64 {
+    match DrmFourcc::try_from(pixel_format.fourcc()) {
+        Ok(DrmFourcc::Nv12 | DrmFourcc::Nv21 | DrmFourcc::Yuv420 | DrmFourcc::Yvu420) => 12,
+        Ok(DrmFourcc::Rgb888 | DrmFourcc::Bgr888) => 24,
+        Ok(DrmFourcc::Xrgb8888 | DrmFourcc::Argb8888 | DrmFourcc::Xbgr8888 | DrmFourcc::Abgr8888) => 32,
+        Ok(DrmFourcc::R8) => 8,
+        // YUV 4:2:2, RGB565 and unpacked raw formats
+        _ => 16,
+    }
+}
+
+/// Data rate limit of a link or processing block in the capture path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandwidthBudget {
+    pub name: Cow<'static, str>,
+    pub bytes_per_sec: f64,
+}
+
+impl BandwidthBudget {
+    /// USB 2.0 high-speed isochronous endpoint with 3 transactions of 1024 bytes per microframe, typical for UVC.
+    pub const USB2: Self = Self::new_static("USB 2.0", 24_576_000.0);
+    /// Practically achievable USB 3.0 throughput.
+    pub const USB3: Self = Self::new_static("USB 3.0", 400_000_000.0);
+    /// MIPI CSI-2 D-PHY link with 2 lanes at 1 Gbps per lane.
+    pub const CSI2_2_LANE: Self = Self::new_static("CSI-2 2 lanes", 250_000_000.0);
+    /// MIPI CSI-2 D-PHY link with 4 lanes at 1 Gbps per lane.
+    pub const CSI2_4_LANE: Self = Self::new_static("CSI-2 4 lanes", 500_000_000.0);
+
+    pub fn new(name: impl Into<Cow<'static, str>>, bytes_per_sec: f64) -> Self {
+        Self {
+            name: name.into(),
+            bytes_per_sec,
+        }
+    }
+
+    const fn new_static(name: &'static str, bytes_per_sec: f64) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            bytes_per_sec,
+        }
+    }
+}
+
+/// Reported by [BandwidthEstimate::check()] when aggregate data rate exceeds a budget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandwidthWarning {
+    pub budget: BandwidthBudget,
+    /// Aggregate estimated data rate in bytes per second.
+    pub estimated: f64,
+}
+
+impl core::fmt::Display for BandwidthWarning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Estimated {:.1} MB/s exceeds {} budget of {:.1} MB/s",
+            self.estimated / 1e6,
+            self.budget.name,
+            self.budget.bytes_per_sec / 1e6
+        )
+    }
+}
+
+/// Estimated data rates of all streams of a [CameraConfiguration].
+///
+/// Helps to understand why [CameraConfiguration::validate()] adjusts a configuration or why frames are dropped at
+/// runtime. Estimates only account for pixel data, actual link overhead (blanking, packet headers) is higher.
+#[derive(Debug, Clone)]
+pub struct BandwidthEstimate {
+    pub streams: Vec<StreamBandwidth>,
+}
+
+impl BandwidthEstimate {
+    /// Estimates data rates of all streams running at the same `fps`.
+    pub fn new(config: &CameraConfiguration, fps: f64) -> Self {
+        Self {
+            streams: (0..config.len())
+                .filter_map(|i| Some(StreamBandwidth::new(i, config.get(i)?.value(), fps)))
+                .collect(),
+        }
+    }
+
+    /// Aggregate estimated data rate of all streams in bytes per second.
+    pub fn total(&self) -> f64 {
+        self.streams.iter().map(|s| s.bytes_per_sec()).sum()
+    }
+
+    /// Checks aggregate data rate against the given budgets and returns all that were exceeded.
+    ///
+    /// Pass the budgets of links and blocks the camera data actually goes through, i.e.
+    /// `&[BandwidthBudget::USB2]` for a UVC webcam.
+    pub fn check(&self, budgets: &[BandwidthBudget]) -> Vec<BandwidthWarning> {
+        let estimated = self.total();
+        budgets
+            .iter()
+            .filter(|b| estimated > b.bytes_per_sec)
+            .map(|b| BandwidthWarning {
+                budget: b.clone(),
+                estimated,
+            })
+            .collect()
+    }
+}
+
+impl CameraConfiguration {
+    /// Estimates data rates of all streams running at `fps` frames per second, see [BandwidthEstimate].
+    pub fn bandwidth(&self, fps: f64) -> BandwidthEstimate {
+        BandwidthEstimate::new(self, fps)
+    }
+}
@@ -1,20 +1,29 @@
 use std::{
-    collections::HashMap,
-    ffi::CStr,
-    io,
+    ffi::{c_void, CStr},
     marker::PhantomData,
     ops::{Deref, DerefMut},
     ptr::NonNull,
-    sync::Mutex,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc, Condvar, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
 };
 
 use libcamera_sys::*;
 
 use crate::{
-    control::{ControlInfoMap, ControlList, PropertyList},
-    request::Request,
-    stream::{StreamConfigurationRef, StreamRole},
-    utils::Immutable,
+    control::{ClampedControl, ControlInfoMap, ControlList, ControlValidationError, PropertyList},
+    error::Error,
+    frame::CompletedFrame,
+    geometry::{Rectangle, Size},
+    mounting::MountingInfo,
+    observer::RequestObserver,
+    orientation::Orientation,
+    request::{QueueState, Request, RequestStatus, ReuseFlag},
+    signal::SignalSubscription,
+    stream::{SizeAdjustment, Stream, StreamAdjustment, StreamConfiguration, StreamConfigurationRef, StreamRole},
+    utils::{Immutable, ListenerId, Listeners},
 };
 
 /// Status of [CameraConfiguration]
@@ -55,16 +64,100 @@ impl TryFrom<libcamera_camera_configuration_status_t> for CameraConfigurationSta
     }
 }
 
+/// Explicit sensor mode request, see [CameraConfiguration::set_sensor_configuration()].
+///
+/// Requires libcamera v0.3.0 or newer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorConfiguration {
+    /// Bit depth of the sensor output.
+    pub bit_depth: u32,
+    /// Analog crop rectangle within the full pixel array. Currently ignored by most pipeline handlers.
+    pub analog_crop: Rectangle,
+    /// Horizontal binning factor.
+    pub bin_x: u32,
+    /// Vertical binning factor.
+    pub bin_y: u32,
+    /// Horizontal increment for odd rows.
+    pub x_odd_inc: u32,
+    /// Horizontal increment for even rows.
+    pub x_even_inc: u32,
+    /// Vertical increment for odd columns.
+    pub y_odd_inc: u32,
+    /// Vertical increment for even columns.
+    pub y_even_inc: u32,
+    /// Size of the sensor output.
+    pub output_size: Size,
+}
+
+impl SensorConfiguration {
+    /// Creates sensor configuration with the given output size and bit depth without binning and skipping.
+    pub fn new(output_size: Size, bit_depth: u32) -> Self {
+        Self {
+            bit_depth,
+            analog_crop: Rectangle {
+                x: 0,
+                y: 0,
+                width: 0,
+                height: 0,
+            },
+            bin_x: 1,
+            bin_y: 1,
+            x_odd_inc: 1,
+            x_even_inc: 1,
+            y_odd_inc: 1,
+            y_even_inc: 1,
+            output_size,
+        }
+    }
+}
+
+impl From<libcamera_sensor_configuration_t> for SensorConfiguration {
+    fn from(c: libcamera_sensor_configuration_t) -> Self {
+        Self {
+            bit_depth: c.bit_depth,
+            analog_crop: c.analog_crop.into(),
+            bin_x: c.bin_x,
+            bin_y: c.bin_y,
+            x_odd_inc: c.x_odd_inc,
+            x_even_inc: c.x_even_inc,
+            y_odd_inc: c.y_odd_inc,
+            y_even_inc: c.y_even_inc,
+            output_size: c.output_size.into(),
+        }
+    }
+}
+
+impl From<SensorConfiguration> for libcamera_sensor_configuration_t {
+    fn from(c: SensorConfiguration) -> Self {
+        Self {
+            bit_depth: c.bit_depth,
+            analog_crop: c.analog_crop.into(),
+            bin_x: c.bin_x,
+            bin_y: c.bin_y,
+            x_odd_inc: c.x_odd_inc,
+            x_even_inc: c.x_even_inc,
+            y_odd_inc: c.y_odd_inc,
+            y_even_inc: c.y_even_inc,
+            output_size: c.output_size.into(),
+        }
+    }
+}
+
 /// Camera configuration.
 ///
 /// Contains [StreamConfigurationRef] for each stream used by the camera.
 pub struct CameraConfiguration {
     ptr: NonNull<libcamera_camera_configuration_t>,
+    /// Generation of the last [ActiveCamera::configure()] that applied this configuration, 0 if never applied.
+    generation: u64,
 }
 
+/// Source of configuration generations, shared by all cameras so that streams of different cameras never match.
+static CONFIGURATION_GENERATION: AtomicU64 = AtomicU64::new(0);
+
 impl CameraConfiguration {
     pub(crate) unsafe fn from_ptr(ptr: NonNull<libcamera_camera_configuration_t>) -> Self {
-        Self { ptr }
+        Self { ptr, generation: 0 }
     }
 
     /// Returns immutable [StreamConfigurationRef] for the camera stream.
@@ -74,7 +167,7 @@ impl CameraConfiguration {
     /// * `index` - Camera stream index.
     pub fn get(&self, index: usize) -> Option<Immutable<StreamConfigurationRef<'_>>> {
         let ptr = unsafe { libcamera_camera_configuration_at(self.ptr.as_ptr(), index as _) };
-        NonNull::new(ptr).map(|p| Immutable(unsafe { StreamConfigurationRef::from_ptr(p) }))
+        NonNull::new(ptr).map(|p| Immutable(unsafe { StreamConfigurationRef::from_ptr(p, self.generation) }))
     }
 
     /// Returns mutable [StreamConfigurationRef] for the camera stream.
@@ -84,7 +177,7 @@ impl CameraConfiguration {
     /// * `index` - Camera stream index.
     pub fn get_mut(&mut self, index: usize) -> Option<StreamConfigurationRef<'_>> {
         let ptr = unsafe { libcamera_camera_configuration_at(self.ptr.as_ptr(), index as _) };
-        NonNull::new(ptr).map(|p| unsafe { StreamConfigurationRef::from_ptr(p) })
+        NonNull::new(ptr).map(|p| unsafe { StreamConfigurationRef::from_ptr(p, self.generation) })
     }
 
     /// Returns number of streams within camera configuration.
@@ -97,19 +190,105 @@ impl CameraConfiguration {
         self.len() == 0
     }
 
+    /// Requests an explicit sensor configuration instead of relying on the pipeline handler mode selection heuristics,
+    /// i.e. to force a full field of view binned mode.
+    ///
+    /// Fails with [Error::ConfigurationInvalid] if sensor configuration is incomplete and with [Error::Unsupported] if
+    /// linked libcamera is older than v0.3.0. Whether the sensor supports requested mode is only known after
+    /// [CameraConfiguration::validate()], which returns [CameraConfigurationStatus::Invalid] otherwise.
+    pub fn set_sensor_configuration(&mut self, sensor_config: SensorConfiguration) -> Result<(), Error> {
+        let sensor_config = libcamera_sensor_configuration_t::from(sensor_config);
+        let ret = unsafe { libcamera_camera_configuration_set_sensor_configuration(self.ptr.as_ptr(), &sensor_config) };
+        if ret < 0 {
+            Err(Error::from_ret(ret, |errno| match errno {
+                libc::EINVAL => Error::ConfigurationInvalid,
+                errno => Error::ConfigureFailed { errno },
+            }))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns requested sensor configuration, if any.
+    pub fn sensor_configuration(&self) -> Option<SensorConfiguration> {
+        let mut sensor_config =
+            libcamera_sensor_configuration_t::from(SensorConfiguration::new(Size { width: 0, height: 0 }, 0));
+        unsafe { libcamera_camera_configuration_get_sensor_configuration(self.ptr.as_ptr(), &mut sensor_config) }
+            .then(|| sensor_config.into())
+    }
+
+    /// Removes explicit sensor configuration request.
+    pub fn clear_sensor_configuration(&mut self) {
+        unsafe { libcamera_camera_configuration_clear_sensor_configuration(self.ptr.as_ptr()) }
+    }
+
+    /// Returns requested image orientation.
+    ///
+    /// After [CameraConfiguration::validate()] it contains orientation that the pipeline is actually able to produce.
+    pub fn orientation(&self) -> Orientation {
+        Orientation::try_from(unsafe { libcamera_camera_configuration_get_orientation(self.ptr.as_ptr()) }).unwrap()
+    }
+
+    /// Requests image orientation, i.e. [Orientation::Rotate180] to compensate for a camera mounted upside down.
+    ///
+    /// Pipelines may only support a subset of orientations (usually flips done by the sensor), in which case
+    /// [CameraConfiguration::validate()] adjusts it to the closest supported one.
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        unsafe { libcamera_camera_configuration_set_orientation(self.ptr.as_ptr(), orientation.into()) }
+    }
+
     /// Validates camera configuration.
     pub fn validate(&mut self) -> CameraConfigurationStatus {
         unsafe { libcamera_camera_configuration_validate(self.ptr.as_ptr()) }
             .try_into()
             .unwrap()
     }
+
+    /// Validates camera configuration and reports every stream changed by the pipeline, comparing requested and
+    /// validated values.
+    ///
+    /// Allows applications to see why a size was changed, i.e. to pre-align requested sizes with
+    /// [StreamConfigurationRef::alignment()] instead of getting surprise padding.
+    pub fn validate_detailed(&mut self) -> (CameraConfigurationStatus, Vec<StreamAdjustment>) {
+        let requested = (0..self.len())
+            .filter_map(|index| Some(self.get(index)?.snapshot()))
+            .collect::<Vec<_>>();
+        let status = self.validate();
+
+        let adjustments = requested
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, requested)| {
+                let cfg = self.get(index)?;
+                let validated = cfg.snapshot();
+                if validated == requested {
+                    return None;
+                }
+                let size = (validated.size != requested.size).then(|| {
+                    let range = cfg.formats().range(validated.pixel_format);
+                    SizeAdjustment::new(requested.size, validated.size, range)
+                });
+                let color_space = validated
+                    .color_space
+                    .filter(|_| validated.color_space != requested.color_space);
+                Some(StreamAdjustment {
+                    index,
+                    requested,
+                    validated,
+                    size,
+                    color_space,
+                })
+            })
+            .collect();
+        (status, adjustments)
+    }
 }
 
 impl core::fmt::Debug for CameraConfiguration {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut list = f.debug_list();
         for i in 0..self.len() {
-            list.entry(&self.get(i).unwrap().0);
+            list.entry(self.get(i).unwrap().value());
         }
         list.finish()
     }
@@ -156,6 +335,14 @@ impl<'d> Camera<'d> {
         }
     }
 
+    /// Checks controls against [Camera::controls()] before queueing them.
+    ///
+    /// [ControlList::set()] silently accepts unsupported controls and out of range values, this allows to detect them
+    /// beforehand. Returns an empty list if all controls are valid.
+    pub fn validate_controls(&self, controls: &ControlList) -> Vec<ControlValidationError> {
+        self.controls().validate(controls)
+    }
+
     /// Returns a list of camera properties.
     ///
     /// See [properties](crate::properties) for available items.
@@ -165,6 +352,11 @@ impl<'d> Camera<'d> {
         }
     }
 
+    /// Returns location, rotation and model of the camera, see [MountingInfo].
+    pub fn mounting_info(&self) -> MountingInfo {
+        MountingInfo::from_properties(self.properties())
+    }
+
     /// Generates default camera configuration for the given [StreamRole]s.
     ///
     /// The resulting [CameraConfiguration] contains stream configurations for each of the requested roles.
@@ -178,14 +370,34 @@ impl<'d> Camera<'d> {
     }
 
     /// Acquires exclusive rights to the camera, which allows changing configuration and capturing.
-    pub fn acquire(&self) -> io::Result<ActiveCamera<'_>> {
+    ///
+    /// Fails with [Error::Busy] if camera is already acquired, either by this or by another process.
+    pub fn acquire(&self) -> Result<ActiveCamera<'_>, Error> {
         let ret = unsafe { libcamera_camera_acquire(self.ptr.as_ptr()) };
         if ret < 0 {
-            Err(io::Error::from_raw_os_error(ret))
+            Err(Error::from_ret(ret, |errno| Error::AcquireFailed { errno }))
         } else {
             Ok(unsafe { ActiveCamera::from_ptr(NonNull::new(libcamera_camera_copy(self.ptr.as_ptr())).unwrap()) })
         }
     }
+
+    /// Subscribes to camera disconnection, i.e. when a USB camera is unplugged.
+    ///
+    /// A disconnected camera can no longer capture and should be released. Callback is executed in the libcamera thread
+    /// context. The subscription keeps its own reference to the camera, so it may outlive `self`.
+    pub fn on_disconnected(&self, cb: impl FnMut() + Send + 'd) -> SignalSubscription<'d, DisconnectedCb<'d>> {
+        let cam = unsafe { libcamera_camera_copy(self.ptr.as_ptr()) };
+        unsafe {
+            SignalSubscription::connect(
+                Box::new(cb),
+                |data| libcamera_camera_disconnected_connect(cam, Some(camera_disconnected_cb), data),
+                move |handle| {
+                    libcamera_camera_disconnected_disconnect(cam, handle.as_ptr());
+                    libcamera_camera_destroy(cam);
+                },
+            )
+        }
+    }
 }
 
 impl<'d> Drop for Camera<'d> {
@@ -194,24 +406,210 @@ impl<'d> Drop for Camera<'d> {
     }
 }
 
-extern "C" fn camera_request_completed_cb(ptr: *mut core::ffi::c_void, req: *mut libcamera_request_t) {
-    let mut state = unsafe { &*(ptr as *const Mutex<ActiveCameraState<'_>>) }
-        .lock()
-        .unwrap();
-    let req = state.requests.remove(&req).unwrap();
+/// Callback of [Camera::on_disconnected()].
+pub type DisconnectedCb<'d> = dyn FnMut() + Send + 'd;
+type RequestCompletedCb<'d> = dyn FnMut(*mut libcamera_request_t) + 'd;
+type BufferCompletedCb<'d> = dyn FnMut(*mut libcamera_request_t, *mut libcamera_framebuffer_t) + 'd;
+
+extern "C" fn camera_disconnected_cb(data: *mut c_void) {
+    let cb = unsafe { SignalSubscription::<DisconnectedCb<'_>>::callback(data) };
+    cb();
+}
+
+extern "C" fn camera_request_completed_cb(data: *mut c_void, req: *mut libcamera_request_t) {
+    let cb = unsafe { SignalSubscription::<RequestCompletedCb<'_>>::callback(data) };
+    cb(req);
+}
+
+extern "C" fn camera_buffer_completed_cb(
+    data: *mut c_void,
+    req: *mut libcamera_request_t,
+    buffer: *mut libcamera_framebuffer_t,
+) {
+    let cb = unsafe { SignalSubscription::<BufferCompletedCb<'_>>::callback(data) };
+    cb(req, buffer);
+}
+
+fn request_completed(state: &Mutex<ActiveCameraState<'_>>, req: *mut libcamera_request_t) {
+    let mut state = state.lock().unwrap();
+    let mut req = state.requests.remove(req).unwrap();
+    req.set_queue_state(QueueState::Completed);
+    state.slot_freed.notify_all();
+
+    #[cfg(feature = "tracing")]
+    if let Some(span) = state.spans.remove(&req.ptr.as_ptr()) {
+        trace_completed(&span, &req);
+    }
+
+    for observer in state.request_observers.iter_mut() {
+        observer.on_complete(&req);
+    }
+    for listener in state.request_completed_listeners.iter_mut() {
+        listener(&req);
+    }
+
+    if matches!(&state.probe, Some((ptr, _)) if *ptr == req.ptr.as_ptr()) {
+        let (_, tx) = state.probe.take().unwrap();
+        let _ = tx.send(req);
+        return;
+    }
 
-    if let Some(cb) = &mut state.request_completed_cb {
+    if let Some(cancelled) = &mut state.cancelled {
+        cancelled.push(req);
+    } else if let Some(cb) = &mut state.request_completed_cb {
         cb(req);
     }
 }
 
+fn buffer_completed(
+    state: &Mutex<ActiveCameraState<'_>>,
+    req: *mut libcamera_request_t,
+    buffer: *mut libcamera_framebuffer_t,
+) {
+    let mut state = state.lock().unwrap();
+    let state = &mut *state;
+    let (Some(cb), Some(req)) = (&mut state.buffer_completed_cb, state.requests.get(req)) else {
+        return;
+    };
+    if let Some(stream) = req.find_stream(buffer) {
+        cb(req, &stream);
+    }
+}
+
+/// Records sequence and sensor timestamp of a completed request into its span, which is closed once dropped.
+#[cfg(feature = "tracing")]
+fn trace_completed(span: &tracing::Span, req: &Request) {
+    span.record("sequence", req.sequence());
+    if let Ok(timestamp) = req.metadata().get::<crate::controls::SensorTimestamp>() {
+        span.record("timestamp", timestamp.0);
+    }
+    tracing::debug!(parent: span, status = ?req.status(), "request completed");
+}
+
+type ControlClampedCb<'d> = Box<dyn FnMut(&ClampedControl) + Send + 'd>;
+type RequestListener<'d> = dyn FnMut(&Request) + Send + 'd;
+type BufferListener<'d> = Box<dyn FnMut(&Request, &Stream) + Send + 'd>;
+
 #[derive(Default)]
 struct ActiveCameraState<'d> {
     /// List of queued requests that are yet to be executed.
     /// Used to temporarily store [Request] before returning it back to the user.
-    requests: HashMap<*mut libcamera_request_t, Request>,
+    requests: RequestSlots,
     /// Callback for libcamera `requestCompleted` signal.
     request_completed_cb: Option<Box<dyn FnMut(Request) + Send + 'd>>,
+    /// Observers of completed requests, called before [ActiveCameraState::request_completed_cb].
+    request_completed_listeners: Listeners<RequestListener<'d>>,
+    /// Request lifecycle observers, see [ActiveCamera::add_request_observer()].
+    request_observers: Listeners<dyn RequestObserver + 'd>,
+    /// Callback for libcamera `bufferCompleted` signal.
+    buffer_completed_cb: Option<BufferListener<'d>>,
+    /// Callback for controls adjusted when [ActiveCamera::set_clamp_controls()] is enabled.
+    control_clamped_cb: Option<ControlClampedCb<'d>>,
+    /// Request queued by [ActiveCamera::probe()], which is returned through the channel instead of the callback.
+    probe: Option<(*mut libcamera_request_t, mpsc::Sender<Request>)>,
+    /// Spans of queued requests, closed when the request completes.
+    #[cfg(feature = "tracing")]
+    spans: std::collections::HashMap<*mut libcamera_request_t, tracing::Span>,
+    /// Limit of [ActiveCameraState::requests], see [ActiveCamera::set_max_in_flight()].
+    max_in_flight: Option<usize>,
+    /// Notified whenever a request leaves [ActiveCameraState::requests].
+    slot_freed: Arc<Condvar>,
+    /// Collects completed requests instead of the callback during [ActiveCamera::cancel_requests()].
+    cancelled: Option<Vec<Request>>,
+}
+
+/// Queued requests, kept in slots which are reused once their request completes.
+///
+/// Completion is dispatched by a single `requestCompleted` handler, which finds the request by pointer. Request
+/// cookies belong to the application, so slots are scanned instead, which is cheap for the few requests a camera
+/// keeps in flight. Queueing does not allocate once the number of requests in flight stops growing.
+#[derive(Default)]
+struct RequestSlots {
+    slots: Vec<Option<Request>>,
+    len: usize,
+}
+
+impl RequestSlots {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn position(&self, ptr: *mut libcamera_request_t) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|slot| slot.as_ref().is_some_and(|req| req.ptr.as_ptr() == ptr))
+    }
+
+    /// Stores the request in the first free slot.
+    fn insert(&mut self, req: Request) {
+        self.len += 1;
+        match self.slots.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => *slot = Some(req),
+            None => self.slots.push(Some(req)),
+        }
+    }
+
+    fn get(&self, ptr: *mut libcamera_request_t) -> Option<&Request> {
+        self.slots[self.position(ptr)?].as_ref()
+    }
+
+    fn remove(&mut self, ptr: *mut libcamera_request_t) -> Option<Request> {
+        let index = self.position(ptr)?;
+        self.len -= 1;
+        self.slots[index].take()
+    }
+
+    /// Removes all requests, keeping the slots for reuse.
+    fn drain(&mut self) -> impl Iterator<Item = Request> + '_ {
+        self.len = 0;
+        self.slots.iter_mut().filter_map(Option::take)
+    }
+}
+
+impl ActiveCameraState<'_> {
+    fn is_queue_full(&self) -> bool {
+        self.max_in_flight.is_some_and(|max| self.requests.len() >= max)
+    }
+}
+
+/// Camera configuration as applied by [ActiveCamera::configure()].
+///
+/// Contains the final values of all streams, i.e. stride and frame size adjusted by the pipeline, and their [Stream]
+/// handles, which stay valid until the camera is configured again.
+#[derive(Debug, Clone)]
+pub struct AppliedConfiguration {
+    streams: Vec<(Stream, StreamConfiguration)>,
+    orientation: Orientation,
+}
+
+impl AppliedConfiguration {
+    /// Returns configuration of the stream at `index` within the applied [CameraConfiguration].
+    pub fn get(&self, index: usize) -> Option<&StreamConfiguration> {
+        self.streams.get(index).map(|(_, cfg)| cfg)
+    }
+
+    /// Returns handle of the stream at `index` within the applied [CameraConfiguration].
+    pub fn stream(&self, index: usize) -> Option<Stream> {
+        self.streams.get(index).map(|(stream, _)| *stream)
+    }
+
+    /// Iterates over `(stream, configuration)` of all streams.
+    pub fn iter(&self) -> impl Iterator<Item = (Stream, &StreamConfiguration)> {
+        self.streams.iter().map(|(stream, cfg)| (*stream, cfg))
+    }
+
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    /// Returns image orientation produced by the pipeline.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
 }
 
 /// An active instance of a camera.
@@ -221,29 +619,57 @@ struct ActiveCameraState<'d> {
 /// Obtained by [Camera::acquire()].
 pub struct ActiveCamera<'d> {
     cam: Camera<'d>,
-    /// Handle to disconnect `requestCompleted` signal.
-    request_completed_handle: *mut libcamera_callback_handle_t,
+    /// `requestCompleted` signal, forwarded to [ActiveCameraState].
+    request_completed: SignalSubscription<'d, RequestCompletedCb<'d>>,
+    /// `bufferCompleted` signal, forwarded to [ActiveCameraState].
+    buffer_completed: SignalSubscription<'d, BufferCompletedCb<'d>>,
     /// Internal state that is shared with callback handlers.
     state: Box<Mutex<ActiveCameraState<'d>>>,
+    /// Whether capture session is currently started.
+    running: bool,
+    /// Set by [ActiveCamera::notify_resumed()] until [ActiveCamera::reinitialize()] is called.
+    needs_reinitialize: bool,
+    /// Whether request controls are clamped to camera limits before queueing.
+    clamp_controls: bool,
+    /// Generation of the applied configuration, 0 if unconfigured. Streams carry the generation they were obtained in.
+    generation: u64,
+    /// Serializes `libcamera_camera_queue_request()` calls of [RequestQueueHandle]s in different threads.
+    queue_lock: Mutex<()>,
 }
 
 impl<'d> ActiveCamera<'d> {
     pub(crate) unsafe fn from_ptr(ptr: NonNull<libcamera_camera_t>) -> Self {
-        let mut state = Box::new(Mutex::new(ActiveCameraState::default()));
-
-        let request_completed_handle = unsafe {
-            libcamera_camera_request_completed_connect(
-                ptr.as_ptr(),
-                Some(camera_request_completed_cb),
-                // state is valid for the lifetime of `ActiveCamera` and this callback will be disconnected on drop.
-                state.as_mut() as *mut Mutex<ActiveCameraState<'_>> as *mut _,
+        let state = Box::new(Mutex::new(ActiveCameraState::default()));
+        // state is valid for the lifetime of `ActiveCamera` and signals are disconnected on drop before it is freed.
+        let state_ptr = state.as_ref() as *const Mutex<ActiveCameraState<'d>>;
+
+        let request_completed = unsafe {
+            SignalSubscription::connect(
+                Box::new(move |req| request_completed(&*state_ptr, req)) as Box<RequestCompletedCb<'d>>,
+                |data| {
+                    libcamera_camera_request_completed_connect(ptr.as_ptr(), Some(camera_request_completed_cb), data)
+                },
+                move |handle| libcamera_camera_request_completed_disconnect(ptr.as_ptr(), handle.as_ptr()),
+            )
+        };
+        let buffer_completed = unsafe {
+            SignalSubscription::connect(
+                Box::new(move |req, buffer| buffer_completed(&*state_ptr, req, buffer)) as Box<BufferCompletedCb<'d>>,
+                |data| libcamera_camera_buffer_completed_connect(ptr.as_ptr(), Some(camera_buffer_completed_cb), data),
+                move |handle| libcamera_camera_buffer_completed_disconnect(ptr.as_ptr(), handle.as_ptr()),
             )
         };
 
         Self {
             cam: Camera::from_ptr(ptr),
-            request_completed_handle,
+            request_completed,
+            buffer_completed,
             state,
+            running: false,
+            needs_reinitialize: false,
+            clamp_controls: false,
+            generation: 0,
+            queue_lock: Mutex::new(()),
         }
     }
 
@@ -252,23 +678,156 @@ impl<'d> ActiveCamera<'d> {
     /// Callback is executed in the libcamera thread context so it is best to setup a channel to send all requests for
     /// processing elsewhere.
     ///
-    /// Only one callback can be set at a time. If there was a previously set callback, it will be discarded when
-    /// setting a new one.
+    /// Only one callback can be set at a time, as it takes ownership of the requests. If there was a previously set
+    /// callback, it will be discarded when setting a new one. Use [ActiveCamera::add_request_completed_listener()] to
+    /// observe completed requests without taking them.
     pub fn on_request_completed(&mut self, cb: impl FnMut(Request) + Send + 'd) {
         let mut state = self.state.lock().unwrap();
         state.request_completed_cb = Some(Box::new(cb));
     }
 
+    /// Sets a callback for completed requests, which receives them as [CompletedFrame] with typed access to mapped
+    /// buffers and metadata.
+    ///
+    /// Replaces the callback set by [ActiveCamera::on_request_completed()], see its documentation for details.
+    pub fn on_frame(&mut self, mut cb: impl FnMut(CompletedFrame) + Send + 'd) {
+        self.on_request_completed(move |req| cb(CompletedFrame::new(req)));
+    }
+
+    /// Returns a blocking iterator over completed requests, for synchronous programs which do not want to deal with
+    /// callbacks.
+    ///
+    /// Requests queued with [ActiveCamera::queue_request()] are yielded as [FrameRequest]s once completed, and queued
+    /// again with the same buffers when dropped. The callback set by [ActiveCamera::on_request_completed()] is
+    /// suspended while the iterator exists. Iteration blocks until the next request completes, unless a timeout is set
+    /// with [Frames::timeout()].
+    pub fn frames(&mut self) -> Frames<'_, 'd> {
+        let (tx, rx) = mpsc::channel();
+        let previous = self
+            .state
+            .lock()
+            .unwrap()
+            .request_completed_cb
+            .replace(Box::new(move |req| {
+                let _ = tx.send(req);
+            }));
+        Frames {
+            cam: self,
+            rx,
+            timeout: None,
+            timed_out: false,
+            previous,
+        }
+    }
+
+    /// Registers an observer of completed requests, i.e. for statistics or tracing.
+    ///
+    /// Any number of listeners can be registered. They are called in registration order in the libcamera thread
+    /// context before the callback set by [ActiveCamera::on_request_completed()], including for requests returned by
+    /// [ActiveCamera::probe()] and [ActiveCamera::stop()]. Listeners must not register or remove listeners themselves.
+    pub fn add_request_completed_listener(&mut self, listener: impl FnMut(&Request) + Send + 'd) -> ListenerId {
+        self.state
+            .lock()
+            .unwrap()
+            .request_completed_listeners
+            .add(Box::new(listener))
+    }
+
+    /// Removes a listener registered with [ActiveCamera::add_request_completed_listener()].
+    ///
+    /// Returns `false` if the listener was already removed.
+    pub fn remove_request_completed_listener(&mut self, id: ListenerId) -> bool {
+        self.state.lock().unwrap().request_completed_listeners.remove(id)
+    }
+
+    /// Registers an observer of the request lifecycle, see [RequestObserver].
+    ///
+    /// Any number of observers can be registered. They are called in registration order, before listeners and
+    /// callbacks of completed requests.
+    pub fn add_request_observer(&mut self, observer: impl RequestObserver + 'd) -> ListenerId {
+        self.state.lock().unwrap().request_observers.add(Box::new(observer))
+    }
+
+    /// Removes an observer registered with [ActiveCamera::add_request_observer()].
+    ///
+    /// Returns `false` if the observer was already removed.
+    pub fn remove_request_observer(&mut self, id: ListenerId) -> bool {
+        self.state.lock().unwrap().request_observers.remove(id)
+    }
+
+    /// Sets a callback for buffers completed ahead of their request.
+    ///
+    /// Requests with multiple streams complete once all buffers are done, this allows to start processing each buffer
+    /// as soon as it is filled. The callback receives the still pending request, where the buffer can be accessed
+    /// with [Request::buffer()] using the given stream. Callback is executed in the libcamera thread context.
+    pub fn on_buffer_completed(&mut self, cb: impl FnMut(&Request, &Stream) + Send + 'd) {
+        let mut state = self.state.lock().unwrap();
+        state.buffer_completed_cb = Some(Box::new(cb));
+    }
+
+    /// Enables clamping of request controls to the camera limits.
+    ///
+    /// When enabled, numeric controls of every request passed to [ActiveCamera::queue_request()] are clamped to the
+    /// min/max values of [Camera::controls()]. This protects interactive applications from user-entered out of range
+    /// values, which pipelines might otherwise reject or misbehave on. Adjustments are reported to the callback set by
+    /// [ActiveCamera::on_control_clamped()].
+    pub fn set_clamp_controls(&mut self, enabled: bool) {
+        self.clamp_controls = enabled;
+    }
+
+    /// Sets a callback, which is called for every control adjusted due to [ActiveCamera::set_clamp_controls()].
+    ///
+    /// Callback is executed in the thread queueing the request.
+    pub fn on_control_clamped(&mut self, cb: impl FnMut(&ClampedControl) + Send + 'd) {
+        let mut state = self.state.lock().unwrap();
+        state.control_clamped_cb = Some(Box::new(cb));
+    }
+
     /// Applies camera configuration.
     ///
     /// Default configuration can be obtained from [Camera::generate_configuration()] and then adjusted as needed.
-    pub fn configure(&mut self, config: &mut CameraConfiguration) -> io::Result<()> {
+    ///
+    /// `config` is updated in place with the values chosen by the pipeline, which are also returned as
+    /// [AppliedConfiguration]. [Stream] handles obtained before are invalidated on success and stay valid on failure.
+    ///
+    /// Fails with [Error::ConfigurationInvalid] if `config` is not accepted by the pipeline and with
+    /// [Error::InvalidState] if camera is running.
+    pub fn configure(&mut self, config: &mut CameraConfiguration) -> Result<AppliedConfiguration, Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("configure", camera = self.id(), streams = config.len()).entered();
+
         let ret = unsafe { libcamera_camera_configure(self.ptr.as_ptr(), config.ptr.as_ptr()) };
         if ret < 0 {
-            Err(io::Error::from_raw_os_error(ret))
-        } else {
-            Ok(())
+            let err = Error::from_ret(ret, |errno| match errno {
+                libc::EINVAL => Error::ConfigurationInvalid,
+                errno => Error::ConfigureFailed { errno },
+            });
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %err, "configure failed");
+            return Err(err);
         }
+
+        // libcamera reuses Stream objects across reconfigurations, so the pointer alone does not identify them
+        self.generation = CONFIGURATION_GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+        config.generation = self.generation;
+        let streams = (0..config.len())
+            .filter_map(|index| {
+                let cfg = config.get(index)?;
+                Some((cfg.stream()?, cfg.snapshot()))
+            })
+            .collect::<Vec<_>>();
+        Ok(AppliedConfiguration {
+            streams,
+            orientation: config.orientation(),
+        })
+    }
+
+    /// Returns `true` if `stream` was obtained from the currently applied configuration.
+    ///
+    /// Every successful [ActiveCamera::configure()] starts a new generation, so streams obtained before are stale even
+    /// if libcamera kept the same underlying stream objects.
+    pub fn is_stream_valid(&self, stream: &Stream) -> bool {
+        self.generation != 0 && stream.generation == self.generation
     }
 
     /// Creates a capture [`Request`].
@@ -282,21 +841,203 @@ impl<'d> ActiveCamera<'d> {
     ///   completed callback.
     pub fn create_request(&mut self, cookie: Option<u64>) -> Option<Request> {
         let req = unsafe { libcamera_camera_create_request(self.ptr.as_ptr(), cookie.unwrap_or(0)) };
-        NonNull::new(req).map(|p| unsafe { Request::from_ptr(p) })
+        let req = NonNull::new(req).map(|p| unsafe { Request::from_ptr(p) })?;
+        for observer in self.state.lock().unwrap().request_observers.iter_mut() {
+            observer.on_create(&req);
+        }
+        Some(req)
     }
 
     /// Queues [`Request`] for execution. Completed requests are returned in request completed callback, set by the
     /// `ActiveCamera::on_request_completed()`.
     ///
-    /// Requests that do not have attached framebuffers are invalid and are rejected without being queued.
-    pub fn queue_request(&self, req: Request) -> io::Result<()> {
+    /// Requests that do not have attached framebuffers are invalid and are rejected without being queued. Requests with
+    /// buffers of streams that do not belong to the currently applied configuration (see
+    /// [ActiveCamera::is_stream_valid()]) are rejected as well, with [Error::StaleStream].
+    ///
+    /// Fails with [Error::QueueFull] if [ActiveCamera::set_max_in_flight()] limit is reached, use
+    /// [ActiveCamera::queue_request_blocking()] to wait for a request to complete instead. Requests can be queued from
+    /// multiple threads through [ActiveCamera::queue_handle()].
+    ///
+    /// The camera owns queued requests until they complete, so a request can not be queued twice:
+    ///
+    /// ```compile_fail
+    /// # use libcamera::{camera::ActiveCamera, request::Request};
+    /// # fn queue_twice(cam: &ActiveCamera<'_>, req: Request) {
+    /// cam.queue_request(req).unwrap();
+    /// cam.queue_request(req).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// Completed requests have to be reset with [Request::reuse()] before queueing them again, otherwise they are
+    /// rejected with [Error::AlreadyQueued].
+    pub fn queue_request(&self, req: Request) -> Result<(), Error> {
+        self.queue_request_inner(req, None).map_err(|(e, _)| e)
+    }
+
+    /// Queues [`Request`] like [ActiveCamera::queue_request()], but waits until the number of requests in flight drops
+    /// below [ActiveCamera::max_in_flight()] instead of failing.
+    ///
+    /// Fails with [Error::Timeout] if no request completes within `timeout` ([None] waits indefinitely), in which case
+    /// `req` is dropped. Use [ActiveCamera::wait_queue_slot()] beforehand to keep the request in that case.
+    pub fn queue_request_blocking(&self, req: Request, timeout: Option<Duration>) -> Result<(), Error> {
+        self.queue_request_inner(req, Some(timeout.map(|t| Instant::now() + t)))
+            .map_err(|(e, _)| e)
+    }
+
+    /// Queues request like [ActiveCamera::queue_request()], but hands it back on failure so it can be queued again.
+    pub(crate) fn try_queue_request(&self, req: Request) -> Result<(), (Error, Box<Request>)> {
+        self.queue_request_inner(req, None)
+    }
+
+    /// Limits the number of requests in flight, i.e. queued and not yet completed, or removes the limit with [None].
+    ///
+    /// Requests queued beyond the limit are rejected by [ActiveCamera::queue_request()] or delayed by
+    /// [ActiveCamera::queue_request_blocking()], which keeps applications from accumulating latency when they queue
+    /// requests faster than the pipeline completes them. Requests already in flight are not affected when lowering the
+    /// limit.
+    pub fn set_max_in_flight(&mut self, max: Option<usize>) {
+        let mut state = self.state.lock().unwrap();
+        state.max_in_flight = max;
+        state.slot_freed.notify_all();
+    }
+
+    /// Returns a handle for queueing requests from multiple threads.
+    ///
+    /// [ActiveCamera] itself is not [Sync], as configuration changes must not race with capture. The handle only
+    /// exposes queueing, so producer threads (i.e. separate still and video submitters spawned with
+    /// [std::thread::scope()]) can share the camera without wrapping it in a mutex. Camera can not be stopped or
+    /// reconfigured while handles exist.
+    pub fn queue_handle(&self) -> RequestQueueHandle<'_, 'd> {
+        RequestQueueHandle { cam: self }
+    }
+
+    /// Returns the limit set by [ActiveCamera::set_max_in_flight()].
+    pub fn max_in_flight(&self) -> Option<usize> {
+        self.state.lock().unwrap().max_in_flight
+    }
+
+    /// Returns the number of requests queued and not yet completed.
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().unwrap().requests.len()
+    }
+
+    /// Returns `true` if [ActiveCamera::max_in_flight()] requests are in flight.
+    pub fn is_queue_full(&self) -> bool {
+        self.state.lock().unwrap().is_queue_full()
+    }
+
+    /// Waits until a request can be queued without exceeding [ActiveCamera::max_in_flight()].
+    ///
+    /// Returns `false` if no request completed within `timeout` ([None] waits indefinitely). Another thread queueing a
+    /// request in the meantime can take the slot again.
+    pub fn wait_queue_slot(&self, timeout: Option<Duration>) -> bool {
+        self.lock_queue_slot(timeout.map(|t| Instant::now() + t)).is_ok()
+    }
+
+    /// Locks state once a request can be queued, waiting until `deadline` at the latest.
+    fn lock_queue_slot(&self, deadline: Option<Instant>) -> Result<MutexGuard<'_, ActiveCameraState<'d>>, Error> {
+        let mut state = self.state.lock().unwrap();
+        let slot_freed = state.slot_freed.clone();
+        while state.is_queue_full() {
+            state = match deadline {
+                Some(deadline) => {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    if timeout.is_zero() {
+                        return Err(Error::Timeout);
+                    }
+                    slot_freed.wait_timeout(state, timeout).unwrap().0
+                }
+                None => slot_freed.wait(state).unwrap(),
+            };
+        }
+        Ok(state)
+    }
+
+    /// Queues request, waiting for a free slot until the given deadline if `wait` is set. Returns the request on
+    /// failure.
+    fn queue_request_inner(
+        &self,
+        mut req: Request,
+        wait: Option<Option<Instant>>,
+    ) -> Result<(), (Error, Box<Request>)> {
+        if let Err(e) = req.queue_state().check() {
+            return Err((e, Box::new(req)));
+        }
+
+        if req.streams().any(|stream| !self.is_stream_valid(stream)) {
+            return Err((Error::StaleStream, Box::new(req)));
+        }
+
+        if self.clamp_controls {
+            let clamped = match self.controls().clamp(req.controls_mut()) {
+                Ok(clamped) => clamped,
+                Err(e) => return Err((e.into(), Box::new(req))),
+            };
+            if let Some(cb) = &mut self.state.lock().unwrap().control_clamped_cb {
+                for c in clamped.iter() {
+                    cb(c);
+                }
+            }
+        }
+
         let ptr = req.ptr.as_ptr();
-        self.state.lock().unwrap().requests.insert(ptr, req);
+        {
+            let mut state = match wait {
+                Some(deadline) => match self.lock_queue_slot(deadline) {
+                    Ok(state) => state,
+                    Err(e) => return Err((e, Box::new(req))),
+                },
+                None => {
+                    let state = self.state.lock().unwrap();
+                    if state.is_queue_full() {
+                        return Err((Error::QueueFull, Box::new(req)));
+                    }
+                    state
+                }
+            };
+            let reused = req.take_reused();
+            for observer in state.request_observers.iter_mut() {
+                if let Some(flags) = reused {
+                    observer.on_reuse(&req, flags);
+                }
+                observer.on_queue(&req);
+            }
+            #[cfg(feature = "tracing")]
+            state.spans.insert(
+                ptr,
+                tracing::debug_span!(
+                    "request",
+                    camera = self.id(),
+                    cookie = req.cookie(),
+                    sequence = tracing::field::Empty,
+                    timestamp = tracing::field::Empty,
+                ),
+            );
+            req.set_queue_state(QueueState::InFlight);
+            state.requests.insert(req);
+        }
 
-        let ret = unsafe { libcamera_camera_queue_request(self.ptr.as_ptr(), ptr) };
+        let ret = {
+            let _guard = self.queue_lock.lock().unwrap();
+            unsafe { libcamera_camera_queue_request(self.ptr.as_ptr(), ptr) }
+        };
 
         if ret < 0 {
-            Err(io::Error::from_raw_os_error(ret))
+            let err = Error::from_ret(ret, |errno| Error::QueueFailed { errno });
+            let mut state = self.state.lock().unwrap();
+            // rejected requests never complete, take them back to free their slot
+            let mut req = state.requests.remove(ptr).unwrap();
+            req.set_queue_state(QueueState::Idle);
+            for observer in state.request_observers.iter_mut() {
+                observer.on_queue_failed(&req);
+            }
+            state.slot_freed.notify_all();
+            #[cfg(feature = "tracing")]
+            if let Some(span) = state.spans.remove(&ptr) {
+                tracing::warn!(parent: &span, error = %err, "queue_request failed");
+            }
+            Err((err, Box::new(req)))
         } else {
             Ok(())
         }
@@ -305,12 +1046,13 @@ impl<'d> ActiveCamera<'d> {
     /// Starts camera capture session.
     ///
     /// Once started, [ActiveCamera::queue_request()] is permitted and camera configuration can no longer be changed.
-    pub fn start(&mut self, controls: Option<&ControlList>) -> io::Result<()> {
+    pub fn start(&mut self, controls: Option<&ControlList>) -> Result<(), Error> {
         let ctrl_ptr = controls.map(|c| c.ptr()).unwrap_or(core::ptr::null_mut());
         let ret = unsafe { libcamera_camera_start(self.ptr.as_ptr(), ctrl_ptr) };
         if ret < 0 {
-            Err(io::Error::from_raw_os_error(ret))
+            Err(Error::from_ret(ret, |errno| Error::StartFailed { errno }))
         } else {
+            self.running = true;
             Ok(())
         }
     }
@@ -318,14 +1060,159 @@ impl<'d> ActiveCamera<'d> {
     /// Stops camera capture session.
     ///
     /// Once stopped, [ActiveCamera::queue_request()] is no longer permitted and camera configuration can be adjusted.
-    pub fn stop(&mut self) -> io::Result<()> {
+    ///
+    /// All requests that are still queued are returned through the request completed callback, including the ones
+    /// that libcamera did not complete on its own (i.e. when the device stopped after a system suspend).
+    pub fn stop(&mut self) -> Result<(), Error> {
         let ret = unsafe { libcamera_camera_stop(self.ptr.as_ptr()) };
         if ret < 0 {
-            Err(io::Error::from_raw_os_error(ret))
+            Err(Error::from_ret(ret, |errno| Error::StopFailed { errno }))
         } else {
+            self.running = false;
+            self.flush_requests();
             Ok(())
         }
     }
+
+    /// Cancels all requests in flight and returns them to the caller instead of the request completed callback, so that
+    /// their buffers can be reused right away, i.e. when switching modes.
+    ///
+    /// libcamera only cancels requests when stopping the camera, so camera is stopped and has to be started again.
+    /// Returned requests have [RequestStatus::Cancelled] status, unless they completed while stopping. Request
+    /// observers and completed request listeners are notified as usual.
+    pub fn cancel_requests(&mut self) -> Result<Vec<Request>, Error> {
+        self.state.lock().unwrap().cancelled = Some(Vec::new());
+        let res = self.stop();
+        let mut state = self.state.lock().unwrap();
+        let cancelled = state.cancelled.take().unwrap_or_default();
+        if let Err(e) = res {
+            // camera keeps running, hand requests that completed meanwhile to the callback as usual
+            if let Some(cb) = &mut state.request_completed_cb {
+                for req in cancelled {
+                    cb(req);
+                }
+            }
+            return Err(e);
+        }
+        Ok(cancelled)
+    }
+
+    /// Returns `true` if capture session is started.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Hands all requests that are still tracked back to the request completed callback.
+    ///
+    /// Must only be called when camera is stopped, as libcamera no longer references any requests then.
+    fn flush_requests(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        let requests = state.requests.drain().collect::<Vec<_>>();
+        state.slot_freed.notify_all();
+        let probe = state.probe.take();
+        for req in requests {
+            #[cfg(feature = "tracing")]
+            if let Some(span) = state.spans.remove(&req.ptr.as_ptr()) {
+                trace_completed(&span, &req);
+            }
+            for observer in state.request_observers.iter_mut() {
+                observer.on_complete(&req);
+            }
+            for listener in state.request_completed_listeners.iter_mut() {
+                listener(&req);
+            }
+            match &probe {
+                Some((ptr, tx)) if *ptr == req.ptr.as_ptr() => {
+                    let _ = tx.send(req);
+                }
+                _ => {
+                    if let Some(cancelled) = &mut state.cancelled {
+                        cancelled.push(req);
+                    } else if let Some(cb) = &mut state.request_completed_cb {
+                        cb(req);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Notifies that the system has resumed from suspend.
+    ///
+    /// Camera pipelines often do not survive suspend, so this marks camera as requiring [ActiveCamera::reinitialize()].
+    /// Resume can be detected with [ResumeDetector](crate::resume::ResumeDetector) or by other means (i.e. logind
+    /// `PrepareForSleep` signal).
+    pub fn notify_resumed(&mut self) {
+        self.needs_reinitialize = true;
+    }
+
+    /// Returns `true` if [ActiveCamera::notify_resumed()] was called and camera was not reinitialized since.
+    pub fn needs_reinitialize(&self) -> bool {
+        self.needs_reinitialize
+    }
+
+    /// Checks that camera is functional by executing a single request.
+    ///
+    /// Camera must be started. The request is not passed to the request completed callback, but returned once completed
+    /// instead. Fails with [Error::Timeout] if request does not complete within `timeout`, in which case request will
+    /// be returned through the callback whenever it completes.
+    pub fn probe(&self, req: Request, timeout: Duration) -> Result<Request, Error> {
+        let (tx, rx) = mpsc::channel();
+        self.state.lock().unwrap().probe = Some((req.ptr.as_ptr(), tx));
+
+        if let Err(e) = self.queue_request(req) {
+            self.state.lock().unwrap().probe = None;
+            return Err(e);
+        }
+
+        let req = rx.recv_timeout(timeout).ok().or_else(|| {
+            // clearing the probe under the lock routes any later completion to the request completed callback, if it
+            // is gone the request completed concurrently and is already in the channel
+            if self.state.lock().unwrap().probe.take().is_some() {
+                None
+            } else {
+                rx.try_recv().ok()
+            }
+        });
+        match req {
+            Some(req) if req.status() == RequestStatus::Complete => Ok(req),
+            Some(_) => Err(Error::Cancelled),
+            None => Err(Error::Timeout),
+        }
+    }
+
+    /// Reinitializes camera after it became unusable, i.e. due to system suspend.
+    ///
+    /// Stops the camera, releases and re-acquires it and applies the given configuration. If camera was running, it is
+    /// started again with `controls`. Requests that were queued are returned through the request completed callback
+    /// and have to be queued again by the application. Buffers allocated for the previous configuration might have to
+    /// be reallocated if configuration was adjusted.
+    pub fn reinitialize(
+        &mut self,
+        config: &mut CameraConfiguration,
+        controls: Option<&ControlList>,
+    ) -> Result<(), Error> {
+        let was_running = self.running;
+        if was_running {
+            self.stop()?;
+        }
+
+        let ret = unsafe { libcamera_camera_release(self.ptr.as_ptr()) };
+        if ret < 0 {
+            return Err(Error::from_ret(ret, |errno| Error::ReleaseFailed { errno }));
+        }
+        let ret = unsafe { libcamera_camera_acquire(self.ptr.as_ptr()) };
+        if ret < 0 {
+            return Err(Error::from_ret(ret, |errno| Error::AcquireFailed { errno }));
+        }
+
+        self.configure(config)?;
+        if was_running {
+            self.start(controls)?;
+        }
+
+        self.needs_reinitialize = false;
+        Ok(())
+    }
 }
 
 impl<'d> Deref for ActiveCamera<'d> {
@@ -342,10 +1229,143 @@ impl<'d> DerefMut for ActiveCamera<'d> {
     }
 }
 
+/// Cloneable handle for queueing requests from multiple threads, see [ActiveCamera::queue_handle()].
+#[derive(Clone, Copy)]
+pub struct RequestQueueHandle<'a, 'd> {
+    cam: &'a ActiveCamera<'d>,
+}
+
+impl RequestQueueHandle<'_, '_> {
+    /// Queues request, see [ActiveCamera::queue_request()].
+    pub fn queue_request(&self, req: Request) -> Result<(), Error> {
+        self.cam.queue_request(req)
+    }
+
+    /// Queues request once a slot is free, see [ActiveCamera::queue_request_blocking()].
+    pub fn queue_request_blocking(&self, req: Request, timeout: Option<Duration>) -> Result<(), Error> {
+        self.cam.queue_request_blocking(req, timeout)
+    }
+
+    /// Waits until a request can be queued, see [ActiveCamera::wait_queue_slot()].
+    pub fn wait_queue_slot(&self, timeout: Option<Duration>) -> bool {
+        self.cam.wait_queue_slot(timeout)
+    }
+
+    /// Returns the number of requests queued and not yet completed.
+    pub fn in_flight(&self) -> usize {
+        self.cam.in_flight()
+    }
+
+    /// Returns `true` if capture session is started.
+    pub fn is_running(&self) -> bool {
+        self.cam.is_running()
+    }
+}
+
+// Queueing only reads immutable camera fields and otherwise goes through `ActiveCameraState` and `queue_lock` mutexes.
+// Callbacks invoked while queueing are `Send` and called with camera state locked.
+unsafe impl Send for RequestQueueHandle<'_, '_> {}
+unsafe impl Sync for RequestQueueHandle<'_, '_> {}
+
+/// Blocking iterator over completed requests, see [ActiveCamera::frames()].
+pub struct Frames<'a, 'd> {
+    cam: &'a ActiveCamera<'d>,
+    rx: mpsc::Receiver<Request>,
+    timeout: Option<Duration>,
+    timed_out: bool,
+    /// Request completed callback, which is restored on drop.
+    previous: Option<Box<dyn FnMut(Request) + Send + 'd>>,
+}
+
+impl<'a, 'd> Frames<'a, 'd> {
+    /// Ends iteration if no request completes within `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns `true` if iteration ended because of the timeout.
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+}
+
+impl<'a, 'd> Iterator for Frames<'a, 'd> {
+    type Item = FrameRequest<'a, 'd>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let req = match self.timeout {
+            Some(timeout) => self.rx.recv_timeout(timeout).map_err(|e| {
+                self.timed_out = e == mpsc::RecvTimeoutError::Timeout;
+            }),
+            None => self.rx.recv().map_err(|_| ()),
+        }
+        .ok()?;
+
+        Some(FrameRequest {
+            cam: self.cam,
+            req: Some(req),
+        })
+    }
+}
+
+impl<'a, 'd> Drop for Frames<'a, 'd> {
+    fn drop(&mut self) {
+        let mut state = self.cam.state.lock().unwrap();
+        state.request_completed_cb = self.previous.take();
+        // Hand requests completed in the meantime over to the restored callback
+        if let Some(cb) = state.request_completed_cb.as_mut() {
+            while let Ok(req) = self.rx.try_recv() {
+                cb(req);
+            }
+        }
+    }
+}
+
+/// A completed request yielded by [Frames], which is queued again when dropped.
+pub struct FrameRequest<'a, 'd> {
+    cam: &'a ActiveCamera<'d>,
+    req: Option<Request>,
+}
+
+impl<'a, 'd> FrameRequest<'a, 'd> {
+    /// Takes the request out, so that it is not queued again.
+    pub fn into_request(mut self) -> Request {
+        self.req.take().unwrap()
+    }
+}
+
+impl<'a, 'd> Deref for FrameRequest<'a, 'd> {
+    type Target = Request;
+
+    fn deref(&self) -> &Self::Target {
+        self.req.as_ref().unwrap()
+    }
+}
+
+impl<'a, 'd> DerefMut for FrameRequest<'a, 'd> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.req.as_mut().unwrap()
+    }
+}
+
+impl<'a, 'd> Drop for FrameRequest<'a, 'd> {
+    fn drop(&mut self) {
+        if let Some(mut req) = self.req.take() {
+            if self.cam.is_running() {
+                req.reuse(ReuseFlag::REUSE_BUFFERS);
+                // Request is dropped if it can not be queued, there is no one to report the error to
+                let _ = self.cam.queue_request(req);
+            }
+        }
+    }
+}
+
 impl<'d> Drop for ActiveCamera<'d> {
     fn drop(&mut self) {
+        self.request_completed.disconnect();
+        self.buffer_completed.disconnect();
         unsafe {
-            libcamera_camera_request_completed_disconnect(self.ptr.as_ptr(), self.request_completed_handle);
             libcamera_camera_stop(self.ptr.as_ptr());
             libcamera_camera_release(self.ptr.as_ptr());
         }
@@ -1,13 +1,30 @@
 use std::{
-    ffi::{CStr, CString},
-    io,
+    ffi::{c_void, CStr, CString},
     marker::PhantomData,
     ptr::NonNull,
+    sync::mpsc,
+    time::Duration,
 };
 
 use libcamera_sys::*;
+use thiserror::Error;
 
-use crate::{camera::Camera, logging::LoggingLevel, utils::handle_result};
+use crate::{
+    camera::{ActiveCamera, Camera},
+    device_access::{self, DeviceAccessError},
+    error::Error as CameraError,
+    logging::{parse_log_levels, LogLevelParseError, LoggingLevel},
+    request::Request,
+    signal::SignalSubscription,
+};
+
+#[derive(Debug, Error)]
+pub enum CameraLookupError {
+    #[error("Camera {0:?} not found")]
+    NotFound(String),
+    #[error("Camera index {index} is out of range, {len} cameras are available")]
+    IndexOutOfRange { index: usize, len: usize },
+}
 
 /// Camera manager used to enumerate available cameras in the system.
 pub struct CameraManager {
@@ -16,10 +33,20 @@ pub struct CameraManager {
 
 impl CameraManager {
     /// Initializes `libcamera` and creates [Self].
-    pub fn new() -> io::Result<Self> {
+    ///
+    /// With the `dlopen` feature libcamera is loaded first, failing with [CameraError::LibraryNotFound] if it is not
+    /// installed.
+    pub fn new() -> Result<Self, CameraError> {
+        #[cfg(feature = "dlopen")]
+        libcamera_sys::load().map_err(|e| CameraError::LibraryNotFound(e.to_string()))?;
+
         let ptr = NonNull::new(unsafe { libcamera_camera_manager_create() }).unwrap();
         let ret = unsafe { libcamera_camera_manager_start(ptr.as_ptr()) };
-        handle_result(ret)?;
+        if ret < 0 {
+            return Err(CameraError::from_ret(ret, |errno| CameraError::ManagerStartFailed {
+                errno,
+            }));
+        }
         Ok(CameraManager { ptr })
     }
 
@@ -35,6 +62,84 @@ impl CameraManager {
         unsafe { CameraList::from_ptr(NonNull::new(libcamera_camera_manager_cameras(self.ptr.as_ptr())).unwrap()) }
     }
 
+    /// Enumerates cameras within the system, returning the reason if none are accessible.
+    ///
+    /// An empty list is returned only if no device access problem is detected, see [device_access::diagnose()].
+    pub fn cameras_checked(&self) -> Result<CameraList<'_>, DeviceAccessError> {
+        let cameras = self.cameras();
+        if cameras.is_empty() {
+            if let Some(err) = device_access::diagnose() {
+                return Err(err);
+            }
+        }
+        Ok(cameras)
+    }
+
+    /// Looks up a camera by its [Camera::id()], i.e. one persisted in application settings.
+    pub fn get_by_id(&self, id: &str) -> Result<Camera<'_>, CameraLookupError> {
+        let not_found = || CameraLookupError::NotFound(id.to_string());
+        let c_id = CString::new(id).map_err(|_| not_found())?;
+        let ptr = unsafe { libcamera_camera_manager_get_id(self.ptr.as_ptr(), c_id.as_ptr()) };
+        NonNull::new(ptr)
+            .map(|p| unsafe { Camera::from_ptr(p) })
+            .ok_or_else(not_found)
+    }
+
+    /// Returns camera at a given index of [CameraManager::cameras()].
+    pub fn get_by_index(&self, index: usize) -> Result<Camera<'_>, CameraLookupError> {
+        let cameras = self.cameras();
+        let ptr = unsafe { libcamera_camera_list_get(cameras.ptr.as_ptr(), index as _) };
+        NonNull::new(ptr)
+            .map(|p| unsafe { Camera::from_ptr(p) })
+            .ok_or(CameraLookupError::IndexOutOfRange {
+                index,
+                len: cameras.len(),
+            })
+    }
+
+    /// Creates a single receiver of completed requests, multiplexed across all cameras attached to it with
+    /// [Completions::attach()].
+    pub fn completions(&self) -> Completions {
+        let (tx, rx) = mpsc::channel();
+        Completions { tx, rx }
+    }
+
+    /// Subscribes to cameras added to the system, i.e. plugged in USB cameras.
+    ///
+    /// Callback is executed in the libcamera thread context. The camera is only borrowed for the duration of the
+    /// callback, use [Camera::id()] to find it in [CameraManager::cameras()] afterwards.
+    pub fn on_camera_added<'a>(
+        &'a self,
+        cb: impl FnMut(Camera<'_>) + Send + 'a,
+    ) -> SignalSubscription<'a, HotplugCb<'a>> {
+        let mgr = self.ptr;
+        unsafe {
+            SignalSubscription::connect(
+                Box::new(cb),
+                |data| libcamera_camera_manager_camera_added_connect(mgr.as_ptr(), Some(camera_hotplug_cb), data),
+                move |handle| libcamera_camera_manager_camera_added_disconnect(mgr.as_ptr(), handle.as_ptr()),
+            )
+        }
+    }
+
+    /// Subscribes to cameras removed from the system, i.e. unplugged USB cameras.
+    ///
+    /// Callback is executed in the libcamera thread context. Active instances of the camera also receive
+    /// [Camera::on_disconnected()].
+    pub fn on_camera_removed<'a>(
+        &'a self,
+        cb: impl FnMut(Camera<'_>) + Send + 'a,
+    ) -> SignalSubscription<'a, HotplugCb<'a>> {
+        let mgr = self.ptr;
+        unsafe {
+            SignalSubscription::connect(
+                Box::new(cb),
+                |data| libcamera_camera_manager_camera_removed_connect(mgr.as_ptr(), Some(camera_hotplug_cb), data),
+                move |handle| libcamera_camera_manager_camera_removed_disconnect(mgr.as_ptr(), handle.as_ptr()),
+            )
+        }
+    }
+
     /// Set the log level.
     ///
     /// # Parameters
@@ -49,6 +154,87 @@ impl CameraManager {
             libcamera_log_set_level(category.as_ptr(), level.as_ptr());
         }
     }
+
+    /// Sets log levels of many categories at once, i.e. from a `HashMap<String, LoggingLevel>`.
+    ///
+    /// See [CameraManager::log_set_level()] for details.
+    pub fn log_set_levels<S: AsRef<str>>(&self, levels: impl IntoIterator<Item = (S, LoggingLevel)>) {
+        for (category, level) in levels {
+            self.log_set_level(category.as_ref(), level);
+        }
+    }
+
+    /// Sets log levels from a `LIBCAMERA_LOG_LEVELS` style string, i.e. `Camera:DEBUG,RPI:WARN`.
+    ///
+    /// Nothing is changed if any of the entries is invalid, see [parse_log_levels()].
+    pub fn log_set_levels_str(&self, spec: &str) -> Result<(), LogLevelParseError> {
+        self.log_set_levels(parse_log_levels(spec)?);
+        Ok(())
+    }
+}
+
+/// A completed request together with the camera it was queued on.
+#[derive(Debug)]
+pub struct CompletedRequest {
+    /// [Camera::id()] of the camera which completed the request.
+    pub camera_id: String,
+    pub request: Request,
+}
+
+/// Completed requests of multiple cameras, obtained by [CameraManager::completions()].
+///
+/// Allows multi-camera applications to process all cameras from one consumer loop instead of a callback and a channel
+/// per camera.
+pub struct Completions {
+    tx: mpsc::Sender<CompletedRequest>,
+    rx: mpsc::Receiver<CompletedRequest>,
+}
+
+impl Completions {
+    /// Routes completed requests of the camera into this receiver.
+    ///
+    /// Replaces the callback set by [ActiveCamera::on_request_completed()], and is itself replaced by setting a new
+    /// one. Requests of a camera released while still in the receiver can be dropped, but not queued again.
+    pub fn attach(&self, cam: &mut ActiveCamera<'_>) {
+        let camera_id = cam.id().to_string();
+        let tx = self.tx.clone();
+        cam.on_request_completed(move |request| {
+            let _ = tx.send(CompletedRequest {
+                camera_id: camera_id.clone(),
+                request,
+            });
+        });
+    }
+
+    /// Blocks until a request of any attached camera completes.
+    pub fn recv(&self) -> CompletedRequest {
+        // Sender is owned by self, so the channel is never disconnected
+        self.rx.recv().unwrap()
+    }
+
+    /// Blocks until a request completes, returning [None] if none did within `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<CompletedRequest> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+
+    /// Returns a completed request if one is available, without blocking.
+    pub fn try_recv(&self) -> Option<CompletedRequest> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Returns an iterator over completed requests, which blocks waiting for each of them.
+    pub fn iter(&self) -> impl Iterator<Item = CompletedRequest> + '_ {
+        self.rx.iter()
+    }
+}
+
+/// Callback of [CameraManager::on_camera_added()] and [CameraManager::on_camera_removed()].
+pub type HotplugCb<'d> = dyn for<'c> FnMut(Camera<'c>) + Send + 'd;
+
+extern "C" fn camera_hotplug_cb(data: *mut c_void, cam: *mut libcamera_camera_t) {
+    let cam = unsafe { Camera::from_ptr(NonNull::new(cam).unwrap()) };
+    let cb = unsafe { SignalSubscription::<HotplugCb<'_>>::callback(data) };
+    cb(cam);
 }
 
 impl Drop for CameraManager {
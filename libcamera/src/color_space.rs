@@ -0,0 +1,150 @@
+use libcamera_sys::*;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+use crate::{
+    pixel_format::{self, formats, ColourEncoding, PixelFormat},
+    stream::StreamRole,
+};
+
+/// Color primaries of a [ColorSpace].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum Primaries {
+    /// Unprocessed sensor colors, used for raw streams.
+    Raw = libcamera_color_space_primaries::LIBCAMERA_COLOR_SPACE_PRIMARIES_RAW,
+    Smpte170m = libcamera_color_space_primaries::LIBCAMERA_COLOR_SPACE_PRIMARIES_SMPTE170M,
+    Rec709 = libcamera_color_space_primaries::LIBCAMERA_COLOR_SPACE_PRIMARIES_REC709,
+    Rec2020 = libcamera_color_space_primaries::LIBCAMERA_COLOR_SPACE_PRIMARIES_REC2020,
+}
+
+/// Transfer function of a [ColorSpace].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum TransferFunction {
+    Linear = libcamera_color_space_transfer_function::LIBCAMERA_COLOR_SPACE_TRANSFER_FUNCTION_LINEAR,
+    Srgb = libcamera_color_space_transfer_function::LIBCAMERA_COLOR_SPACE_TRANSFER_FUNCTION_SRGB,
+    Rec709 = libcamera_color_space_transfer_function::LIBCAMERA_COLOR_SPACE_TRANSFER_FUNCTION_REC709,
+}
+
+/// Y'CbCr encoding of a [ColorSpace].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum YcbcrEncoding {
+    /// No encoding, used for RGB and raw formats.
+    None = libcamera_color_space_ycbcr_encoding::LIBCAMERA_COLOR_SPACE_YCBCR_ENCODING_NONE,
+    Rec601 = libcamera_color_space_ycbcr_encoding::LIBCAMERA_COLOR_SPACE_YCBCR_ENCODING_REC601,
+    Rec709 = libcamera_color_space_ycbcr_encoding::LIBCAMERA_COLOR_SPACE_YCBCR_ENCODING_REC709,
+    Rec2020 = libcamera_color_space_ycbcr_encoding::LIBCAMERA_COLOR_SPACE_YCBCR_ENCODING_REC2020,
+}
+
+/// Quantization range of a [ColorSpace].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum Range {
+    Full = libcamera_color_space_range::LIBCAMERA_COLOR_SPACE_RANGE_FULL,
+    Limited = libcamera_color_space_range::LIBCAMERA_COLOR_SPACE_RANGE_LIMITED,
+}
+
+/// Color space of a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorSpace {
+    pub primaries: Primaries,
+    pub transfer_function: TransferFunction,
+    pub ycbcr_encoding: YcbcrEncoding,
+    pub range: Range,
+}
+
+impl ColorSpace {
+    pub const RAW: Self = Self::new(
+        Primaries::Raw,
+        TransferFunction::Linear,
+        YcbcrEncoding::None,
+        Range::Full,
+    );
+    pub const SRGB: Self = Self::new(
+        Primaries::Rec709,
+        TransferFunction::Srgb,
+        YcbcrEncoding::None,
+        Range::Full,
+    );
+    /// sRGB primaries and transfer function with full range Rec.601 encoding, as used by JPEG.
+    pub const SYCC: Self = Self::new(
+        Primaries::Rec709,
+        TransferFunction::Srgb,
+        YcbcrEncoding::Rec601,
+        Range::Full,
+    );
+    pub const SMPTE170M: Self = Self::new(
+        Primaries::Smpte170m,
+        TransferFunction::Rec709,
+        YcbcrEncoding::Rec601,
+        Range::Limited,
+    );
+    pub const REC709: Self = Self::new(
+        Primaries::Rec709,
+        TransferFunction::Rec709,
+        YcbcrEncoding::Rec709,
+        Range::Limited,
+    );
+    pub const REC2020: Self = Self::new(
+        Primaries::Rec2020,
+        TransferFunction::Rec709,
+        YcbcrEncoding::Rec2020,
+        Range::Limited,
+    );
+
+    pub const fn new(
+        primaries: Primaries,
+        transfer_function: TransferFunction,
+        ycbcr_encoding: YcbcrEncoding,
+        range: Range,
+    ) -> Self {
+        Self {
+            primaries,
+            transfer_function,
+            ycbcr_encoding,
+            range,
+        }
+    }
+
+    /// Returns the color space pipelines typically choose for a stream when none is requested, i.e. to tag encoded
+    /// output when the pipeline does not report one.
+    ///
+    /// Raw streams use [ColorSpace::RAW] and RGB formats [ColorSpace::SRGB]. YUV formats and `MJPEG` use
+    /// [ColorSpace::SYCC] for stills and viewfinders and [ColorSpace::REC709] for video recording. Formats unknown to
+    /// [pixel_format::info()] are treated as YUV.
+    pub fn suggested_for(format: PixelFormat, role: StreamRole) -> Self {
+        let encoding = pixel_format::info(format).map(|info| info.colour_encoding);
+        match (role, encoding) {
+            (StreamRole::Raw, _) | (_, Some(ColourEncoding::Raw)) => Self::RAW,
+            (_, Some(ColourEncoding::Rgb)) => Self::SRGB,
+            _ if format == formats::MJPEG => Self::SYCC,
+            (StreamRole::VideoRecording, _) => Self::REC709,
+            (StreamRole::StillCapture | StreamRole::ViewFinder, _) => Self::SYCC,
+        }
+    }
+}
+
+impl TryFrom<libcamera_color_space_t> for ColorSpace {
+    type Error = String;
+
+    fn try_from(value: libcamera_color_space_t) -> Result<Self, Self::Error> {
+        Ok(Self {
+            primaries: Primaries::try_from(value.primaries).map_err(|e| e.to_string())?,
+            transfer_function: TransferFunction::try_from(value.transfer_function).map_err(|e| e.to_string())?,
+            ycbcr_encoding: YcbcrEncoding::try_from(value.ycbcr_encoding).map_err(|e| e.to_string())?,
+            range: Range::try_from(value.range).map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+impl From<ColorSpace> for libcamera_color_space_t {
+    fn from(value: ColorSpace) -> Self {
+        Self {
+            primaries: value.primaries.into(),
+            transfer_function: value.transfer_function.into(),
+            ycbcr_encoding: value.ycbcr_encoding.into(),
+            range: value.range.into(),
+        }
+    }
+}
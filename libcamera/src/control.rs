@@ -4,7 +4,7 @@ use libcamera_sys::*;
 use thiserror::Error;
 
 use crate::{
-    control_value::{ControlValue, ControlValueError},
+    control_value::{ControlValue, ControlValueError, ControlValueRef},
     controls::{self, ControlId},
     properties::{self, PropertyId},
     utils::{UniquePtr, UniquePtrTarget},
@@ -16,6 +16,10 @@ pub enum ControlError {
     NotFound(u32),
     #[error("Control value error: {0}")]
     ValueError(#[from] ControlValueError),
+    #[error("Control {0} is not available in this libcamera version")]
+    Unavailable(&'static str),
+    #[error("Control id {id} value can not be read: {error}")]
+    Unreadable { id: u32, error: ControlValueError },
 }
 
 pub trait ControlEntry:
@@ -24,7 +28,71 @@ pub trait ControlEntry:
     const ID: u32;
 }
 
-pub trait Control: ControlEntry {}
+/// Direction in which a control is passed between application and camera.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControlDirection {
+    /// Control is only set on requests.
+    In,
+    /// Control is only reported in request metadata, i.e. `Lux`.
+    Out,
+    /// Control is set on requests and reported in metadata.
+    InOut,
+}
+
+impl ControlDirection {
+    /// Returns `true` if the control can be set on requests.
+    pub fn is_input(&self) -> bool {
+        matches!(self, Self::In | Self::InOut)
+    }
+
+    /// Returns `true` if the control is reported in metadata.
+    pub fn is_output(&self) -> bool {
+        matches!(self, Self::Out | Self::InOut)
+    }
+}
+
+/// Description of a control or property as data, see [controls::descriptor_table()] and
+/// [properties::descriptor_table()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlDescriptor {
+    pub id: u32,
+    /// Name as used in libcamera YAML definitions, i.e. `ExposureTime`.
+    pub name: &'static str,
+    /// Vendor namespace, `libcamera` for core controls.
+    pub vendor: &'static str,
+    /// libcamera control type id, as returned by [ControlValue::ty()].
+    pub ty: u32,
+    /// Type name as used in libcamera YAML definitions, i.e. `int32_t` or `Rectangle`.
+    pub type_name: &'static str,
+    /// Direction of the control, always [ControlDirection::InOut] for properties.
+    pub direction: ControlDirection,
+    /// Array dimensions, empty for scalars. [None] marks a dimension of variable length.
+    pub size: &'static [Option<usize>],
+    /// Variants of enumerated controls, empty otherwise.
+    pub enumeration: &'static [EnumValueDescriptor],
+    /// First paragraph of the documentation.
+    pub summary: &'static str,
+}
+
+impl ControlDescriptor {
+    pub fn is_array(&self) -> bool {
+        !self.size.is_empty()
+    }
+}
+
+/// Variant of an enumerated control within [ControlDescriptor].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnumValueDescriptor {
+    pub name: &'static str,
+    pub value: i32,
+    pub summary: &'static str,
+}
+
+pub trait Control: ControlEntry {
+    /// Direction of the control as declared by libcamera. Versions that do not declare directions mark controls
+    /// documented as metadata-only as [ControlDirection::Out], all others are bidirectional.
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 pub trait Property: ControlEntry {}
 
 /// Dynamic Control, which does not have strong typing.
@@ -43,6 +111,143 @@ impl<T: ControlEntry> DynControlEntry for T {
     }
 }
 
+/// Reason why a control value was rejected by [ControlInfoMap::validate()].
+#[derive(Debug, Error)]
+pub enum ControlValidationError {
+    #[error("Control id {0} is not supported by the camera")]
+    Unsupported(u32),
+    #[error("Control id {0} is only reported in metadata and has no effect on requests")]
+    OutputOnly(u32),
+    #[error("Control id {id} expects type {expected}, found {found}")]
+    InvalidType { id: u32, expected: u32, found: u32 },
+    #[error("Control id {id} value {value:?} is out of range [{min:?}, {max:?}]")]
+    OutOfRange {
+        id: u32,
+        value: ControlValue,
+        min: ControlValue,
+        max: ControlValue,
+    },
+    #[error("Control id {id} value {value:?} is not one of {allowed:?}")]
+    InvalidValue {
+        id: u32,
+        value: ControlValue,
+        allowed: Vec<ControlValue>,
+    },
+    #[error("Control id {id} value can not be read: {error}")]
+    Unreadable { id: u32, error: ControlValueError },
+}
+
+/// A control value that was adjusted by [ControlInfoMap::clamp()] to fit the limits of the camera.
+#[derive(Debug, Clone)]
+pub struct ClampedControl {
+    pub id: u32,
+    /// Value that was originally set.
+    pub requested: ControlValue,
+    /// Value after clamping.
+    pub applied: ControlValue,
+}
+
+/// Describes limits of a control supported by the camera.
+#[repr(transparent)]
+pub struct ControlInfo(libcamera_control_info_t);
+
+impl ControlInfo {
+    pub(crate) unsafe fn from_ptr<'a>(ptr: NonNull<libcamera_control_info_t>) -> &'a Self {
+        // Safety: we can cast it because of `#[repr(transparent)]`
+        &*(ptr.as_ptr() as *const Self)
+    }
+
+    pub(crate) fn ptr(&self) -> *const libcamera_control_info_t {
+        // Safety: we can cast it because of `#[repr(transparent)]`
+        &self.0 as *const libcamera_control_info_t
+    }
+
+    /// Minimum value of the control, [ControlValue::None] if unbounded.
+    pub fn min(&self) -> ControlValue {
+        unsafe { Self::read(libcamera_control_info_min(self.ptr())) }
+    }
+
+    /// Maximum value of the control, [ControlValue::None] if unbounded.
+    pub fn max(&self) -> ControlValue {
+        unsafe { Self::read(libcamera_control_info_max(self.ptr())) }
+    }
+
+    /// Default value of the control, [ControlValue::None] if not provided.
+    pub fn def(&self) -> ControlValue {
+        unsafe { Self::read(libcamera_control_info_def(self.ptr())) }
+    }
+
+    /// List of valid values for enumerated controls, empty otherwise.
+    pub fn values(&self) -> Vec<ControlValue> {
+        let len = unsafe { libcamera_control_info_values_size(self.ptr()) };
+        (0..len)
+            .map(|i| unsafe { Self::read(libcamera_control_info_values_at(self.ptr(), i)) })
+            .collect()
+    }
+
+    unsafe fn read(val: *const libcamera_control_value_t) -> ControlValue {
+        ControlValue::read(NonNull::new(val.cast_mut()).unwrap()).unwrap_or(ControlValue::None)
+    }
+
+    /// Clamps numeric value to the min/max limits of the control.
+    ///
+    /// Returns [None] if value is already within the limits, if it is not numeric or if the control has enumerated
+    /// values (see [ControlInfo::values()]), as the nearest limit is not necessarily a valid variant.
+    pub fn clamp(&self, value: &ControlValue) -> Option<ControlValue> {
+        if unsafe { libcamera_control_info_values_size(self.ptr()) } > 0 {
+            return None;
+        }
+        value.clamp(&self.min(), &self.max())
+    }
+
+    /// Checks given value against the limits of the control.
+    pub fn validate(&self, id: u32, value: &ControlValue) -> Result<(), ControlValidationError> {
+        let (min, max) = (self.min(), self.max());
+
+        let expected = if min != ControlValue::None { min.ty() } else { max.ty() };
+        if expected != ControlValue::None.ty() && expected != value.ty() {
+            return Err(ControlValidationError::InvalidType {
+                id,
+                expected,
+                found: value.ty(),
+            });
+        }
+
+        let allowed = self.values();
+        if !allowed.is_empty() && !value.elements().iter().all(|v| allowed.contains(v)) {
+            return Err(ControlValidationError::InvalidValue {
+                id,
+                value: value.clone(),
+                allowed,
+            });
+        }
+
+        let below = |v: &ControlValue| v.partial_cmp_element(&min) == Some(core::cmp::Ordering::Less);
+        let above = |v: &ControlValue| v.partial_cmp_element(&max) == Some(core::cmp::Ordering::Greater);
+        if value.elements().iter().any(|v| below(v) || above(v)) {
+            return Err(ControlValidationError::OutOfRange {
+                id,
+                value: value.clone(),
+                min,
+                max,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for ControlInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ControlInfo")
+            .field("min", &self.min())
+            .field("max", &self.max())
+            .field("def", &self.def())
+            .field("values", &self.values())
+            .finish()
+    }
+}
+
 #[repr(transparent)]
 pub struct ControlInfoMap(libcamera_control_info_map_t);
 
@@ -51,6 +256,109 @@ impl ControlInfoMap {
         // Safety: we can cast it because of `#[repr(transparent)]`
         &mut *(ptr.as_ptr() as *mut Self)
     }
+
+    pub(crate) fn ptr(&self) -> *const libcamera_control_info_map_t {
+        // Safety: we can cast it because of `#[repr(transparent)]`
+        &self.0 as *const libcamera_control_info_map_t
+    }
+
+    /// Returns limits of a control by numeric id, or [None] if control is not supported.
+    pub fn get(&self, id: u32) -> Option<&ControlInfo> {
+        NonNull::new(unsafe { libcamera_control_info_map_at(self.ptr(), id as _) }.cast_mut())
+            .map(|p| unsafe { ControlInfo::from_ptr(p) })
+    }
+
+    /// Returns `true` if control is supported.
+    pub fn contains(&self, id: u32) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Number of supported controls.
+    pub fn len(&self) -> usize {
+        unsafe { libcamera_control_info_map_size(self.ptr()) }
+    }
+
+    /// Returns `true` if no controls are supported.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Clamps numeric values of all supported controls within the list to their limits.
+    ///
+    /// Returns list of adjusted controls. Unsupported controls and enumerated controls, either declared as such by
+    /// libcamera or reporting a list of valid values, are left unchanged, use [ControlInfoMap::validate()] to detect
+    /// those.
+    pub fn clamp(&self, list: &mut ControlList) -> Result<Vec<ClampedControl>, ControlError> {
+        let mut clamped = Vec::new();
+        for (id, requested) in list.into_iter().filter(|(id, _)| !is_enumerated(*id)) {
+            let requested = requested.map_err(|error| ControlError::Unreadable { id, error })?;
+            if let Some(applied) = self.get(id).and_then(|info| info.clamp(&requested)) {
+                clamped.push(ClampedControl { id, requested, applied });
+            }
+        }
+
+        for c in clamped.iter() {
+            list.set_raw(c.id, c.applied.clone())?;
+        }
+
+        Ok(clamped)
+    }
+
+    /// Returns a lightweight summary of supported controls, see [ControlSummary].
+    pub fn summary(&self) -> ControlSummary<'_> {
+        ControlSummary::new(SummarySource::Info(self))
+    }
+
+    /// Checks every entry of the list against supported controls and their limits.
+    ///
+    /// Output-only controls (see [ControlId::direction()]) are reported as well, as libcamera ignores them on
+    /// requests. Returns all detected issues, an empty list means that all controls are valid.
+    pub fn validate(&self, list: &ControlList) -> Vec<ControlValidationError> {
+        list.into_iter()
+            .filter_map(|(id, val)| match (self.get(id), val) {
+                _ if ControlId::try_from(id).is_ok_and(|c| c.direction() == ControlDirection::Out) => {
+                    Some(ControlValidationError::OutputOnly(id))
+                }
+                (None, _) => Some(ControlValidationError::Unsupported(id)),
+                (Some(_), Err(error)) => Some(ControlValidationError::Unreadable { id, error }),
+                (Some(info), Ok(val)) => info.validate(id, &val).err(),
+            })
+            .collect()
+    }
+}
+
+/// Returns `true` if the control is declared with enumerated values by libcamera.
+fn is_enumerated(id: u32) -> bool {
+    controls::descriptor_table()
+        .iter()
+        .any(|d| d.id == id && !d.enumeration.is_empty())
+}
+
+impl<'d> IntoIterator for &'d ControlInfoMap {
+    type Item = (u32, &'d ControlInfo);
+
+    type IntoIter = ControlInfoMapIterator<'d>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ControlInfoMapIterator {
+            it: NonNull::new(unsafe { libcamera_control_info_map_iter(self.ptr()) }).unwrap(),
+            _phantom: Default::default(),
+        }
+    }
+}
+
+impl core::fmt::Debug for ControlInfoMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut map = f.debug_map();
+        for (id, info) in self.into_iter() {
+            match ControlId::try_from(id) {
+                Ok(id) => map.entry(&id, info),
+                // If ControlId is unknown just use u32 as key
+                Err(_) => map.entry(&id, info),
+            };
+        }
+        map.finish()
+    }
 }
 
 #[repr(transparent)]
@@ -82,11 +390,7 @@ impl ControlList {
     }
 
     pub fn get<C: Control>(&self) -> Result<C, ControlError> {
-        let val_ptr = NonNull::new(unsafe { libcamera_control_list_get(self.ptr().cast_mut(), C::ID as _).cast_mut() })
-            .ok_or(ControlError::NotFound(C::ID))?;
-
-        let val = unsafe { ControlValue::read(val_ptr) }?;
-        Ok(C::try_from(val)?)
+        Ok(C::try_from(self.get_raw(C::ID)?)?)
     }
 
     /// Sets control value.
@@ -94,21 +398,112 @@ impl ControlList {
     /// This can fail if control is not supported by the camera, but due to libcamera API limitations an error will not
     /// be returned. Use [ControlList::get] if you need to ensure that value was set.
     pub fn set<C: Control>(&mut self, val: C) -> Result<(), ControlError> {
-        let ctrl_val: ControlValue = val.into();
+        self.set_raw(C::ID, val.into())
+    }
+
+    /// Returns untyped control value by numeric id.
+    ///
+    /// Useful for controls that are only known at runtime, see [ControlList::get] for a typed variant.
+    pub fn get_raw(&self, id: u32) -> Result<ControlValue, ControlError> {
+        let val_ptr = NonNull::new(unsafe { libcamera_control_list_get(self.ptr().cast_mut(), id as _).cast_mut() })
+            .ok_or(ControlError::NotFound(id))?;
 
+        Ok(unsafe { ControlValue::read(val_ptr) }?)
+    }
+
+    /// Borrows control value by numeric id without copying its data, see [ControlValueRef].
+    ///
+    /// Prefer this over [ControlList::get_raw()] for large array controls read on every frame.
+    pub fn get_ref(&self, id: u32) -> Result<ControlValueRef<'_>, ControlError> {
+        let val_ptr = NonNull::new(unsafe { libcamera_control_list_get(self.ptr().cast_mut(), id as _).cast_mut() })
+            .ok_or(ControlError::NotFound(id))?;
+
+        Ok(unsafe { ControlValueRef::read(val_ptr) }?)
+    }
+
+    /// Borrows data of a byte array control without copying it, i.e. vendor statistics such as
+    /// `Bcm2835StatsOutput` which are hundreds of KB per frame.
+    ///
+    /// Returns [ControlValueError::InvalidType] if the control is not a byte array.
+    pub fn get_bytes<C: Control>(&self) -> Result<&[u8], ControlError> {
+        let val = self.get_ref(C::ID)?;
+        val.as_bytes().ok_or_else(|| {
+            ControlError::ValueError(ControlValueError::InvalidType {
+                expected: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_BYTE,
+                found: val.ty(),
+            })
+        })
+    }
+
+    /// Sets untyped control value by numeric id.
+    ///
+    /// Same limitations as in [ControlList::set] apply.
+    pub fn set_raw(&mut self, id: u32, val: ControlValue) -> Result<(), ControlError> {
         unsafe {
             let val_ptr = NonNull::new(libcamera_control_value_create()).unwrap();
-            ctrl_val.write(val_ptr);
-            libcamera_control_list_set(self.ptr().cast_mut(), C::ID as _, val_ptr.as_ptr());
+            val.write(val_ptr);
+            libcamera_control_list_set(self.ptr().cast_mut(), id as _, val_ptr.as_ptr());
             libcamera_control_value_destroy(val_ptr.as_ptr());
         }
 
         Ok(())
     }
+
+    /// Returns `true` if list contains a value for the given control.
+    pub fn contains<C: Control>(&self) -> bool {
+        unsafe { libcamera_control_list_contains(self.ptr(), C::ID as _) }
+    }
+
+    /// Removes control value from the list.
+    ///
+    /// Returns `true` if control was present in the list.
+    pub fn remove<C: Control>(&mut self) -> bool {
+        unsafe { libcamera_control_list_remove(self.ptr().cast_mut(), C::ID as _) }
+    }
+
+    /// Number of controls within the list.
+    pub fn len(&self) -> usize {
+        unsafe { libcamera_control_list_size(self.ptr()) }
+    }
+
+    /// Returns `true` if list contains no controls.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all controls from the list.
+    pub fn clear(&mut self) {
+        unsafe { libcamera_control_list_clear(self.ptr().cast_mut()) }
+    }
+
+    /// Returns an iterator over numeric ids of controls within the list without reading their values.
+    pub fn ids(&self) -> ControlListIdIterator<'_> {
+        ControlListIdIterator {
+            it: NonNull::new(unsafe { libcamera_control_list_iter(self.ptr().cast_mut()) }).unwrap(),
+            _phantom: Default::default(),
+        }
+    }
+
+    /// Returns an iterator over `(id, value)` entries of the list, which borrows values instead of copying them, see
+    /// [ControlValueRef].
+    ///
+    /// Unlike iterating `&ControlList`, this does not allocate per value, which makes it suitable for scanning or
+    /// logging metadata of every frame.
+    pub fn entries(&self) -> ControlListValueRefIterator<'_> {
+        ControlListValueRefIterator {
+            it: NonNull::new(unsafe { libcamera_control_list_iter(self.ptr().cast_mut()) }).unwrap(),
+            _phantom: Default::default(),
+        }
+    }
+
+    /// Returns a lightweight summary of the list, see [ControlSummary].
+    pub fn summary(&self) -> ControlSummary<'_> {
+        ControlSummary::new(SummarySource::Controls(self))
+    }
 }
 
 impl<'d> IntoIterator for &'d ControlList {
-    type Item = (u32, ControlValue);
+    type Item = (u32, Result<ControlValue, ControlValueError>);
 
     type IntoIter = ControlListRefIterator<'d>;
 
@@ -120,10 +515,32 @@ impl<'d> IntoIterator for &'d ControlList {
     }
 }
 
+/// Placeholder printed by `Debug` of lists in place of values that can not be read.
+struct Unreadable<'a>(&'a ControlValueError);
+
+impl core::fmt::Debug for Unreadable<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            ControlValueError::UnknownType(ty) => write!(f, "<unreadable: type {ty}>"),
+            e => write!(f, "<unreadable: {e}>"),
+        }
+    }
+}
+
 impl core::fmt::Debug for ControlList {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut map = f.debug_map();
         for (id, val) in self.into_iter() {
+            let val = match val {
+                Ok(val) => val,
+                Err(e) => {
+                    match ControlId::try_from(id) {
+                        Ok(id) => map.entry(&id, &Unreadable(&e)),
+                        Err(_) => map.entry(&id, &Unreadable(&e)),
+                    };
+                    continue;
+                }
+            };
             match ControlId::try_from(id) {
                 // Try to parse dynamic control, if not successful, just display the raw ControlValue
                 Ok(id) => match controls::make_dyn(id, val.clone()) {
@@ -176,10 +593,34 @@ impl PropertyList {
 
         Ok(())
     }
+
+    /// Returns an iterator over numeric ids of properties within the list without reading their values.
+    pub fn ids(&self) -> ControlListIdIterator<'_> {
+        ControlListIdIterator {
+            it: NonNull::new(unsafe { libcamera_control_list_iter(self.ptr().cast_mut()) }).unwrap(),
+            _phantom: Default::default(),
+        }
+    }
+
+    /// Returns an iterator over `(id, value)` entries of the list, which borrows values instead of copying them, see
+    /// [ControlValueRef].
+    ///
+    /// Unlike iterating `&PropertyList`, this does not allocate per value.
+    pub fn entries(&self) -> ControlListValueRefIterator<'_> {
+        ControlListValueRefIterator {
+            it: NonNull::new(unsafe { libcamera_control_list_iter(self.ptr().cast_mut()) }).unwrap(),
+            _phantom: Default::default(),
+        }
+    }
+
+    /// Returns a lightweight summary of the list, see [ControlSummary].
+    pub fn summary(&self) -> ControlSummary<'_> {
+        ControlSummary::new(SummarySource::Properties(self))
+    }
 }
 
 impl<'d> IntoIterator for &'d PropertyList {
-    type Item = (u32, ControlValue);
+    type Item = (u32, Result<ControlValue, ControlValueError>);
 
     type IntoIter = ControlListRefIterator<'d>;
 
@@ -195,6 +636,16 @@ impl core::fmt::Debug for PropertyList {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut map = f.debug_map();
         for (id, val) in self.into_iter() {
+            let val = match val {
+                Ok(val) => val,
+                Err(e) => {
+                    match PropertyId::try_from(id) {
+                        Ok(id) => map.entry(&id, &Unreadable(&e)),
+                        Err(_) => map.entry(&id, &Unreadable(&e)),
+                    };
+                    continue;
+                }
+            };
             match PropertyId::try_from(id) {
                 // Try to parse dynamic property, if not successful, just display the raw ControlValue
                 Ok(id) => match properties::make_dyn(id, val.clone()) {
@@ -209,32 +660,370 @@ impl core::fmt::Debug for PropertyList {
     }
 }
 
+/// Serializes list as a map of numeric control ids to [ControlValue]s.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ControlList {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_entries(self.into_iter(), serializer)
+    }
+}
+
+/// Deserializes list from a map of numeric control ids to [ControlValue]s.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for UniquePtr<ControlList> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = <std::collections::BTreeMap<u32, ControlValue>>::deserialize(deserializer)?;
+        let mut list = ControlList::new();
+        for (id, val) in entries {
+            list.set_raw(id, val).map_err(serde::de::Error::custom)?;
+        }
+        Ok(list)
+    }
+}
+
+/// Serializes list as a map of numeric property ids to [ControlValue]s.
+#[cfg(feature = "serde")]
+impl serde::Serialize for PropertyList {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_entries(self.into_iter(), serializer)
+    }
+}
+
+/// Serializes entries of a list as a map, failing on values that can not be read.
+#[cfg(feature = "serde")]
+fn serialize_entries<S: serde::Serializer>(
+    entries: ControlListRefIterator<'_>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use serde::ser::{Error, SerializeMap};
+
+    let mut map = serializer.serialize_map(None)?;
+    for (id, val) in entries {
+        let val = val.map_err(|error| S::Error::custom(ControlError::Unreadable { id, error }))?;
+        map.serialize_entry(&id, &val)?;
+    }
+    map.end()
+}
+
+/// An owned copy of a [ControlList] or [PropertyList], which can be freely shared between threads.
+///
+/// Values are read in a single pass over the FFI list and stored ordered by id, so copying cost only depends on the
+/// number of entries. Used for request metadata and request templates, see
+/// [CompletedRequest::metadata_snapshot()](crate::session::CompletedRequest::metadata_snapshot) and
+/// [CaptureSession::set_request_template()](crate::session::CaptureSession::set_request_template).
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlListSnapshot {
+    entries: Vec<(u32, ControlValue)>,
+}
+
+impl ControlListSnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn from_entries(entries: ControlListRefIterator<'_>) -> Result<Self, ControlError> {
+        let mut entries = entries
+            .map(|(id, val)| Ok((id, val.map_err(|error| ControlError::Unreadable { id, error })?)))
+            .collect::<Result<Vec<_>, ControlError>>()?;
+        entries.sort_unstable_by_key(|(id, _)| *id);
+        Ok(Self { entries })
+    }
+
+    /// Returns typed value of a control or property.
+    pub fn get<C: ControlEntry>(&self) -> Result<C, ControlError> {
+        Ok(C::try_from(self.get_raw(C::ID)?.clone())?)
+    }
+
+    /// Returns untyped value by numeric id.
+    pub fn get_raw(&self, id: u32) -> Result<&ControlValue, ControlError> {
+        self.position(id)
+            .map(|i| &self.entries[i].1)
+            .map_err(|_| ControlError::NotFound(id))
+    }
+
+    /// Sets value of a control or property, returning the previous one.
+    pub fn set<C: ControlEntry>(&mut self, val: C) -> Option<ControlValue> {
+        self.set_raw(C::ID, val.into())
+    }
+
+    /// Sets untyped value by numeric id, returning the previous one.
+    pub fn set_raw(&mut self, id: u32, val: ControlValue) -> Option<ControlValue> {
+        match self.position(id) {
+            Ok(i) => Some(core::mem::replace(&mut self.entries[i].1, val)),
+            Err(i) => {
+                self.entries.insert(i, (id, val));
+                None
+            }
+        }
+    }
+
+    /// Removes value by numeric id, returning it.
+    pub fn remove(&mut self, id: u32) -> Option<ControlValue> {
+        self.position(id).ok().map(|i| self.entries.remove(i).1)
+    }
+
+    pub fn contains(&self, id: u32) -> bool {
+        self.position(id).is_ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over `(id, value)` pairs ordered by id.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &ControlValue)> {
+        self.entries.iter().map(|(id, val)| (*id, val))
+    }
+
+    /// Sets all values into a control list, i.e. to apply a request template.
+    pub fn apply_to(&self, list: &mut ControlList) -> Result<(), ControlError> {
+        for (id, val) in self.entries.iter() {
+            list.set_raw(*id, val.clone())?;
+        }
+        Ok(())
+    }
+
+    fn position(&self, id: u32) -> Result<usize, usize> {
+        self.entries.binary_search_by_key(&id, |(id, _)| *id)
+    }
+}
+
+impl ControlList {
+    /// Copies all values into an owned [ControlListSnapshot].
+    ///
+    /// Fails with [ControlError::Unreadable] if a value has a type unknown to this crate.
+    pub fn snapshot(&self) -> Result<ControlListSnapshot, ControlError> {
+        ControlListSnapshot::from_entries(self.into_iter())
+    }
+}
+
+impl PropertyList {
+    /// Copies all values into an owned [ControlListSnapshot].
+    ///
+    /// Fails with [ControlError::Unreadable] if a value has a type unknown to this crate.
+    pub fn snapshot(&self) -> Result<ControlListSnapshot, ControlError> {
+        ControlListSnapshot::from_entries(self.into_iter())
+    }
+}
+
+impl FromIterator<(u32, ControlValue)> for ControlListSnapshot {
+    fn from_iter<T: IntoIterator<Item = (u32, ControlValue)>>(iter: T) -> Self {
+        let mut snapshot = Self::new();
+        for (id, val) in iter {
+            snapshot.set_raw(id, val);
+        }
+        snapshot
+    }
+}
+
+impl IntoIterator for ControlListSnapshot {
+    type Item = (u32, ControlValue);
+
+    type IntoIter = std::vec::IntoIter<(u32, ControlValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+/// Lightweight [Display](core::fmt::Display) of a [ControlList], [PropertyList] or [ControlInfoMap].
+///
+/// Only the number of entries and their names are printed, values are never read or copied. Unlike `Debug` it only
+/// walks the ids, which is cheap enough for logging requests in hot or error paths, i.e. `3 controls [AeEnable,
+/// ExposureTime, 12345]` or `2 properties [Model, Location]`. Ids unknown to this crate are printed as numbers.
+#[derive(Clone, Copy)]
+pub struct ControlSummary<'a> {
+    source: SummarySource<'a>,
+    limit: usize,
+}
+
+#[derive(Clone, Copy)]
+enum SummarySource<'a> {
+    Controls(&'a ControlList),
+    Properties(&'a PropertyList),
+    Info(&'a ControlInfoMap),
+}
+
+impl<'a> ControlSummary<'a> {
+    fn new(source: SummarySource<'a>) -> Self {
+        Self { source, limit: 16 }
+    }
+
+    /// Sets maximum number of names to print, the rest is elided. Defaults to 16.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    fn fmt_ids(
+        f: &mut std::fmt::Formatter<'_>,
+        limit: usize,
+        ids: impl Iterator<Item = u32>,
+        name: impl Fn(u32) -> Option<&'static str>,
+    ) -> std::fmt::Result {
+        for (i, id) in ids.take(limit).enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            match name(id) {
+                Some(name) => f.write_str(name)?,
+                None => write!(f, "{id}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Display for ControlSummary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let control_name = |id| ControlId::try_from(id).ok().map(|id| id.name());
+        let len = match self.source {
+            SummarySource::Controls(list) => list.len(),
+            SummarySource::Properties(list) => unsafe { libcamera_control_list_size(list.ptr()) },
+            SummarySource::Info(map) => map.len(),
+        };
+
+        let (singular, plural) = match self.source {
+            SummarySource::Properties(_) => ("property", "properties"),
+            SummarySource::Controls(_) | SummarySource::Info(_) => ("control", "controls"),
+        };
+        write!(f, "{len} {} [", if len == 1 { singular } else { plural })?;
+        match self.source {
+            SummarySource::Controls(list) => Self::fmt_ids(f, self.limit, list.ids(), control_name)?,
+            SummarySource::Properties(list) => Self::fmt_ids(f, self.limit, list.ids(), |id| {
+                PropertyId::try_from(id).ok().map(|id| id.name())
+            })?,
+            SummarySource::Info(map) => Self::fmt_ids(f, self.limit, map.into_iter().map(|(id, _)| id), control_name)?,
+        }
+        if len > self.limit {
+            write!(f, ", ... {} more", len - self.limit)?;
+        }
+        f.write_str("]")
+    }
+}
+
+impl core::fmt::Debug for ControlSummary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+/// Iterator over copied values of a [ControlList] or [PropertyList].
+///
+/// Values of types unknown to this crate are yielded as errors, so they are not mistaken for missing entries.
 pub struct ControlListRefIterator<'d> {
     it: NonNull<libcamera_control_list_iter_t>,
     _phantom: PhantomData<&'d ()>,
 }
 
 impl<'d> Iterator for ControlListRefIterator<'d> {
-    type Item = (u32, ControlValue);
+    type Item = (u32, Result<ControlValue, ControlValueError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if unsafe { libcamera_control_list_iter_end(self.it.as_ptr()) } {
+            return None;
+        }
+        let id = unsafe { libcamera_control_list_iter_id(self.it.as_ptr()) };
+        let val_ptr = NonNull::new(unsafe { libcamera_control_list_iter_value(self.it.as_ptr()).cast_mut() }).unwrap();
+        let val = unsafe { ControlValue::read(val_ptr) };
+
+        unsafe { libcamera_control_list_iter_next(self.it.as_ptr()) };
+        Some((id, val))
+    }
+}
+
+impl<'d> Drop for ControlListRefIterator<'d> {
+    fn drop(&mut self) {
+        unsafe { libcamera_control_list_iter_destroy(self.it.as_ptr()) }
+    }
+}
+
+/// Iterator over borrowed values of a [ControlList] or [PropertyList], see [ControlList::entries()]. Values of types
+/// unknown to this crate are yielded as errors, same as when iterating the list by value.
+pub struct ControlListValueRefIterator<'d> {
+    it: NonNull<libcamera_control_list_iter_t>,
+    _phantom: PhantomData<&'d ()>,
+}
+
+impl<'d> Iterator for ControlListValueRefIterator<'d> {
+    type Item = (u32, Result<ControlValueRef<'d>, ControlValueError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if unsafe { libcamera_control_list_iter_end(self.it.as_ptr()) } {
+            return None;
+        }
+        let id = unsafe { libcamera_control_list_iter_id(self.it.as_ptr()) };
+        let val_ptr = NonNull::new(unsafe { libcamera_control_list_iter_value(self.it.as_ptr()).cast_mut() }).unwrap();
+        // The list is borrowed for 'd, so the value can not be modified while borrowed
+        let val = unsafe { ControlValueRef::read(val_ptr) };
+
+        unsafe { libcamera_control_list_iter_next(self.it.as_ptr()) };
+        Some((id, val))
+    }
+}
+
+impl<'d> Drop for ControlListValueRefIterator<'d> {
+    fn drop(&mut self) {
+        unsafe { libcamera_control_list_iter_destroy(self.it.as_ptr()) }
+    }
+}
+
+/// Iterator over numeric ids of a [ControlList] or [PropertyList].
+pub struct ControlListIdIterator<'d> {
+    it: NonNull<libcamera_control_list_iter_t>,
+    _phantom: PhantomData<&'d ()>,
+}
+
+impl<'d> Iterator for ControlListIdIterator<'d> {
+    type Item = u32;
 
     fn next(&mut self) -> Option<Self::Item> {
         if unsafe { libcamera_control_list_iter_end(self.it.as_ptr()) } {
             None
         } else {
             let id = unsafe { libcamera_control_list_iter_id(self.it.as_ptr()) };
-            let val_ptr =
-                NonNull::new(unsafe { libcamera_control_list_iter_value(self.it.as_ptr()).cast_mut() }).unwrap();
-            let val = unsafe { ControlValue::read(val_ptr) }.unwrap();
-
             unsafe { libcamera_control_list_iter_next(self.it.as_ptr()) };
-
-            Some((id, val))
+            Some(id)
         }
     }
 }
 
-impl<'d> Drop for ControlListRefIterator<'d> {
+impl<'d> Drop for ControlListIdIterator<'d> {
     fn drop(&mut self) {
         unsafe { libcamera_control_list_iter_destroy(self.it.as_ptr()) }
     }
 }
+
+pub struct ControlInfoMapIterator<'d> {
+    it: NonNull<libcamera_control_info_map_iter_t>,
+    _phantom: PhantomData<&'d ()>,
+}
+
+impl<'d> Iterator for ControlInfoMapIterator<'d> {
+    type Item = (u32, &'d ControlInfo);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if unsafe { libcamera_control_info_map_iter_end(self.it.as_ptr()) } {
+            None
+        } else {
+            let id = unsafe { libcamera_control_info_map_iter_id(self.it.as_ptr()) };
+            let info =
+                NonNull::new(unsafe { libcamera_control_info_map_iter_info(self.it.as_ptr()) }.cast_mut()).unwrap();
+
+            unsafe { libcamera_control_info_map_iter_next(self.it.as_ptr()) };
+
+            Some((id, unsafe { ControlInfo::from_ptr(info) }))
+        }
+    }
+}
+
+impl<'d> Drop for ControlInfoMapIterator<'d> {
+    fn drop(&mut self) {
+        unsafe { libcamera_control_info_map_iter_destroy(self.it.as_ptr()) }
+    }
+}
@@ -0,0 +1,209 @@
+//! Tolerant comparison of control values.
+//!
+//! Values reported in request metadata rarely match the requested ones exactly, e.g. exposure time is quantized to
+//! sensor line length and gains go through fixed point conversions. [Tolerance] defines when two numeric values are
+//! considered equal, and [ControlTolerances] selects it per control at runtime, so that diffing control lists,
+//! checking convergence and verifying replayed controls all share the same semantics.
+
+use std::collections::HashMap;
+
+use crate::{
+    control::{ControlError, ControlList},
+    control_value::ControlValue,
+};
+
+/// Tolerance for comparing numeric control values.
+///
+/// Two numbers are equal if their difference is within `absolute` or within `relative` fraction of the larger
+/// magnitude. Applies to `Int32`, `Int64` and `Float` values, all other types are compared exactly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Tolerance {
+    /// Exact comparison.
+    pub const EXACT: Self = Self {
+        absolute: 0.0,
+        relative: 0.0,
+    };
+
+    /// Ignores rounding errors of 32-bit floats, integers are compared exactly.
+    pub const DEFAULT: Self = Self {
+        absolute: 0.0,
+        relative: 4.0 * f32::EPSILON as f64,
+    };
+
+    pub const fn absolute(absolute: f64) -> Self {
+        Self {
+            absolute,
+            relative: 0.0,
+        }
+    }
+
+    pub const fn relative(relative: f64) -> Self {
+        Self {
+            absolute: 0.0,
+            relative,
+        }
+    }
+
+    /// Compares two numbers.
+    pub fn eq_f64(&self, a: f64, b: f64) -> bool {
+        if a == b {
+            return true;
+        }
+        let diff = (a - b).abs();
+        diff <= self.absolute || diff <= self.relative * a.abs().max(b.abs())
+    }
+
+    /// Compares two control values element-wise.
+    ///
+    /// Values of different types or lengths are never equal.
+    pub fn values_eq(&self, a: &ControlValue, b: &ControlValue) -> bool {
+        fn elements_eq<T: Copy>(a: &[T], b: &[T], eq: impl Fn(T, T) -> bool) -> bool {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| eq(*a, *b))
+        }
+
+        match (a, b) {
+            (ControlValue::Int32(a), ControlValue::Int32(b)) => elements_eq(a, b, |a, b| self.eq_f64(a as _, b as _)),
+            (ControlValue::Int64(a), ControlValue::Int64(b)) => elements_eq(a, b, |a, b| self.eq_f64(a as _, b as _)),
+            (ControlValue::Float(a), ControlValue::Float(b)) => elements_eq(a, b, |a, b| self.eq_f64(a as _, b as _)),
+            _ => a == b,
+        }
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// A difference between two control lists, see [ControlTolerances::diff()].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlDiff {
+    pub id: u32,
+    /// Value in the first list, [None] if it is missing or can not be read.
+    pub left: Option<ControlValue>,
+    /// Value in the second list, [None] if it is missing or can not be read.
+    pub right: Option<ControlValue>,
+}
+
+/// Per-control [Tolerance] selection.
+///
+/// ```
+/// # use libcamera::{control::ControlEntry, control_compare::{ControlTolerances, Tolerance}, controls};
+/// let mut tolerances = ControlTolerances::default();
+/// // Exposure is quantized to sensor lines, allow 2% error
+/// tolerances.set(controls::ExposureTime::ID, Tolerance::relative(0.02));
+/// tolerances.set(controls::AnalogueGain::ID, Tolerance::absolute(0.05));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ControlTolerances {
+    default: Tolerance,
+    overrides: HashMap<u32, Tolerance>,
+}
+
+impl ControlTolerances {
+    /// Creates tolerances using `default` for all controls.
+    pub fn new(default: Tolerance) -> Self {
+        Self {
+            default,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Tolerance used for controls without an override.
+    pub fn default_tolerance(&self) -> Tolerance {
+        self.default
+    }
+
+    pub fn set_default_tolerance(&mut self, tolerance: Tolerance) {
+        self.default = tolerance;
+    }
+
+    /// Overrides tolerance of a control, returning the previous override.
+    pub fn set(&mut self, id: u32, tolerance: Tolerance) -> Option<Tolerance> {
+        self.overrides.insert(id, tolerance)
+    }
+
+    /// Removes tolerance override of a control.
+    pub fn remove(&mut self, id: u32) -> Option<Tolerance> {
+        self.overrides.remove(&id)
+    }
+
+    /// Returns tolerance of a control.
+    pub fn get(&self, id: u32) -> Tolerance {
+        self.overrides.get(&id).copied().unwrap_or(self.default)
+    }
+
+    /// Compares two values of a control.
+    pub fn values_eq(&self, id: u32, a: &ControlValue, b: &ControlValue) -> bool {
+        self.get(id).values_eq(a, b)
+    }
+
+    /// Lists controls that are missing from either list or differ beyond tolerance.
+    ///
+    /// Differences are ordered by control id. Values of types unknown to this crate never compare equal.
+    pub fn diff(&self, left: &ControlList, right: &ControlList) -> Vec<ControlDiff> {
+        let mut diffs = Vec::new();
+
+        for (id, l) in left.into_iter() {
+            match (l, right.get_raw(id)) {
+                (Ok(l), Ok(r)) if self.values_eq(id, &l, &r) => {}
+                (l, r) => diffs.push(ControlDiff {
+                    id,
+                    left: l.ok(),
+                    right: r.ok(),
+                }),
+            }
+        }
+        for (id, r) in right.into_iter() {
+            if matches!(left.get_raw(id), Err(ControlError::NotFound(_))) {
+                diffs.push(ControlDiff {
+                    id,
+                    left: None,
+                    right: r.ok(),
+                });
+            }
+        }
+
+        diffs.sort_by_key(|d| d.id);
+        diffs
+    }
+
+    /// Returns `true` if every control of `expected` is reported within tolerance in `metadata`.
+    ///
+    /// Controls which are not reported in metadata are ignored, as pipelines only report a subset of them. Values of
+    /// types unknown to this crate are never converged.
+    pub fn converged(&self, expected: &ControlList, metadata: &ControlList) -> bool {
+        self.mismatches(expected.into_iter().map(|(id, val)| (id, val.ok())), metadata)
+            .is_empty()
+    }
+
+    /// Lists `expected` controls reported in `metadata` with a value beyond tolerance.
+    pub(crate) fn mismatches(
+        &self,
+        expected: impl IntoIterator<Item = (u32, Option<ControlValue>)>,
+        metadata: &ControlList,
+    ) -> Vec<ControlDiff> {
+        expected
+            .into_iter()
+            .filter_map(|(id, val)| {
+                let reported = match metadata.get_raw(id) {
+                    Err(ControlError::NotFound(_)) => return None,
+                    reported => reported.ok(),
+                };
+                let equal = matches!((&val, &reported), (Some(v), Some(r)) if self.values_eq(id, v, r));
+                (!equal).then_some(ControlDiff {
+                    id,
+                    left: val,
+                    right: reported,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,348 @@
+//! Recording and replay of per-request controls.
+//!
+//! [ControlRecorder] captures controls of every queued request, and [ControlReplayer] re-applies them frame-by-frame
+//! in a later capture session. Frames are aligned by their index, which counts requests from the start of the
+//! recording, so identical control trajectories can be reproduced to compare e.g. different tuning files.
+//!
+//! Recordings can be persisted with [ControlRecording::write_to()] and [ControlRecording::read_from()] using a simple
+//! line-based text format.
+
+use std::io::{self, BufRead, Write};
+
+use thiserror::Error;
+
+use crate::{
+    control::{ControlError, ControlList},
+    control_compare::{ControlDiff, ControlTolerances},
+    control_value::ControlValue,
+    geometry::{Rectangle, Size},
+};
+
+const HEADER: &str = "# libcamera-rs control recording v1";
+
+#[derive(Debug, Error)]
+pub enum ControlRecordingError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid recording at line {line}: {msg}")]
+    Parse { line: usize, msg: String },
+}
+
+/// Controls of a single request within [ControlRecording].
+#[derive(Debug, Clone)]
+pub struct RecordedFrame {
+    /// Index of the request from the beginning of the recording.
+    pub index: u64,
+    /// List of `(control id, value)` pairs that were set on the request.
+    pub controls: Vec<(u32, ControlValue)>,
+}
+
+/// A sequence of recorded request controls ordered by frame index.
+///
+/// Frames without any controls are not stored.
+#[derive(Debug, Clone, Default)]
+pub struct ControlRecording {
+    frames: Vec<RecordedFrame>,
+    len: u64,
+}
+
+impl ControlRecording {
+    /// Returns recorded frames that have at least one control set.
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// Number of frames covered by the recording, including frames without controls.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if recording does not cover any frames.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns controls recorded for a given frame index.
+    pub fn get(&self, index: u64) -> Option<&RecordedFrame> {
+        self.frames
+            .binary_search_by_key(&index, |f| f.index)
+            .ok()
+            .map(|i| &self.frames[i])
+    }
+
+    fn insert(&mut self, frame: RecordedFrame) {
+        self.len = self.len.max(frame.index + 1);
+        if frame.controls.is_empty() {
+            return;
+        }
+        match self.frames.binary_search_by_key(&frame.index, |f| f.index) {
+            Ok(i) => self.frames[i] = frame,
+            Err(i) => self.frames.insert(i, frame),
+        }
+    }
+
+    /// Writes recording in a line-based text format.
+    ///
+    /// Each line contains tab separated frame index, control id, value type and comma separated value elements.
+    pub fn write_to<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "{HEADER}")?;
+        writeln!(w, "frames\t{}", self.len)?;
+        for frame in self.frames.iter() {
+            for (id, val) in frame.controls.iter() {
+                writeln!(w, "{}\t{}\t{}", frame.index, id, format_value(val))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads recording previously written with [ControlRecording::write_to()].
+    pub fn read_from<R: BufRead>(r: R) -> Result<Self, ControlRecordingError> {
+        let mut recording = ControlRecording::default();
+        let mut current: Option<RecordedFrame> = None;
+
+        for (i, line) in r.lines().enumerate() {
+            let line = line?;
+            let err = |msg: &str| ControlRecordingError::Parse {
+                line: i + 1,
+                msg: msg.to_string(),
+            };
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = line.split('\t').collect::<Vec<_>>();
+            if fields[0] == "frames" {
+                let len = fields
+                    .get(1)
+                    .and_then(|f| f.parse().ok())
+                    .ok_or_else(|| err("invalid frame count"))?;
+                recording.len = recording.len.max(len);
+                continue;
+            }
+
+            let [index, id, ty, values] = fields[..] else {
+                return Err(err("expected 4 fields"));
+            };
+            let index: u64 = index.parse().map_err(|_| err("invalid frame index"))?;
+            let id: u32 = id.parse().map_err(|_| err("invalid control id"))?;
+            let val = parse_value(ty, values).map_err(|msg| err(&msg))?;
+
+            match current.as_mut() {
+                Some(frame) if frame.index == index => frame.controls.push((id, val)),
+                _ => {
+                    if let Some(frame) = current.take() {
+                        recording.insert(frame);
+                    }
+                    current = Some(RecordedFrame {
+                        index,
+                        controls: vec![(id, val)],
+                    });
+                }
+            }
+        }
+
+        if let Some(frame) = current.take() {
+            recording.insert(frame);
+        }
+
+        Ok(recording)
+    }
+}
+
+fn join<T>(values: &[T], f: impl Fn(&T) -> String) -> String {
+    values.iter().map(f).collect::<Vec<_>>().join(",")
+}
+
+fn format_value(val: &ControlValue) -> String {
+    match val {
+        ControlValue::None => "none\t".to_string(),
+        ControlValue::Bool(v) => format!("bool\t{}", join(v, |v| v.to_string())),
+        ControlValue::Byte(v) => format!("byte\t{}", join(v, |v| v.to_string())),
+        ControlValue::Int32(v) => format!("int32\t{}", join(v, |v| v.to_string())),
+        ControlValue::Int64(v) => format!("int64\t{}", join(v, |v| v.to_string())),
+        ControlValue::Float(v) => format!("float\t{}", join(v, |v| v.to_string())),
+        // Strings are hex encoded to avoid escaping separators
+        ControlValue::String(v) => format!("string\t{}", join(v.as_bytes(), |b| format!("{b:02x}"))),
+        ControlValue::Rectangle(v) => format!(
+            "rectangle\t{}",
+            join(v, |r| format!("{}/{}/{}/{}", r.x, r.y, r.width, r.height))
+        ),
+        ControlValue::Size(v) => format!("size\t{}", join(v, |s| format!("{}/{}", s.width, s.height))),
+    }
+}
+
+fn parse_value(ty: &str, values: &str) -> Result<ControlValue, String> {
+    fn elements<T: std::str::FromStr>(values: &str) -> Result<Vec<T>, String> {
+        values
+            .split(',')
+            .filter(|v| !v.is_empty())
+            .map(|v| v.parse().map_err(|_| format!("invalid value {v:?}")))
+            .collect()
+    }
+
+    fn components<T: std::str::FromStr, const N: usize>(value: &str) -> Result<[T; N], String> {
+        let parts = elements::<T>(&value.replace('/', ","))?;
+        parts.try_into().map_err(|_| format!("invalid value {value:?}"))
+    }
+
+    Ok(match ty {
+        "none" => ControlValue::None,
+        "bool" => elements::<bool>(values)?.into(),
+        "byte" => elements::<u8>(values)?.into(),
+        "int32" => elements::<i32>(values)?.into(),
+        "int64" => elements::<i64>(values)?.into(),
+        "float" => elements::<f32>(values)?.into(),
+        "string" => {
+            let bytes = values
+                .split(',')
+                .filter(|v| !v.is_empty())
+                .map(|b| u8::from_str_radix(b, 16).map_err(|_| format!("invalid string byte {b:?}")))
+                .collect::<Result<Vec<_>, _>>()?;
+            ControlValue::String(String::from_utf8(bytes).map_err(|_| "invalid utf-8 string".to_string())?)
+        }
+        "rectangle" => values
+            .split(',')
+            .filter(|v| !v.is_empty())
+            .map(|v| {
+                let [x, y, width, height] = components::<i64, 4>(v)?;
+                Ok(Rectangle {
+                    x: x as _,
+                    y: y as _,
+                    width: width as _,
+                    height: height as _,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?
+            .into(),
+        "size" => values
+            .split(',')
+            .filter(|v| !v.is_empty())
+            .map(|v| {
+                let [width, height] = components::<u32, 2>(v)?;
+                Ok(Size { width, height })
+            })
+            .collect::<Result<Vec<_>, String>>()?
+            .into(),
+        _ => return Err(format!("unknown value type {ty:?}")),
+    })
+}
+
+/// Records controls of consecutive requests.
+///
+/// Call [ControlRecorder::record()] for every request right before it is queued.
+#[derive(Debug, Default)]
+pub struct ControlRecorder {
+    recording: ControlRecording,
+    next_index: u64,
+}
+
+impl ControlRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records request controls as the next frame and returns its index.
+    pub fn record(&mut self, controls: &ControlList) -> Result<u64, ControlError> {
+        let index = self.next_index;
+        self.record_at(index, controls)?;
+        Ok(index)
+    }
+
+    /// Records request controls for a specific frame index, replacing previously recorded ones.
+    ///
+    /// Following [ControlRecorder::record()] calls continue after this index. Fails with [ControlError::Unreadable]
+    /// without recording anything if a value has a type unknown to this crate.
+    pub fn record_at(&mut self, index: u64, controls: &ControlList) -> Result<(), ControlError> {
+        let controls = controls
+            .into_iter()
+            .map(|(id, val)| Ok((id, val.map_err(|error| ControlError::Unreadable { id, error })?)))
+            .collect::<Result<_, ControlError>>()?;
+        self.recording.insert(RecordedFrame { index, controls });
+        self.next_index = self.next_index.max(index + 1);
+        Ok(())
+    }
+
+    /// Returns the recording made so far.
+    pub fn recording(&self) -> &ControlRecording {
+        &self.recording
+    }
+
+    /// Finishes recording.
+    pub fn finish(self) -> ControlRecording {
+        self.recording
+    }
+}
+
+/// Re-applies [ControlRecording] onto consecutive requests.
+///
+/// Call [ControlReplayer::apply_next()] for every request right before it is queued.
+#[derive(Debug)]
+pub struct ControlReplayer {
+    recording: ControlRecording,
+    next_index: u64,
+}
+
+impl ControlReplayer {
+    pub fn new(recording: ControlRecording) -> Self {
+        Self {
+            recording,
+            next_index: 0,
+        }
+    }
+
+    /// Index of the frame that will be applied by the next [ControlReplayer::apply_next()] call.
+    pub fn position(&self) -> u64 {
+        self.next_index
+    }
+
+    /// Returns `true` if all recorded frames were replayed.
+    pub fn is_finished(&self) -> bool {
+        self.next_index >= self.recording.len()
+    }
+
+    /// Restarts replay from the first frame.
+    pub fn rewind(&mut self) {
+        self.next_index = 0;
+    }
+
+    /// Applies controls recorded for the next frame.
+    ///
+    /// Returns `false` without modifying controls once all frames were replayed.
+    pub fn apply_next(&mut self, controls: &mut ControlList) -> Result<bool, ControlError> {
+        if self.is_finished() {
+            return Ok(false);
+        }
+        self.apply(self.next_index, controls)?;
+        self.next_index += 1;
+        Ok(true)
+    }
+
+    /// Applies controls recorded for a given frame index, if there are any.
+    pub fn apply(&self, index: u64, controls: &mut ControlList) -> Result<(), ControlError> {
+        if let Some(frame) = self.recording.get(index) {
+            for (id, val) in frame.controls.iter() {
+                controls.set_raw(*id, val.clone())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares controls recorded for a given frame index with the values reported in request metadata.
+    ///
+    /// Returns recorded controls which the camera applied differently, see [ControlTolerances::converged()].
+    pub fn verify(&self, index: u64, metadata: &ControlList, tolerances: &ControlTolerances) -> Vec<ControlDiff> {
+        match self.recording.get(index) {
+            Some(frame) => tolerances.mismatches(
+                frame.controls.iter().map(|(id, val)| (*id, Some(val.clone()))),
+                metadata,
+            ),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the replayed recording.
+    pub fn into_recording(self) -> ControlRecording {
+        self.recording
+    }
+}
@@ -1,4 +1,4 @@
-use std::ptr::NonNull;
+use std::{borrow::Cow, ptr::NonNull};
 
 use libcamera_sys::*;
 use smallvec::{smallvec, SmallVec};
@@ -23,7 +23,8 @@ pub enum ControlValueError {
 }
 
 /// A value of a control or a property.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ControlValue {
     None,
     Bool(SmallVec<[bool; 1]>),
@@ -207,49 +208,7 @@ impl TryFrom<ControlValue> for String {
 
 impl ControlValue {
     pub(crate) unsafe fn read(val: NonNull<libcamera_control_value_t>) -> Result<Self, ControlValueError> {
-        let ty = unsafe { libcamera_control_value_type(val.as_ptr()) };
-        let num_elements = unsafe { libcamera_control_value_num_elements(val.as_ptr()) };
-        let data = unsafe { libcamera_control_value_get(val.as_ptr()) };
-
-        use libcamera_control_type::*;
-        match ty {
-            LIBCAMERA_CONTROL_TYPE_NONE => Ok(Self::None),
-            LIBCAMERA_CONTROL_TYPE_BOOL => {
-                let slice = core::slice::from_raw_parts(data as *const bool, num_elements);
-                Ok(Self::Bool(SmallVec::from_slice(slice)))
-            }
-            LIBCAMERA_CONTROL_TYPE_BYTE => {
-                let slice = core::slice::from_raw_parts(data as *const u8, num_elements);
-                Ok(Self::Byte(SmallVec::from_slice(slice)))
-            }
-            LIBCAMERA_CONTROL_TYPE_INT32 => {
-                let slice = core::slice::from_raw_parts(data as *const i32, num_elements);
-                Ok(Self::Int32(SmallVec::from_slice(slice)))
-            }
-            LIBCAMERA_CONTROL_TYPE_INT64 => {
-                let slice = core::slice::from_raw_parts(data as *const i64, num_elements);
-                Ok(Self::Int64(SmallVec::from_slice(slice)))
-            }
-            LIBCAMERA_CONTROL_TYPE_FLOAT => {
-                let slice = core::slice::from_raw_parts(data as *const f32, num_elements);
-                Ok(Self::Float(SmallVec::from_slice(slice)))
-            }
-            LIBCAMERA_CONTROL_TYPE_STRING => {
-                let slice = core::slice::from_raw_parts(data as *const u8, num_elements);
-                Ok(Self::String(core::str::from_utf8(slice).unwrap().to_string()))
-            }
-            LIBCAMERA_CONTROL_TYPE_RECTANGLE => {
-                let slice = core::slice::from_raw_parts(data as *const libcamera_rectangle_t, num_elements);
-                Ok(Self::Rectangle(SmallVec::from_iter(
-                    slice.iter().map(|r| Rectangle::from(*r)),
-                )))
-            }
-            LIBCAMERA_CONTROL_TYPE_SIZE => {
-                let slice = core::slice::from_raw_parts(data as *const libcamera_size_t, num_elements);
-                Ok(Self::Size(SmallVec::from_iter(slice.iter().map(|r| Size::from(*r)))))
-            }
-            _ => Err(ControlValueError::UnknownType(ty)),
-        }
+        Ok(unsafe { ControlValueRef::read(val) }?.into())
     }
 
     pub(crate) unsafe fn write(&self, val: NonNull<libcamera_control_value_t>) {
@@ -275,6 +234,92 @@ impl ControlValue {
         libcamera_control_value_set(val.as_ptr(), self.ty(), data, is_array, len as _);
     }
 
+    /// Splits array value into single element values.
+    ///
+    /// Strings are considered a single element.
+    pub(crate) fn elements(&self) -> Vec<ControlValue> {
+        fn split<T: Copy>(v: &[T], f: impl Fn(SmallVec<[T; 1]>) -> ControlValue) -> Vec<ControlValue> {
+            v.iter().map(|e| f(smallvec![*e])).collect()
+        }
+
+        match self {
+            ControlValue::None => Vec::new(),
+            ControlValue::Bool(v) => split(v, ControlValue::Bool),
+            ControlValue::Byte(v) => split(v, ControlValue::Byte),
+            ControlValue::Int32(v) => split(v, ControlValue::Int32),
+            ControlValue::Int64(v) => split(v, ControlValue::Int64),
+            ControlValue::Float(v) => split(v, ControlValue::Float),
+            ControlValue::String(_) => vec![self.clone()],
+            ControlValue::Rectangle(v) => split(v, ControlValue::Rectangle),
+            ControlValue::Size(v) => split(v, ControlValue::Size),
+        }
+    }
+
+    /// Compares first elements of numeric values of the same type.
+    ///
+    /// Returns [None] if values are not comparable.
+    pub(crate) fn partial_cmp_element(&self, other: &ControlValue) -> Option<core::cmp::Ordering> {
+        match (self, other) {
+            (ControlValue::Byte(a), ControlValue::Byte(b)) => a.first()?.partial_cmp(b.first()?),
+            (ControlValue::Int32(a), ControlValue::Int32(b)) => a.first()?.partial_cmp(b.first()?),
+            (ControlValue::Int64(a), ControlValue::Int64(b)) => a.first()?.partial_cmp(b.first()?),
+            (ControlValue::Float(a), ControlValue::Float(b)) => a.first()?.partial_cmp(b.first()?),
+            _ => None,
+        }
+    }
+
+    /// Clamps numeric elements to the first elements of `min` and `max`, if those are of the same type.
+    ///
+    /// Returns [None] if value is already within the limits or can not be clamped.
+    pub(crate) fn clamp(&self, min: &ControlValue, max: &ControlValue) -> Option<ControlValue> {
+        fn clamp<T: PartialOrd + Copy>(
+            v: &[T],
+            min: Option<&T>,
+            max: Option<&T>,
+            f: impl Fn(SmallVec<[T; 1]>) -> ControlValue,
+        ) -> Option<ControlValue> {
+            let mut changed = false;
+            let out = v
+                .iter()
+                .map(|e| {
+                    let mut e = *e;
+                    if let Some(min) = min.filter(|min| e < **min) {
+                        e = *min;
+                        changed = true;
+                    }
+                    if let Some(max) = max.filter(|max| e > **max) {
+                        e = *max;
+                        changed = true;
+                    }
+                    e
+                })
+                .collect();
+            changed.then(|| f(out))
+        }
+
+        macro_rules! clamp_variant {
+            ($p:path, $v:expr) => {{
+                let min = match min {
+                    $p(m) => m.first(),
+                    _ => None,
+                };
+                let max = match max {
+                    $p(m) => m.first(),
+                    _ => None,
+                };
+                clamp($v, min, max, $p)
+            }};
+        }
+
+        match self {
+            ControlValue::Byte(v) => clamp_variant!(ControlValue::Byte, v),
+            ControlValue::Int32(v) => clamp_variant!(ControlValue::Int32, v),
+            ControlValue::Int64(v) => clamp_variant!(ControlValue::Int64, v),
+            ControlValue::Float(v) => clamp_variant!(ControlValue::Float, v),
+            _ => None,
+        }
+    }
+
     pub fn ty(&self) -> u32 {
         use libcamera_control_type::*;
         match self {
@@ -290,3 +335,107 @@ impl ControlValue {
         }
     }
 }
+
+// Borrowed rectangles and sizes are reinterpreted from libcamera arrays
+const _: () = assert!(core::mem::size_of::<Rectangle>() == core::mem::size_of::<libcamera_rectangle_t>());
+const _: () = assert!(core::mem::align_of::<Rectangle>() == core::mem::align_of::<libcamera_rectangle_t>());
+const _: () = assert!(core::mem::size_of::<Size>() == core::mem::size_of::<libcamera_size_t>());
+const _: () = assert!(core::mem::align_of::<Size>() == core::mem::align_of::<libcamera_size_t>());
+
+/// A [ControlValue] borrowed from a control list without copying its data.
+///
+/// Reading large array controls, i.e. vendor statistics blobs, as [ControlValue] allocates on every frame. Borrowed
+/// values reference the storage of the list instead and are valid until the list is mutated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlValueRef<'a> {
+    None,
+    Bool(&'a [bool]),
+    Byte(&'a [u8]),
+    Int32(&'a [i32]),
+    Int64(&'a [i64]),
+    Float(&'a [f32]),
+    /// Borrowed if the string is valid UTF-8.
+    String(Cow<'a, str>),
+    Rectangle(&'a [Rectangle]),
+    Size(&'a [Size]),
+}
+
+impl<'a> ControlValueRef<'a> {
+    /// # Safety
+    ///
+    /// `val` must be valid for `'a` and must not be modified during `'a`.
+    pub(crate) unsafe fn read(val: NonNull<libcamera_control_value_t>) -> Result<Self, ControlValueError> {
+        let ty = unsafe { libcamera_control_value_type(val.as_ptr()) };
+        let num_elements = unsafe { libcamera_control_value_num_elements(val.as_ptr()) };
+        let data = unsafe { libcamera_control_value_get(val.as_ptr()) };
+
+        unsafe fn slice<'a, T>(data: *const core::ffi::c_void, len: usize) -> &'a [T] {
+            if data.is_null() || len == 0 {
+                &[]
+            } else {
+                unsafe { core::slice::from_raw_parts(data.cast(), len) }
+            }
+        }
+
+        use libcamera_control_type::*;
+        unsafe {
+            match ty {
+                LIBCAMERA_CONTROL_TYPE_NONE => Ok(Self::None),
+                LIBCAMERA_CONTROL_TYPE_BOOL => Ok(Self::Bool(slice(data, num_elements))),
+                LIBCAMERA_CONTROL_TYPE_BYTE => Ok(Self::Byte(slice(data, num_elements))),
+                LIBCAMERA_CONTROL_TYPE_INT32 => Ok(Self::Int32(slice(data, num_elements))),
+                LIBCAMERA_CONTROL_TYPE_INT64 => Ok(Self::Int64(slice(data, num_elements))),
+                LIBCAMERA_CONTROL_TYPE_FLOAT => Ok(Self::Float(slice(data, num_elements))),
+                LIBCAMERA_CONTROL_TYPE_STRING => Ok(Self::String(String::from_utf8_lossy(slice(data, num_elements)))),
+                LIBCAMERA_CONTROL_TYPE_RECTANGLE => Ok(Self::Rectangle(slice(data, num_elements))),
+                LIBCAMERA_CONTROL_TYPE_SIZE => Ok(Self::Size(slice(data, num_elements))),
+                _ => Err(ControlValueError::UnknownType(ty)),
+            }
+        }
+    }
+
+    /// Returns libcamera type of the value, see [ControlValue::ty()].
+    pub fn ty(&self) -> u32 {
+        use libcamera_control_type::*;
+        match self {
+            Self::None => LIBCAMERA_CONTROL_TYPE_NONE,
+            Self::Bool(_) => LIBCAMERA_CONTROL_TYPE_BOOL,
+            Self::Byte(_) => LIBCAMERA_CONTROL_TYPE_BYTE,
+            Self::Int32(_) => LIBCAMERA_CONTROL_TYPE_INT32,
+            Self::Int64(_) => LIBCAMERA_CONTROL_TYPE_INT64,
+            Self::Float(_) => LIBCAMERA_CONTROL_TYPE_FLOAT,
+            Self::String(_) => LIBCAMERA_CONTROL_TYPE_STRING,
+            Self::Rectangle(_) => LIBCAMERA_CONTROL_TYPE_RECTANGLE,
+            Self::Size(_) => LIBCAMERA_CONTROL_TYPE_SIZE,
+        }
+    }
+
+    /// Returns data of a byte array control, i.e. a vendor statistics blob.
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            Self::Byte(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Copies the value into an owned [ControlValue].
+    pub fn to_value(&self) -> ControlValue {
+        self.clone().into()
+    }
+}
+
+impl From<ControlValueRef<'_>> for ControlValue {
+    fn from(value: ControlValueRef<'_>) -> Self {
+        match value {
+            ControlValueRef::None => Self::None,
+            ControlValueRef::Bool(v) => Self::Bool(SmallVec::from_slice(v)),
+            ControlValueRef::Byte(v) => Self::Byte(SmallVec::from_slice(v)),
+            ControlValueRef::Int32(v) => Self::Int32(SmallVec::from_slice(v)),
+            ControlValueRef::Int64(v) => Self::Int64(SmallVec::from_slice(v)),
+            ControlValueRef::Float(v) => Self::Float(SmallVec::from_slice(v)),
+            ControlValueRef::String(v) => Self::String(v.into_owned()),
+            ControlValueRef::Rectangle(v) => Self::Rectangle(SmallVec::from_slice(v)),
+            ControlValueRef::Size(v) => Self::Size(SmallVec::from_slice(v)),
+        }
+    }
+}
@@ -0,0 +1,360 @@
+//! CPU demosaicing of raw Bayer frames.
+//!
+//! [Debayer] converts frames of a [StreamRole::Raw](crate::stream::StreamRole::Raw) stream into [RgbImage]s, so that
+//! raw captures can be inspected without external tools. It is meant for previews and debugging rather than image
+//! quality: no white balance, color correction or gamma is applied.
+
+use thiserror::Error;
+
+use crate::{
+    control::ControlList,
+    controls::SensorBlackLevels,
+    framebuffer::AsFrameBuffer,
+    framebuffer_map::MemoryMappedFrameBuffer,
+    geometry::Size,
+    pixel_format::{formats, PixelFormat},
+    raw_mode::raw_format_info,
+    rgb::RgbImage,
+    stream::StreamConfigurationRef,
+};
+#[cfg(feature = "vendor_draft")]
+use crate::{control::PropertyList, properties::ColorFilterArrangement};
+
+#[derive(Debug, Error)]
+pub enum DebayerError {
+    /// Pixel format is not an uncompressed raw Bayer format
+    #[error("Unsupported pixel format {0:?}")]
+    UnsupportedFormat(PixelFormat),
+    /// Frame is smaller than 2x2 pixels
+    #[error("Frame size {0:?} is too small")]
+    TooSmall(Size),
+    /// Frame data is shorter than implied by the frame size and stride
+    #[error("Frame is {len} bytes long, but at least {expected} bytes are required")]
+    FrameTooShort { len: usize, expected: usize },
+}
+
+/// Color filter order of the top-left 2x2 pixels, in reading order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BayerPattern {
+    Rggb,
+    Grbg,
+    Gbrg,
+    Bggr,
+}
+
+impl BayerPattern {
+    /// Returns pattern of libcamera raw Bayer formats, such as `SRGGB10_CSI2P`.
+    ///
+    /// Prefer it over the `ColorFilterArrangement` property, as the format accounts for sensor flips.
+    pub fn from_pixel_format(pixel_format: PixelFormat) -> Option<Self> {
+        // Packed variants share the fourcc of the unpacked format and differ only in modifier
+        let fourcc = pixel_format.fourcc();
+        BAYER_FORMATS
+            .iter()
+            .find_map(|(format, pattern)| (format.fourcc() == fourcc).then_some(*pattern))
+    }
+
+    /// Reads pattern from the `ColorFilterArrangement` camera property.
+    #[cfg(feature = "vendor_draft")]
+    pub fn from_properties(properties: &PropertyList) -> Option<Self> {
+        properties.get::<ColorFilterArrangement>().ok()?.try_into().ok()
+    }
+
+    /// Black level channel (in `SensorBlackLevels` order R, Gr, Gb, B) of each pixel in the top-left 2x2 pixels.
+    fn channels(self) -> [usize; 4] {
+        match self {
+            Self::Rggb => [0, 1, 2, 3],
+            Self::Grbg => [1, 0, 3, 2],
+            Self::Gbrg => [2, 3, 0, 1],
+            Self::Bggr => [3, 2, 1, 0],
+        }
+    }
+}
+
+const BAYER_FORMATS: &[(PixelFormat, BayerPattern)] = &[
+    (formats::SRGGB8, BayerPattern::Rggb),
+    (formats::SGRBG8, BayerPattern::Grbg),
+    (formats::SGBRG8, BayerPattern::Gbrg),
+    (formats::SBGGR8, BayerPattern::Bggr),
+    (formats::SRGGB10, BayerPattern::Rggb),
+    (formats::SGRBG10, BayerPattern::Grbg),
+    (formats::SGBRG10, BayerPattern::Gbrg),
+    (formats::SBGGR10, BayerPattern::Bggr),
+    (formats::SRGGB12, BayerPattern::Rggb),
+    (formats::SGRBG12, BayerPattern::Grbg),
+    (formats::SGBRG12, BayerPattern::Gbrg),
+    (formats::SBGGR12, BayerPattern::Bggr),
+    (formats::SRGGB14, BayerPattern::Rggb),
+    (formats::SGRBG14, BayerPattern::Grbg),
+    (formats::SGBRG14, BayerPattern::Gbrg),
+    (formats::SBGGR14, BayerPattern::Bggr),
+    (formats::SRGGB16, BayerPattern::Rggb),
+    (formats::SGRBG16, BayerPattern::Grbg),
+    (formats::SGBRG16, BayerPattern::Gbrg),
+    (formats::SBGGR16, BayerPattern::Bggr),
+];
+
+#[cfg(feature = "vendor_draft")]
+impl TryFrom<ColorFilterArrangement> for BayerPattern {
+    type Error = ColorFilterArrangement;
+
+    fn try_from(value: ColorFilterArrangement) -> Result<Self, Self::Error> {
+        match value {
+            ColorFilterArrangement::RGGB => Ok(Self::Rggb),
+            ColorFilterArrangement::GRBG => Ok(Self::Grbg),
+            ColorFilterArrangement::GBRG => Ok(Self::Gbrg),
+            ColorFilterArrangement::BGGR => Ok(Self::Bggr),
+            other => Err(other),
+        }
+    }
+}
+
+/// Interpolation used by [Debayer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DebayerMethod {
+    /// Uses the colors of each 2x2 block for all of its pixels. Fast, but blocky.
+    Nearest,
+    /// Averages the nearest pixels of each missing color.
+    #[default]
+    Bilinear,
+}
+
+/// Memory layout of raw pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Packing {
+    /// One byte per pixel.
+    Unpacked8,
+    /// Little-endian 16-bit words with the value in the low bits.
+    Unpacked16,
+    /// MIPI CSI-2 packing of 10, 12 or 14 bit pixels.
+    Csi2,
+}
+
+/// Converts raw Bayer frames into [RgbImage].
+///
+/// Supports 8-bit, 16-bit padded and MIPI CSI-2 packed (`_CSI2P`) 10, 12 and 14-bit formats.
+#[derive(Debug, Clone, Copy)]
+pub struct Debayer {
+    pixel_format: PixelFormat,
+    size: Size,
+    stride: u32,
+    bit_depth: u32,
+    packing: Packing,
+    pattern: BayerPattern,
+    method: DebayerMethod,
+    /// Black levels in R, Gr, Gb, B order, scaled to 16 bits.
+    black_levels: [u16; 4],
+}
+
+impl Debayer {
+    pub fn new(pixel_format: PixelFormat, size: Size, stride: u32) -> Result<Self, DebayerError> {
+        let unsupported = || DebayerError::UnsupportedFormat(pixel_format);
+        let (bit_depth, packed) = raw_format_info(pixel_format).ok_or_else(unsupported)?;
+        let pattern = BayerPattern::from_pixel_format(pixel_format).ok_or_else(unsupported)?;
+        let packing = match (bit_depth, packed) {
+            (8, false) => Packing::Unpacked8,
+            (9..=16, false) => Packing::Unpacked16,
+            (10 | 12 | 14, true) if pixel_format.modifier() == formats::MIPI_FORMAT_MOD_CSI2_PACKED => Packing::Csi2,
+            _ => return Err(unsupported()),
+        };
+        if size.width < 2 || size.height < 2 {
+            return Err(DebayerError::TooSmall(size));
+        }
+
+        Ok(Self {
+            pixel_format,
+            size,
+            stride,
+            bit_depth,
+            packing,
+            pattern,
+            method: DebayerMethod::default(),
+            black_levels: [0; 4],
+        })
+    }
+
+    /// Creates debayer for frames produced by the given (validated) raw stream configuration.
+    pub fn from_stream_config(cfg: &StreamConfigurationRef<'_>) -> Result<Self, DebayerError> {
+        Self::new(cfg.get_pixel_format(), cfg.get_size(), cfg.get_stride())
+    }
+
+    pub fn method(mut self, method: DebayerMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Overrides the color filter pattern parsed from the pixel format.
+    pub fn pattern(mut self, pattern: BayerPattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Sets black levels in R, Gr, Gb, B order out of a 16-bit range, see [SensorBlackLevels].
+    pub fn black_levels(mut self, levels: [i32; 4]) -> Self {
+        self.black_levels = levels.map(|l| l.clamp(0, u16::MAX as i32) as u16);
+        self
+    }
+
+    /// Updates black levels from request metadata, if the pipeline reports [SensorBlackLevels].
+    pub fn apply_metadata(&mut self, metadata: &ControlList) {
+        if let Ok(levels) = metadata.get::<SensorBlackLevels>() {
+            *self = self.black_levels(levels.0);
+        }
+    }
+
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.pixel_format
+    }
+
+    /// Converts memory mapped framebuffer into RGB.
+    pub fn debayer_buffer<T: AsFrameBuffer>(&self, fb: &MemoryMappedFrameBuffer<T>) -> Result<RgbImage, DebayerError> {
+        let planes = fb.data_used();
+        self.debayer(planes.first().copied().unwrap_or_default())
+    }
+
+    /// Converts raw frame data into RGB.
+    pub fn debayer(&self, data: &[u8]) -> Result<RgbImage, DebayerError> {
+        let (width, height) = (self.size.width as usize, self.size.height as usize);
+        let raw = self.unpack(data)?;
+        let channels = self.pattern.channels();
+        // Color (R, G, B) of the pixel at the given coordinates
+        let color = |x: usize, y: usize| [0, 1, 1, 2][channels[(y & 1) * 2 + (x & 1)]];
+
+        let mut out = Vec::with_capacity(width * height * 3);
+        match self.method {
+            DebayerMethod::Nearest => {
+                for y in 0..height {
+                    // Odd sizes reuse the last complete block
+                    let y0 = (y & !1).min(height - 2);
+                    for x in 0..width {
+                        let x0 = (x & !1).min(width - 2);
+                        let mut rgb = [0u32; 3];
+                        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                            rgb[color(x0 + dx, y0 + dy)] += raw[(y0 + dy) * width + x0 + dx] as u32;
+                        }
+                        // Green is sampled twice per block
+                        rgb[1] /= 2;
+                        out.extend(rgb.map(|c| (c >> 8) as u8));
+                    }
+                }
+            }
+            DebayerMethod::Bilinear => {
+                // Mirrors coordinates at the edges, which keeps the color filter parity
+                let reflect = |v: isize, len: usize| {
+                    if v < 0 {
+                        (-v) as usize
+                    } else if v as usize >= len {
+                        2 * (len - 1) - v as usize
+                    } else {
+                        v as usize
+                    }
+                };
+                for y in 0..height {
+                    for x in 0..width {
+                        let own = color(x, y);
+                        let mut sums = [0u32; 3];
+                        let mut counts = [0u32; 3];
+                        for dy in -1..=1 {
+                            let sy = reflect(y as isize + dy, height);
+                            for dx in -1..=1 {
+                                let sx = reflect(x as isize + dx, width);
+                                let c = color(sx, sy);
+                                sums[c] += raw[sy * width + sx] as u32;
+                                counts[c] += 1;
+                            }
+                        }
+                        let mut rgb = [0u8; 3];
+                        for c in 0..3 {
+                            let value = if c == own {
+                                raw[y * width + x] as u32
+                            } else {
+                                sums[c] / counts[c].max(1)
+                            };
+                            rgb[c] = (value >> 8) as u8;
+                        }
+                        out.extend(rgb);
+                    }
+                }
+            }
+        }
+
+        Ok(RgbImage {
+            width: self.size.width,
+            height: self.size.height,
+            data: out,
+        })
+    }
+
+    /// Number of bytes of a single line of pixels.
+    fn row_len(&self) -> usize {
+        let width = self.size.width as usize;
+        match (self.packing, self.bit_depth) {
+            (Packing::Unpacked8, _) => width,
+            (Packing::Unpacked16, _) => width * 2,
+            (Packing::Csi2, 12) => width.div_ceil(2) * 3,
+            (Packing::Csi2, 14) => width.div_ceil(4) * 7,
+            (Packing::Csi2, _) => width.div_ceil(4) * 5,
+        }
+    }
+
+    /// Unpacks pixels to 16-bit values with black level subtracted and the remaining range stretched.
+    fn unpack(&self, data: &[u8]) -> Result<Vec<u16>, DebayerError> {
+        let (width, height) = (self.size.width as usize, self.size.height as usize);
+        let row_len = self.row_len();
+        let stride = (self.stride as usize).max(row_len);
+        let expected = (height - 1) * stride + row_len;
+        if data.len() < expected {
+            return Err(DebayerError::FrameTooShort {
+                len: data.len(),
+                expected,
+            });
+        }
+
+        let shift = 16 - self.bit_depth;
+        let mask = ((1u32 << self.bit_depth) - 1) as u16;
+        let channels = self.pattern.channels();
+        let mut raw = Vec::with_capacity(width * height);
+        let mut line = Vec::with_capacity(width.next_multiple_of(4));
+        for y in 0..height {
+            let row = &data[y * stride..][..row_len];
+            line.clear();
+            match (self.packing, self.bit_depth) {
+                (Packing::Unpacked8, _) => line.extend(row.iter().map(|&b| b as u16)),
+                (Packing::Unpacked16, _) => {
+                    line.extend(row.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]]) & mask))
+                }
+                (Packing::Csi2, 12) => {
+                    for b in row.chunks_exact(3) {
+                        let (msb, lsb) = (b[..2].iter(), b[2] as u16);
+                        line.extend(msb.zip([lsb & 0xf, lsb >> 4]).map(|(&m, l)| (m as u16) << 4 | l));
+                    }
+                }
+                (Packing::Csi2, 14) => {
+                    for b in row.chunks_exact(7) {
+                        let lsb = u32::from_le_bytes([b[4], b[5], b[6], 0]);
+                        line.extend((0..4).map(|i| (b[i] as u16) << 6 | ((lsb >> (6 * i)) & 0x3f) as u16));
+                    }
+                }
+                (Packing::Csi2, _) => {
+                    for b in row.chunks_exact(5) {
+                        line.extend((0..4).map(|i| (b[i] as u16) << 2 | ((b[4] >> (2 * i)) & 0x3) as u16));
+                    }
+                }
+            }
+
+            for (x, &value) in line[..width].iter().enumerate() {
+                let black = self.black_levels[channels[(y & 1) * 2 + (x & 1)]] as u32;
+                let value = ((value as u32) << shift).saturating_sub(black);
+                raw.push((value * u16::MAX as u32 / (u16::MAX as u32 - black).max(1)).min(u16::MAX as u32) as u16);
+            }
+        }
+        Ok(raw)
+    }
+}
+
+impl<T: AsFrameBuffer> MemoryMappedFrameBuffer<T> {
+    /// Converts raw framebuffer contents into RGB, see [Debayer].
+    pub fn debayer(&self, debayer: &Debayer) -> Result<RgbImage, DebayerError> {
+        debayer.debayer_buffer(self)
+    }
+}
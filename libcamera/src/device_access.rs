@@ -0,0 +1,126 @@
+//! Diagnostics for cameras missing due to device node access.
+//!
+//! libcamera silently skips media devices it can not open, so a user outside of the `video` group or a container
+//! started without the device nodes sees an empty camera list rather than an error. [diagnose()] probes the device
+//! nodes libcamera needs to tell these situations apart.
+
+use std::{
+    fs::{self, OpenOptions},
+    io,
+    os::unix::fs::MetadataExt,
+    path::PathBuf,
+};
+
+use thiserror::Error;
+
+/// Device node name prefixes opened by libcamera pipeline handlers.
+const NODE_PREFIXES: [&str; 3] = ["media", "video", "v4l-subdev"];
+
+#[derive(Debug, Error)]
+pub enum DeviceAccessError {
+    /// No media controller nodes exist, i.e. there is no camera or a container was started without passing them
+    /// through.
+    #[error("No /dev/media* device nodes found, containers need camera devices passed (i.e. `--device /dev/media0`)")]
+    NoMediaNodes,
+    /// Media controller nodes exist, but the video nodes they reference do not.
+    #[error("No /dev/video* device nodes found, pass all camera devices to the container, not only /dev/media*")]
+    NoVideoNodes,
+    /// Device nodes exist, but can not be opened by the current user.
+    #[error("Permission denied opening {}", permission_hint(.paths, .group))]
+    PermissionDenied { paths: Vec<PathBuf>, group: Option<String> },
+}
+
+fn permission_hint(paths: &[PathBuf], group: &Option<String>) -> String {
+    let paths = paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    match group {
+        Some(group) => format!("{paths}, add the user to the `{group}` group (i.e. `usermod -aG {group} $USER`)"),
+        None => paths,
+    }
+}
+
+/// A probed camera device node.
+#[derive(Debug)]
+pub struct DeviceNode {
+    pub path: PathBuf,
+    /// Name of the owning group, if it can be resolved.
+    pub group: Option<String>,
+    /// Error opening the node for reading and writing, as libcamera does.
+    pub error: Option<io::Error>,
+}
+
+/// Probes camera device nodes in `/dev`.
+pub fn probe() -> io::Result<Vec<DeviceNode>> {
+    let mut nodes = Vec::new();
+    for entry in fs::read_dir("/dev")? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        let is_node = NODE_PREFIXES.iter().any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        });
+        if !is_node {
+            continue;
+        }
+
+        let path = entry.path();
+        let group = entry.metadata().ok().and_then(|m| group_name(m.gid()));
+        let error = OpenOptions::new().read(true).write(true).open(&path).err();
+        nodes.push(DeviceNode { path, group, error });
+    }
+    nodes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(nodes)
+}
+
+/// Looks up group name in `/etc/group`.
+fn group_name(gid: u32) -> Option<String> {
+    let groups = fs::read_to_string("/etc/group").ok()?;
+    groups.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        let id = fields.nth(1)?.parse::<u32>().ok()?;
+        (id == gid).then(|| name.to_string())
+    })
+}
+
+/// Returns the reason camera devices are inaccessible, or [None] if no access problem was found.
+///
+/// Only meaningful when no cameras were enumerated, as cameras may legitimately use only some of the nodes.
+pub fn diagnose() -> Option<DeviceAccessError> {
+    let nodes = probe().ok()?;
+    let is = |node: &DeviceNode, prefix: &str| {
+        node.path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with(prefix))
+    };
+
+    if !nodes.iter().any(|n| is(n, "media")) {
+        return Some(DeviceAccessError::NoMediaNodes);
+    }
+    if !nodes.iter().any(|n| is(n, "video")) {
+        return Some(DeviceAccessError::NoVideoNodes);
+    }
+
+    let denied = nodes
+        .iter()
+        .filter(|n| {
+            n.error
+                .as_ref()
+                .is_some_and(|e| e.kind() == io::ErrorKind::PermissionDenied)
+        })
+        .collect::<Vec<_>>();
+    if denied.is_empty() {
+        return None;
+    }
+    Some(DeviceAccessError::PermissionDenied {
+        paths: denied.iter().map(|n| n.path.clone()).collect(),
+        group: denied.iter().find_map(|n| n.group.clone()),
+    })
+}
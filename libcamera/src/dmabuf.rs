@@ -0,0 +1,148 @@
+use std::{
+    io,
+    os::fd::{AsFd, AsRawFd, BorrowedFd},
+    ptr::NonNull,
+};
+
+use libcamera_sys::*;
+
+use crate::framebuffer::AsFrameBuffer;
+
+/// A plane of a caller-provided dmabuf, see [DmaBufFrameBuffer::new()].
+#[derive(Debug, Clone, Copy)]
+pub struct DmaBufPlane<'fd> {
+    pub fd: BorrowedFd<'fd>,
+    /// Offset of plane data within the file descriptor.
+    pub offset: usize,
+    /// Length of plane data in bytes.
+    pub length: usize,
+}
+
+/// A framebuffer backed by externally allocated dmabufs, i.e. exported by a GPU, codec or a custom allocator.
+///
+/// Can be attached to a [Request](crate::request::Request) in the same way as buffers of
+/// [FrameBufferAllocator](crate::framebuffer_allocator::FrameBufferAllocator). Plane layout must match the stream
+/// configuration, which is validated by libcamera when the request is queued.
+pub struct DmaBufFrameBuffer {
+    ptr: NonNull<libcamera_framebuffer_t>,
+}
+
+impl DmaBufFrameBuffer {
+    /// Creates framebuffer from the given planes.
+    ///
+    /// File descriptors are duplicated, so the caller keeps ownership of the originals and may close them at any time.
+    /// `cookie` is an arbitrary user value to identify the buffer.
+    pub fn new(planes: &[DmaBufPlane<'_>], cookie: u64) -> io::Result<Self> {
+        if planes.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "framebuffer requires at least one plane",
+            ));
+        }
+
+        let planes = planes
+            .iter()
+            .map(|p| libcamera_framebuffer_plane_info {
+                fd: p.fd.as_raw_fd(),
+                offset: p.offset,
+                length: p.length,
+            })
+            .collect::<Vec<_>>();
+
+        let ptr = NonNull::new(unsafe { libcamera_framebuffer_create(planes.as_ptr(), planes.len(), cookie) })
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid plane file descriptor"))?;
+
+        // Mark metadata as unavailable, see `AsFrameBuffer::ptr()`
+        unsafe {
+            libcamera_framebuffer_metadata(ptr.as_ptr())
+                .cast_mut()
+                .cast::<u32>()
+                .write(u32::MAX)
+        };
+
+        Ok(Self { ptr })
+    }
+
+    /// Returns the cookie given at creation.
+    pub fn cookie(&self) -> u64 {
+        unsafe { libcamera_framebuffer_cookie(self.ptr.as_ptr()) }
+    }
+}
+
+impl core::fmt::Debug for DmaBufFrameBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DmaBufFrameBuffer")
+            .field("metadata", &self.metadata())
+            .field("planes", &self.planes())
+            .finish()
+    }
+}
+
+unsafe impl Send for DmaBufFrameBuffer {}
+
+impl AsFrameBuffer for DmaBufFrameBuffer {
+    unsafe fn ptr(&self) -> NonNull<libcamera_framebuffer_t> {
+        self.ptr
+    }
+}
+
+impl Drop for DmaBufFrameBuffer {
+    fn drop(&mut self) {
+        unsafe { libcamera_framebuffer_destroy(self.ptr.as_ptr()) }
+    }
+}
+
+/// `_IOW('b', 0, struct dma_buf_sync)` from `linux/dma-buf.h`.
+const DMA_BUF_IOCTL_SYNC: u64 = 0x4008_6200;
+const DMA_BUF_SYNC_READ: u64 = 1 << 0;
+const DMA_BUF_SYNC_WRITE: u64 = 1 << 1;
+const DMA_BUF_SYNC_END: u64 = 1 << 2;
+
+#[repr(C)]
+struct DmaBufSync {
+    flags: u64,
+}
+
+/// CPU access direction for [sync_start()] and [sync_end()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmaBufAccess {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl DmaBufAccess {
+    fn flags(self) -> u64 {
+        match self {
+            DmaBufAccess::Read => DMA_BUF_SYNC_READ,
+            DmaBufAccess::Write => DMA_BUF_SYNC_WRITE,
+            DmaBufAccess::ReadWrite => DMA_BUF_SYNC_READ | DMA_BUF_SYNC_WRITE,
+        }
+    }
+}
+
+fn sync(fd: BorrowedFd<'_>, flags: u64) -> io::Result<()> {
+    let arg = DmaBufSync { flags };
+    loop {
+        let ret = unsafe { libc::ioctl(fd.as_raw_fd(), DMA_BUF_IOCTL_SYNC as _, &arg) };
+        if ret == 0 {
+            return Ok(());
+        }
+        let err = io::Error::last_os_error();
+        if !matches!(err.raw_os_error(), Some(libc::EINTR | libc::EAGAIN)) {
+            return Err(err);
+        }
+    }
+}
+
+/// Prepares dmabuf for CPU access, invalidating CPU caches of non-coherent buffers.
+///
+/// Must be paired with [sync_end()] using the same access once CPU access is finished.
+pub fn sync_start(fd: impl AsFd, access: DmaBufAccess) -> io::Result<()> {
+    sync(fd.as_fd(), access.flags())
+}
+
+/// Finishes CPU access started by [sync_start()], flushing CPU writes back to memory.
+pub fn sync_end(fd: impl AsFd, access: DmaBufAccess) -> io::Result<()> {
+    sync(fd.as_fd(), access.flags() | DMA_BUF_SYNC_END)
+}
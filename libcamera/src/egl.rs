@@ -0,0 +1,264 @@
+//! Zero-copy import of framebuffers into EGL.
+//!
+//! [EglImageImporter] creates `EGLImageKHR`s from framebuffer dmabufs using `EGL_EXT_image_dma_buf_import`, which can
+//! then be bound to GL textures with `glEGLImageTargetTexture2DOES()` for preview or GPU processing without copying
+//! frame data. The EGL display is provided by the application, i.e. from `glutin` or `khronos-egl`, so this module
+//! does not depend on any particular windowing stack.
+
+use std::{
+    ffi::{c_void, CStr},
+    ptr,
+};
+
+use thiserror::Error;
+
+use crate::{
+    framebuffer::{AsFrameBuffer, PlaneGroup},
+    geometry::Size,
+    pixel_format::PixelFormat,
+    stream::StreamConfigurationRef,
+};
+
+pub type EGLDisplay = *mut c_void;
+pub type EGLImageKHR = *mut c_void;
+type EGLint = i32;
+type EGLenum = u32;
+type EGLBoolean = u32;
+
+const EGL_NONE: EGLint = 0x3038;
+const EGL_WIDTH: EGLint = 0x3057;
+const EGL_HEIGHT: EGLint = 0x3056;
+const EGL_LINUX_DMA_BUF_EXT: EGLenum = 0x3270;
+const EGL_LINUX_DRM_FOURCC_EXT: EGLint = 0x3271;
+const EGL_YUV_COLOR_SPACE_HINT_EXT: EGLint = 0x327B;
+const EGL_SAMPLE_RANGE_HINT_EXT: EGLint = 0x327C;
+const EGL_ITU_REC601_EXT: EGLint = 0x327F;
+const EGL_ITU_REC709_EXT: EGLint = 0x3280;
+const EGL_ITU_REC2020_EXT: EGLint = 0x3281;
+const EGL_YUV_FULL_RANGE_EXT: EGLint = 0x3282;
+const EGL_YUV_NARROW_RANGE_EXT: EGLint = 0x3283;
+const DRM_FORMAT_MOD_INVALID: u64 = 0x00ff_ffff_ffff_ffff;
+
+/// `(fd, offset, pitch, modifier lo, modifier hi)` attributes of each plane.
+const PLANE_ATTRIBS: [[EGLint; 5]; 4] = [
+    [0x3272, 0x3273, 0x3274, 0x3443, 0x3444],
+    [0x3275, 0x3276, 0x3277, 0x3445, 0x3446],
+    [0x3278, 0x3279, 0x327A, 0x3447, 0x3448],
+    [0x3440, 0x3441, 0x3442, 0x3449, 0x344A],
+];
+
+type CreateImageFn = unsafe extern "C" fn(EGLDisplay, *mut c_void, EGLenum, *mut c_void, *const EGLint) -> EGLImageKHR;
+type DestroyImageFn = unsafe extern "C" fn(EGLDisplay, EGLImageKHR) -> EGLBoolean;
+
+#[link(name = "EGL")]
+extern "C" {
+    fn eglGetProcAddress(procname: *const std::ffi::c_char) -> *mut c_void;
+    fn eglQueryString(dpy: EGLDisplay, name: EGLint) -> *const std::ffi::c_char;
+    fn eglGetError() -> EGLint;
+}
+
+const EGL_EXTENSIONS: EGLint = 0x3055;
+
+#[derive(Debug, Error)]
+pub enum EglError {
+    #[error("EGL extension {0} is not supported")]
+    Unsupported(&'static str),
+    #[error("Framebuffer has {0} planes, at most 4 are supported")]
+    TooManyPlanes(usize),
+    #[error("Stride of plane {0} is unknown")]
+    MissingStride(usize),
+    #[error("eglCreateImageKHR failed with error 0x{0:x}")]
+    CreateImage(EGLint),
+}
+
+/// YUV color space hint for [DmaBufImage].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YuvColorSpace {
+    Rec601,
+    Rec709,
+    Rec2020,
+}
+
+/// Description of a dmabuf backed image to import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DmaBufImage {
+    pub size: Size,
+    pub pixel_format: PixelFormat,
+    /// Plane layout with strides filled in, see [PlaneGroup::set_strides()].
+    pub planes: Vec<PlaneGroup>,
+    pub color_space: Option<YuvColorSpace>,
+    /// Whether YUV values use the full range, as opposed to the limited (studio) range.
+    pub full_range: Option<bool>,
+}
+
+impl DmaBufImage {
+    /// Describes a framebuffer of a stream with the given configuration.
+    pub fn new(fb: &impl AsFrameBuffer, cfg: &StreamConfigurationRef<'_>) -> Self {
+        let pixel_format = cfg.get_pixel_format();
+        let mut planes = fb.plane_groups();
+        PlaneGroup::set_strides(&mut planes, pixel_format, cfg.get_stride());
+        Self {
+            size: cfg.get_size(),
+            pixel_format,
+            planes,
+            color_space: None,
+            full_range: None,
+        }
+    }
+
+    fn attribs(&self) -> Result<Vec<EGLint>, EglError> {
+        let mut attribs = vec![
+            EGL_WIDTH,
+            self.size.width as _,
+            EGL_HEIGHT,
+            self.size.height as _,
+            EGL_LINUX_DRM_FOURCC_EXT,
+            self.pixel_format.fourcc() as _,
+        ];
+
+        let modifier = self.pixel_format.modifier();
+        let planes = self
+            .planes
+            .iter()
+            .flat_map(|g| g.planes.iter().map(move |p| (g.fd, p)))
+            .collect::<Vec<_>>();
+        if planes.len() > PLANE_ATTRIBS.len() {
+            return Err(EglError::TooManyPlanes(planes.len()));
+        }
+
+        for (i, (fd, plane)) in planes.into_iter().enumerate() {
+            let [fd_attr, offset_attr, pitch_attr, mod_lo_attr, mod_hi_attr] = PLANE_ATTRIBS[i];
+            let stride = plane.stride.ok_or(EglError::MissingStride(plane.index))?;
+            attribs.extend([fd_attr, fd, offset_attr, plane.offset as _, pitch_attr, stride as _]);
+            // Linear buffers are imported without a modifier, which does not require the modifiers extension
+            if modifier != 0 && modifier != DRM_FORMAT_MOD_INVALID {
+                attribs.extend([
+                    mod_lo_attr,
+                    modifier as u32 as _,
+                    mod_hi_attr,
+                    (modifier >> 32) as u32 as _,
+                ]);
+            }
+        }
+
+        if let Some(color_space) = self.color_space {
+            attribs.extend([
+                EGL_YUV_COLOR_SPACE_HINT_EXT,
+                match color_space {
+                    YuvColorSpace::Rec601 => EGL_ITU_REC601_EXT,
+                    YuvColorSpace::Rec709 => EGL_ITU_REC709_EXT,
+                    YuvColorSpace::Rec2020 => EGL_ITU_REC2020_EXT,
+                },
+            ]);
+        }
+        if let Some(full_range) = self.full_range {
+            attribs.extend([
+                EGL_SAMPLE_RANGE_HINT_EXT,
+                if full_range {
+                    EGL_YUV_FULL_RANGE_EXT
+                } else {
+                    EGL_YUV_NARROW_RANGE_EXT
+                },
+            ]);
+        }
+
+        attribs.push(EGL_NONE);
+        Ok(attribs)
+    }
+}
+
+/// Creates `EGLImageKHR`s from dmabufs on a given EGL display.
+#[derive(Debug)]
+pub struct EglImageImporter {
+    display: EGLDisplay,
+    create: CreateImageFn,
+    destroy: DestroyImageFn,
+}
+
+impl EglImageImporter {
+    /// Creates importer for an initialized EGL display.
+    ///
+    /// # Safety
+    ///
+    /// `display` must be a valid and initialized `EGLDisplay`, which outlives the importer and all images created by
+    /// it.
+    pub unsafe fn new(display: EGLDisplay) -> Result<Self, EglError> {
+        let extensions = eglQueryString(display, EGL_EXTENSIONS);
+        let extensions = if extensions.is_null() {
+            ""
+        } else {
+            CStr::from_ptr(extensions).to_str().unwrap_or("")
+        };
+        if !extensions.split(' ').any(|e| e == "EGL_EXT_image_dma_buf_import") {
+            return Err(EglError::Unsupported("EGL_EXT_image_dma_buf_import"));
+        }
+
+        let create = eglGetProcAddress(c"eglCreateImageKHR".as_ptr());
+        let destroy = eglGetProcAddress(c"eglDestroyImageKHR".as_ptr());
+        if create.is_null() || destroy.is_null() {
+            return Err(EglError::Unsupported("EGL_KHR_image_base"));
+        }
+
+        Ok(Self {
+            display,
+            create: core::mem::transmute::<*mut c_void, CreateImageFn>(create),
+            destroy: core::mem::transmute::<*mut c_void, DestroyImageFn>(destroy),
+        })
+    }
+
+    /// Imports an image.
+    ///
+    /// EGL keeps its own reference to the dmabufs, so the framebuffer may be reused by the camera while the image
+    /// exists. The image then shows the new frame contents once the buffer is filled again.
+    pub fn import(&self, image: &DmaBufImage) -> Result<EglImage, EglError> {
+        let attribs = image.attribs()?;
+        let ptr = unsafe {
+            (self.create)(
+                self.display,
+                ptr::null_mut(),
+                EGL_LINUX_DMA_BUF_EXT,
+                ptr::null_mut(),
+                attribs.as_ptr(),
+            )
+        };
+        if ptr.is_null() {
+            return Err(EglError::CreateImage(unsafe { eglGetError() }));
+        }
+
+        Ok(EglImage {
+            display: self.display,
+            image: ptr,
+            destroy: self.destroy,
+        })
+    }
+
+    /// Imports a framebuffer of a stream with the given configuration, see [DmaBufImage::new()].
+    pub fn import_framebuffer(
+        &self,
+        fb: &impl AsFrameBuffer,
+        cfg: &StreamConfigurationRef<'_>,
+    ) -> Result<EglImage, EglError> {
+        self.import(&DmaBufImage::new(fb, cfg))
+    }
+}
+
+/// An imported `EGLImageKHR`, destroyed on drop.
+#[derive(Debug)]
+pub struct EglImage {
+    display: EGLDisplay,
+    image: EGLImageKHR,
+    destroy: DestroyImageFn,
+}
+
+impl EglImage {
+    /// Returns raw image handle, i.e. for `glEGLImageTargetTexture2DOES()`.
+    pub fn as_ptr(&self) -> EGLImageKHR {
+        self.image
+    }
+}
+
+impl Drop for EglImage {
+    fn drop(&mut self) {
+        unsafe { (self.destroy)(self.display, self.image) };
+    }
+}
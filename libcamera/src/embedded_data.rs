@@ -0,0 +1,241 @@
+//! Parsing of sensor embedded data, as produced by the Sony sensors used on Raspberry Pi cameras.
+//!
+//! Sensors output a copy of their registers in SMIA/CCS embedded data lines alongside each frame, which shows the
+//! exposure and gain the frame was actually captured with. This allows to cross-check them against request metadata,
+//! i.e. to detect AGC settings applied a frame late.
+//!
+//! libcamera consumes embedded data within the pipeline handler and does not expose it as an application stream, so
+//! the buffer has to be obtained separately, i.e. from the embedded data node of the CSI-2 receiver.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use thiserror::Error;
+
+use crate::{control::ControlList, controls};
+
+const LINE_START: u8 = 0x0a;
+const LINE_END: u8 = 0x07;
+const REG_HI_BITS: u8 = 0xaa;
+const REG_LOW_BITS: u8 = 0xa5;
+const REG_VALUE: u8 = 0x5a;
+const REG_SKIP: u8 = 0x55;
+
+#[derive(Debug, Error)]
+pub enum EmbeddedDataError {
+    #[error("Embedded data line does not start with a line start tag")]
+    NoLineStart,
+    #[error("Unexpected byte {0:#04x} in place of a dummy byte")]
+    BadDummy(u8),
+    #[error("Illegal tag {0:#04x}")]
+    IllegalTag(u8),
+    #[error("Embedded data ended before the line end tag")]
+    Truncated,
+    #[error("Register {0:#06x} is missing from embedded data")]
+    MissingRegister(u16),
+}
+
+/// Sensors with known register layouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sensor {
+    /// Camera Module v2.
+    Imx219,
+    /// High Quality Camera.
+    Imx477,
+    /// Camera Module 3.
+    Imx708,
+}
+
+impl Sensor {
+    /// Detects the sensor from the [Model](crate::properties::Model) property, i.e. `imx708_wide`.
+    pub fn from_model(model: &str) -> Option<Self> {
+        let model = model.to_ascii_lowercase();
+        [
+            ("imx219", Self::Imx219),
+            ("imx477", Self::Imx477),
+            ("imx708", Self::Imx708),
+        ]
+        .into_iter()
+        .find_map(|(name, sensor)| model.starts_with(name).then_some(sensor))
+    }
+
+    fn registers(&self) -> SensorRegisters {
+        match self {
+            Self::Imx219 => SensorRegisters {
+                exposure: [0x15a, 0x15b],
+                gain: &[0x157],
+                frame_length: [0x160, 0x161],
+                gain_max_code: 256,
+            },
+            Self::Imx477 | Self::Imx708 => SensorRegisters {
+                exposure: [0x0202, 0x0203],
+                gain: &[0x0204, 0x0205],
+                frame_length: [0x0340, 0x0341],
+                gain_max_code: 1024,
+            },
+        }
+    }
+}
+
+struct SensorRegisters {
+    exposure: [u16; 2],
+    /// Analogue gain code registers, most significant first.
+    gain: &'static [u16],
+    frame_length: [u16; 2],
+    /// Gain is `gain_max_code / (gain_max_code - code)`.
+    gain_max_code: u32,
+}
+
+/// Register values of a single frame, parsed from SMIA/CCS embedded data.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EmbeddedRegisters {
+    registers: BTreeMap<u16, u8>,
+}
+
+impl EmbeddedRegisters {
+    /// Parses embedded data lines.
+    ///
+    /// Embedded data is packed like the image data, so `bits_per_sample` is the bit depth of the raw sensor mode.
+    /// `line_length` is the length of a line in bytes including padding, which is needed to continue parsing in
+    /// following lines if registers do not fit into the first one. Pass [None] to only parse the first line.
+    pub fn parse(data: &[u8], bits_per_sample: u32, line_length: Option<usize>) -> Result<Self, EmbeddedDataError> {
+        let mut registers = BTreeMap::new();
+        let mut line_start = 0;
+        loop {
+            if data.get(line_start) != Some(&LINE_START) {
+                return Err(EmbeddedDataError::NoLineStart);
+            }
+            Self::parse_line(&data[line_start..], bits_per_sample, &mut registers)?;
+
+            match line_length {
+                Some(len) if len > 0 && line_start + len < data.len() && data[line_start + len] == LINE_START => {
+                    line_start += len;
+                }
+                _ => return Ok(Self { registers }),
+            }
+        }
+    }
+
+    fn parse_line(
+        line: &[u8],
+        bits_per_sample: u32,
+        registers: &mut BTreeMap<u16, u8>,
+    ) -> Result<(), EmbeddedDataError> {
+        // Packed 10 and 12 bit lines carry low bits of the preceding samples in every 5th or 3rd byte
+        let is_dummy = |offset: usize| match bits_per_sample {
+            10 => (offset + 1).is_multiple_of(5),
+            12 => (offset + 1).is_multiple_of(3),
+            _ => false,
+        };
+        let mut bytes = line.iter().copied().enumerate().skip(1);
+        let next = |bytes: &mut dyn Iterator<Item = (usize, u8)>| -> Result<u8, EmbeddedDataError> {
+            loop {
+                let (offset, byte) = bytes.next().ok_or(EmbeddedDataError::Truncated)?;
+                if !is_dummy(offset) {
+                    return Ok(byte);
+                }
+                if byte != REG_SKIP {
+                    return Err(EmbeddedDataError::BadDummy(byte));
+                }
+            }
+        };
+
+        let mut address = 0u16;
+        loop {
+            let tag = next(&mut bytes)?;
+            let data = next(&mut bytes)?;
+            match tag {
+                LINE_END => return Ok(()),
+                REG_HI_BITS => address = (address & 0x00ff) | (data as u16) << 8,
+                REG_LOW_BITS => address = (address & 0xff00) | data as u16,
+                REG_SKIP => address = address.wrapping_add(1),
+                REG_VALUE => {
+                    registers.insert(address, data);
+                    address = address.wrapping_add(1);
+                }
+                tag => return Err(EmbeddedDataError::IllegalTag(tag)),
+            }
+        }
+    }
+
+    /// Returns value of a single register.
+    pub fn get(&self, address: u16) -> Option<u8> {
+        self.registers.get(&address).copied()
+    }
+
+    /// Returns value of consecutive registers interpreted as a big-endian number.
+    pub fn get_be(&self, addresses: &[u16]) -> Result<u32, EmbeddedDataError> {
+        addresses.iter().try_fold(0u32, |acc, &address| {
+            let v = self.get(address).ok_or(EmbeddedDataError::MissingRegister(address))?;
+            Ok(acc << 8 | v as u32)
+        })
+    }
+
+    /// Returns all parsed registers by address.
+    pub fn registers(&self) -> &BTreeMap<u16, u8> {
+        &self.registers
+    }
+}
+
+/// Exposure and gain a frame was captured with, derived from sensor registers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorFrameValues {
+    /// Exposure in lines.
+    pub exposure_lines: u32,
+    pub analogue_gain: f32,
+    /// Frame length in lines, including vertical blanking.
+    pub frame_length: u32,
+}
+
+impl SensorFrameValues {
+    /// Parses embedded data of the given sensor, see [EmbeddedRegisters::parse()].
+    pub fn parse(
+        sensor: Sensor,
+        data: &[u8],
+        bits_per_sample: u32,
+        line_length: Option<usize>,
+    ) -> Result<Self, EmbeddedDataError> {
+        Self::from_registers(sensor, &EmbeddedRegisters::parse(data, bits_per_sample, line_length)?)
+    }
+
+    pub fn from_registers(sensor: Sensor, registers: &EmbeddedRegisters) -> Result<Self, EmbeddedDataError> {
+        let regs = sensor.registers();
+        let gain_code = registers.get_be(regs.gain)?;
+        Ok(Self {
+            exposure_lines: registers.get_be(&regs.exposure)?,
+            analogue_gain: regs.gain_max_code as f32 / regs.gain_max_code.saturating_sub(gain_code).max(1) as f32,
+            frame_length: registers.get_be(&regs.frame_length)?,
+        })
+    }
+
+    /// Returns exposure time for the duration of a single line of the sensor mode.
+    pub fn exposure_time(&self, line_duration: Duration) -> Duration {
+        line_duration * self.exposure_lines
+    }
+
+    /// Compares sensor values with `ExposureTime` and `AnalogueGain` reported in request metadata of the same frame.
+    pub fn compare(&self, metadata: &ControlList, line_duration: Duration) -> MetadataComparison {
+        let exposure_time = self.exposure_time(line_duration).as_micros() as i64;
+        MetadataComparison {
+            exposure_time_diff: metadata
+                .get::<controls::ExposureTime>()
+                .ok()
+                .map(|e| exposure_time - e.0 as i64),
+            analogue_gain_diff: metadata
+                .get::<controls::AnalogueGain>()
+                .ok()
+                .map(|g| self.analogue_gain - g.0),
+        }
+    }
+}
+
+/// Differences between sensor values and request metadata, see [SensorFrameValues::compare()].
+///
+/// Values are [None] if metadata does not contain the control. Small exposure differences are expected, as metadata
+/// is rounded to whole lines by the pipeline using its own line duration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetadataComparison {
+    /// Sensor exposure time minus metadata `ExposureTime`, in microseconds.
+    pub exposure_time_diff: Option<i64>,
+    /// Sensor analogue gain minus metadata `AnalogueGain`.
+    pub analogue_gain_diff: Option<f32>,
+}
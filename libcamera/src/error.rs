@@ -0,0 +1,117 @@
+//! Error type of camera and request operations.
+//!
+//! libcamera reports failures as negative errno values. [Error] classifies the common ones into variants that can be
+//! matched on, i.e. to retry on [Error::Busy] or to give up on [Error::Disconnected], while the original errno remains
+//! available through [Error::errno()].
+
+use std::{ffi::c_int, io};
+
+use thiserror::Error;
+
+use crate::control::ControlError;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// libcamera is not installed, only returned with the `dlopen` feature.
+    #[error("{0}")]
+    LibraryNotFound(String),
+    #[error("Failed to start camera manager: {}", io::Error::from_raw_os_error(*errno))]
+    ManagerStartFailed { errno: i32 },
+    #[error("Camera is in use")]
+    Busy,
+    #[error("Camera was disconnected")]
+    Disconnected,
+    #[error("Operation is not permitted in the current camera state")]
+    InvalidState,
+    #[error("Operation is not supported by the linked libcamera version")]
+    Unsupported,
+    #[error("Failed to acquire camera: {}", io::Error::from_raw_os_error(*errno))]
+    AcquireFailed { errno: i32 },
+    #[error("Failed to release camera: {}", io::Error::from_raw_os_error(*errno))]
+    ReleaseFailed { errno: i32 },
+    #[error("Camera configuration is invalid")]
+    ConfigurationInvalid,
+    #[error("Failed to configure camera: {}", io::Error::from_raw_os_error(*errno))]
+    ConfigureFailed { errno: i32 },
+    #[error("Failed to start camera: {}", io::Error::from_raw_os_error(*errno))]
+    StartFailed { errno: i32 },
+    #[error("Failed to stop camera: {}", io::Error::from_raw_os_error(*errno))]
+    StopFailed { errno: i32 },
+    #[error("Request has buffers of a stream from a previous configuration")]
+    StaleStream,
+    #[error("Request controls are invalid: {0}")]
+    InvalidControls(#[from] ControlError),
+    #[error("Failed to add buffer to request: {}", io::Error::from_raw_os_error(*errno))]
+    BufferFailed { errno: i32 },
+    #[error("Failed to queue request: {}", io::Error::from_raw_os_error(*errno))]
+    QueueFailed { errno: i32 },
+    #[error("Request queue is full")]
+    QueueFull,
+    /// Request was queued before and completed, it has to be reset with
+    /// [Request::reuse()](crate::request::Request::reuse) before queueing it again.
+    #[error("Request was already queued and not reused since")]
+    AlreadyQueued,
+    #[error("Request did not complete in time")]
+    Timeout,
+    #[error("Request was cancelled")]
+    Cancelled,
+}
+
+impl Error {
+    /// Classifies a negative libcamera return value, falling back to `other` for errno values without a dedicated
+    /// variant.
+    pub(crate) fn from_ret(ret: c_int, other: impl FnOnce(i32) -> Self) -> Self {
+        // libcamera return codes are negative errno values, which are not always negated back by the C API
+        match ret.abs() {
+            libc::EBUSY => Self::Busy,
+            libc::ENODEV => Self::Disconnected,
+            libc::EACCES => Self::InvalidState,
+            libc::ENOTSUP => Self::Unsupported,
+            errno => other(errno),
+        }
+    }
+
+    /// Returns the errno reported by libcamera, if the error originates from it.
+    pub fn errno(&self) -> Option<i32> {
+        match self {
+            Self::Busy => Some(libc::EBUSY),
+            Self::Disconnected => Some(libc::ENODEV),
+            Self::InvalidState => Some(libc::EACCES),
+            Self::Unsupported => Some(libc::ENOTSUP),
+            Self::ConfigurationInvalid => Some(libc::EINVAL),
+            Self::ManagerStartFailed { errno }
+            | Self::AcquireFailed { errno }
+            | Self::ReleaseFailed { errno }
+            | Self::ConfigureFailed { errno }
+            | Self::StartFailed { errno }
+            | Self::StopFailed { errno }
+            | Self::BufferFailed { errno }
+            | Self::QueueFailed { errno } => Some(*errno),
+            Self::LibraryNotFound(_)
+            | Self::StaleStream
+            | Self::InvalidControls(_)
+            | Self::QueueFull
+            | Self::AlreadyQueued
+            | Self::Timeout
+            | Self::Cancelled => None,
+        }
+    }
+}
+
+/// Converts to an [io::Error] with the original errno, so that errors can still be propagated as [io::Result].
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e.errno() {
+            Some(errno) => io::Error::from_raw_os_error(errno),
+            None => match e {
+                Error::LibraryNotFound(_) => io::Error::new(io::ErrorKind::NotFound, e),
+                Error::Timeout => io::Error::new(io::ErrorKind::TimedOut, e),
+                Error::QueueFull => io::Error::new(io::ErrorKind::WouldBlock, e),
+                Error::StaleStream | Error::InvalidControls(_) | Error::AlreadyQueued => {
+                    io::Error::new(io::ErrorKind::InvalidInput, e)
+                }
+                _ => io::Error::other(e),
+            },
+        }
+    }
+}
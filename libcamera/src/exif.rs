@@ -0,0 +1,349 @@
+//! EXIF metadata for JPEG images produced from captures.
+//!
+//! [ExifBuilder] collects capture parameters from request metadata and camera properties and serializes them into an
+//! EXIF `APP1` segment, which [ExifBuilder::insert()] adds to an encoded JPEG, i.e. one produced by `JpegEncoder` of
+//! the `jpeg` feature.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use thiserror::Error;
+
+use crate::{
+    control::{ControlList, PropertyList},
+    controls::{AnalogueGain, ExposureTime, LensPosition, SensorTimestamp},
+    orientation::Orientation,
+    properties::Model,
+    resume::clock_time,
+};
+
+#[derive(Debug, Error)]
+pub enum ExifError {
+    /// Data does not start with a JPEG SOI marker
+    #[error("Not a JPEG image")]
+    NotJpeg,
+    /// EXIF data does not fit into a single JPEG segment
+    #[error("EXIF data is {0} bytes long, which exceeds the JPEG segment limit")]
+    TooLarge(usize),
+}
+
+const TAG_MAKE: u16 = 0x010f;
+const TAG_MODEL: u16 = 0x0110;
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_EXPOSURE_TIME: u16 = 0x829a;
+const TAG_ISO: u16 = 0x8827;
+const TAG_EXIF_VERSION: u16 = 0x9000;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+const TAG_OFFSET_TIME_ORIGINAL: u16 = 0x9011;
+const TAG_SUBJECT_DISTANCE: u16 = 0x9206;
+const TAG_SUBSEC_TIME_ORIGINAL: u16 = 0x9291;
+
+const TYPE_ASCII: u16 = 2;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+const TYPE_RATIONAL: u16 = 5;
+const TYPE_UNDEFINED: u16 = 7;
+
+/// Largest payload of a JPEG marker segment, excluding the length field.
+const MAX_SEGMENT_LEN: usize = 65533;
+
+/// Builds EXIF metadata of a captured frame.
+///
+/// ```ignore
+/// let jpeg = encoder.encode_buffer(framebuffer)?;
+/// let jpeg = ExifBuilder::new()
+///     .properties(cam.properties())
+///     .metadata(req.metadata())
+///     .insert(&jpeg)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExifBuilder {
+    make: Option<String>,
+    model: Option<String>,
+    orientation: Option<Orientation>,
+    exposure_time: Option<Duration>,
+    iso: Option<u16>,
+    /// Subject distance in meters, [f32::INFINITY] when focused at infinity.
+    subject_distance: Option<f32>,
+    timestamp: Option<SystemTime>,
+}
+
+impl ExifBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets camera manufacturer, which libcamera does not report.
+    pub fn make(mut self, make: impl Into<String>) -> Self {
+        self.make = Some(make.into());
+        self
+    }
+
+    /// Sets camera model.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Sets orientation of the encoded image, i.e. [CameraConfiguration::orientation()] if frames are not rotated
+    /// before encoding.
+    ///
+    /// [CameraConfiguration::orientation()]: crate::camera::CameraConfiguration::orientation
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = Some(orientation);
+        self
+    }
+
+    pub fn exposure_time(mut self, exposure_time: Duration) -> Self {
+        self.exposure_time = Some(exposure_time);
+        self
+    }
+
+    pub fn iso(mut self, iso: u16) -> Self {
+        self.iso = Some(iso);
+        self
+    }
+
+    /// Sets ISO from analogue gain, where gain of 1.0 corresponds to ISO 100.
+    pub fn analogue_gain(self, gain: f32) -> Self {
+        self.iso((gain * 100.0).round().clamp(0.0, u16::MAX as f32) as u16)
+    }
+
+    /// Sets subject distance from lens position in dioptres, as used by [LensPosition].
+    pub fn lens_position(mut self, dioptres: f32) -> Self {
+        self.subject_distance = Some(if dioptres > 0.0 { 1.0 / dioptres } else { f32::INFINITY });
+        self
+    }
+
+    /// Sets capture time, which is written in UTC.
+    pub fn timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Takes camera model from camera properties.
+    pub fn properties(mut self, properties: &PropertyList) -> Self {
+        if let Ok(model) = properties.get::<Model>() {
+            self.model = Some(model.0);
+        }
+        self
+    }
+
+    /// Takes exposure time, analogue gain, lens position and sensor timestamp from request metadata.
+    ///
+    /// The sensor timestamp is converted to wall clock time, so metadata should be applied soon after the request
+    /// completed.
+    pub fn metadata(mut self, metadata: &ControlList) -> Self {
+        if let Ok(exposure) = metadata.get::<ExposureTime>() {
+            self.exposure_time = Some(Duration::from_micros(exposure.max(0) as u64));
+        }
+        if let Ok(gain) = metadata.get::<AnalogueGain>() {
+            self = self.analogue_gain(*gain);
+        }
+        if let Ok(position) = metadata.get::<LensPosition>() {
+            self = self.lens_position(*position);
+        }
+        if let Ok(timestamp) = metadata.get::<SensorTimestamp>() {
+            if let Ok(now) = clock_time(libc::CLOCK_MONOTONIC) {
+                let age = now.saturating_sub(Duration::from_nanos(timestamp.max(0) as u64));
+                self.timestamp = SystemTime::now().checked_sub(age);
+            }
+        }
+        self
+    }
+
+    /// Serializes metadata into the payload of an `APP1` segment, starting with the `Exif` identifier.
+    pub fn build(&self) -> Vec<u8> {
+        let mut ifd0 = Vec::new();
+        let mut exif = vec![IfdEntry::new(TAG_EXIF_VERSION, TYPE_UNDEFINED, 4, b"0231".to_vec())];
+
+        if let Some(make) = &self.make {
+            ifd0.push(IfdEntry::ascii(TAG_MAKE, make));
+        }
+        if let Some(model) = &self.model {
+            ifd0.push(IfdEntry::ascii(TAG_MODEL, model));
+        }
+        if let Some(orientation) = self.orientation {
+            ifd0.push(IfdEntry::new(
+                TAG_ORIENTATION,
+                TYPE_SHORT,
+                1,
+                orientation.to_exif().to_le_bytes().to_vec(),
+            ));
+        }
+        if let Some((datetime, subsec)) = self.timestamp.and_then(format_datetime) {
+            ifd0.push(IfdEntry::ascii(TAG_DATE_TIME, &datetime));
+            exif.push(IfdEntry::ascii(TAG_DATE_TIME_ORIGINAL, &datetime));
+            exif.push(IfdEntry::ascii(TAG_OFFSET_TIME_ORIGINAL, "+00:00"));
+            exif.push(IfdEntry::ascii(TAG_SUBSEC_TIME_ORIGINAL, &subsec));
+        }
+        if let Some(exposure) = self.exposure_time {
+            let (num, den) = reduce(exposure.as_micros().min(u32::MAX as u128) as u32, 1_000_000);
+            exif.push(IfdEntry::rational(TAG_EXPOSURE_TIME, num, den));
+        }
+        if let Some(iso) = self.iso {
+            exif.push(IfdEntry::new(TAG_ISO, TYPE_SHORT, 1, iso.to_le_bytes().to_vec()));
+        }
+        if let Some(distance) = self.subject_distance {
+            // Numerator of 0xFFFFFFFF denotes infinity
+            let (num, den) = if distance.is_finite() {
+                ((distance * 1000.0).round().min(u32::MAX as f32 - 1.0) as u32, 1000)
+            } else {
+                (u32::MAX, 1)
+            };
+            exif.push(IfdEntry::rational(TAG_SUBJECT_DISTANCE, num, den));
+        }
+
+        // TIFF header is followed by IFD0 and then by the Exif IFD it points to
+        let exif_offset = 8 + ifd_len(&ifd0) + 12;
+        ifd0.push(IfdEntry::new(
+            TAG_EXIF_IFD,
+            TYPE_LONG,
+            1,
+            (exif_offset as u32).to_le_bytes().to_vec(),
+        ));
+
+        let mut out = b"Exif\0\0".to_vec();
+        let mut tiff = vec![b'I', b'I', 42, 0, 8, 0, 0, 0];
+        write_ifd(&mut tiff, &mut ifd0);
+        write_ifd(&mut tiff, &mut exif);
+        out.extend(tiff);
+        out
+    }
+
+    /// Inserts EXIF metadata into an encoded JPEG image.
+    ///
+    /// The `APP1` segment is placed right after the start of image marker, or after the JFIF `APP0` segment if present.
+    pub fn insert(&self, jpeg: &[u8]) -> Result<Vec<u8>, ExifError> {
+        if !jpeg.starts_with(&[0xff, 0xd8]) {
+            return Err(ExifError::NotJpeg);
+        }
+        let payload = self.build();
+        if payload.len() > MAX_SEGMENT_LEN {
+            return Err(ExifError::TooLarge(payload.len()));
+        }
+
+        let mut pos = 2;
+        if jpeg.get(2..4) == Some(&[0xff, 0xe0]) {
+            let len = jpeg.get(4..6).map(|l| u16::from_be_bytes([l[0], l[1]]) as usize);
+            pos = len
+                .map(|len| 4 + len)
+                .filter(|end| *end <= jpeg.len())
+                .ok_or(ExifError::NotJpeg)?;
+        }
+
+        let mut out = Vec::with_capacity(jpeg.len() + payload.len() + 4);
+        out.extend(&jpeg[..pos]);
+        out.extend([0xff, 0xe1]);
+        out.extend(((payload.len() + 2) as u16).to_be_bytes());
+        out.extend(payload);
+        out.extend(&jpeg[pos..]);
+        Ok(out)
+    }
+}
+
+struct IfdEntry {
+    tag: u16,
+    format: u16,
+    count: u32,
+    /// Little-endian value, stored inline if it fits into 4 bytes.
+    data: Vec<u8>,
+}
+
+impl IfdEntry {
+    fn new(tag: u16, format: u16, count: u32, data: Vec<u8>) -> Self {
+        Self {
+            tag,
+            format,
+            count,
+            data,
+        }
+    }
+
+    fn ascii(tag: u16, value: &str) -> Self {
+        let mut data = value.as_bytes().to_vec();
+        data.push(0);
+        Self::new(tag, TYPE_ASCII, data.len() as u32, data)
+    }
+
+    fn rational(tag: u16, num: u32, den: u32) -> Self {
+        let mut data = num.to_le_bytes().to_vec();
+        data.extend(den.to_le_bytes());
+        Self::new(tag, TYPE_RATIONAL, 1, data)
+    }
+}
+
+/// Serialized length of an IFD including out of line values.
+fn ifd_len(entries: &[IfdEntry]) -> usize {
+    let values: usize = entries
+        .iter()
+        .filter(|e| e.data.len() > 4)
+        .map(|e| e.data.len().next_multiple_of(2))
+        .sum();
+    2 + 12 * entries.len() + 4 + values
+}
+
+/// Appends an IFD without a following IFD to `tiff`, whose start is the offset base.
+fn write_ifd(tiff: &mut Vec<u8>, entries: &mut [IfdEntry]) {
+    entries.sort_by_key(|e| e.tag);
+
+    let mut values = Vec::new();
+    let values_offset = tiff.len() + 2 + 12 * entries.len() + 4;
+    tiff.extend((entries.len() as u16).to_le_bytes());
+    for entry in entries.iter() {
+        tiff.extend(entry.tag.to_le_bytes());
+        tiff.extend(entry.format.to_le_bytes());
+        tiff.extend(entry.count.to_le_bytes());
+        if entry.data.len() <= 4 {
+            let mut inline = [0; 4];
+            inline[..entry.data.len()].copy_from_slice(&entry.data);
+            tiff.extend(inline);
+        } else {
+            tiff.extend(((values_offset + values.len()) as u32).to_le_bytes());
+            values.extend(&entry.data);
+            if values.len() % 2 == 1 {
+                values.push(0);
+            }
+        }
+    }
+    tiff.extend(0u32.to_le_bytes());
+    tiff.extend(values);
+}
+
+fn reduce(num: u32, den: u32) -> (u32, u32) {
+    let (mut a, mut b) = (num, den);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    match a {
+        0 => (0, 1),
+        gcd => (num / gcd, den / gcd),
+    }
+}
+
+/// Formats time as EXIF `YYYY:MM:DD HH:MM:SS` in UTC and milliseconds for the sub-second tag.
+fn format_datetime(time: SystemTime) -> Option<(String, String)> {
+    let since_epoch = time.duration_since(UNIX_EPOCH).ok()?;
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = ((secs / 86400) as i64, secs % 86400);
+
+    // Civil date from days since epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let datetime = format!(
+        "{year:04}:{month:02}:{day:02} {:02}:{:02}:{:02}",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    );
+    Some((datetime, format!("{:03}", since_epoch.subsec_millis())))
+}
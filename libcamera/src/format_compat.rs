@@ -0,0 +1,105 @@
+//! Pixel formats accepted by common downstream consumers, enabled by the `format-compat` feature.
+//!
+//! Helps to pick a stream format during negotiation that the consumer actually accepts, instead of finding out when
+//! importing the first frame. The table lists formats with linear layout that are widely supported by the respective
+//! consumers (i.e. vc4, v3d and i915 drivers, Mesa, stateful V4L2 H.264 encoders), so a format missing from it may
+//! still work on a specific device, and listed formats are not guaranteed on every device.
+
+use crate::pixel_format::{formats, PixelFormat};
+
+/// Downstream consumer of frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Consumer {
+    /// Scanout on a DRM/KMS plane, see [KmsSink](crate::kms::KmsSink).
+    Kms,
+    /// GL textures imported with `EGL_EXT_image_dma_buf_import`.
+    Gl,
+    /// V4L2 memory-to-memory video encoders, i.e. `bcm2835-codec` on Raspberry Pi.
+    V4l2Encoder,
+}
+
+const KMS_FORMATS: &[PixelFormat] = &[
+    formats::XRGB8888,
+    formats::XBGR8888,
+    formats::ARGB8888,
+    formats::ABGR8888,
+    formats::RGB565,
+    formats::RGB888,
+    formats::BGR888,
+    formats::NV12,
+    formats::NV21,
+    formats::NV16,
+    formats::NV61,
+    formats::YUV420,
+    formats::YVU420,
+    formats::YUYV,
+    formats::UYVY,
+];
+
+const GL_FORMATS: &[PixelFormat] = &[
+    formats::XRGB8888,
+    formats::XBGR8888,
+    formats::ARGB8888,
+    formats::ABGR8888,
+    formats::RGB565,
+    formats::R8,
+    formats::NV12,
+    formats::NV21,
+    formats::YUV420,
+    formats::YVU420,
+    formats::YUYV,
+    formats::UYVY,
+];
+
+const V4L2_ENCODER_FORMATS: &[PixelFormat] = &[
+    formats::YUV420,
+    formats::YVU420,
+    formats::NV12,
+    formats::NV21,
+    formats::YUYV,
+    formats::YVYU,
+    formats::UYVY,
+    formats::VYUY,
+];
+
+impl Consumer {
+    /// Returns formats accepted by the consumer.
+    pub fn formats(&self) -> &'static [PixelFormat] {
+        match self {
+            Self::Kms => KMS_FORMATS,
+            Self::Gl => GL_FORMATS,
+            Self::V4l2Encoder => V4L2_ENCODER_FORMATS,
+        }
+    }
+
+    pub fn accepts(&self, format: PixelFormat) -> bool {
+        self.formats().contains(&format)
+    }
+}
+
+impl PixelFormat {
+    /// Returns `true` if the format can be scanned out on a DRM/KMS plane, see [Consumer::Kms].
+    pub fn is_displayable_kms(&self) -> bool {
+        Consumer::Kms.accepts(*self)
+    }
+
+    /// Returns `true` if the format can be imported into GL through EGL, see [Consumer::Gl].
+    pub fn is_gl_importable(&self) -> bool {
+        Consumer::Gl.accepts(*self)
+    }
+
+    /// Returns `true` if the format is accepted by V4L2 video encoders, see [Consumer::V4l2Encoder].
+    pub fn is_v4l2_encoder_friendly(&self) -> bool {
+        Consumer::V4l2Encoder.accepts(*self)
+    }
+}
+
+/// Returns the first of the `offered` formats accepted by all `consumers`.
+///
+/// Pass formats in order of preference, i.e. as listed by
+/// [StreamFormatsRef::pixel_formats()](crate::stream::StreamFormatsRef::pixel_formats).
+pub fn negotiate(offered: impl IntoIterator<Item = PixelFormat>, consumers: &[Consumer]) -> Option<PixelFormat> {
+    offered
+        .into_iter()
+        .find(|format| consumers.iter().all(|consumer| consumer.accepts(*format)))
+}
@@ -0,0 +1,157 @@
+//! Completed requests with typed access to their buffers and metadata.
+//!
+//! [ActiveCamera::on_frame()](crate::camera::ActiveCamera::on_frame) hands out [CompletedFrame]s instead of raw
+//! [Request]s, for applications that attach [MemoryMappedFrameBuffer]s to their requests.
+
+use std::time::Duration;
+
+use crate::{
+    control::ControlList,
+    controls::{
+        AeLocked, AnalogueGain, ColourGains, ColourTemperature, DigitalGain, ExposureTime, FrameDuration, LensPosition,
+        Lux, SensorTemperature, SensorTimestamp,
+    },
+    framebuffer_allocator::FrameBuffer,
+    framebuffer_map::MemoryMappedFrameBuffer,
+    request::{Request, RequestStatus},
+    stream::Stream,
+};
+
+/// Typed view of common request metadata.
+///
+/// Each accessor returns [None] if the pipeline did not report the control. Use [FrameMetadata::controls()] for
+/// anything else.
+#[derive(Clone, Copy)]
+pub struct FrameMetadata<'a> {
+    controls: &'a ControlList,
+}
+
+impl<'a> FrameMetadata<'a> {
+    pub fn new(controls: &'a ControlList) -> Self {
+        Self { controls }
+    }
+
+    pub fn controls(&self) -> &'a ControlList {
+        self.controls
+    }
+
+    /// Time when the first row of the frame was exposed, in nanoseconds of `CLOCK_BOOTTIME`.
+    pub fn sensor_timestamp(&self) -> Option<i64> {
+        self.controls.get::<SensorTimestamp>().ok().map(|v| v.0)
+    }
+
+    pub fn exposure_time(&self) -> Option<Duration> {
+        let exposure = self.controls.get::<ExposureTime>().ok()?;
+        Some(Duration::from_micros(exposure.0.max(0) as u64))
+    }
+
+    pub fn frame_duration(&self) -> Option<Duration> {
+        let duration = self.controls.get::<FrameDuration>().ok()?;
+        Some(Duration::from_micros(duration.0.max(0) as u64))
+    }
+
+    pub fn analogue_gain(&self) -> Option<f32> {
+        self.controls.get::<AnalogueGain>().ok().map(|v| v.0)
+    }
+
+    pub fn digital_gain(&self) -> Option<f32> {
+        self.controls.get::<DigitalGain>().ok().map(|v| v.0)
+    }
+
+    /// Returns `true` once automatic exposure has converged.
+    pub fn ae_locked(&self) -> Option<bool> {
+        self.controls.get::<AeLocked>().ok().map(|v| v.0)
+    }
+
+    /// Red and blue white balance gains.
+    pub fn colour_gains(&self) -> Option<[f32; 2]> {
+        self.controls.get::<ColourGains>().ok().map(|v| v.0)
+    }
+
+    /// Estimated colour temperature in Kelvin.
+    pub fn colour_temperature(&self) -> Option<i32> {
+        self.controls.get::<ColourTemperature>().ok().map(|v| v.0)
+    }
+
+    /// Estimated illuminance in lux.
+    pub fn lux(&self) -> Option<f32> {
+        self.controls.get::<Lux>().ok().map(|v| v.0)
+    }
+
+    /// Lens position in dioptres.
+    pub fn lens_position(&self) -> Option<f32> {
+        self.controls.get::<LensPosition>().ok().map(|v| v.0)
+    }
+
+    /// Sensor temperature in degrees Celsius.
+    pub fn sensor_temperature(&self) -> Option<f32> {
+        self.controls.get::<SensorTemperature>().ok().map(|v| v.0)
+    }
+}
+
+impl core::fmt::Debug for FrameMetadata<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.controls.fmt(f)
+    }
+}
+
+/// A completed request, passed to the [ActiveCamera::on_frame()](crate::camera::ActiveCamera::on_frame) callback.
+///
+/// Buffers are accessible if they were attached as [MemoryMappedFrameBuffer]`<`[FrameBuffer]`>`. The request has to be
+/// taken out with [CompletedFrame::into_request()] to queue it again.
+pub struct CompletedFrame {
+    request: Request,
+}
+
+impl CompletedFrame {
+    pub fn new(request: Request) -> Self {
+        Self { request }
+    }
+
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+
+    pub fn status(&self) -> RequestStatus {
+        self.request.status()
+    }
+
+    pub fn sequence(&self) -> u32 {
+        self.request.sequence()
+    }
+
+    pub fn cookie(&self) -> u64 {
+        self.request.cookie()
+    }
+
+    pub fn metadata(&self) -> FrameMetadata<'_> {
+        FrameMetadata::new(self.request.metadata())
+    }
+
+    /// Returns the mapped buffer of a stream.
+    pub fn buffer(&self, stream: &Stream) -> Option<&MemoryMappedFrameBuffer<FrameBuffer>> {
+        self.request.buffer(stream)
+    }
+
+    /// Iterates over `(stream, buffer)` of all mapped buffers.
+    pub fn buffers(&self) -> impl Iterator<Item = (Stream, &MemoryMappedFrameBuffer<FrameBuffer>)> {
+        self.request
+            .streams()
+            .filter_map(|stream| Some((*stream, self.request.buffer(stream)?)))
+    }
+
+    pub fn into_request(self) -> Request {
+        self.request
+    }
+}
+
+impl core::fmt::Debug for CompletedFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompletedFrame")
+            .field("sequence", &self.sequence())
+            .field("cookie", &self.cookie())
+            .field("status", &self.status())
+            .field("metadata", &self.metadata())
+            .finish()
+    }
+}
@@ -1,9 +1,14 @@
-use std::{marker::PhantomData, ptr::NonNull};
+use std::{
+    io,
+    marker::PhantomData,
+    os::fd::{AsFd, BorrowedFd, OwnedFd},
+    ptr::NonNull,
+};
 
 use libcamera_sys::*;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 
-use crate::utils::Immutable;
+use crate::{pixel_format::PixelFormat, utils::Immutable};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
 #[repr(u32)]
@@ -105,23 +110,45 @@ impl<'d> FrameMetadataRef<'d> {
         }
     }
 
+    /// Capture status of the frame. Data of frames that are not [FrameMetadataStatus::Success] must not be used.
     pub fn status(&self) -> FrameMetadataStatus {
         FrameMetadataStatus::try_from(unsafe { libcamera_frame_metadata_status(self.ptr.as_ptr()) }).unwrap()
     }
 
+    /// Frame sequence number, gaps indicate dropped frames.
     pub fn sequence(&self) -> u32 {
         unsafe { libcamera_frame_metadata_sequence(self.ptr.as_ptr()) }
     }
 
+    /// Time when the frame was captured in nanoseconds.
     pub fn timestamp(&self) -> u64 {
         unsafe { libcamera_frame_metadata_timestamp(self.ptr.as_ptr()) }
     }
 
+    /// Per-plane metadata, see [FrameMetadataRef::bytes_used()].
     pub fn planes(&self) -> FrameMetadataPlanes {
         unsafe {
             FrameMetadataPlanes::from_ptr(NonNull::new(libcamera_frame_metadata_planes(self.ptr.as_ptr())).unwrap())
         }
     }
+
+    /// Returns `true` if the frame was captured successfully.
+    pub fn is_success(&self) -> bool {
+        let status = unsafe { libcamera_frame_metadata_status(self.ptr.as_ptr()) };
+        status == libcamera_frame_metadata_status::LIBCAMERA_FRAME_METADATA_STATUS_SUCCESS
+    }
+
+    /// Number of bytes of valid data in each plane.
+    ///
+    /// This is less than the plane length for compressed formats such as MJPEG.
+    pub fn bytes_used(&self) -> Vec<usize> {
+        self.planes().into_iter().map(|p| p.bytes_used as usize).collect()
+    }
+
+    /// Total number of bytes of valid data in all planes.
+    pub fn total_bytes_used(&self) -> usize {
+        self.planes().into_iter().map(|p| p.bytes_used as usize).sum()
+    }
 }
 
 impl<'d> core::fmt::Debug for FrameMetadataRef<'d> {
@@ -155,6 +182,13 @@ impl<'d> FrameBufferPlaneRef<'d> {
         unsafe { libcamera_framebuffer_plane_fd(self.ptr.as_ptr()) }
     }
 
+    /// Duplicates plane file descriptor, which can be shared with encoders or displays and outlives the framebuffer.
+    ///
+    /// Duplicate refers to the same dmabuf, so the data is not copied.
+    pub fn try_clone_fd(&self) -> io::Result<OwnedFd> {
+        self.as_fd().try_clone_to_owned()
+    }
+
     /// Offset of data within the file descriptor.
     pub fn offset(&self) -> Option<usize> {
         if unsafe { libcamera_framebuffer_plane_offset_valid(self.ptr.as_ptr()) } {
@@ -175,6 +209,13 @@ impl<'d> FrameBufferPlaneRef<'d> {
     }
 }
 
+impl<'d> AsFd for FrameBufferPlaneRef<'d> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        // Safety: file descriptor is owned by the framebuffer and stays open while the plane is borrowed
+        unsafe { BorrowedFd::borrow_raw(self.fd()) }
+    }
+}
+
 impl<'d> core::fmt::Debug for FrameBufferPlaneRef<'d> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("FrameBufferPlaneRef")
@@ -291,4 +332,98 @@ pub trait AsFrameBuffer: Send {
             ))
         }
     }
+
+    /// Number of planes within framebuffer.
+    ///
+    /// Plane layout is read from the framebuffer itself, so it is available without mapping the buffer.
+    fn num_planes(&self) -> usize {
+        self.planes().len()
+    }
+
+    /// Data length in bytes of the plane at a given index, or [None] if the index is out of range.
+    fn plane_length(&self, index: usize) -> Option<usize> {
+        self.planes().get(index).map(|p| p.len())
+    }
+
+    /// Offset of the plane data within its file descriptor, or [None] if the index is out of range or the offset is
+    /// not set.
+    fn plane_offset(&self, index: usize) -> Option<usize> {
+        self.planes().get(index).and_then(|p| p.offset())
+    }
+
+    /// Duplicates file descriptors of all planes, see [FrameBufferPlaneRef::try_clone_fd()].
+    ///
+    /// Planes sharing a single dmabuf get separate duplicates of it.
+    fn try_clone_plane_fds(&self) -> io::Result<Vec<OwnedFd>> {
+        self.planes().into_iter().map(|p| p.try_clone_fd()).collect()
+    }
+
+    /// Returns planes grouped by the file descriptor backing them, see [PlaneGroup].
+    ///
+    /// Strides are not known to the framebuffer itself, use [PlaneGroup::set_strides()] to fill them in.
+    fn plane_groups(&self) -> Vec<PlaneGroup> {
+        let mut groups: Vec<PlaneGroup> = Vec::new();
+        for (index, plane) in self.planes().into_iter().enumerate() {
+            let layout = PlaneLayout {
+                index,
+                offset: plane.offset().unwrap_or(0),
+                length: plane.len(),
+                stride: None,
+            };
+            match groups.iter_mut().find(|g| g.fd == plane.fd()) {
+                Some(group) => group.planes.push(layout),
+                None => groups.push(PlaneGroup {
+                    fd: plane.fd(),
+                    planes: vec![layout],
+                }),
+            }
+        }
+        groups
+    }
+}
+
+/// Location of a single plane within [PlaneGroup].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaneLayout {
+    /// Index of the plane within the framebuffer.
+    pub index: usize,
+    /// Offset of plane data within the file descriptor.
+    pub offset: usize,
+    pub length: usize,
+    /// Bytes per line, if known.
+    pub stride: Option<u32>,
+}
+
+/// Framebuffer planes sharing a single file descriptor.
+///
+/// Some allocators back all planes of a frame with one dmabuf at different offsets, others use a separate dmabuf per
+/// plane. Importers such as V4L2 M2M encoders (single vs multi-planar API) or DRM (`drmModeAddFB2()` with repeated
+/// handles) need to construct their descriptors differently in each case. A framebuffer with a single group is
+/// contiguous.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaneGroup {
+    pub fd: i32,
+    pub planes: Vec<PlaneLayout>,
+}
+
+impl PlaneGroup {
+    /// Fills in strides of all planes from the stride of the first plane, as reported by
+    /// [StreamConfigurationRef::get_stride()](crate::stream::StreamConfigurationRef::get_stride).
+    ///
+    /// Strides of other planes are derived from the format layout, i.e. chroma planes of fully planar YUV formats use
+    /// half of the stride. Planes of formats with unknown layout use the same stride, strides are left unknown if
+    /// `stride` is zero (i.e. for compressed formats).
+    pub fn set_strides(groups: &mut [PlaneGroup], pixel_format: PixelFormat, stride: u32) {
+        if stride == 0 {
+            return;
+        }
+
+        let info = pixel_format.info();
+        for plane in groups.iter_mut().flat_map(|g| g.planes.iter_mut()) {
+            plane.stride = Some(
+                info.and_then(|info| info.plane_stride(plane.index, stride))
+                    .unwrap_or(stride),
+            );
+        }
+    }
 }
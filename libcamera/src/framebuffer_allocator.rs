@@ -6,23 +6,49 @@ use std::{
 
 use libcamera_sys::*;
 
-use crate::{camera::Camera, framebuffer::AsFrameBuffer, stream::Stream};
+use crate::{
+    camera::Camera,
+    framebuffer::{AsFrameBuffer, PlaneGroup},
+    stream::Stream,
+};
 
 /// Buffers are stored inside `libcamera_framebuffer_allocator_t` so we use Arc<FrameBufferAllocatorInstance>
 /// to keep the allocator alive as long as there are active buffers.
 struct FrameBufferAllocatorInstance {
     ptr: NonNull<libcamera_framebuffer_allocator_t>,
-    /// List of streams for which buffers were allocated.
-    /// We use this list to free buffers on drop.
-    allocated_streams: Vec<NonNull<libcamera_stream_t>>,
+    /// List of streams for which buffers were allocated, with a token shared by all of their buffers.
+    /// We use this list to free buffers on drop, and the token to detect buffers that are still in use.
+    pools: Vec<(NonNull<libcamera_stream_t>, Arc<()>)>,
 }
 
 unsafe impl Send for FrameBufferAllocatorInstance {}
 
+impl FrameBufferAllocatorInstance {
+    fn pool(&self, stream: &Stream) -> Option<usize> {
+        self.pools.iter().position(|(s, _)| *s == stream.ptr)
+    }
+
+    /// Frees buffers of a stream, failing with `EBUSY` if any of them are still in use.
+    fn free(&mut self, index: usize) -> io::Result<()> {
+        let (stream, token) = &self.pools[index];
+        if Arc::strong_count(token) > 1 {
+            return Err(io::Error::from_raw_os_error(libc::EBUSY));
+        }
+
+        let ret = unsafe { libcamera_framebuffer_allocator_free(self.ptr.as_ptr(), stream.as_ptr()) };
+        self.pools.remove(index);
+        if ret < 0 {
+            Err(io::Error::from_raw_os_error(ret))
+        } else {
+            Ok(())
+        }
+    }
+}
+
 impl Drop for FrameBufferAllocatorInstance {
     fn drop(&mut self) {
         // Free allocated streams
-        for stream in self.allocated_streams.drain(..) {
+        for (stream, _) in self.pools.drain(..) {
             unsafe {
                 libcamera_framebuffer_allocator_free(self.ptr.as_ptr(), stream.as_ptr());
             }
@@ -32,6 +58,12 @@ impl Drop for FrameBufferAllocatorInstance {
     }
 }
 
+/// Allocates buffers for camera streams.
+///
+/// Buffers are managed as an independent pool per stream, which can be freed or reallocated (i.e. after changing
+/// buffer count of one stream) without affecting the others. Multiple allocators can be used with the same camera,
+/// e.g. to give streams pools with different lifetimes, but a stream should only have buffers of one allocator at a
+/// time. Buffers keep their allocator alive, so they remain valid after the allocator itself is dropped.
 pub struct FrameBufferAllocator {
     inner: Arc<Mutex<FrameBufferAllocatorInstance>>,
 }
@@ -41,21 +73,77 @@ impl FrameBufferAllocator {
         Self {
             inner: Arc::new(Mutex::new(FrameBufferAllocatorInstance {
                 ptr: NonNull::new(unsafe { libcamera_framebuffer_allocator_create(cam.ptr.as_ptr()) }).unwrap(),
-                allocated_streams: Vec::new(),
+                pools: Vec::new(),
             })),
         }
     }
 
+    /// Returns `true` if buffers are allocated for the stream.
+    pub fn is_allocated(&self, stream: &Stream) -> bool {
+        self.inner.lock().unwrap().pool(stream).is_some()
+    }
+
+    /// Number of buffers of the stream, which were not dropped yet.
+    pub fn buffers_in_use(&self, stream: &Stream) -> usize {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .pool(stream)
+            .map(|i| Arc::strong_count(&inner.pools[i].1) - 1)
+            .unwrap_or(0)
+    }
+
+    /// Frees buffers of a stream.
+    ///
+    /// Fails with `EBUSY` if some of the buffers are still in use, i.e. attached to requests. Does nothing if no
+    /// buffers are allocated for the stream.
+    pub fn free(&mut self, stream: &Stream) -> io::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.pool(stream) {
+            Some(index) => inner.free(index),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns plane layouts of buffers allocated for a given stream, or an empty list if there are none.
+    ///
+    /// Allows constructing dmabuf importers (i.e. for an encoder or a display) right after allocation, before any
+    /// buffer is mapped or captured. File descriptors stay valid until the allocator and all of its buffers are
+    /// dropped.
+    pub fn buffers(&self, stream: &Stream) -> Vec<BufferLayout> {
+        let inner = self.inner.lock().unwrap();
+
+        let buffers = unsafe { libcamera_framebuffer_allocator_buffers(inner.ptr.as_ptr(), stream.ptr.as_ptr()) };
+        let len = unsafe { libcamera_framebuffer_list_size(buffers) };
+
+        (0..len)
+            .map(|index| {
+                let ptr = NonNull::new(unsafe { libcamera_framebuffer_list_get(buffers, index) }.cast_mut()).unwrap();
+                BufferLayout {
+                    index,
+                    planes: BorrowedFrameBuffer(ptr).plane_groups(),
+                }
+            })
+            .collect()
+    }
+
     /// Allocate N buffers for a given stream, where N is equal to
     /// [StreamConfigurationRef::get_buffer_count()](crate::stream::StreamConfigurationRef::get_buffer_count).
+    ///
+    /// Previously allocated buffers of the stream are freed first, which fails with `EBUSY` if some of them are still
+    /// in use. Buffers of other streams are not affected.
     pub fn alloc(&mut self, stream: &Stream) -> io::Result<Vec<FrameBuffer>> {
         let mut inner = self.inner.lock().unwrap();
 
+        if let Some(index) = inner.pool(stream) {
+            inner.free(index)?;
+        }
+
         let ret = unsafe { libcamera_framebuffer_allocator_allocate(inner.ptr.as_ptr(), stream.ptr.as_ptr()) };
         if ret < 0 {
             Err(io::Error::from_raw_os_error(ret))
         } else {
-            inner.allocated_streams.push(stream.ptr);
+            let token = Arc::new(());
+            inner.pools.push((stream.ptr, token.clone()));
 
             let buffers = unsafe { libcamera_framebuffer_allocator_buffers(inner.ptr.as_ptr(), stream.ptr.as_ptr()) };
 
@@ -78,6 +166,7 @@ impl FrameBufferAllocator {
 
                     FrameBuffer {
                         ptr,
+                        _pool: token.clone(),
                         _alloc: self.inner.clone(),
                     }
                 })
@@ -86,8 +175,28 @@ impl FrameBufferAllocator {
     }
 }
 
+/// Plane layout of a buffer allocated by [FrameBufferAllocator], see [FrameBufferAllocator::buffers()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BufferLayout {
+    /// Index of the buffer in the order returned by [FrameBufferAllocator::alloc()].
+    pub index: usize,
+    pub planes: Vec<PlaneGroup>,
+}
+
+/// Framebuffer owned by the allocator, only used to read its planes.
+struct BorrowedFrameBuffer(NonNull<libcamera_framebuffer_t>);
+
+unsafe impl Send for BorrowedFrameBuffer {}
+
+impl AsFrameBuffer for BorrowedFrameBuffer {
+    unsafe fn ptr(&self) -> NonNull<libcamera_framebuffer_t> {
+        self.0
+    }
+}
+
 pub struct FrameBuffer {
     ptr: NonNull<libcamera_framebuffer_t>,
+    _pool: Arc<()>,
     _alloc: Arc<Mutex<FrameBufferAllocatorInstance>>,
 }
 
@@ -1,8 +1,21 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Read, Seek, SeekFrom},
+    ops::Deref,
+    os::fd::BorrowedFd,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
 
 use thiserror::Error;
 
-use crate::framebuffer::AsFrameBuffer;
+use crate::{
+    dmabuf::{self, DmaBufAccess},
+    framebuffer::{AsFrameBuffer, FrameMetadataStatus},
+    geometry::Size,
+    pixel_format::{formats, PixelFormat},
+    stream::StreamConfigurationRef,
+};
 
 #[derive(Debug, Error)]
 pub enum MemoryMappedFrameBufferError {
@@ -17,17 +30,132 @@ pub enum MemoryMappedFrameBufferError {
     MemoryMapError(std::io::Error),
 }
 
+/// Plane size mismatch found by [MemoryMappedFrameBuffer::validate()].
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum PlaneValidationError {
+    #[error("Pixel format {pixel_format:?} requires {expected} planes, framebuffer has {found}")]
+    PlaneCount {
+        pixel_format: PixelFormat,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Plane {index} is {len} bytes long, but stride {stride} x {rows} rows requires {expected} bytes")]
+    PlaneTooShort {
+        index: usize,
+        len: usize,
+        stride: u32,
+        rows: u32,
+        expected: usize,
+    },
+    #[error("Plane {index} reports {bytes_used} bytes used, but {expected} bytes are expected for the frame size")]
+    BytesUsedTooShort {
+        index: usize,
+        bytes_used: usize,
+        expected: usize,
+    },
+    #[error("Plane {index} reports {bytes_used} bytes used, which exceeds the plane length of {len} bytes")]
+    BytesUsedOutOfBounds {
+        index: usize,
+        bytes_used: usize,
+        len: usize,
+    },
+}
+
+/// Outcome of a captured frame, see [MemoryMappedFrameBuffer::frame_status()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameStatus {
+    /// Frame was captured successfully and all planes contain the expected amount of data.
+    Complete,
+    /// Frame was reported as successful, but a plane contains less data than the frame requires.
+    Truncated {
+        plane: usize,
+        bytes_used: usize,
+        /// Expected bytes, [None] for compressed formats.
+        expected: Option<usize>,
+    },
+    /// Framebuffer does not match the layout, which points to a wrong layout rather than a capture failure.
+    Invalid(PlaneValidationError),
+    /// Capture failed, see [FrameMetadataStatus::Error].
+    Error,
+    /// Request was cancelled before the frame was captured.
+    Cancelled,
+    /// Metadata is not available yet, i.e. the request has not completed.
+    Pending,
+}
+
+impl FrameStatus {
+    /// Returns `true` if frame data can be used.
+    pub fn is_complete(&self) -> bool {
+        matches!(self, Self::Complete)
+    }
+}
+
+/// Expected memory layout of frames of a stream, used to validate framebuffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameLayout {
+    pub pixel_format: PixelFormat,
+    pub size: Size,
+    /// Bytes per line of the first plane.
+    pub stride: u32,
+}
+
+impl FrameLayout {
+    pub fn from_stream_config(cfg: &StreamConfigurationRef<'_>) -> Self {
+        Self {
+            pixel_format: cfg.get_pixel_format(),
+            size: cfg.get_size(),
+            stride: cfg.get_stride(),
+        }
+    }
+
+    /// Returns `(stride, rows)` of each plane, or [None] if the layout can not be derived, i.e. for compressed
+    /// formats.
+    ///
+    /// Formats with unknown layout are assumed to have a single plane.
+    pub fn planes(&self) -> Option<Vec<(u32, u32)>> {
+        if self.stride == 0 {
+            return None;
+        }
+        let Some(info) = self.pixel_format.info() else {
+            return Some(vec![(self.stride, self.size.height)]);
+        };
+        if info.bits_per_pixel == 0 {
+            return None;
+        }
+        (0..info.num_planes())
+            .map(|plane| {
+                let stride = info.plane_stride(plane, self.stride)?;
+                let rows = self.size.height.div_ceil(info.planes[plane].vertical_sub_sampling);
+                Some((stride, rows))
+            })
+            .collect()
+    }
+}
+
 struct MappedPlane {
     fd: i32,
     offset: usize,
     len: usize,
 }
 
+/// A single mapping covering all planes of one file descriptor.
+struct FdMapping {
+    ptr: *const core::ffi::c_void,
+    /// File offset of the mapping start, aligned down to a page boundary.
+    offset: usize,
+    len: usize,
+}
+
 /// FrameBuffer wrapper, which exposes internal file descriptors as memory mapped [&[u8]] plane slices.
+///
+/// Planes sharing a file descriptor (i.e. luma and chroma planes of `NV12` at different offsets) are mapped once,
+/// covering the union of their ranges, and handed out as sub-slices of that mapping.
 pub struct MemoryMappedFrameBuffer<T: AsFrameBuffer> {
     fb: T,
-    mmaps: HashMap<i32, (*const core::ffi::c_void, usize)>,
+    mmaps: HashMap<i32, FdMapping>,
     planes: Vec<MappedPlane>,
+    /// Worker queue of the [MapPool] which unmaps the buffer on drop, see [MemoryMappedFrameBuffer::unmap_in()].
+    unmapper: Option<mpsc::Sender<MapJob>>,
 }
 
 impl<T: AsFrameBuffer> MemoryMappedFrameBuffer<T> {
@@ -35,83 +163,352 @@ impl<T: AsFrameBuffer> MemoryMappedFrameBuffer<T> {
     ///
     /// This might fail if framebuffer has invalid plane sizes/offsets or if [libc::mmap] fails itself.
     pub fn new(fb: T) -> Result<Self, MemoryMappedFrameBufferError> {
-        struct MapInfo {
-            /// Maximum offset used by data planes
-            mapped_len: usize,
-            /// Total file descriptor size
-            total_len: usize,
-        }
+        let planes = fb
+            .planes()
+            .into_iter()
+            .map(|plane| MappedPlane {
+                fd: plane.fd(),
+                offset: plane.offset().unwrap(),
+                len: plane.len(),
+            })
+            .collect::<Vec<_>>();
+        let mmaps = map_planes(&planes)?;
 
-        let mut planes = Vec::new();
-        let mut map_info: HashMap<i32, MapInfo> = HashMap::new();
+        Ok(Self {
+            fb,
+            mmaps,
+            planes,
+            unmapper: None,
+        })
+    }
 
-        for (index, plane) in fb.planes().into_iter().enumerate() {
-            let fd = plane.fd();
-            let offset = plane.offset().unwrap();
-            let len = plane.len();
+    /// Moves unmapping of this framebuffer to a worker of `pool` when it is dropped.
+    ///
+    /// Unmapping large buffers takes long enough to introduce jitter in the thread dropping them. Buffers are unmapped
+    /// inline if the pool is gone by then.
+    pub fn unmap_in(&mut self, pool: &MapPool) {
+        self.unmapper = Some(pool.tx.clone());
+    }
 
-            planes.push(MappedPlane { fd, offset, len });
+    /// Returns number of memory mappings, which is the number of distinct file descriptors used by the planes.
+    pub fn mapping_count(&self) -> usize {
+        self.mmaps.len()
+    }
 
-            // Find total FD length if not known yet
-            map_info.entry(fd).or_insert_with(|| {
-                let total_len = unsafe { libc::lseek64(fd, 0, libc::SEEK_END) } as usize;
-                MapInfo {
-                    mapped_len: 0,
-                    total_len,
+    fn plane_data(&self, plane: &MappedPlane) -> &[u8] {
+        plane_slice(&self.mmaps, plane)
+    }
+
+    /// Returns data slice for each plane within the framebuffer.
+    pub fn data(&self) -> Vec<&[u8]> {
+        self.planes.iter().map(|plane| self.plane_data(plane)).collect()
+    }
+
+    /// Returns data slice for each plane truncated to the number of bytes used by the frame.
+    ///
+    /// Whole planes are returned if metadata is not available yet or driver did not report bytes used.
+    pub fn data_used(&self) -> Vec<&[u8]> {
+        let mut planes = self.data();
+        if let Some(metadata) = self.fb.metadata() {
+            for (data, plane) in planes.iter_mut().zip(&metadata.planes()) {
+                let used = (plane.bytes_used as usize).min(data.len());
+                if used > 0 {
+                    *data = &data[..used];
                 }
-            });
+            }
+        }
+        planes
+    }
+
+    /// Starts CPU read access to the framebuffer, which is finished when returned guard is dropped.
+    ///
+    /// Buffers are not cache coherent on many ARM platforms (i.e. Raspberry Pi), so reads without the sync may return
+    /// stale cache lines of a previous frame. Sync is a cheap no-op on coherent buffers.
+    pub fn sync(&self) -> io::Result<SyncGuard<'_, T>> {
+        let mut synced = Vec::with_capacity(self.mmaps.len());
+        for fd in self.mmaps.keys() {
+            let fd = unsafe { BorrowedFd::borrow_raw(*fd) };
+            if let Err(e) = dmabuf::sync_start(fd, DmaBufAccess::Read) {
+                for fd in synced {
+                    let _ = dmabuf::sync_end(fd, DmaBufAccess::Read);
+                }
+                return Err(e);
+            }
+            synced.push(fd);
+        }
+        Ok(SyncGuard { fb: self })
+    }
+
+    /// Returns data slice of a single plane, or [None] if index is out of range.
+    pub fn plane(&self, index: usize) -> Option<&[u8]> {
+        Some(self.plane_data(self.planes.get(index)?))
+    }
+
+    /// Checks plane lengths and bytes used reported in metadata against the size implied by stride and rows of the
+    /// format.
+    ///
+    /// Mismatches otherwise show up later as distorted or shifted images. Frames with all planes in a single
+    /// framebuffer plane are checked against the total size. Formats without a known layout always pass.
+    pub fn validate(&self, layout: &FrameLayout) -> Result<(), PlaneValidationError> {
+        let Some(expected) = layout.planes() else {
+            return Ok(());
+        };
 
-            let info = map_info.get_mut(&fd).unwrap();
+        let found = self.planes.len();
+        let expected = if found == 1 && expected.len() > 1 {
+            let (stride, _) = expected[0];
+            let total: u32 = expected.iter().map(|(s, r)| s * r).sum();
+            vec![(stride, total / stride)]
+        } else if found != expected.len() {
+            return Err(PlaneValidationError::PlaneCount {
+                pixel_format: layout.pixel_format,
+                expected: expected.len(),
+                found,
+            });
+        } else {
+            expected
+        };
 
-            if offset + len > info.total_len {
-                return Err(MemoryMappedFrameBufferError::PlaneOutOfBounds {
+        let bytes_used = self.fb.metadata().map(|m| m.bytes_used()).unwrap_or_default();
+        for (index, (plane, (stride, rows))) in self.planes.iter().zip(expected).enumerate() {
+            let expected = stride as usize * rows as usize;
+            if plane.len < expected {
+                return Err(PlaneValidationError::PlaneTooShort {
                     index,
-                    offset,
-                    len,
-                    fd_len: info.total_len,
+                    len: plane.len,
+                    stride,
+                    rows,
+                    expected,
                 });
             }
 
-            info.mapped_len = info.mapped_len.max(offset + len);
-        }
-
-        let mmaps = map_info
-            .iter()
-            .map(|(fd, info)| {
-                let addr = unsafe {
-                    libc::mmap64(
-                        core::ptr::null_mut(),
-                        info.mapped_len,
-                        libc::PROT_READ,
-                        libc::MAP_SHARED,
-                        *fd,
-                        0,
-                    )
+            match bytes_used.get(index).copied() {
+                Some(used) if used > plane.len => {
+                    return Err(PlaneValidationError::BytesUsedOutOfBounds {
+                        index,
+                        bytes_used: used,
+                        len: plane.len,
+                    })
+                }
+                // Zero means the driver did not report it
+                Some(used) if used != 0 && used < expected => {
+                    return Err(PlaneValidationError::BytesUsedTooShort {
+                        index,
+                        bytes_used: used,
+                        expected,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Classifies the captured frame, distinguishing frames truncated by the driver from complete ones.
+    ///
+    /// Plane bytes used are compared against the layout, `MJPEG` frames are checked for the end of image marker, as
+    /// USB cameras occasionally deliver short buffers reported as successful. Call it on the guard returned by
+    /// [MemoryMappedFrameBuffer::sync()] to avoid reading stale data on non-coherent platforms.
+    pub fn frame_status(&self, layout: &FrameLayout) -> FrameStatus {
+        let Some(metadata) = self.fb.metadata() else {
+            return FrameStatus::Pending;
+        };
+        match metadata.status() {
+            FrameMetadataStatus::Success => {}
+            FrameMetadataStatus::Error => return FrameStatus::Error,
+            FrameMetadataStatus::Cancelled => return FrameStatus::Cancelled,
+        }
+
+        if layout.pixel_format == formats::MJPEG {
+            let data = self.data_used().first().copied().unwrap_or_default();
+            // Some drivers pad JPEG data with zeros up to bytes used
+            let end = data.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+            if !data[..end].ends_with(&[0xff, 0xd9]) {
+                return FrameStatus::Truncated {
+                    plane: 0,
+                    bytes_used: data.len(),
+                    expected: None,
                 };
+            }
+            return FrameStatus::Complete;
+        }
 
-                if addr == libc::MAP_FAILED {
-                    Err(MemoryMappedFrameBufferError::MemoryMapError(
-                        std::io::Error::last_os_error(),
-                    ))
-                } else {
-                    Ok((*fd, (addr.cast_const(), info.mapped_len)))
-                }
-            })
-            .collect::<Result<HashMap<i32, (*const core::ffi::c_void, usize)>, MemoryMappedFrameBufferError>>()
-            .unwrap();
+        match self.validate(layout) {
+            Ok(()) => FrameStatus::Complete,
+            Err(PlaneValidationError::BytesUsedTooShort {
+                index,
+                bytes_used,
+                expected,
+            }) => FrameStatus::Truncated {
+                plane: index,
+                bytes_used,
+                expected: Some(expected),
+            },
+            Err(e) => FrameStatus::Invalid(e),
+        }
+    }
 
-        Ok(Self { fb, mmaps, planes })
+    /// Returns a reader over a single plane data, or [None] if index is out of range.
+    pub fn plane_reader(&self, index: usize) -> Option<PlaneReader<'_>> {
+        self.plane(index).map(PlaneReader::new)
     }
 
-    /// Returns data slice for each plane within the framebuffer.
-    pub fn data(&self) -> Vec<&[u8]> {
-        self.planes
-            .iter()
-            .map(|plane| {
-                let mmap_ptr: *const u8 = self.mmaps[&plane.fd].0.cast();
-                unsafe { core::slice::from_raw_parts(mmap_ptr.add(plane.offset), plane.len) }
-            })
-            .collect()
+    /// Returns a reader, which streams data of all planes one after another in the plane index order.
+    ///
+    /// Data is read directly from the mapped memory without intermediate copies.
+    pub fn chained_reader(&self) -> ChainedPlaneReader<'_> {
+        ChainedPlaneReader {
+            planes: self.data(),
+            index: 0,
+            pos: 0,
+        }
+    }
+}
+
+/// CPU access section of [MemoryMappedFrameBuffer] obtained by [MemoryMappedFrameBuffer::sync()].
+///
+/// Dereferences to the framebuffer and ends the dmabuf sync on drop.
+pub struct SyncGuard<'d, T: AsFrameBuffer> {
+    fb: &'d MemoryMappedFrameBuffer<T>,
+}
+
+impl<'d, T: AsFrameBuffer> Deref for SyncGuard<'d, T> {
+    type Target = MemoryMappedFrameBuffer<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.fb
+    }
+}
+
+impl<'d, T: AsFrameBuffer> Drop for SyncGuard<'d, T> {
+    fn drop(&mut self) {
+        for fd in self.fb.mmaps.keys() {
+            // Errors are ignored, as the mapping stays valid and there is nothing left to recover
+            let _ = dmabuf::sync_end(unsafe { BorrowedFd::borrow_raw(*fd) }, DmaBufAccess::Read);
+        }
+    }
+}
+
+/// [Read] implementation over a single memory mapped plane.
+///
+/// Obtained by [MemoryMappedFrameBuffer::plane_reader()].
+#[derive(Debug, Clone)]
+pub struct PlaneReader<'d> {
+    data: &'d [u8],
+    pos: usize,
+}
+
+impl<'d> PlaneReader<'d> {
+    fn new(data: &'d [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Total plane length in bytes.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if plane has no data.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Number of bytes that are yet to be read.
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+}
+
+impl<'d> Read for PlaneReader<'d> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = (&self.data[self.pos..]).read(buf)?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl<'d> BufRead for PlaneReader<'d> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(&self.data[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.data.len());
+    }
+}
+
+impl<'d> Seek for PlaneReader<'d> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(p) => Some(p as i64),
+            SeekFrom::End(p) => (self.data.len() as i64).checked_add(p),
+            SeekFrom::Current(p) => (self.pos as i64).checked_add(p),
+        };
+
+        match new_pos {
+            Some(p) if p >= 0 => {
+                self.pos = (p as usize).min(self.data.len());
+                Ok(self.pos as u64)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+/// [Read] implementation over all memory mapped planes of a framebuffer.
+///
+/// Obtained by [MemoryMappedFrameBuffer::chained_reader()].
+#[derive(Debug, Clone)]
+pub struct ChainedPlaneReader<'d> {
+    planes: Vec<&'d [u8]>,
+    index: usize,
+    pos: usize,
+}
+
+impl<'d> ChainedPlaneReader<'d> {
+    /// Total length of all planes in bytes.
+    pub fn len(&self) -> usize {
+        self.planes.iter().map(|p| p.len()).sum()
+    }
+
+    /// Returns `true` if framebuffer has no data.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Index of the plane that is currently being read.
+    pub fn plane_index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<'d> Read for ChainedPlaneReader<'d> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = (self.fill_buf()?).read(buf)?;
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<'d> BufRead for ChainedPlaneReader<'d> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        // Skip exhausted (or empty) planes
+        while self.index < self.planes.len() && self.pos >= self.planes[self.index].len() {
+            self.index += 1;
+            self.pos = 0;
+        }
+
+        Ok(self.planes.get(self.index).map(|p| &p[self.pos..]).unwrap_or_default())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Some(plane) = self.planes.get(self.index) {
+            self.pos = (self.pos + amt).min(plane.len());
+        }
     }
 }
 
@@ -125,11 +522,346 @@ unsafe impl<T: AsFrameBuffer> Send for MemoryMappedFrameBuffer<T> {}
 
 impl<T: AsFrameBuffer> Drop for MemoryMappedFrameBuffer<T> {
     fn drop(&mut self) {
-        // Unmap
-        for (_fd, (ptr, size)) in self.mmaps.drain() {
-            unsafe {
-                libc::munmap(ptr.cast_mut(), size);
+        let mappings = self.mmaps.drain().map(|(_, mapping)| mapping).collect::<Vec<_>>();
+        if let Some(tx) = self.unmapper.take() {
+            let mappings = SendMappings(mappings);
+            if let Err(mpsc::SendError(job)) = tx.send(Box::new(move || mappings.unmap())) {
+                // Pool has shut down, unmap inline
+                job();
             }
+        } else {
+            SendMappings(mappings).unmap();
         }
     }
 }
+
+/// Maps each file descriptor used by `planes` once, covering the union of its planes.
+fn map_planes(planes: &[MappedPlane]) -> Result<HashMap<i32, FdMapping>, MemoryMappedFrameBufferError> {
+    struct MapInfo {
+        /// Minimum offset used by data planes
+        start: usize,
+        /// Maximum offset used by data planes
+        end: usize,
+        /// Total file descriptor size
+        total_len: usize,
+    }
+
+    let mut map_info: HashMap<i32, MapInfo> = HashMap::new();
+
+    for (index, &MappedPlane { fd, offset, len }) in planes.iter().enumerate() {
+        // Find total FD length if not known yet
+        let info = map_info.entry(fd).or_insert_with(|| {
+            let total_len = unsafe { libc::lseek64(fd, 0, libc::SEEK_END) } as usize;
+            MapInfo {
+                start: usize::MAX,
+                end: 0,
+                total_len,
+            }
+        });
+
+        if offset.checked_add(len).is_none_or(|end| end > info.total_len) {
+            return Err(MemoryMappedFrameBufferError::PlaneOutOfBounds {
+                index,
+                offset,
+                len,
+                fd_len: info.total_len,
+            });
+        }
+
+        info.start = info.start.min(offset);
+        info.end = info.end.max(offset + len);
+    }
+
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let mut mmaps = HashMap::with_capacity(map_info.len());
+    for (fd, info) in map_info {
+        // mmap offset has to be page aligned
+        let offset = info.start - info.start % page_size;
+        let len = info.end - offset;
+        let ptr = unsafe {
+            libc::mmap64(
+                core::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                fd,
+                offset as libc::off64_t,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            let err = std::io::Error::last_os_error();
+            for mapping in mmaps.into_values() {
+                unmap(mapping);
+            }
+            return Err(MemoryMappedFrameBufferError::MemoryMapError(err));
+        }
+
+        mmaps.insert(
+            fd,
+            FdMapping {
+                ptr: ptr.cast_const(),
+                offset,
+                len,
+            },
+        );
+    }
+
+    Ok(mmaps)
+}
+
+/// Returns data of `plane` within the mapping of its file descriptor.
+fn plane_slice<'a>(mmaps: &'a HashMap<i32, FdMapping>, plane: &MappedPlane) -> &'a [u8] {
+    let mapping = &mmaps[&plane.fd];
+    let ptr: *const u8 = mapping.ptr.cast();
+    unsafe { core::slice::from_raw_parts(ptr.add(plane.offset - mapping.offset), plane.len) }
+}
+
+fn unmap(mapping: FdMapping) {
+    unsafe {
+        libc::munmap(mapping.ptr.cast_mut(), mapping.len);
+    }
+}
+
+/// Mappings of a dropped framebuffer, handed to a [MapPool] worker.
+struct SendMappings(Vec<FdMapping>);
+
+// Safety: mappings are no longer referenced by the dropped framebuffer, so they can be unmapped from any thread
+unsafe impl Send for SendMappings {}
+
+impl SendMappings {
+    fn unmap(self) {
+        for mapping in self.0 {
+            unmap(mapping);
+        }
+    }
+}
+
+type MapJob = Box<dyn FnOnce() + Send>;
+
+/// Worker threads, which memory map and unmap framebuffers away from latency sensitive threads.
+///
+/// Mapping and unmapping large buffers (i.e. 18MB RAW frames) takes milliseconds. [MapPool::map_all()] maps buffers in
+/// parallel ahead of capture, and [MemoryMappedFrameBuffer::unmap_in()] defers unmapping to the workers, so that
+/// request completion handling keeps a fixed latency budget. Workers exit once the pool and all buffers unmapped in
+/// it are dropped.
+pub struct MapPool {
+    tx: mpsc::Sender<MapJob>,
+    threads: usize,
+}
+
+impl MapPool {
+    /// Spawns `threads` workers, at least one.
+    pub fn new(threads: usize) -> io::Result<Self> {
+        let (tx, rx) = mpsc::channel::<MapJob>();
+        let rx = Arc::new(Mutex::new(rx));
+        let threads = threads.max(1);
+        for index in 0..threads {
+            let rx = rx.clone();
+            thread::Builder::new()
+                .name(format!("libcamera-map-{index}"))
+                .spawn(move || loop {
+                    let job = rx.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                })?;
+        }
+        Ok(Self { tx, threads })
+    }
+
+    /// Number of worker threads.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Memory maps all framebuffers in parallel, preserving their order.
+    ///
+    /// Returned buffers are unmapped within the pool, see [MemoryMappedFrameBuffer::unmap_in()]. If any buffer fails to
+    /// map, the error is returned and all other buffers are dropped.
+    pub fn map_all<T>(&self, fbs: Vec<T>) -> Result<Vec<MemoryMappedFrameBuffer<T>>, MemoryMappedFrameBufferError>
+    where
+        T: AsFrameBuffer + 'static,
+    {
+        let count = fbs.len();
+        let (tx, rx) = mpsc::channel();
+        for (index, fb) in fbs.into_iter().enumerate() {
+            let tx = tx.clone();
+            let job: MapJob = Box::new(move || {
+                let _ = tx.send((index, MemoryMappedFrameBuffer::new(fb)));
+            });
+            if let Err(mpsc::SendError(job)) = self.tx.send(job) {
+                job();
+            }
+        }
+        drop(tx);
+
+        let mut mapped = (0..count).map(|_| None).collect::<Vec<_>>();
+        for (index, result) in rx {
+            let mut fb = result?;
+            fb.unmap_in(self);
+            mapped[index] = Some(fb);
+        }
+        Ok(mapped.into_iter().flatten().collect())
+    }
+}
+
+impl core::fmt::Debug for MapPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapPool").field("threads", &self.threads).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        io::Write,
+        os::fd::{AsRawFd, FromRawFd},
+    };
+
+    use super::*;
+
+    const WIDTH: usize = 640;
+    const HEIGHT: usize = 480;
+    const LUMA_LEN: usize = WIDTH * HEIGHT;
+    const CHROMA_LEN: usize = WIDTH * HEIGHT / 2;
+
+    /// Creates an anonymous file with `len` bytes, each byte set to its offset modulo 251.
+    fn memfd(len: usize) -> File {
+        let fd = unsafe { libc::memfd_create(c"nv12".as_ptr(), 0) };
+        assert!(fd >= 0, "memfd_create failed: {}", io::Error::last_os_error());
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        file.write_all(&(0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>())
+            .unwrap();
+        file
+    }
+
+    fn expected(offset: usize, len: usize) -> Vec<u8> {
+        (offset..offset + len).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn unmap_all(mmaps: HashMap<i32, FdMapping>) {
+        SendMappings(mmaps.into_values().collect()).unmap();
+    }
+
+    #[test]
+    fn nv12_planes_sharing_fd_are_mapped_once() {
+        let file = memfd(LUMA_LEN + CHROMA_LEN);
+        let fd = file.as_raw_fd();
+        let planes = [
+            MappedPlane {
+                fd,
+                offset: 0,
+                len: LUMA_LEN,
+            },
+            MappedPlane {
+                fd,
+                offset: LUMA_LEN,
+                len: CHROMA_LEN,
+            },
+        ];
+
+        let mmaps = map_planes(&planes).unwrap();
+        assert_eq!(mmaps.len(), 1);
+        assert_eq!(mmaps[&fd].offset, 0);
+        assert_eq!(mmaps[&fd].len, LUMA_LEN + CHROMA_LEN);
+
+        let luma = plane_slice(&mmaps, &planes[0]);
+        let chroma = plane_slice(&mmaps, &planes[1]);
+        assert_eq!(luma.len(), LUMA_LEN);
+        assert_eq!(chroma.len(), CHROMA_LEN);
+        assert_eq!(luma, expected(0, LUMA_LEN).as_slice());
+        assert_eq!(chroma, expected(LUMA_LEN, CHROMA_LEN).as_slice());
+        assert_eq!(unsafe { luma.as_ptr().add(LUMA_LEN) }, chroma.as_ptr());
+
+        unmap_all(mmaps);
+    }
+
+    #[test]
+    fn nv12_planes_in_separate_fds() {
+        let luma_file = memfd(LUMA_LEN);
+        let chroma_file = memfd(CHROMA_LEN);
+        let planes = [
+            MappedPlane {
+                fd: luma_file.as_raw_fd(),
+                offset: 0,
+                len: LUMA_LEN,
+            },
+            MappedPlane {
+                fd: chroma_file.as_raw_fd(),
+                offset: 0,
+                len: CHROMA_LEN,
+            },
+        ];
+
+        let mmaps = map_planes(&planes).unwrap();
+        assert_eq!(mmaps.len(), 2);
+        assert_eq!(plane_slice(&mmaps, &planes[0]), expected(0, LUMA_LEN).as_slice());
+        assert_eq!(plane_slice(&mmaps, &planes[1]), expected(0, CHROMA_LEN).as_slice());
+
+        unmap_all(mmaps);
+    }
+
+    #[test]
+    fn unaligned_plane_offsets_map_from_page_boundary() {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        // luma starts after a header that is not a multiple of the page size
+        let header = page_size + 100;
+        let file = memfd(header + LUMA_LEN + CHROMA_LEN);
+        let fd = file.as_raw_fd();
+        let planes = [
+            MappedPlane {
+                fd,
+                offset: header,
+                len: LUMA_LEN,
+            },
+            MappedPlane {
+                fd,
+                offset: header + LUMA_LEN,
+                len: CHROMA_LEN,
+            },
+        ];
+
+        let mmaps = map_planes(&planes).unwrap();
+        assert_eq!(mmaps.len(), 1);
+        assert_eq!(mmaps[&fd].offset, page_size);
+        assert_eq!(mmaps[&fd].len, 100 + LUMA_LEN + CHROMA_LEN);
+        assert_eq!(plane_slice(&mmaps, &planes[0]), expected(header, LUMA_LEN).as_slice());
+        assert_eq!(
+            plane_slice(&mmaps, &planes[1]),
+            expected(header + LUMA_LEN, CHROMA_LEN).as_slice()
+        );
+
+        unmap_all(mmaps);
+    }
+
+    #[test]
+    fn plane_exceeding_fd_is_rejected() {
+        let file = memfd(LUMA_LEN + CHROMA_LEN - 1);
+        let fd = file.as_raw_fd();
+        let planes = [
+            MappedPlane {
+                fd,
+                offset: 0,
+                len: LUMA_LEN,
+            },
+            MappedPlane {
+                fd,
+                offset: LUMA_LEN,
+                len: CHROMA_LEN,
+            },
+        ];
+
+        assert!(matches!(
+            map_planes(&planes),
+            Err(MemoryMappedFrameBufferError::PlaneOutOfBounds {
+                index: 1,
+                offset: LUMA_LEN,
+                len: CHROMA_LEN,
+                fd_len,
+            }) if fd_len == LUMA_LEN + CHROMA_LEN - 1
+        ));
+    }
+}
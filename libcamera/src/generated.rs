@@ -5,3 +5,7 @@ pub mod controls {
 pub mod properties {
     include!(concat!(env!("OUT_DIR"), "/properties.rs"));
 }
+
+pub(crate) mod formats {
+    include!(concat!(env!("OUT_DIR"), "/formats.rs"));
+}
@@ -1,7 +1,10 @@
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
 use libcamera_sys::*;
 
 /// Represents `libcamera::Point`
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub x: i32,
     pub y: i32,
@@ -13,8 +16,55 @@ impl From<libcamera_point_t> for Point {
     }
 }
 
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self { x: -self.x, y: -self.y }
+    }
+}
+
 /// Represents `libcamera::Size`
-#[derive(Debug, Clone, Copy)]
+///
+/// Layout matches `libcamera_size_t`, which allows borrowing control values, see
+/// [ControlValueRef](crate::control_value::ControlValueRef).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
 pub struct Size {
     pub width: u32,
     pub height: u32,
@@ -29,6 +79,38 @@ impl From<libcamera_size_t> for Size {
     }
 }
 
+impl Size {
+    /// Returns the size with width and height rounded up to multiples of `h_align` and `v_align`.
+    pub fn aligned_to(&self, h_align: u32, v_align: u32) -> Self {
+        Self {
+            width: self.width.next_multiple_of(h_align.max(1)),
+            height: self.height.next_multiple_of(v_align.max(1)),
+        }
+    }
+
+    /// Returns the size with width and height rounded down to multiples of `h_align` and `v_align`.
+    pub fn aligned_down_to(&self, h_align: u32, v_align: u32) -> Self {
+        Self {
+            width: self.width / h_align.max(1) * h_align.max(1),
+            height: self.height / v_align.max(1) * v_align.max(1),
+        }
+    }
+
+    /// Returns width divided by height, NaN or infinite for sizes with zero height.
+    pub fn aspect_ratio(&self) -> f64 {
+        self.width as f64 / self.height as f64
+    }
+
+    /// Returns `true` if neither width nor height exceed those of `other`.
+    pub fn fits_within(&self, other: &Size) -> bool {
+        self.width <= other.width && self.height <= other.height
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
 impl From<Size> for libcamera_size_t {
     fn from(s: Size) -> Self {
         Self {
@@ -39,7 +121,8 @@ impl From<Size> for libcamera_size_t {
 }
 
 /// Represents `libcamera::SizeRange`
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SizeRange {
     pub min: Size,
     pub max: Size,
@@ -70,7 +153,11 @@ impl From<SizeRange> for libcamera_size_range_t {
 }
 
 /// Represents `libcamera::Rectangle`
-#[derive(Debug, Clone, Copy)]
+///
+/// Layout matches `libcamera_rectangle_t`, see [Size].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
 pub struct Rectangle {
     pub x: i32,
     pub y: i32,
@@ -78,6 +165,114 @@ pub struct Rectangle {
     pub height: u32,
 }
 
+impl Rectangle {
+    pub fn from_point_size(top_left: Point, size: Size) -> Self {
+        Self {
+            x: top_left.x,
+            y: top_left.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+
+    pub fn top_left(&self) -> Point {
+        Point { x: self.x, y: self.y }
+    }
+
+    pub fn size(&self) -> Size {
+        Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Returns the center, rounded towards the top left corner.
+    pub fn center(&self) -> Point {
+        Point {
+            x: (self.x as i64 + self.width as i64 / 2) as i32,
+            y: (self.y as i64 + self.height as i64 / 2) as i32,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Returns `true` if the point lies within the rectangle, right and bottom edges are exclusive.
+    pub fn contains(&self, point: Point) -> bool {
+        let (x, y) = (point.x as i64, point.y as i64);
+        x >= self.x as i64 && x < self.right() && y >= self.y as i64 && y < self.bottom()
+    }
+
+    /// Returns `true` if `other` lies entirely within the rectangle.
+    pub fn contains_rectangle(&self, other: &Rectangle) -> bool {
+        other.x as i64 >= self.x as i64
+            && other.y as i64 >= self.y as i64
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    /// Returns the overlapping area of both rectangles, [None] if they do not overlap.
+    ///
+    /// Useful to clamp a requested `ScalerCrop` or AF window to the sensor area.
+    pub fn intersection(&self, other: &Rectangle) -> Option<Rectangle> {
+        let x = (self.x as i64).max(other.x as i64);
+        let y = (self.y as i64).max(other.y as i64);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        (right > x && bottom > y).then(|| Self::from_edges(x, y, right, bottom))
+    }
+
+    /// Returns the smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &Rectangle) -> Rectangle {
+        Self::from_edges(
+            (self.x as i64).min(other.x as i64),
+            (self.y as i64).min(other.y as i64),
+            self.right().max(other.right()),
+            self.bottom().max(other.bottom()),
+        )
+    }
+
+    /// Scales position and size by `numerator / denominator` per axis, same as `libcamera::Rectangle::scaledBy()`.
+    ///
+    /// Maps a rectangle between coordinate systems, i.e. from output image pixels to sensor pixels for `ScalerCrop`.
+    /// Returns the rectangle unchanged if the denominator has zero width or height.
+    pub fn scaled_by(&self, numerator: Size, denominator: Size) -> Rectangle {
+        if denominator.is_null() {
+            return *self;
+        }
+        let scale = |v: i64, num: u32, den: u32| v * num as i64 / den as i64;
+        Self {
+            x: scale(self.x as i64, numerator.width, denominator.width) as i32,
+            y: scale(self.y as i64, numerator.height, denominator.height) as i32,
+            width: scale(self.width as i64, numerator.width, denominator.width) as u32,
+            height: scale(self.height as i64, numerator.height, denominator.height) as u32,
+        }
+    }
+
+    /// Returns the rectangle moved by `offset`.
+    pub fn translated_by(&self, offset: Point) -> Rectangle {
+        Self::from_point_size(self.top_left() + offset, self.size())
+    }
+
+    fn right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    fn bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    fn from_edges(x: i64, y: i64, right: i64, bottom: i64) -> Self {
+        Self {
+            x: x as i32,
+            y: y as i32,
+            width: (right - x) as u32,
+            height: (bottom - y) as u32,
+        }
+    }
+}
+
 impl From<libcamera_rectangle_t> for Rectangle {
     fn from(r: libcamera_rectangle_t) -> Self {
         Self {
@@ -0,0 +1,130 @@
+//! Conversion of frames into [image](https://docs.rs/image) crate buffers.
+//!
+//! Frames whose memory layout matches an `image` pixel type can be borrowed as [FrameView] without copying, as long
+//! as lines are not padded. Any other frame is copied with the stride padding removed, and converted to RGB with
+//! [RgbConverter] if `image` has no matching pixel type.
+
+use image::{DynamicImage, ImageBuffer, Luma, Rgb, Rgba};
+
+use crate::{
+    framebuffer::AsFrameBuffer,
+    framebuffer_map::MemoryMappedFrameBuffer,
+    pixel_format::{formats, PixelFormat},
+    rgb::{RgbConverter, RgbError, RgbImage},
+    stream::StreamConfiguration,
+};
+
+/// A frame borrowed as an `image` buffer.
+#[derive(Debug, Clone)]
+pub enum FrameView<'a> {
+    Luma8(ImageBuffer<Luma<u8>, &'a [u8]>),
+    Rgb8(ImageBuffer<Rgb<u8>, &'a [u8]>),
+    Rgba8(ImageBuffer<Rgba<u8>, &'a [u8]>),
+}
+
+impl FrameView<'_> {
+    /// Copies the frame into an owned image.
+    pub fn to_dynamic_image(&self) -> DynamicImage {
+        match self {
+            Self::Luma8(v) => {
+                DynamicImage::ImageLuma8(ImageBuffer::from_raw(v.width(), v.height(), v.as_raw().to_vec()).unwrap())
+            }
+            Self::Rgb8(v) => {
+                DynamicImage::ImageRgb8(ImageBuffer::from_raw(v.width(), v.height(), v.as_raw().to_vec()).unwrap())
+            }
+            Self::Rgba8(v) => {
+                DynamicImage::ImageRgba8(ImageBuffer::from_raw(v.width(), v.height(), v.as_raw().to_vec()).unwrap())
+            }
+        }
+    }
+}
+
+/// Formats stored in the channel order of an `image` pixel type, i.e. `BGR888` as R, G, B bytes.
+const NATIVE_FORMATS: &[PixelFormat] = &[formats::R8, formats::BGR888, formats::ABGR8888];
+
+/// Number of channels of the `image` pixel type with the same memory layout as `format`.
+fn native_channels(format: PixelFormat) -> Option<usize> {
+    if !NATIVE_FORMATS.contains(&format) {
+        return None;
+    }
+    format.info().map(|info| info.bits_per_pixel as usize / 8)
+}
+
+/// Borrows the first plane of a frame as an `image` buffer.
+///
+/// Returns [None] if the pixel format has no matching `image` pixel type, lines are padded or data is too short, in
+/// which case [to_dynamic_image()] has to be used instead.
+pub fn view<'a>(data: &'a [u8], cfg: &StreamConfiguration) -> Option<FrameView<'a>> {
+    let channels = native_channels(cfg.pixel_format)?;
+    let (width, height) = (cfg.size.width, cfg.size.height);
+    let row_len = width as usize * channels;
+    if cfg.stride as usize != row_len {
+        return None;
+    }
+    let data = data.get(..row_len * height as usize)?;
+
+    match channels {
+        1 => ImageBuffer::from_raw(width, height, data).map(FrameView::Luma8),
+        3 => ImageBuffer::from_raw(width, height, data).map(FrameView::Rgb8),
+        _ => ImageBuffer::from_raw(width, height, data).map(FrameView::Rgba8),
+    }
+}
+
+/// Copies frame planes into an owned image, removing line padding and converting to RGB where needed.
+pub fn to_dynamic_image(planes: &[&[u8]], cfg: &StreamConfiguration) -> Result<DynamicImage, RgbError> {
+    let Some(channels) = native_channels(cfg.pixel_format) else {
+        let converter = RgbConverter::new(cfg.pixel_format, cfg.size, cfg.stride);
+        return Ok(converter.convert(planes)?.into());
+    };
+
+    let (width, height, stride) = (cfg.size.width as usize, cfg.size.height as usize, cfg.stride as usize);
+    let row_len = width * channels;
+    let data = planes
+        .first()
+        .ok_or(RgbError::MissingPlanes { expected: 1, found: 0 })?;
+    let expected = height.saturating_sub(1) * stride + row_len;
+    if data.len() < expected {
+        return Err(RgbError::PlaneTooShort {
+            index: 0,
+            len: data.len(),
+            expected,
+        });
+    }
+
+    let mut out = Vec::with_capacity(row_len * height);
+    for y in 0..height {
+        out.extend_from_slice(&data[y * stride..][..row_len]);
+    }
+
+    let (width, height) = (cfg.size.width, cfg.size.height);
+    Ok(match channels {
+        1 => DynamicImage::ImageLuma8(ImageBuffer::from_raw(width, height, out).unwrap()),
+        3 => DynamicImage::ImageRgb8(ImageBuffer::from_raw(width, height, out).unwrap()),
+        _ => DynamicImage::ImageRgba8(ImageBuffer::from_raw(width, height, out).unwrap()),
+    })
+}
+
+impl From<RgbImage> for image::RgbImage {
+    fn from(image: RgbImage) -> Self {
+        ImageBuffer::from_raw(image.width, image.height, image.data).unwrap()
+    }
+}
+
+impl From<RgbImage> for DynamicImage {
+    fn from(image: RgbImage) -> Self {
+        DynamicImage::ImageRgb8(image.into())
+    }
+}
+
+impl<T: AsFrameBuffer> MemoryMappedFrameBuffer<T> {
+    /// Borrows framebuffer contents as an `image` buffer, see [view()].
+    pub fn image_view(&self, cfg: &StreamConfiguration) -> Option<FrameView<'_>> {
+        let data = *self.data_used().first()?;
+        view(data, cfg)
+    }
+
+    /// Copies framebuffer contents into an owned `image`, see [to_dynamic_image()].
+    pub fn to_image(&self, cfg: &StreamConfiguration) -> Result<DynamicImage, RgbError> {
+        to_dynamic_image(&self.data_used(), cfg)
+    }
+}
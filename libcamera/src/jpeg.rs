@@ -0,0 +1,486 @@
+//! JPEG encoding of captured frames.
+//!
+//! [JpegEncoder] produces baseline JFIF images with 4:2:0 chroma subsampling. `MJPEG` frames are already encoded and
+//! are returned as is, `NV12`, `NV21` and `YUV420` frames are encoded without an intermediate RGB conversion, and all
+//! other formats supported by [RgbConverter] are converted to RGB first.
+
+use drm_fourcc::DrmFourcc;
+use thiserror::Error;
+
+use crate::{
+    framebuffer::AsFrameBuffer,
+    framebuffer_map::MemoryMappedFrameBuffer,
+    geometry::Size,
+    pixel_format::{formats, PixelFormat},
+    rgb::{RgbConverter, RgbError, RgbImage},
+    stream::StreamConfigurationRef,
+};
+
+#[derive(Debug, Error)]
+pub enum JpegError {
+    #[error(transparent)]
+    Rgb(#[from] RgbError),
+    /// JPEG dimensions are limited to 65535 pixels
+    #[error("Frame size {0:?} is too large")]
+    TooLarge(Size),
+}
+
+/// Quantization tables of JPEG Annex K in natural order, used at quality 50.
+const LUMA_QUANT: [u8; 64] = [
+    16, 11, 10, 16, 24, 40, 51, 61, 12, 12, 14, 19, 26, 58, 60, 55, 14, 13, 16, 24, 40, 57, 69, 56, 14, 17, 22, 29, 51,
+    87, 80, 62, 18, 22, 37, 56, 68, 109, 103, 77, 24, 35, 55, 64, 81, 104, 113, 92, 49, 64, 78, 87, 103, 121, 120, 101,
+    72, 92, 95, 98, 112, 100, 103, 99,
+];
+const CHROMA_QUANT: [u8; 64] = [
+    17, 18, 24, 47, 99, 99, 99, 99, 18, 21, 26, 66, 99, 99, 99, 99, 24, 26, 56, 99, 99, 99, 99, 99, 47, 66, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99,
+];
+
+/// Natural order index of each coefficient in zigzag order.
+const ZIGZAG: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27, 20, 13, 6, 7, 14, 21,
+    28, 35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51, 58, 59, 52, 45, 38, 31, 39, 46, 53, 60, 61, 54,
+    47, 55, 62, 63,
+];
+
+/// Huffman tables of JPEG Annex K as code counts per length and symbols.
+const DC_LUMA_BITS: [u8; 16] = [0, 1, 5, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0];
+const DC_CHROMA_BITS: [u8; 16] = [0, 3, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0];
+const DC_VALUES: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+const AC_LUMA_BITS: [u8; 16] = [0, 2, 1, 3, 3, 2, 4, 3, 5, 5, 4, 4, 0, 0, 1, 0x7d];
+const AC_LUMA_VALUES: [u8; 162] = [
+    0x01, 0x02, 0x03, 0x00, 0x04, 0x11, 0x05, 0x12, 0x21, 0x31, 0x41, 0x06, 0x13, 0x51, 0x61, 0x07, 0x22, 0x71, 0x14,
+    0x32, 0x81, 0x91, 0xa1, 0x08, 0x23, 0x42, 0xb1, 0xc1, 0x15, 0x52, 0xd1, 0xf0, 0x24, 0x33, 0x62, 0x72, 0x82, 0x09,
+    0x0a, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3a,
+    0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4a, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5a, 0x63, 0x64, 0x65,
+    0x66, 0x67, 0x68, 0x69, 0x6a, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7a, 0x83, 0x84, 0x85, 0x86, 0x87, 0x88,
+    0x89, 0x8a, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98, 0x99, 0x9a, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7, 0xa8, 0xa9,
+    0xaa, 0xb2, 0xb3, 0xb4, 0xb5, 0xb6, 0xb7, 0xb8, 0xb9, 0xba, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7, 0xc8, 0xc9, 0xca,
+    0xd2, 0xd3, 0xd4, 0xd5, 0xd6, 0xd7, 0xd8, 0xd9, 0xda, 0xe1, 0xe2, 0xe3, 0xe4, 0xe5, 0xe6, 0xe7, 0xe8, 0xe9, 0xea,
+    0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa,
+];
+const AC_CHROMA_BITS: [u8; 16] = [0, 2, 1, 2, 4, 4, 3, 4, 7, 5, 4, 4, 0, 1, 2, 0x77];
+const AC_CHROMA_VALUES: [u8; 162] = [
+    0x00, 0x01, 0x02, 0x03, 0x11, 0x04, 0x05, 0x21, 0x31, 0x06, 0x12, 0x41, 0x51, 0x07, 0x61, 0x71, 0x13, 0x22, 0x32,
+    0x81, 0x08, 0x14, 0x42, 0x91, 0xa1, 0xb1, 0xc1, 0x09, 0x23, 0x33, 0x52, 0xf0, 0x15, 0x62, 0x72, 0xd1, 0x0a, 0x16,
+    0x24, 0x34, 0xe1, 0x25, 0xf1, 0x17, 0x18, 0x19, 0x1a, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x35, 0x36, 0x37, 0x38, 0x39,
+    0x3a, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4a, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5a, 0x63, 0x64,
+    0x65, 0x66, 0x67, 0x68, 0x69, 0x6a, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7a, 0x82, 0x83, 0x84, 0x85, 0x86,
+    0x87, 0x88, 0x89, 0x8a, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98, 0x99, 0x9a, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7,
+    0xa8, 0xa9, 0xaa, 0xb2, 0xb3, 0xb4, 0xb5, 0xb6, 0xb7, 0xb8, 0xb9, 0xba, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7, 0xc8,
+    0xc9, 0xca, 0xd2, 0xd3, 0xd4, 0xd5, 0xd6, 0xd7, 0xd8, 0xd9, 0xda, 0xe2, 0xe3, 0xe4, 0xe5, 0xe6, 0xe7, 0xe8, 0xe9,
+    0xea, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa,
+];
+
+/// Encodes frames of a single stream into JPEG.
+#[derive(Debug, Clone, Copy)]
+pub struct JpegEncoder {
+    format: PixelFormat,
+    size: Size,
+    stride: u32,
+    quality: u8,
+}
+
+impl JpegEncoder {
+    pub const DEFAULT_QUALITY: u8 = 90;
+
+    pub fn new(format: PixelFormat, size: Size, stride: u32) -> Self {
+        Self {
+            format,
+            size,
+            stride,
+            quality: Self::DEFAULT_QUALITY,
+        }
+    }
+
+    /// Creates encoder for frames produced by the given (validated) stream configuration.
+    pub fn from_stream_config(cfg: &StreamConfigurationRef<'_>) -> Self {
+        Self::new(cfg.get_pixel_format(), cfg.get_size(), cfg.get_stride())
+    }
+
+    /// Sets quality in range `1..=100`, which does not affect `MJPEG` frames.
+    pub fn quality(mut self, quality: u8) -> Self {
+        self.quality = quality.clamp(1, 100);
+        self
+    }
+
+    /// Returns `true` if frames of the configured pixel format can be encoded.
+    pub fn is_supported(&self) -> bool {
+        self.format == formats::MJPEG || RgbConverter::new(self.format, self.size, self.stride).is_supported()
+    }
+
+    /// Encodes memory mapped framebuffer.
+    ///
+    /// Only the bytes used by the frame are returned for `MJPEG` frames if framebuffer metadata is available.
+    pub fn encode_buffer<T: AsFrameBuffer>(&self, fb: &MemoryMappedFrameBuffer<T>) -> Result<Vec<u8>, JpegError> {
+        self.encode(&fb.data_used())
+    }
+
+    /// Encodes frame planes.
+    pub fn encode(&self, planes: &[&[u8]]) -> Result<Vec<u8>, JpegError> {
+        if self.format == formats::MJPEG {
+            let data = planes
+                .first()
+                .ok_or(RgbError::MissingPlanes { expected: 1, found: 0 })?;
+            return Ok(data.to_vec());
+        }
+
+        let image = match DrmFourcc::try_from(self.format.fourcc()) {
+            Ok(fourcc @ (DrmFourcc::Nv12 | DrmFourcc::Nv21 | DrmFourcc::Yuv420)) => self.read_yuv420(fourcc, planes)?,
+            _ => Ycbcr420::from_rgb(&RgbConverter::new(self.format, self.size, self.stride).convert(planes)?),
+        };
+        image.encode(self.quality)
+    }
+
+    /// Encodes an RGB image.
+    pub fn encode_rgb(image: &RgbImage, quality: u8) -> Result<Vec<u8>, JpegError> {
+        Ycbcr420::from_rgb(image).encode(quality.clamp(1, 100))
+    }
+
+    /// Copies planes of a 4:2:0 YUV frame, dropping the stride padding.
+    fn read_yuv420(&self, fourcc: DrmFourcc, planes: &[&[u8]]) -> Result<Ycbcr420, RgbError> {
+        let (width, height, stride) = (
+            self.size.width as usize,
+            self.size.height as usize,
+            self.stride as usize,
+        );
+        let (cw, ch) = (width.div_ceil(2), height.div_ceil(2));
+        let plane = |index: usize, stride: usize, rows: usize, row_len: usize| -> Result<&[u8], RgbError> {
+            let data = planes.get(index).ok_or(RgbError::MissingPlanes {
+                expected: index + 1,
+                found: planes.len(),
+            })?;
+            let expected = rows.saturating_sub(1) * stride + row_len;
+            if data.len() < expected {
+                return Err(RgbError::PlaneTooShort {
+                    index,
+                    len: data.len(),
+                    expected,
+                });
+            }
+            Ok(data)
+        };
+
+        let luma = plane(0, stride, height, width)?;
+        let mut image = Ycbcr420 {
+            width,
+            height,
+            y: Vec::with_capacity(width * height),
+            cb: Vec::with_capacity(cw * ch),
+            cr: Vec::with_capacity(cw * ch),
+        };
+        for row in 0..height {
+            image.y.extend_from_slice(&luma[row * stride..][..width]);
+        }
+
+        if fourcc == DrmFourcc::Yuv420 {
+            let chroma_stride = stride / 2;
+            let cb = plane(1, chroma_stride, ch, cw)?;
+            let cr = plane(2, chroma_stride, ch, cw)?;
+            for row in 0..ch {
+                image.cb.extend_from_slice(&cb[row * chroma_stride..][..cw]);
+                image.cr.extend_from_slice(&cr[row * chroma_stride..][..cw]);
+            }
+        } else {
+            let chroma = plane(1, stride, ch, cw * 2)?;
+            let (u, v) = if fourcc == DrmFourcc::Nv12 { (0, 1) } else { (1, 0) };
+            for row in 0..ch {
+                for uv in chroma[row * stride..][..cw * 2].chunks_exact(2) {
+                    image.cb.push(uv[u]);
+                    image.cr.push(uv[v]);
+                }
+            }
+        }
+        Ok(image)
+    }
+}
+
+/// Full range YCbCr image with chroma subsampled by 2 in both directions.
+struct Ycbcr420 {
+    width: usize,
+    height: usize,
+    y: Vec<u8>,
+    cb: Vec<u8>,
+    cr: Vec<u8>,
+}
+
+impl Ycbcr420 {
+    fn from_rgb(image: &RgbImage) -> Self {
+        let (width, height) = (image.width as usize, image.height as usize);
+        let (cw, ch) = (width.div_ceil(2), height.div_ceil(2));
+        let rgb = |x: usize, y: usize| {
+            let px = &image.data[(y * width + x) * 3..][..3];
+            [px[0] as f32, px[1] as f32, px[2] as f32]
+        };
+        let clamp = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+
+        let mut y = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for col in 0..width {
+                let [r, g, b] = rgb(col, row);
+                y.push(clamp(0.299 * r + 0.587 * g + 0.114 * b));
+            }
+        }
+
+        let mut cb = Vec::with_capacity(cw * ch);
+        let mut cr = Vec::with_capacity(cw * ch);
+        for row in 0..ch {
+            for col in 0..cw {
+                // Average of the 2x2 block, clamped at the right and bottom edges
+                let mut sum = [0.0; 3];
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let px = rgb((col * 2 + dx).min(width - 1), (row * 2 + dy).min(height - 1));
+                    for c in 0..3 {
+                        sum[c] += px[c] / 4.0;
+                    }
+                }
+                let [r, g, b] = sum;
+                cb.push(clamp(-0.168736 * r - 0.331264 * g + 0.5 * b + 128.0));
+                cr.push(clamp(0.5 * r - 0.418688 * g - 0.081312 * b + 128.0));
+            }
+        }
+
+        Self {
+            width,
+            height,
+            y,
+            cb,
+            cr,
+        }
+    }
+
+    fn encode(&self, quality: u8) -> Result<Vec<u8>, JpegError> {
+        let (width, height) = (self.width, self.height);
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(JpegError::TooLarge(Size {
+                width: width as _,
+                height: height as _,
+            }));
+        }
+        let (cw, ch) = (width.div_ceil(2), height.div_ceil(2));
+        let luma_quant = scale_quant(&LUMA_QUANT, quality);
+        let chroma_quant = scale_quant(&CHROMA_QUANT, quality);
+
+        let mut out = Vec::with_capacity(width * height / 4);
+        // SOI and JFIF APP0 with 1:1 pixel aspect ratio
+        out.extend([0xff, 0xd8]);
+        out.extend([0xff, 0xe0, 0, 16, b'J', b'F', b'I', b'F', 0, 1, 1, 0, 0, 1, 0, 1, 0, 0]);
+        for (id, quant) in [&luma_quant, &chroma_quant].into_iter().enumerate() {
+            out.extend([0xff, 0xdb, 0, 67, id as u8]);
+            out.extend(ZIGZAG.iter().map(|&i| quant[i]));
+        }
+        // Baseline frame, luma sampled 2x2 relative to chroma
+        out.extend([0xff, 0xc0, 0, 17, 8]);
+        out.extend((height as u16).to_be_bytes());
+        out.extend((width as u16).to_be_bytes());
+        out.extend([3, 1, 0x22, 0, 2, 0x11, 1, 3, 0x11, 1]);
+        for (class_id, bits, values) in [
+            (0x00, &DC_LUMA_BITS, &DC_VALUES[..]),
+            (0x10, &AC_LUMA_BITS, &AC_LUMA_VALUES[..]),
+            (0x01, &DC_CHROMA_BITS, &DC_VALUES[..]),
+            (0x11, &AC_CHROMA_BITS, &AC_CHROMA_VALUES[..]),
+        ] {
+            out.extend([0xff, 0xc4]);
+            out.extend(((19 + values.len()) as u16).to_be_bytes());
+            out.push(class_id);
+            out.extend(bits);
+            out.extend(values);
+        }
+        out.extend([0xff, 0xda, 0, 12, 3, 1, 0x00, 2, 0x11, 3, 0x11, 0, 63, 0]);
+
+        let luma_tables = (
+            HuffmanTable::new(&DC_LUMA_BITS, &DC_VALUES),
+            HuffmanTable::new(&AC_LUMA_BITS, &AC_LUMA_VALUES),
+        );
+        let chroma_tables = (
+            HuffmanTable::new(&DC_CHROMA_BITS, &DC_VALUES),
+            HuffmanTable::new(&AC_CHROMA_BITS, &AC_CHROMA_VALUES),
+        );
+        let dct = DctMatrix::new();
+        let mut writer = BitWriter { out, acc: 0, bits: 0 };
+        let mut dc = [0i32; 3];
+
+        for mcu_y in 0..height.div_ceil(16) {
+            for mcu_x in 0..width.div_ceil(16) {
+                for (bx, by) in [(0, 0), (8, 0), (0, 8), (8, 8)] {
+                    let block = read_block(&self.y, width, height, mcu_x * 16 + bx, mcu_y * 16 + by);
+                    let coefs = dct.quantize(&block, &luma_quant);
+                    encode_block(&mut writer, &coefs, &mut dc[0], &luma_tables);
+                }
+                for (plane, index) in [(&self.cb, 1), (&self.cr, 2)] {
+                    let block = read_block(plane, cw, ch, mcu_x * 8, mcu_y * 8);
+                    let coefs = dct.quantize(&block, &chroma_quant);
+                    encode_block(&mut writer, &coefs, &mut dc[index], &chroma_tables);
+                }
+            }
+        }
+
+        let mut out = writer.finish();
+        out.extend([0xff, 0xd9]);
+        Ok(out)
+    }
+}
+
+/// Scales an Annex K quantization table in the same way as libjpeg.
+fn scale_quant(table: &[u8; 64], quality: u8) -> [u8; 64] {
+    let quality = quality.clamp(1, 100) as u32;
+    let scale = if quality < 50 {
+        5000 / quality
+    } else {
+        200 - quality * 2
+    };
+    table.map(|q| ((q as u32 * scale + 50) / 100).clamp(1, 255) as u8)
+}
+
+/// Reads an 8x8 block, repeating the last column and row past the edges.
+fn read_block(plane: &[u8], width: usize, height: usize, x0: usize, y0: usize) -> [f32; 64] {
+    let mut block = [0.0; 64];
+    for y in 0..8 {
+        let row = &plane[(y0 + y).min(height - 1) * width..][..width];
+        for x in 0..8 {
+            block[y * 8 + x] = row[(x0 + x).min(width - 1)] as f32 - 128.0;
+        }
+    }
+    block
+}
+
+/// Orthonormal 8-point DCT-II basis.
+struct DctMatrix([[f32; 8]; 8]);
+
+impl DctMatrix {
+    fn new() -> Self {
+        let mut m = [[0.0; 8]; 8];
+        for (u, row) in m.iter_mut().enumerate() {
+            let scale = if u == 0 { (1.0f32 / 8.0).sqrt() } else { 0.5 };
+            for (x, c) in row.iter_mut().enumerate() {
+                *c = scale * ((2 * x + 1) as f32 * u as f32 * std::f32::consts::PI / 16.0).cos();
+            }
+        }
+        Self(m)
+    }
+
+    /// Transforms a level shifted block and quantizes the coefficients, returned in zigzag order.
+    fn quantize(&self, block: &[f32; 64], quant: &[u8; 64]) -> [i32; 64] {
+        let m = &self.0;
+        let mut rows = [0.0f32; 64];
+        for y in 0..8 {
+            for u in 0..8 {
+                rows[y * 8 + u] = (0..8).map(|x| m[u][x] * block[y * 8 + x]).sum();
+            }
+        }
+        let mut coefs = [0i32; 64];
+        for (i, &n) in ZIGZAG.iter().enumerate() {
+            let (v, u) = (n / 8, n % 8);
+            let value: f32 = (0..8).map(|y| m[v][y] * rows[y * 8 + u]).sum();
+            coefs[i] = (value / quant[n] as f32).round() as i32;
+        }
+        coefs
+    }
+}
+
+/// Huffman codes indexed by symbol.
+struct HuffmanTable {
+    codes: [(u16, u8); 256],
+}
+
+impl HuffmanTable {
+    fn new(bits: &[u8; 16], values: &[u8]) -> Self {
+        let mut codes = [(0, 0); 256];
+        let mut code = 0u16;
+        let mut values = values.iter();
+        for (len, &count) in bits.iter().enumerate() {
+            for _ in 0..count {
+                codes[*values.next().unwrap() as usize] = (code, len as u8 + 1);
+                code += 1;
+            }
+            code <<= 1;
+        }
+        Self { codes }
+    }
+}
+
+/// Entropy coded segment writer with `0xff` byte stuffing.
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, len: u8) {
+        if len == 0 {
+            return;
+        }
+        self.acc = (self.acc << len) | (value & ((1 << len) - 1));
+        self.bits += len as u32;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            let byte = (self.acc >> self.bits) as u8;
+            self.out.push(byte);
+            if byte == 0xff {
+                self.out.push(0);
+            }
+        }
+    }
+
+    fn write_code(&mut self, table: &HuffmanTable, symbol: u8) {
+        let (code, len) = table.codes[symbol as usize];
+        self.write(code as u32, len);
+    }
+
+    /// Pads the last byte with ones.
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.write(0x7f, (8 - self.bits) as u8);
+        }
+        self.out
+    }
+}
+
+/// Returns JPEG magnitude category and the bits of a coefficient.
+fn magnitude(value: i32) -> (u8, u32) {
+    let category = (32 - value.unsigned_abs().leading_zeros()) as u8;
+    let bits = if value < 0 { value - 1 } else { value };
+    (category, bits as u32)
+}
+
+fn encode_block(
+    writer: &mut BitWriter,
+    coefs: &[i32; 64],
+    dc: &mut i32,
+    (dc_table, ac_table): &(HuffmanTable, HuffmanTable),
+) {
+    let (category, bits) = magnitude(coefs[0] - *dc);
+    *dc = coefs[0];
+    writer.write_code(dc_table, category);
+    writer.write(bits, category);
+
+    let mut run = 0;
+    for &coef in &coefs[1..] {
+        if coef == 0 {
+            run += 1;
+            continue;
+        }
+        while run > 15 {
+            // Zero run length of 16
+            writer.write_code(ac_table, 0xf0);
+            run -= 16;
+        }
+        let (category, bits) = magnitude(coef);
+        writer.write_code(ac_table, (run << 4) | category);
+        writer.write(bits, category);
+        run = 0;
+    }
+    if run > 0 {
+        // End of block
+        writer.write_code(ac_table, 0x00);
+    }
+}
+
+impl<T: AsFrameBuffer> MemoryMappedFrameBuffer<T> {
+    /// Encodes framebuffer contents into JPEG, see [JpegEncoder].
+    pub fn jpeg(&self, encoder: &JpegEncoder) -> Result<Vec<u8>, JpegError> {
+        encoder.encode_buffer(self)
+    }
+}
@@ -0,0 +1,556 @@
+//! Live preview by scanning out frames directly on a DRM/KMS display.
+//!
+//! [KmsSink] imports framebuffers of a [CaptureSession] stream into DRM and page-flips between them, so headless
+//! systems (i.e. Raspberry Pi without X11 or Wayland) can show a preview without copying frames. Like the KMS sink of
+//! libcamera's `cam` utility, the stream size must match one of the display modes, see [KmsSink::display_sizes()].
+//! The process must be the DRM master, which is the case when no display server is running.
+
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{self, Read},
+    os::fd::AsRawFd,
+    path::Path,
+};
+
+use thiserror::Error;
+
+use crate::{
+    framebuffer::{AsFrameBuffer, PlaneGroup},
+    geometry::Size,
+    pixel_format::PixelFormat,
+    session::{CaptureSession, CompletedRequest},
+    stream::StreamRole,
+};
+
+#[derive(Debug, Error)]
+pub enum KmsError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("No connected display found")]
+    NoConnector,
+    #[error("Display has no mode of size {0:?}")]
+    NoMode(Size),
+    #[error("No CRTC available for the display")]
+    NoCrtc,
+    #[error("Stream {0:?} is not configured")]
+    NoStream(StreamRole),
+    #[error("Request has no frame of stream {0:?}")]
+    MissingFrame(StreamRole),
+}
+
+const fn iowr<T>(nr: u64) -> u64 {
+    (3 << 30) | ((core::mem::size_of::<T>() as u64) << 16) | ((b'd' as u64) << 8) | nr
+}
+
+const fn iow<T>(nr: u64) -> u64 {
+    (1 << 30) | ((core::mem::size_of::<T>() as u64) << 16) | ((b'd' as u64) << 8) | nr
+}
+
+const DRM_IOCTL_GEM_CLOSE: u64 = iow::<DrmGemClose>(0x09);
+const DRM_IOCTL_PRIME_FD_TO_HANDLE: u64 = iowr::<DrmPrimeHandle>(0x2e);
+const DRM_IOCTL_MODE_GETRESOURCES: u64 = iowr::<DrmModeCardRes>(0xa0);
+const DRM_IOCTL_MODE_SETCRTC: u64 = iowr::<DrmModeCrtc>(0xa2);
+const DRM_IOCTL_MODE_GETENCODER: u64 = iowr::<DrmModeGetEncoder>(0xa6);
+const DRM_IOCTL_MODE_GETCONNECTOR: u64 = iowr::<DrmModeGetConnector>(0xa7);
+const DRM_IOCTL_MODE_RMFB: u64 = iowr::<u32>(0xaf);
+const DRM_IOCTL_MODE_PAGE_FLIP: u64 = iowr::<DrmModeCrtcPageFlip>(0xb0);
+const DRM_IOCTL_MODE_ADDFB2: u64 = iowr::<DrmModeFbCmd2>(0xb8);
+
+const DRM_MODE_CONNECTED: u32 = 1;
+const DRM_MODE_TYPE_PREFERRED: u32 = 1 << 3;
+const DRM_MODE_PAGE_FLIP_EVENT: u32 = 0x01;
+const DRM_MODE_FB_MODIFIERS: u32 = 1 << 1;
+const DRM_EVENT_FLIP_COMPLETE: u32 = 0x02;
+
+#[repr(C)]
+#[derive(Default)]
+struct DrmGemClose {
+    handle: u32,
+    pad: u32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct DrmPrimeHandle {
+    handle: u32,
+    flags: u32,
+    fd: i32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct DrmModeCardRes {
+    fb_id_ptr: u64,
+    crtc_id_ptr: u64,
+    connector_id_ptr: u64,
+    encoder_id_ptr: u64,
+    count_fbs: u32,
+    count_crtcs: u32,
+    count_connectors: u32,
+    count_encoders: u32,
+    min_width: u32,
+    max_width: u32,
+    min_height: u32,
+    max_height: u32,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct DrmModeModeInfo {
+    clock: u32,
+    hdisplay: u16,
+    hsync_start: u16,
+    hsync_end: u16,
+    htotal: u16,
+    hskew: u16,
+    vdisplay: u16,
+    vsync_start: u16,
+    vsync_end: u16,
+    vtotal: u16,
+    vscan: u16,
+    vrefresh: u32,
+    flags: u32,
+    ty: u32,
+    name: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct DrmModeGetConnector {
+    encoders_ptr: u64,
+    modes_ptr: u64,
+    props_ptr: u64,
+    prop_values_ptr: u64,
+    count_modes: u32,
+    count_props: u32,
+    count_encoders: u32,
+    encoder_id: u32,
+    connector_id: u32,
+    connector_type: u32,
+    connector_type_id: u32,
+    connection: u32,
+    mm_width: u32,
+    mm_height: u32,
+    subpixel: u32,
+    pad: u32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct DrmModeGetEncoder {
+    encoder_id: u32,
+    encoder_type: u32,
+    crtc_id: u32,
+    possible_crtcs: u32,
+    possible_clones: u32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct DrmModeCrtc {
+    set_connectors_ptr: u64,
+    count_connectors: u32,
+    crtc_id: u32,
+    fb_id: u32,
+    x: u32,
+    y: u32,
+    gamma_size: u32,
+    mode_valid: u32,
+    mode: DrmModeModeInfo,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct DrmModeFbCmd2 {
+    fb_id: u32,
+    width: u32,
+    height: u32,
+    pixel_format: u32,
+    flags: u32,
+    handles: [u32; 4],
+    pitches: [u32; 4],
+    offsets: [u32; 4],
+    modifier: [u64; 4],
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct DrmModeCrtcPageFlip {
+    crtc_id: u32,
+    fb_id: u32,
+    flags: u32,
+    reserved: u32,
+    user_data: u64,
+}
+
+fn drm_ioctl<T>(file: &File, request: u64, arg: &mut T) -> io::Result<()> {
+    loop {
+        let ret = unsafe { libc::ioctl(file.as_raw_fd(), request as _, arg as *mut T) };
+        if ret == 0 {
+            return Ok(());
+        }
+        let err = io::Error::last_os_error();
+        if !matches!(err.raw_os_error(), Some(libc::EINTR | libc::EAGAIN)) {
+            return Err(err);
+        }
+    }
+}
+
+struct Connector {
+    id: u32,
+    encoder_id: u32,
+    encoders: Vec<u32>,
+    modes: Vec<DrmModeModeInfo>,
+}
+
+struct Card {
+    file: File,
+    crtcs: Vec<u32>,
+    connectors: Vec<Connector>,
+}
+
+impl Card {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+
+        let mut res = DrmModeCardRes::default();
+        drm_ioctl(&file, DRM_IOCTL_MODE_GETRESOURCES, &mut res)?;
+        let mut crtcs = vec![0u32; res.count_crtcs as usize];
+        let mut connector_ids = vec![0u32; res.count_connectors as usize];
+        let mut res = DrmModeCardRes {
+            crtc_id_ptr: crtcs.as_mut_ptr() as u64,
+            count_crtcs: crtcs.len() as _,
+            connector_id_ptr: connector_ids.as_mut_ptr() as u64,
+            count_connectors: connector_ids.len() as _,
+            ..Default::default()
+        };
+        drm_ioctl(&file, DRM_IOCTL_MODE_GETRESOURCES, &mut res)?;
+        crtcs.truncate(res.count_crtcs as usize);
+        connector_ids.truncate(res.count_connectors as usize);
+
+        let mut connectors = Vec::new();
+        for id in connector_ids {
+            let mut conn = DrmModeGetConnector {
+                connector_id: id,
+                ..Default::default()
+            };
+            drm_ioctl(&file, DRM_IOCTL_MODE_GETCONNECTOR, &mut conn)?;
+            if conn.connection != DRM_MODE_CONNECTED {
+                continue;
+            }
+
+            let mut modes = vec![DrmModeModeInfo::default(); conn.count_modes as usize];
+            let mut encoders = vec![0u32; conn.count_encoders as usize];
+            let mut conn = DrmModeGetConnector {
+                connector_id: id,
+                modes_ptr: modes.as_mut_ptr() as u64,
+                count_modes: modes.len() as _,
+                encoders_ptr: encoders.as_mut_ptr() as u64,
+                count_encoders: encoders.len() as _,
+                ..Default::default()
+            };
+            drm_ioctl(&file, DRM_IOCTL_MODE_GETCONNECTOR, &mut conn)?;
+            modes.truncate(conn.count_modes as usize);
+            encoders.truncate(conn.count_encoders as usize);
+
+            connectors.push(Connector {
+                id,
+                encoder_id: conn.encoder_id,
+                encoders,
+                modes,
+            });
+        }
+
+        Ok(Self {
+            file,
+            crtcs,
+            connectors,
+        })
+    }
+
+    fn encoder(&self, id: u32) -> io::Result<DrmModeGetEncoder> {
+        let mut enc = DrmModeGetEncoder {
+            encoder_id: id,
+            ..Default::default()
+        };
+        drm_ioctl(&self.file, DRM_IOCTL_MODE_GETENCODER, &mut enc)?;
+        Ok(enc)
+    }
+
+    /// Returns CRTC currently driving the connector, or the first one it can be driven by.
+    fn crtc_for(&self, conn: &Connector) -> io::Result<Option<u32>> {
+        if conn.encoder_id != 0 {
+            let crtc = self.encoder(conn.encoder_id)?.crtc_id;
+            if crtc != 0 {
+                return Ok(Some(crtc));
+            }
+        }
+        for encoder in conn.encoders.iter() {
+            let possible = self.encoder(*encoder)?.possible_crtcs;
+            if let Some(crtc) = self
+                .crtcs
+                .iter()
+                .enumerate()
+                .find(|(i, _)| possible & (1 << i) != 0)
+                .map(|(_, crtc)| *crtc)
+            {
+                return Ok(Some(crtc));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// A framebuffer imported into DRM.
+struct KmsBuffer {
+    fb_id: u32,
+    handles: Vec<u32>,
+}
+
+/// Displays frames of a [CaptureSession] stream on a DRM/KMS display.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use libcamera::{kms::KmsSink, session::CaptureSession, stream::StreamRole};
+/// # fn example(session: &mut CaptureSession<'_>) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut sink = KmsSink::new("/dev/dri/card0", session, StreamRole::ViewFinder)?;
+/// session.start(None)?;
+/// loop {
+///     let req = session.next_request(Duration::from_secs(1))?;
+///     if let Some(released) = sink.show(req)? {
+///         session.queue(released)?;
+///     }
+/// }
+/// # }
+/// ```
+pub struct KmsSink {
+    card: Card,
+    connector: u32,
+    crtc: u32,
+    mode: DrmModeModeInfo,
+    role: StreamRole,
+    pixel_format: PixelFormat,
+    size: Size,
+    stride: u32,
+    /// Imported framebuffers keyed by the file descriptor of their first plane.
+    buffers: HashMap<i32, KmsBuffer>,
+    /// Request being scanned out.
+    displayed: Option<CompletedRequest>,
+    /// Request whose page flip was not completed yet.
+    pending: Option<CompletedRequest>,
+}
+
+impl KmsSink {
+    /// Lists sizes of display modes of the first connected display.
+    ///
+    /// Configure the previewed stream to one of them.
+    pub fn display_sizes(card: impl AsRef<Path>) -> Result<Vec<Size>, KmsError> {
+        let card = Card::open(card.as_ref())?;
+        let conn = card.connectors.first().ok_or(KmsError::NoConnector)?;
+        let mut sizes = conn
+            .modes
+            .iter()
+            .map(|m| Size {
+                width: m.hdisplay as _,
+                height: m.vdisplay as _,
+            })
+            .collect::<Vec<_>>();
+        sizes.dedup();
+        Ok(sizes)
+    }
+
+    /// Opens DRM card and selects a mode of the first connected display matching the size of the stream.
+    pub fn new(card: impl AsRef<Path>, session: &CaptureSession<'_>, role: StreamRole) -> Result<Self, KmsError> {
+        let cfg = session
+            .streams()
+            .get(role)
+            .and_then(|index| session.config().get(*index))
+            .ok_or(KmsError::NoStream(role))?;
+        let size = cfg.get_size();
+
+        let card = Card::open(card.as_ref())?;
+        let conn = card.connectors.first().ok_or(KmsError::NoConnector)?;
+        let mode = conn
+            .modes
+            .iter()
+            .filter(|m| m.hdisplay as u32 == size.width && m.vdisplay as u32 == size.height)
+            .max_by_key(|m| (m.ty & DRM_MODE_TYPE_PREFERRED != 0, m.vrefresh))
+            .copied()
+            .ok_or(KmsError::NoMode(size))?;
+        let crtc = card.crtc_for(conn)?.ok_or(KmsError::NoCrtc)?;
+
+        Ok(Self {
+            connector: conn.id,
+            crtc,
+            mode,
+            role,
+            pixel_format: cfg.get_pixel_format(),
+            size,
+            stride: cfg.get_stride(),
+            card,
+            buffers: HashMap::new(),
+            displayed: None,
+            pending: None,
+        })
+    }
+
+    /// Refresh rate of the selected display mode in Hz.
+    pub fn refresh_rate(&self) -> u32 {
+        self.mode.vrefresh
+    }
+
+    /// Schedules the frame for display and returns the request that is no longer scanned out.
+    ///
+    /// Requests are held while displayed, so queue the returned one back to the session. Blocks until the previous
+    /// page flip completes, which paces the caller to the display refresh rate if frames arrive faster.
+    pub fn show(&mut self, req: CompletedRequest) -> Result<Option<CompletedRequest>, KmsError> {
+        let fb_id = {
+            let frame = req.frame(self.role).ok_or(KmsError::MissingFrame(self.role))?;
+            self.import(frame.buffer())?
+        };
+
+        let released = self.wait_flip()?;
+
+        if self.displayed.is_none() {
+            let mut connectors = [self.connector];
+            let mut crtc = DrmModeCrtc {
+                set_connectors_ptr: connectors.as_mut_ptr() as u64,
+                count_connectors: 1,
+                crtc_id: self.crtc,
+                fb_id,
+                mode_valid: 1,
+                mode: self.mode,
+                ..Default::default()
+            };
+            drm_ioctl(&self.card.file, DRM_IOCTL_MODE_SETCRTC, &mut crtc)?;
+            self.displayed = Some(req);
+            return Ok(released);
+        }
+
+        let mut flip = DrmModeCrtcPageFlip {
+            crtc_id: self.crtc,
+            fb_id,
+            flags: DRM_MODE_PAGE_FLIP_EVENT,
+            ..Default::default()
+        };
+        drm_ioctl(&self.card.file, DRM_IOCTL_MODE_PAGE_FLIP, &mut flip)?;
+        self.pending = Some(req);
+        Ok(released)
+    }
+
+    /// Waits for a pending page flip and returns all held requests, i.e. before stopping the session.
+    ///
+    /// The display keeps showing the last frame until the next [KmsSink::show()].
+    pub fn release_all(&mut self) -> Result<Vec<CompletedRequest>, KmsError> {
+        let mut released = Vec::new();
+        released.extend(self.wait_flip()?);
+        released.extend(self.displayed.take());
+        Ok(released)
+    }
+
+    /// Waits for the pending page flip to complete and returns the request it replaced.
+    fn wait_flip(&mut self) -> Result<Option<CompletedRequest>, KmsError> {
+        if self.pending.is_none() {
+            return Ok(None);
+        }
+
+        let mut buf = [0u8; 1024];
+        'wait: loop {
+            let len = (&self.card.file).read(&mut buf)?;
+            let mut events = &buf[..len];
+            // struct drm_event { __u32 type; __u32 length; }
+            while events.len() >= 8 {
+                let ty = u32::from_ne_bytes(events[0..4].try_into().unwrap());
+                let length = u32::from_ne_bytes(events[4..8].try_into().unwrap()) as usize;
+                if ty == DRM_EVENT_FLIP_COMPLETE {
+                    break 'wait;
+                }
+                events = &events[length.clamp(8, events.len())..];
+            }
+        }
+
+        let released = self.displayed.take();
+        self.displayed = self.pending.take();
+        Ok(released)
+    }
+
+    fn import(&mut self, fb: &impl AsFrameBuffer) -> Result<u32, KmsError> {
+        let mut groups = fb.plane_groups();
+        let key = groups.first().map(|g| g.fd).unwrap_or(-1);
+        if let Some(buffer) = self.buffers.get(&key) {
+            return Ok(buffer.fb_id);
+        }
+        PlaneGroup::set_strides(&mut groups, self.pixel_format, self.stride);
+
+        let mut cmd = DrmModeFbCmd2 {
+            width: self.size.width,
+            height: self.size.height,
+            pixel_format: self.pixel_format.fourcc(),
+            ..Default::default()
+        };
+        let modifier = self.pixel_format.modifier();
+        if modifier != 0 {
+            cmd.flags |= DRM_MODE_FB_MODIFIERS;
+        }
+
+        let mut handles = Vec::new();
+        let result = (|| {
+            for group in groups.iter() {
+                let mut prime = DrmPrimeHandle {
+                    fd: group.fd,
+                    ..Default::default()
+                };
+                drm_ioctl(&self.card.file, DRM_IOCTL_PRIME_FD_TO_HANDLE, &mut prime)?;
+                handles.push(prime.handle);
+
+                for plane in group.planes.iter().filter(|p| p.index < 4) {
+                    cmd.handles[plane.index] = prime.handle;
+                    cmd.offsets[plane.index] = plane.offset as _;
+                    cmd.pitches[plane.index] = plane.stride.unwrap_or(0);
+                    if modifier != 0 {
+                        cmd.modifier[plane.index] = modifier;
+                    }
+                }
+            }
+            drm_ioctl(&self.card.file, DRM_IOCTL_MODE_ADDFB2, &mut cmd)
+        })();
+
+        let buffer = KmsBuffer {
+            fb_id: cmd.fb_id,
+            handles,
+        };
+        if let Err(e) = result {
+            self.destroy(buffer);
+            return Err(e.into());
+        }
+
+        let fb_id = buffer.fb_id;
+        self.buffers.insert(key, buffer);
+        Ok(fb_id)
+    }
+
+    fn destroy(&self, buffer: KmsBuffer) {
+        if buffer.fb_id != 0 {
+            let mut fb_id = buffer.fb_id;
+            let _ = drm_ioctl(&self.card.file, DRM_IOCTL_MODE_RMFB, &mut fb_id);
+        }
+        let mut handles = buffer.handles;
+        // Importing the same dmabuf twice yields the same GEM handle, which must only be closed once
+        handles.sort_unstable();
+        handles.dedup();
+        for handle in handles {
+            let mut close = DrmGemClose { handle, pad: 0 };
+            let _ = drm_ioctl(&self.card.file, DRM_IOCTL_GEM_CLOSE, &mut close);
+        }
+    }
+}
+
+impl Drop for KmsSink {
+    fn drop(&mut self) {
+        let _ = self.wait_flip();
+        for (_, buffer) in std::mem::take(&mut self.buffers) {
+            self.destroy(buffer);
+        }
+    }
+}
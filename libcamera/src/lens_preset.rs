@@ -0,0 +1,187 @@
+//! Named lens position presets.
+//!
+//! Focus calibration, such as the lens position for a document scanner stand, is only valid for a particular camera
+//! module. [LensPresets] stores positions per camera id, which libcamera keeps stable across reboots, so presets can
+//! be saved once and applied with a manual AF mode on the next startup without running autofocus.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use thiserror::Error;
+
+use crate::{
+    camera::Camera,
+    control::{ControlEntry, ControlError, ControlList},
+    controls::{AfMode, LensPosition},
+};
+
+const HEADER: &str = "# libcamera-rs lens presets v1";
+
+#[derive(Debug, Error)]
+pub enum LensPresetError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid presets at line {line}: {msg}")]
+    Parse { line: usize, msg: String },
+    #[error("Invalid preset name {0:?}")]
+    InvalidName(String),
+    #[error("Preset {name:?} not found for camera {camera_id:?}")]
+    NotFound { camera_id: String, name: String },
+    #[error("Camera does not support lens position control")]
+    Unsupported,
+    #[error("Control error: {0}")]
+    Control(#[from] ControlError),
+}
+
+/// Lens position presets keyed by camera id and preset name.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LensPresets {
+    cameras: BTreeMap<String, BTreeMap<String, f32>>,
+}
+
+impl LensPresets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns lens position of a preset in dioptres.
+    pub fn get(&self, camera_id: &str, name: &str) -> Option<f32> {
+        self.cameras.get(camera_id)?.get(name).copied()
+    }
+
+    /// Stores lens position of a preset, returning the previous one.
+    ///
+    /// Camera ids and names must not contain tabs or line breaks.
+    pub fn set(&mut self, camera_id: &str, name: &str, position: f32) -> Result<Option<f32>, LensPresetError> {
+        for s in [camera_id, name] {
+            if s.is_empty() || s.contains(['\t', '\n', '\r']) {
+                return Err(LensPresetError::InvalidName(s.to_string()));
+            }
+        }
+
+        Ok(self
+            .cameras
+            .entry(camera_id.to_string())
+            .or_default()
+            .insert(name.to_string(), position))
+    }
+
+    /// Removes a preset, returning its lens position.
+    pub fn remove(&mut self, camera_id: &str, name: &str) -> Option<f32> {
+        let presets = self.cameras.get_mut(camera_id)?;
+        let position = presets.remove(name);
+        if presets.is_empty() {
+            self.cameras.remove(camera_id);
+        }
+        position
+    }
+
+    /// Iterates over `(name, lens position)` of all presets of a camera.
+    pub fn presets<'a>(&'a self, camera_id: &str) -> impl Iterator<Item = (&'a str, f32)> {
+        self.cameras
+            .get(camera_id)
+            .into_iter()
+            .flat_map(|p| p.iter().map(|(name, pos)| (name.as_str(), *pos)))
+    }
+
+    /// Stores current lens position reported in request metadata as a preset of the camera.
+    pub fn capture(&mut self, cam: &Camera<'_>, name: &str, metadata: &ControlList) -> Result<f32, LensPresetError> {
+        let position = *metadata.get::<LensPosition>()?;
+        self.set(cam.id(), name, position)?;
+        Ok(position)
+    }
+
+    /// Sets manual AF mode and the lens position of a preset into `controls`.
+    ///
+    /// Pass the list to [ActiveCamera::start()](crate::camera::ActiveCamera::start) to focus from the first frame.
+    /// Position is clamped to the lens limits in case the module was replaced by a slightly different one.
+    pub fn apply(&self, cam: &Camera<'_>, name: &str, controls: &mut ControlList) -> Result<f32, LensPresetError> {
+        let mut position = self.get(cam.id(), name).ok_or_else(|| LensPresetError::NotFound {
+            camera_id: cam.id().to_string(),
+            name: name.to_string(),
+        })?;
+
+        let info = cam
+            .controls()
+            .get(LensPosition::ID)
+            .ok_or(LensPresetError::Unsupported)?;
+        if let Some(clamped) = info.clamp(&position.into()) {
+            position = f32::try_from(clamped).map_err(ControlError::from)?;
+        }
+
+        if cam.controls().contains(AfMode::ID) {
+            controls.set(AfMode::Manual)?;
+        }
+        controls.set(LensPosition(position))?;
+        Ok(position)
+    }
+
+    /// Writes presets in a simple line-based text format.
+    pub fn write_to(&self, mut w: impl Write) -> io::Result<()> {
+        writeln!(w, "{HEADER}")?;
+        for (camera_id, presets) in self.cameras.iter() {
+            for (name, position) in presets.iter() {
+                writeln!(w, "{camera_id}\t{name}\t{position}")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads presets written by [LensPresets::write_to()].
+    pub fn read_from(r: impl BufRead) -> Result<Self, LensPresetError> {
+        let mut presets = Self::new();
+
+        for (index, line) in r.lines().enumerate() {
+            let line = line?;
+            let parse_err = |msg: &str| LensPresetError::Parse {
+                line: index + 1,
+                msg: msg.to_string(),
+            };
+
+            if index == 0 {
+                if line != HEADER {
+                    return Err(parse_err("missing header"));
+                }
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split('\t');
+            let (Some(camera_id), Some(name), Some(position), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(parse_err("expected camera id, name and position"));
+            };
+            let position = position.parse().map_err(|_| parse_err("invalid position"))?;
+            presets
+                .set(camera_id, name, position)
+                .map_err(|e| parse_err(&e.to_string()))?;
+        }
+
+        Ok(presets)
+    }
+
+    /// Loads presets from a file, returning empty presets if it does not exist.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LensPresetError> {
+        match File::open(path) {
+            Ok(file) => Self::read_from(BufReader::new(file)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Saves presets into a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), LensPresetError> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_to(&mut w)?;
+        w.flush()?;
+        Ok(())
+    }
+}
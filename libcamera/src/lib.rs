@@ -1,18 +1,70 @@
 #![warn(rust_2018_idioms)]
 
+#[cfg(feature = "awb")]
+pub mod awb;
+pub mod bandwidth;
 pub mod camera;
 pub mod camera_manager;
+pub mod color_space;
 pub mod control;
+pub mod control_compare;
+pub mod control_recorder;
 pub mod control_value;
+#[cfg(feature = "debayer")]
+pub mod debayer;
+pub mod device_access;
+pub mod dmabuf;
+#[cfg(feature = "egl")]
+pub mod egl;
+#[cfg(feature = "vendor_rpi")]
+pub mod embedded_data;
+pub mod error;
+#[cfg(feature = "exif")]
+pub mod exif;
+#[cfg(feature = "format-compat")]
+pub mod format_compat;
+pub mod frame;
 pub mod framebuffer;
 pub mod framebuffer_allocator;
 pub mod framebuffer_map;
 pub mod geometry;
+#[cfg(feature = "image")]
+pub mod image;
+#[cfg(feature = "jpeg")]
+pub mod jpeg;
+pub mod kms;
+pub mod lens_preset;
 pub mod logging;
+pub mod mounting;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
+pub mod observer;
+pub mod orientation;
 pub mod pixel_format;
+pub mod raw_mode;
+#[cfg(feature = "recorder")]
+pub mod recorder;
 pub mod request;
+pub mod resume;
+pub mod rgb;
+pub mod role_rules;
+#[cfg(feature = "vendor_rpi")]
+pub mod rpi;
+pub mod session;
+#[cfg(feature = "shm-export")]
+pub mod shm_export;
+pub mod signal;
+#[cfg(feature = "sink")]
+pub mod sink;
 pub mod stream;
+pub mod trigger;
 pub mod utils;
+#[cfg(feature = "vulkan")]
+pub mod vulkan;
+pub mod watchdog;
+pub mod yuv;
 
 mod generated;
+mod pixels;
+pub use error::Error;
 pub use generated::*;
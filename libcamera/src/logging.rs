@@ -1,9 +1,14 @@
 use std::{
-    ffi::{CStr, CString},
-    io,
+    collections::VecDeque,
+    ffi::{c_char, c_void, CStr, CString},
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+    sync::Mutex,
 };
 
 use libcamera_sys::*;
+use thiserror::Error;
 
 use crate::utils::handle_result;
 
@@ -23,7 +28,7 @@ impl From<LoggingTarget> for libcamera_logging_target_t {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum LoggingLevel {
     Debug,
     Info,
@@ -44,6 +49,64 @@ impl From<LoggingLevel> for &'static CStr {
     }
 }
 
+#[derive(Debug, Error)]
+pub enum LogLevelParseError {
+    #[error("Unknown logging level {0:?}")]
+    InvalidLevel(String),
+    #[error("Expected `category:level`, found {0:?}")]
+    InvalidEntry(String),
+}
+
+/// Parses level names (`DEBUG`, `INFO`, `WARN`, `ERROR`, `FATAL`) and their numeric values `0` to `4`, as accepted by
+/// libcamera.
+impl FromStr for LoggingLevel {
+    type Err = LogLevelParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let level = match s {
+            "0" => Some(Self::Debug),
+            "1" => Some(Self::Info),
+            "2" => Some(Self::Warn),
+            "3" => Some(Self::Error),
+            "4" => Some(Self::Fatal),
+            _ => Self::parse(&s.to_ascii_uppercase()),
+        };
+        level.ok_or_else(|| LogLevelParseError::InvalidLevel(s.to_string()))
+    }
+}
+
+/// Parses a comma separated list of `category:level` entries, using the syntax of the `LIBCAMERA_LOG_LEVELS`
+/// environment variable, i.e. `Camera:DEBUG,RPI:WARN`.
+///
+/// Use [CameraManager::log_set_levels()](crate::camera_manager::CameraManager::log_set_levels) to apply them.
+/// Wildcard categories are not supported, as libcamera only sets levels of categories by their exact name.
+pub fn parse_log_levels(spec: &str) -> Result<Vec<(String, LoggingLevel)>, LogLevelParseError> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (category, level) = entry
+                .split_once(':')
+                .filter(|(category, _)| !category.is_empty() && !category.contains('*'))
+                .ok_or_else(|| LogLevelParseError::InvalidEntry(entry.to_string()))?;
+            Ok((category.to_string(), level.trim().parse()?))
+        })
+        .collect()
+}
+
+impl LoggingLevel {
+    fn parse(severity: &str) -> Option<Self> {
+        match severity {
+            "DEBUG" => Some(Self::Debug),
+            "INFO" => Some(Self::Info),
+            "WARN" => Some(Self::Warn),
+            "ERROR" => Some(Self::Error),
+            "FATAL" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum LoggingStream {
     StdOut,
@@ -77,3 +140,196 @@ pub fn log_set_target(target: LoggingTarget) -> io::Result<()> {
     let ret = unsafe { libcamera_log_set_target(target.into()) };
     handle_result(ret)
 }
+
+/// A single libcamera log message, passed to the callback of [log_set_callback()].
+#[derive(Clone, Copy, Debug)]
+pub struct LogMessage<'a> {
+    pub level: LoggingLevel,
+    /// Log category, i.e. `Camera` or `RPI`.
+    pub category: &'a str,
+    /// Source location within libcamera as `file:line`.
+    pub location: &'a str,
+    pub message: &'a str,
+}
+
+impl<'a> LogMessage<'a> {
+    /// Parses a line in libcamera format `[timestamp] [pid] SEVERITY Category file:line message`.
+    ///
+    /// Lines in an unknown format are passed through as [LoggingLevel::Info] messages without category.
+    fn parse(line: &'a str) -> Self {
+        Self::try_parse(line).unwrap_or(Self {
+            level: LoggingLevel::Info,
+            category: "",
+            location: "",
+            message: line,
+        })
+    }
+
+    fn try_parse(line: &'a str) -> Option<Self> {
+        let skip_bracket = |s: &'a str| s.strip_prefix('[').and_then(|s| s.split_once("] ")).map(|(_, s)| s);
+        let rest = skip_bracket(skip_bracket(line)?)?.trim_start();
+        let mut fields = rest.splitn(4, ' ');
+        let level = LoggingLevel::parse(fields.next()?)?;
+        let category = fields.next()?;
+        let location = fields.next()?;
+        Some(Self {
+            level,
+            category,
+            location,
+            message: fields.next().unwrap_or(""),
+        })
+    }
+}
+
+/// An owned copy of [LogMessage], as kept by [log_set_buffer()].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRecord {
+    pub level: LoggingLevel,
+    pub category: String,
+    pub location: String,
+    pub message: String,
+}
+
+impl From<&LogMessage<'_>> for LogRecord {
+    fn from(msg: &LogMessage<'_>) -> Self {
+        Self {
+            level: msg.level,
+            category: msg.category.to_string(),
+            location: msg.location.to_string(),
+            message: msg.message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level: &CStr = self.level.into();
+        write!(
+            f,
+            "{} {} {} {}",
+            level.to_string_lossy(),
+            self.category,
+            self.location,
+            self.message
+        )
+    }
+}
+
+/// Receives raw log lines without the trailing newline.
+type LogSink = dyn FnMut(&str) + Send;
+
+static LOG_SINK: Mutex<Option<Box<LogSink>>> = Mutex::new(None);
+
+extern "C" fn log_cb(_data: *mut c_void, line: *const c_char, len: usize) {
+    let line = unsafe { std::slice::from_raw_parts(line.cast::<u8>(), len) };
+    let line = String::from_utf8_lossy(line);
+    if let Some(sink) = LOG_SINK.lock().unwrap().as_mut() {
+        sink(&line);
+    }
+}
+
+fn log_set_sink(sink: Box<LogSink>) -> io::Result<()> {
+    *LOG_SINK.lock().unwrap() = Some(sink);
+    let ret = unsafe { libcamera_log_set_callback(Some(log_cb), core::ptr::null_mut()) };
+    handle_result(ret)
+}
+
+/// Direct logging to a callback, which receives each message parsed into its severity, category and text.
+///
+/// Callback is executed in the context of the logging libcamera thread and replaces any previously set callback or
+/// writer.
+pub fn log_set_callback(cb: impl Fn(&LogMessage<'_>) + Send + Sync + 'static) -> io::Result<()> {
+    log_set_sink(Box::new(move |line| cb(&LogMessage::parse(line))))
+}
+
+/// Direct logging to a writer, i.e. an in-memory buffer or a log file managed by the application.
+///
+/// Each message is written as a single line in libcamera format, without colors. Write errors are ignored, as there
+/// is nowhere to report them. The writer replaces any previously set callback or writer and is dropped once logging is
+/// redirected elsewhere with [log_set_callback()], [log_set_writer()] or [log_disable()].
+pub fn log_set_writer(mut writer: impl Write + Send + 'static) -> io::Result<()> {
+    log_set_sink(Box::new(move |line| {
+        let _ = writeln!(writer, "{line}");
+    }))
+}
+
+struct LogBuffer {
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+}
+
+static LOG_BUFFER: Mutex<LogBuffer> = Mutex::new(LogBuffer {
+    records: VecDeque::new(),
+    capacity: 0,
+});
+
+/// Direct logging into an in-memory ring buffer keeping the last `capacity` messages, which can be queried with
+/// [recent()].
+///
+/// Useful to attach libcamera warnings to test failures or bug reports. Previously buffered messages are discarded.
+/// The buffer replaces any previously set callback or writer, but its contents remain available once logging is
+/// redirected elsewhere.
+pub fn log_set_buffer(capacity: usize) -> io::Result<()> {
+    {
+        let mut buffer = LOG_BUFFER.lock().unwrap();
+        buffer.records.clear();
+        buffer.capacity = capacity;
+    }
+    log_set_callback(|msg| {
+        let mut buffer = LOG_BUFFER.lock().unwrap();
+        if buffer.capacity == 0 {
+            return;
+        }
+        if buffer.records.len() == buffer.capacity {
+            buffer.records.pop_front();
+        }
+        buffer.records.push_back(msg.into());
+    })
+}
+
+/// Returns up to `n` most recent messages captured by [log_set_buffer()], oldest first.
+pub fn recent(n: usize) -> Vec<LogRecord> {
+    let buffer = LOG_BUFFER.lock().unwrap();
+    let skip = buffer.records.len().saturating_sub(n);
+    buffer.records.iter().skip(skip).cloned().collect()
+}
+
+/// Discards all messages captured by [log_set_buffer()], i.e. between test cases.
+pub fn clear_recent() {
+    LOG_BUFFER.lock().unwrap().records.clear();
+}
+
+/// Disables libcamera logging entirely.
+///
+/// Equivalent to [log_set_target()] with [LoggingTarget::None], additionally dropping any writer set by
+/// [log_set_writer()].
+pub fn log_disable() -> io::Result<()> {
+    log_set_target(LoggingTarget::None)?;
+    *LOG_SINK.lock().unwrap() = None;
+    Ok(())
+}
+
+/// Forwards libcamera logging into the [log] crate, so that it shows up in the application logger.
+///
+/// Messages are logged with target `libcamera::<category>`, [LoggingLevel::Fatal] messages as [log::Level::Error].
+/// Use [log_set_level()](crate::camera_manager::CameraManager::log_set_level) to control which messages libcamera
+/// produces in the first place.
+#[cfg(feature = "log")]
+pub fn log_set_log_crate() -> io::Result<()> {
+    log_set_callback(|msg| {
+        let level = match msg.level {
+            LoggingLevel::Debug => log::Level::Debug,
+            LoggingLevel::Info => log::Level::Info,
+            LoggingLevel::Warn => log::Level::Warn,
+            LoggingLevel::Error | LoggingLevel::Fatal => log::Level::Error,
+        };
+        let target = if msg.category.is_empty() {
+            "libcamera".to_string()
+        } else {
+            format!("libcamera::{}", msg.category)
+        };
+        if log::log_enabled!(target: &target, level) {
+            log::log!(target: &target, level, "{} {}", msg.location, msg.message);
+        }
+    })
+}
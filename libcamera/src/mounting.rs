@@ -0,0 +1,79 @@
+//! How a camera is mounted within the device.
+//!
+//! Combines [Location], [Rotation] and [Model] properties, which UI code almost always needs together, i.e. to label
+//! cameras and to display their images upright.
+
+use crate::{
+    control::PropertyList,
+    orientation::{Orientation, Transform},
+    properties::{Location, Model, Rotation},
+};
+
+impl Location {
+    /// Returns `true` if the camera faces the user, i.e. a selfie camera whose preview is usually mirrored.
+    pub fn is_user_facing(&self) -> bool {
+        matches!(self, Self::CameraFront)
+    }
+
+    /// Returns `true` if the camera faces away from the user.
+    pub fn is_world_facing(&self) -> bool {
+        matches!(self, Self::CameraBack)
+    }
+}
+
+impl Rotation {
+    /// Returns orientation of images captured without any transform, see [Orientation::from_rotation()].
+    ///
+    /// Returns [None] if rotation is not a multiple of 90 degrees.
+    pub fn orientation(&self) -> Option<Orientation> {
+        Orientation::from_rotation(self.0)
+    }
+
+    /// Returns transform which has to be applied to captured images to display them upright.
+    pub fn transform(&self) -> Option<Transform> {
+        Some(Orientation::Rotate0.transform_from(self.orientation()?))
+    }
+}
+
+/// Mounting of a camera, assembled from its properties.
+///
+/// Properties that the pipeline does not report are [None].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountingInfo {
+    pub location: Option<Location>,
+    /// Rotation of the camera in degrees, see [Rotation].
+    pub rotation: Option<i32>,
+    pub model: Option<String>,
+}
+
+impl MountingInfo {
+    pub fn from_properties(properties: &PropertyList) -> Self {
+        Self {
+            location: properties.get::<Location>().ok(),
+            rotation: properties.get::<Rotation>().ok().map(|r| r.0),
+            model: properties.get::<Model>().ok().map(|m| m.0),
+        }
+    }
+
+    /// Returns `true` if the camera faces the user, see [Location::is_user_facing()].
+    pub fn is_user_facing(&self) -> bool {
+        self.location.is_some_and(|l| l.is_user_facing())
+    }
+
+    /// Returns transform which displays captured images upright, identity if rotation is unknown.
+    pub fn transform(&self) -> Transform {
+        self.rotation
+            .and_then(|r| Rotation(r).transform())
+            .unwrap_or(Transform::IDENTITY)
+    }
+
+    /// Returns transform for a live preview, which is additionally mirrored for user-facing cameras so that the preview
+    /// behaves like a mirror.
+    pub fn preview_transform(&self) -> Transform {
+        if self.is_user_facing() {
+            self.transform().then(Transform::HFLIP)
+        } else {
+            self.transform()
+        }
+    }
+}
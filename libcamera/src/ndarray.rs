@@ -0,0 +1,90 @@
+//! Zero-copy [ndarray](https://docs.rs/ndarray) views of frame planes.
+//!
+//! Views step over line padding using the plane stride, so frames can be fed into `ndarray` based pipelines without
+//! copying them out of the mapped framebuffer first.
+
+use ndarray::{ArrayView2, ArrayView3, ShapeBuilder, ShapeError};
+use thiserror::Error;
+
+use crate::{
+    framebuffer::AsFrameBuffer,
+    framebuffer_map::{FrameLayout, MemoryMappedFrameBuffer},
+    pixel_format::PixelFormat,
+};
+
+#[derive(Debug, Error)]
+pub enum NdarrayError {
+    /// Plane layout can not be derived for the pixel format, i.e. for compressed formats
+    #[error("Unsupported pixel format {0:?}")]
+    UnsupportedFormat(PixelFormat),
+    /// Frame does not contain the plane
+    #[error("Plane {0} does not exist")]
+    MissingPlane(usize),
+    /// Plane data is shorter than required by the layout
+    #[error(transparent)]
+    Shape(#[from] ShapeError),
+}
+
+/// Bytes per pixel of single plane formats, which store every pixel in whole bytes.
+fn bytes_per_pixel(format: PixelFormat) -> Option<usize> {
+    let info = format.info()?;
+    let [plane] = info.planes else {
+        return None;
+    };
+    if info.packed || info.bits_per_pixel == 0 || !plane.bytes_per_group.is_multiple_of(info.pixels_per_group) {
+        return None;
+    }
+    Some((plane.bytes_per_group / info.pixels_per_group) as usize)
+}
+
+/// Returns `(stride, rows, bytes per line)` of each plane.
+///
+/// Bytes per line exclude padding where the pixel format layout is known, otherwise the whole stride is used.
+fn plane_shapes(layout: &FrameLayout) -> Result<Vec<(usize, usize, usize)>, NdarrayError> {
+    let planes = layout
+        .planes()
+        .ok_or(NdarrayError::UnsupportedFormat(layout.pixel_format))?;
+    let info = layout.pixel_format.info();
+
+    Ok(planes
+        .into_iter()
+        .enumerate()
+        .map(|(plane, (stride, rows))| {
+            let row_len = info
+                .and_then(|info| info.stride(layout.size.width, plane, 1))
+                .unwrap_or(stride);
+            (stride as usize, rows as usize, row_len.min(stride) as usize)
+        })
+        .collect())
+}
+
+/// Views a plane as a `(rows, bytes per line)` array, skipping `stride - row_len` padding bytes after every line.
+pub fn plane_view(data: &[u8], stride: usize, rows: usize, row_len: usize) -> Result<ArrayView2<'_, u8>, ShapeError> {
+    ArrayView2::from_shape((rows, row_len).strides((stride, 1)), data)
+}
+
+/// Views a single plane packed frame as a `(height, width, bytes per pixel)` array.
+pub fn pixel_view<'a>(data: &'a [u8], layout: &FrameLayout) -> Result<ArrayView3<'a, u8>, NdarrayError> {
+    let bpp = bytes_per_pixel(layout.pixel_format).ok_or(NdarrayError::UnsupportedFormat(layout.pixel_format))?;
+    let (width, height) = (layout.size.width as usize, layout.size.height as usize);
+    let shape = (height, width, bpp).strides((layout.stride as usize, bpp, 1));
+    Ok(ArrayView3::from_shape(shape, data)?)
+}
+
+impl<T: AsFrameBuffer> MemoryMappedFrameBuffer<T> {
+    /// Views plane `index` as a `(rows, bytes per line)` array, i.e. the luma plane of an `NV12` frame as
+    /// `(height, width)`.
+    pub fn plane_array(&self, index: usize, layout: &FrameLayout) -> Result<ArrayView2<'_, u8>, NdarrayError> {
+        let (stride, rows, row_len) = *plane_shapes(layout)?
+            .get(index)
+            .ok_or(NdarrayError::MissingPlane(index))?;
+        let data = self.plane(index).ok_or(NdarrayError::MissingPlane(index))?;
+        Ok(plane_view(data, stride, rows, row_len)?)
+    }
+
+    /// Views a packed frame, i.e. `RGB888` or `YUYV`, as a `(height, width, bytes per pixel)` array.
+    pub fn pixel_array(&self, layout: &FrameLayout) -> Result<ArrayView3<'_, u8>, NdarrayError> {
+        let data = self.plane(0).ok_or(NdarrayError::MissingPlane(0))?;
+        pixel_view(data, layout)
+    }
+}
@@ -0,0 +1,30 @@
+//! Instrumentation of the request lifecycle.
+//!
+//! [RequestObserver]s registered with
+//! [ActiveCamera::add_request_observer()](crate::camera::ActiveCamera::add_request_observer) are notified as requests
+//! are created, queued, completed and reused, i.e. to collect metrics, trace latency or implement a watchdog. Cameras
+//! without observers only pay for an empty check on each event.
+
+use crate::request::{Request, ReuseFlag};
+
+/// Hooks into the lifecycle of requests of a single camera.
+///
+/// All hooks have empty default implementations. Hooks are called with internal camera state locked, so they must be
+/// quick and must not call back into the camera. [RequestObserver::on_complete()] is executed in the libcamera thread.
+pub trait RequestObserver: Send {
+    /// Called after a request was created by
+    /// [ActiveCamera::create_request()](crate::camera::ActiveCamera::create_request).
+    fn on_create(&mut self, _req: &Request) {}
+
+    /// Called before a request is queued to the camera.
+    fn on_queue(&mut self, _req: &Request) {}
+
+    /// Called when the camera rejected a request after [RequestObserver::on_queue()]. The request will not complete.
+    fn on_queue_failed(&mut self, _req: &Request) {}
+
+    /// Called when a request completed or was cancelled by stopping the camera, before any completion callbacks.
+    fn on_complete(&mut self, _req: &Request) {}
+
+    /// Called when a request reused with [Request::reuse()] is queued again, before [RequestObserver::on_queue()].
+    fn on_reuse(&mut self, _req: &Request, _flags: ReuseFlag) {}
+}
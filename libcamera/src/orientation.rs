@@ -0,0 +1,162 @@
+use bitflags::bitflags;
+use libcamera_sys::*;
+
+bitflags! {
+    /// A 2D plane transform, combination of horizontal/vertical flips and transposition.
+    ///
+    /// Flips are applied before the transposition, i.e. [Transform::ROT270] is a horizontal flip followed by a
+    /// transposition.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Transform: u8 {
+        const HFLIP = 1 << 0;
+        const VFLIP = 1 << 1;
+        const TRANSPOSE = 1 << 2;
+
+        const IDENTITY = 0;
+        const HVFLIP = Self::HFLIP.bits() | Self::VFLIP.bits();
+        /// Rotation by 180 degrees.
+        const ROT180 = Self::HVFLIP.bits();
+        /// Rotation by 270 degrees clockwise (90 degrees anticlockwise).
+        const ROT270 = Self::HFLIP.bits() | Self::TRANSPOSE.bits();
+        /// Rotation by 90 degrees clockwise (270 degrees anticlockwise).
+        const ROT90 = Self::VFLIP.bits() | Self::TRANSPOSE.bits();
+        /// Rotation by 180 degrees followed by a transposition.
+        const ROT180_TRANSPOSE = Self::HFLIP.bits() | Self::VFLIP.bits() | Self::TRANSPOSE.bits();
+    }
+}
+
+impl Transform {
+    /// Returns clockwise rotation by `degrees`, which must be a multiple of 90.
+    pub fn from_rotation(degrees: i32) -> Option<Self> {
+        match degrees.rem_euclid(360) {
+            0 => Some(Self::IDENTITY),
+            90 => Some(Self::ROT90),
+            180 => Some(Self::ROT180),
+            270 => Some(Self::ROT270),
+            _ => None,
+        }
+    }
+
+    /// Returns transform equivalent to applying `self` followed by `other`.
+    pub fn then(self, other: Self) -> Self {
+        let target = other.apply(self.apply((1, 2)));
+        (0..8)
+            .map(Self::from_bits_truncate)
+            .find(|t| t.apply((1, 2)) == target)
+            .unwrap()
+    }
+
+    /// Returns transform which undoes `self`.
+    pub fn inverse(self) -> Self {
+        (0..8)
+            .map(Self::from_bits_truncate)
+            .find(|t| self.then(*t) == Self::IDENTITY)
+            .unwrap()
+    }
+
+    /// Maps a point relative to the image center.
+    fn apply(self, (mut x, mut y): (i32, i32)) -> (i32, i32) {
+        if self.contains(Self::HFLIP) {
+            x = -x;
+        }
+        if self.contains(Self::VFLIP) {
+            y = -y;
+        }
+        if self.contains(Self::TRANSPOSE) {
+            (x, y) = (y, x);
+        }
+        (x, y)
+    }
+}
+
+/// Image orientation, described by the transform that has to be applied to an image in [Orientation::Rotate0] to
+/// obtain it.
+///
+/// Values match EXIF orientation tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Rotate0 = 1,
+    Rotate0Mirror = 2,
+    Rotate180 = 3,
+    Rotate180Mirror = 4,
+    Rotate90Mirror = 5,
+    Rotate270 = 6,
+    Rotate270Mirror = 7,
+    Rotate90 = 8,
+}
+
+impl Orientation {
+    const ALL: [Self; 8] = [
+        Self::Rotate0,
+        Self::Rotate0Mirror,
+        Self::Rotate180,
+        Self::Rotate180Mirror,
+        Self::Rotate90Mirror,
+        Self::Rotate270,
+        Self::Rotate270Mirror,
+        Self::Rotate90,
+    ];
+
+    /// Returns orientation for a value of the EXIF `Orientation` tag.
+    pub fn from_exif(value: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|o| o.to_exif() == value)
+    }
+
+    /// Returns value of the EXIF `Orientation` tag, which tells image viewers how to display the image.
+    pub fn to_exif(self) -> u16 {
+        self as u16
+    }
+
+    /// Returns orientation of an image rotated clockwise by `degrees`, which must be a multiple of 90.
+    ///
+    /// Can be used with the `Rotation` camera property.
+    pub fn from_rotation(degrees: i32) -> Option<Self> {
+        Transform::from_rotation(degrees).map(Self::from_transform)
+    }
+
+    /// Returns orientation obtained by applying `transform` to an image in [Orientation::Rotate0].
+    pub fn from_transform(transform: Transform) -> Self {
+        Self::ALL.into_iter().find(|o| o.transform() == transform).unwrap()
+    }
+
+    /// Returns transform which turns an image in [Orientation::Rotate0] into this orientation.
+    pub fn transform(self) -> Transform {
+        match self {
+            Self::Rotate0 => Transform::IDENTITY,
+            Self::Rotate0Mirror => Transform::HFLIP,
+            Self::Rotate180 => Transform::ROT180,
+            Self::Rotate180Mirror => Transform::VFLIP,
+            Self::Rotate90Mirror => Transform::TRANSPOSE,
+            Self::Rotate270 => Transform::ROT270,
+            Self::Rotate270Mirror => Transform::ROT180_TRANSPOSE,
+            Self::Rotate90 => Transform::ROT90,
+        }
+    }
+
+    /// Returns orientation of an image in this orientation after applying `transform` to it.
+    pub fn apply(self, transform: Transform) -> Self {
+        Self::from_transform(self.transform().then(transform))
+    }
+
+    /// Returns transform which turns an image in `from` orientation into this orientation.
+    pub fn transform_from(self, from: Orientation) -> Transform {
+        from.transform().inverse().then(self.transform())
+    }
+}
+
+impl TryFrom<libcamera_orientation::Type> for Orientation {
+    type Error = ();
+
+    fn try_from(value: libcamera_orientation::Type) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|o| *o as libcamera_orientation::Type == value)
+            .ok_or(())
+    }
+}
+
+impl From<Orientation> for libcamera_orientation::Type {
+    fn from(orientation: Orientation) -> Self {
+        orientation as _
+    }
+}
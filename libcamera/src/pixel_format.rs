@@ -1,7 +1,14 @@
-use std::{ffi::CStr, ptr::NonNull};
+use std::{
+    ffi::{CStr, CString},
+    ptr::NonNull,
+    str::FromStr,
+};
 
 use drm_fourcc::{DrmFormat, DrmFourcc, DrmModifier};
 use libcamera_sys::*;
+use thiserror::Error;
+
+use crate::{generated::formats::FORMATS, geometry::Size};
 
 /// Represents `libcamera::PixelFormat`, which itself is a pair of fourcc code and u64 modifier as defined in `libdrm`.
 #[derive(Clone, Copy)]
@@ -10,13 +17,15 @@ pub struct PixelFormat(pub(crate) libcamera_pixel_format_t);
 impl PixelFormat {
     /// Constructs new [PixelFormat] from given fourcc code and modifier.
     ///
+    /// Formats known to libcamera are available as constants in [formats].
+    ///
     /// # Examples
     ///
     /// ```rust
-    /// use libcamera::pixel_format::PixelFormat;
+    /// use libcamera::pixel_format::{formats, PixelFormat};
     /// // Constructs MJPEG pixel format
-    /// const PIXEL_FORMAT_MJPEG: PixelFormat =
-    ///     PixelFormat::new(u32::from_le_bytes([b'M', b'J', b'P', b'G']), 0);
+    /// let mjpeg = PixelFormat::new(u32::from_le_bytes([b'M', b'J', b'P', b'G']), 0);
+    /// assert_eq!(mjpeg, formats::MJPEG);
     /// ```
     pub const fn new(fourcc: u32, modifier: u64) -> Self {
         Self(libcamera_pixel_format_t { fourcc, modifier })
@@ -30,6 +39,8 @@ impl PixelFormat {
         self.0.fourcc = fourcc;
     }
 
+    /// Returns the DRM format modifier, which describes memory layout such as tiling, compression or packing of the
+    /// fourcc format. Zero ([DRM_FORMAT_MOD_LINEAR]) for plain linear layout.
     pub fn modifier(&self) -> u64 {
         self.0.modifier
     }
@@ -37,8 +48,49 @@ impl PixelFormat {
     pub fn set_modifier(&mut self, modifier: u64) {
         self.0.modifier = modifier;
     }
+
+    /// Returns the same fourcc with a different modifier, i.e. a tiled or compressed variant of the format.
+    pub const fn with_modifier(self, modifier: u64) -> Self {
+        Self::new(self.0.fourcc, modifier)
+    }
+
+    /// Returns the same fourcc without modifier.
+    pub const fn linear(self) -> Self {
+        self.with_modifier(DRM_FORMAT_MOD_LINEAR)
+    }
+
+    /// Returns `true` if the format has no modifier, i.e. pixels are laid out line by line.
+    pub fn is_linear(&self) -> bool {
+        self.0.modifier == DRM_FORMAT_MOD_LINEAR
+    }
+
+    /// Returns the modifier as a `drm-fourcc` type, i.e. to match known vendor modifiers.
+    pub fn drm_modifier(&self) -> DrmModifier {
+        DrmModifier::from(self.0.modifier)
+    }
+
+    /// Returns memory layout of the format (bits per pixel, planes and packing), see [info()].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use libcamera::{geometry::Size, pixel_format::formats};
+    /// let info = formats::NV12.info().unwrap();
+    /// assert_eq!(info.num_planes(), 2);
+    /// let size = Size {
+    ///     width: 640,
+    ///     height: 480,
+    /// };
+    /// assert_eq!(info.frame_size(size, 1), Some(640 * 480 * 3 / 2));
+    /// ```
+    pub fn info(&self) -> Option<&'static PixelFormatInfo> {
+        info(*self)
+    }
 }
 
+/// Modifier of formats with linear memory layout.
+pub const DRM_FORMAT_MOD_LINEAR: u64 = 0;
+
 impl PartialEq for PixelFormat {
     fn eq(&self, other: &Self) -> bool {
         self.0.fourcc.eq(&other.0.fourcc) && self.0.modifier.eq(&other.0.modifier)
@@ -47,13 +99,46 @@ impl PartialEq for PixelFormat {
 
 impl Eq for PixelFormat {}
 
-impl core::fmt::Debug for PixelFormat {
+impl core::hash::Hash for PixelFormat {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.0.fourcc.hash(state);
+        self.0.modifier.hash(state);
+    }
+}
+
+/// Prints libcamera name of the format, i.e. `SRGGB10_CSI2P`, or fourcc and modifier for unknown formats.
+impl core::fmt::Display for PixelFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let ptr = unsafe { libcamera_pixel_format_str(&self.0) };
-        let out = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap();
-        f.write_str(out)?;
+        let out = unsafe { CStr::from_ptr(ptr) }.to_string_lossy();
+        let res = f.write_str(&out);
         unsafe { libc::free(ptr.cast()) };
-        Ok(())
+        res
+    }
+}
+
+impl core::fmt::Debug for PixelFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("Unknown pixel format {0:?}")]
+pub struct PixelFormatParseError(pub String);
+
+/// Parses libcamera format names as printed by [Display](core::fmt::Display), i.e. `YUV420` or `SRGGB10_CSI2P`.
+impl FromStr for PixelFormat {
+    type Err = PixelFormatParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = CString::new(s).map_err(|_| PixelFormatParseError(s.to_string()))?;
+        let format = PixelFormat(unsafe { libcamera_pixel_format_from_str(name.as_ptr()) });
+        if format.fourcc() == 0 {
+            Err(PixelFormatParseError(s.to_string()))
+        } else {
+            Ok(format)
+        }
     }
 }
 
@@ -73,6 +158,94 @@ impl From<DrmFormat> for PixelFormat {
     }
 }
 
+/// Colour encoding of a [PixelFormatInfo].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColourEncoding {
+    Rgb,
+    Yuv,
+    Raw,
+}
+
+/// Layout of a single plane of a [PixelFormatInfo].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaneInfo {
+    /// Number of bytes used by a group of [PixelFormatInfo::pixels_per_group] pixels within a line of the plane.
+    pub bytes_per_group: u32,
+    /// Number of lines of the image per line of the plane, i.e. 2 for chroma planes of `NV12`.
+    pub vertical_sub_sampling: u32,
+}
+
+/// Memory layout of a pixel format, generated from libcamera `formats.yaml` of each supported version.
+///
+/// Mirrors `libcamera::PixelFormatInfo` and uses the same rules to compute strides and plane sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelFormatInfo {
+    /// Format name as used by libcamera, i.e. `SRGGB10_CSI2P`.
+    pub name: &'static str,
+    pub format: PixelFormat,
+    /// Average number of bits per pixel, zero for compressed formats.
+    pub bits_per_pixel: u32,
+    pub colour_encoding: ColourEncoding,
+    /// Whether pixels are packed across byte boundaries, i.e. MIPI CSI-2 packed raw formats.
+    pub packed: bool,
+    /// Number of pixels sharing the bytes of a group, see [PlaneInfo::bytes_per_group].
+    pub pixels_per_group: u32,
+    pub planes: &'static [PlaneInfo],
+}
+
+impl PixelFormatInfo {
+    pub fn num_planes(&self) -> usize {
+        self.planes.len()
+    }
+
+    /// Minimum line stride in bytes of a plane for the given width, rounded up to a multiple of `align`.
+    ///
+    /// Returns [None] if the plane does not exist or the format has no fixed layout, i.e. `MJPEG`.
+    pub fn stride(&self, width: u32, plane: usize, align: u32) -> Option<u32> {
+        let plane = self.planes.get(plane)?;
+        if self.bits_per_pixel == 0 {
+            return None;
+        }
+        let groups = width.div_ceil(self.pixels_per_group);
+        Some((groups * plane.bytes_per_group).next_multiple_of(align.max(1)))
+    }
+
+    /// Line stride in bytes of a plane given the stride of the first plane, i.e. half of it for chroma planes of
+    /// `YUV420`.
+    pub fn plane_stride(&self, plane: usize, stride: u32) -> Option<u32> {
+        let first = self.planes.first()?;
+        let plane = self.planes.get(plane)?;
+        Some((stride as u64 * plane.bytes_per_group as u64 / first.bytes_per_group.max(1) as u64) as u32)
+    }
+
+    /// Size in bytes of a plane for the given image size and line stride.
+    pub fn plane_size(&self, height: u32, plane: usize, stride: u32) -> Option<usize> {
+        let plane = self.planes.get(plane)?;
+        let lines = height.div_ceil(plane.vertical_sub_sampling);
+        Some(lines as usize * stride as usize)
+    }
+
+    /// Size in bytes of a frame with all planes of minimum stride, rounded up to a multiple of `align`.
+    pub fn frame_size(&self, size: Size, align: u32) -> Option<usize> {
+        (0..self.planes.len())
+            .map(|plane| self.plane_size(size.height, plane, self.stride(size.width, plane, align)?))
+            .sum()
+    }
+}
+
+/// Returns memory layout of a pixel format, if it is known to the libcamera version the crate was built against.
+///
+/// `libcamera::PixelFormatInfo` is internal to libcamera and not available to applications, so layouts come from a
+/// table generated for each libcamera version, see [PixelFormatInfo].
+pub fn info(format: PixelFormat) -> Option<&'static PixelFormatInfo> {
+    FORMATS.iter().find(|info| info.format == format)
+}
+
+/// Returns layouts of all pixel formats known to the libcamera version the crate was built against.
+pub fn all_info() -> &'static [PixelFormatInfo] {
+    FORMATS
+}
+
 /// Vector of [PixelFormat]
 pub struct PixelFormats {
     ptr: NonNull<libcamera_pixel_formats_t>,
@@ -150,3 +323,145 @@ impl<'d> Iterator for PixelFormatsIterator<'d> {
         }
     }
 }
+
+/// Constants for pixel formats known to libcamera, as defined in libcamera `formats.yaml`.
+///
+/// Names follow libcamera (and DRM) naming, i.e. [formats::RGB888] stores pixels as `B, G, R` bytes in memory.
+/// Whether a camera supports a format is only known from
+/// [StreamFormatsRef::pixel_formats()](crate::stream::StreamFormatsRef::pixel_formats).
+pub mod formats {
+    use super::PixelFormat;
+
+    const fn fourcc(code: &[u8; 4]) -> u32 {
+        u32::from_le_bytes(*code)
+    }
+
+    const fn format(code: &[u8; 4]) -> PixelFormat {
+        PixelFormat::new(fourcc(code), 0)
+    }
+
+    const fn csi2_packed(code: &[u8; 4]) -> PixelFormat {
+        PixelFormat::new(fourcc(code), MIPI_FORMAT_MOD_CSI2_PACKED)
+    }
+
+    const fn ipu3_packed(code: &[u8; 4]) -> PixelFormat {
+        PixelFormat::new(fourcc(code), IPU3_FORMAT_MOD_PACKED)
+    }
+
+    const fn pisp_comp1(code: &[u8; 4]) -> PixelFormat {
+        PixelFormat::new(fourcc(code), PISP_FORMAT_MOD_COMPRESS_MODE1)
+    }
+
+    /// Modifier of MIPI CSI-2 packed raw formats, i.e. 4 pixels of 10 bits stored in 5 bytes.
+    pub const MIPI_FORMAT_MOD_CSI2_PACKED: u64 = (0x0b << 56) | 1;
+    /// Modifier of raw formats packed by the Intel IPU3 CIO2 receiver, `fourcc_mod_code(INTEL, 13)`.
+    pub const IPU3_FORMAT_MOD_PACKED: u64 = (0x01 << 56) | 13;
+    /// Modifier of raw formats compressed by the Raspberry Pi PiSP back end, `fourcc_mod_code(RPI, 1)`.
+    pub const PISP_FORMAT_MOD_COMPRESS_MODE1: u64 = (0x0c << 56) | 1;
+    /// Modifier of raw formats compressed by the Raspberry Pi PiSP back end, `fourcc_mod_code(RPI, 2)`.
+    pub const PISP_FORMAT_MOD_COMPRESS_MODE2: u64 = (0x0c << 56) | 2;
+
+    // RGB
+    /// Single channel 8-bit, used for monochrome sensors.
+    pub const R8: PixelFormat = format(b"R8  ");
+    pub const R10: PixelFormat = format(b"R10 ");
+    pub const R12: PixelFormat = format(b"R12 ");
+    pub const R16: PixelFormat = format(b"R16 ");
+    pub const RGB565: PixelFormat = format(b"RG16");
+    /// [RGB565] stored big-endian, flagged by the top bit of the fourcc like `DRM_FORMAT_BIG_ENDIAN`.
+    pub const RGB565_BE: PixelFormat = PixelFormat::new(fourcc(b"RG16") | (1 << 31), 0);
+    /// 24-bit RGB stored as `B, G, R` bytes, following DRM naming.
+    pub const RGB888: PixelFormat = format(b"RG24");
+    /// 24-bit RGB stored as `R, G, B` bytes, following DRM naming.
+    pub const BGR888: PixelFormat = format(b"BG24");
+    pub const XRGB8888: PixelFormat = format(b"XR24");
+    pub const XBGR8888: PixelFormat = format(b"XB24");
+    pub const RGBX8888: PixelFormat = format(b"RX24");
+    pub const BGRX8888: PixelFormat = format(b"BX24");
+    pub const ARGB8888: PixelFormat = format(b"AR24");
+    pub const ABGR8888: PixelFormat = format(b"AB24");
+    pub const RGBA8888: PixelFormat = format(b"RA24");
+    pub const BGRA8888: PixelFormat = format(b"BA24");
+    pub const RGB161616: PixelFormat = format(b"RG48");
+    pub const BGR161616: PixelFormat = format(b"BG48");
+
+    // YUV packed
+    pub const YUYV: PixelFormat = format(b"YUYV");
+    pub const YVYU: PixelFormat = format(b"YVYU");
+    pub const UYVY: PixelFormat = format(b"UYVY");
+    pub const VYUY: PixelFormat = format(b"VYUY");
+    pub const AVUY8888: PixelFormat = format(b"AVUY");
+    pub const XVUY8888: PixelFormat = format(b"XVUY");
+
+    // YUV semi-planar
+    pub const NV12: PixelFormat = format(b"NV12");
+    pub const NV21: PixelFormat = format(b"NV21");
+    pub const NV16: PixelFormat = format(b"NV16");
+    pub const NV61: PixelFormat = format(b"NV61");
+    pub const NV24: PixelFormat = format(b"NV24");
+    pub const NV42: PixelFormat = format(b"NV42");
+
+    // YUV planar
+    pub const YUV420: PixelFormat = format(b"YU12");
+    pub const YVU420: PixelFormat = format(b"YV12");
+    pub const YUV422: PixelFormat = format(b"YU16");
+    pub const YVU422: PixelFormat = format(b"YV16");
+    pub const YUV444: PixelFormat = format(b"YU24");
+    pub const YVU444: PixelFormat = format(b"YV24");
+
+    // Compressed
+    pub const MJPEG: PixelFormat = format(b"MJPG");
+
+    // Mono CSI-2 packed
+    pub const R10_CSI2P: PixelFormat = csi2_packed(b"R10 ");
+    pub const R12_CSI2P: PixelFormat = csi2_packed(b"R12 ");
+
+    // Bayer
+    pub const SRGGB8: PixelFormat = format(b"RGGB");
+    pub const SGRBG8: PixelFormat = format(b"GRBG");
+    pub const SGBRG8: PixelFormat = format(b"GBRG");
+    pub const SBGGR8: PixelFormat = format(b"BA81");
+    pub const SRGGB10: PixelFormat = format(b"RG10");
+    pub const SGRBG10: PixelFormat = format(b"BA10");
+    pub const SGBRG10: PixelFormat = format(b"GB10");
+    pub const SBGGR10: PixelFormat = format(b"BG10");
+    pub const SRGGB12: PixelFormat = format(b"RG12");
+    pub const SGRBG12: PixelFormat = format(b"BA12");
+    pub const SGBRG12: PixelFormat = format(b"GB12");
+    pub const SBGGR12: PixelFormat = format(b"BG12");
+    pub const SRGGB14: PixelFormat = format(b"RG14");
+    pub const SGRBG14: PixelFormat = format(b"BA14");
+    pub const SGBRG14: PixelFormat = format(b"GB14");
+    pub const SBGGR14: PixelFormat = format(b"BG14");
+    pub const SRGGB16: PixelFormat = format(b"RGB6");
+    pub const SGRBG16: PixelFormat = format(b"GR16");
+    pub const SGBRG16: PixelFormat = format(b"GB16");
+    pub const SBGGR16: PixelFormat = format(b"BYR2");
+
+    // Bayer CSI-2 packed
+    pub const SRGGB10_CSI2P: PixelFormat = csi2_packed(b"RG10");
+    pub const SGRBG10_CSI2P: PixelFormat = csi2_packed(b"BA10");
+    pub const SGBRG10_CSI2P: PixelFormat = csi2_packed(b"GB10");
+    pub const SBGGR10_CSI2P: PixelFormat = csi2_packed(b"BG10");
+    pub const SRGGB12_CSI2P: PixelFormat = csi2_packed(b"RG12");
+    pub const SGRBG12_CSI2P: PixelFormat = csi2_packed(b"BA12");
+    pub const SGBRG12_CSI2P: PixelFormat = csi2_packed(b"GB12");
+    pub const SBGGR12_CSI2P: PixelFormat = csi2_packed(b"BG12");
+    pub const SRGGB14_CSI2P: PixelFormat = csi2_packed(b"RG14");
+    pub const SGRBG14_CSI2P: PixelFormat = csi2_packed(b"BA14");
+    pub const SGBRG14_CSI2P: PixelFormat = csi2_packed(b"GB14");
+    pub const SBGGR14_CSI2P: PixelFormat = csi2_packed(b"BG14");
+
+    // Bayer IPU3 packed
+    pub const SRGGB10_IPU3: PixelFormat = ipu3_packed(b"RG10");
+    pub const SGRBG10_IPU3: PixelFormat = ipu3_packed(b"BA10");
+    pub const SGBRG10_IPU3: PixelFormat = ipu3_packed(b"GB10");
+    pub const SBGGR10_IPU3: PixelFormat = ipu3_packed(b"BG10");
+
+    // Raspberry Pi PiSP compressed, 8 bits per pixel in a 16-bit container fourcc
+    pub const SRGGB_PISP_COMP1: PixelFormat = pisp_comp1(b"RGB6");
+    pub const SGRBG_PISP_COMP1: PixelFormat = pisp_comp1(b"GR16");
+    pub const SGBRG_PISP_COMP1: PixelFormat = pisp_comp1(b"GB16");
+    pub const SBGGR_PISP_COMP1: PixelFormat = pisp_comp1(b"BYR2");
+    pub const MONO_PISP_COMP1: PixelFormat = pisp_comp1(b"R16 ");
+}
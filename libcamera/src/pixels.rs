@@ -0,0 +1,174 @@
+//! Pixel walk over packed RGB and YUV frames, shared by [rgb](crate::rgb) and `awb`.
+
+use drm_fourcc::DrmFourcc;
+
+#[cfg(feature = "awb")]
+use crate::awb::AwbError;
+use crate::{geometry::Size, pixel_format::PixelFormat, rgb::RgbError};
+
+/// Reason why frame planes can not be walked.
+#[derive(Debug)]
+pub(crate) enum PixelError {
+    UnsupportedFormat(PixelFormat),
+    MissingPlanes { expected: usize, found: usize },
+    PlaneTooShort { index: usize, len: usize, expected: usize },
+}
+
+impl From<PixelError> for RgbError {
+    fn from(e: PixelError) -> Self {
+        match e {
+            PixelError::UnsupportedFormat(format) => Self::UnsupportedFormat(format),
+            PixelError::MissingPlanes { expected, found } => Self::MissingPlanes { expected, found },
+            PixelError::PlaneTooShort { index, len, expected } => Self::PlaneTooShort { index, len, expected },
+        }
+    }
+}
+
+#[cfg(feature = "awb")]
+impl From<PixelError> for AwbError {
+    fn from(e: PixelError) -> Self {
+        match e {
+            PixelError::UnsupportedFormat(format) => Self::UnsupportedFormat(format),
+            PixelError::MissingPlanes { expected, found } => Self::MissingPlanes { expected, found },
+            PixelError::PlaneTooShort { index, len, expected } => Self::PlaneTooShort { index, len, expected },
+        }
+    }
+}
+
+/// A single pixel visited by [for_each_pixel()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Pixel {
+    /// Red, green and blue components.
+    Rgb([u8; 3]),
+    /// Luma and both chroma components.
+    Yuv([u8; 3]),
+}
+
+/// Returns plane `index`, checking that it holds at least `len` bytes.
+fn plane<'a>(planes: &[&'a [u8]], index: usize, len: usize) -> Result<&'a [u8], PixelError> {
+    let data = planes.get(index).ok_or(PixelError::MissingPlanes {
+        expected: index + 1,
+        found: planes.len(),
+    })?;
+    if data.len() < len {
+        Err(PixelError::PlaneTooShort {
+            index,
+            len: data.len(),
+            expected: len,
+        })
+    } else {
+        Ok(data)
+    }
+}
+
+/// Minimum plane length for `rows` lines of `row_len` bytes.
+fn plane_len(rows: usize, stride: usize, row_len: usize) -> usize {
+    rows.saturating_sub(1) * stride + row_len
+}
+
+/// Returns `true` if frames of `format` can be walked by [for_each_pixel()].
+pub(crate) fn is_supported(format: PixelFormat) -> bool {
+    matches!(
+        DrmFourcc::try_from(format.fourcc()),
+        Ok(DrmFourcc::Rgb888
+            | DrmFourcc::Bgr888
+            | DrmFourcc::Xrgb8888
+            | DrmFourcc::Argb8888
+            | DrmFourcc::Xbgr8888
+            | DrmFourcc::Abgr8888
+            | DrmFourcc::Yuyv
+            | DrmFourcc::Nv12
+            | DrmFourcc::Nv21
+            | DrmFourcc::Yuv420)
+    )
+}
+
+/// Calls `f` for every `step`-th pixel in each direction, in row-major order.
+///
+/// Supports packed RGB (`RGB888`, `BGR888`, `XRGB8888`, `XBGR8888`, `ARGB8888`, `ABGR8888`), `YUYV` and `NV12`,
+/// `NV21`, `YUV420` planar YUV formats. Plane lengths are checked before any pixel is visited.
+pub(crate) fn for_each_pixel(
+    planes: &[&[u8]],
+    format: PixelFormat,
+    size: Size,
+    stride: u32,
+    step: usize,
+    mut f: impl FnMut(Pixel),
+) -> Result<(), PixelError> {
+    let fourcc = DrmFourcc::try_from(format.fourcc()).map_err(|_| PixelError::UnsupportedFormat(format))?;
+    let (width, height, stride, step) = (size.width as usize, size.height as usize, stride as usize, step.max(1));
+
+    match fourcc {
+        // DRM formats are little-endian, i.e. RGB888 is stored as B, G, R in memory
+        DrmFourcc::Rgb888 | DrmFourcc::Bgr888 => {
+            let data = plane(planes, 0, plane_len(height, stride, width * 3))?;
+            let (r, b) = if fourcc == DrmFourcc::Rgb888 { (2, 0) } else { (0, 2) };
+            for y in (0..height).step_by(step) {
+                for px in data[y * stride..][..width * 3].chunks_exact(3).step_by(step) {
+                    f(Pixel::Rgb([px[r], px[1], px[b]]));
+                }
+            }
+        }
+        DrmFourcc::Xrgb8888 | DrmFourcc::Argb8888 | DrmFourcc::Xbgr8888 | DrmFourcc::Abgr8888 => {
+            let data = plane(planes, 0, plane_len(height, stride, width * 4))?;
+            let (r, b) = if matches!(fourcc, DrmFourcc::Xrgb8888 | DrmFourcc::Argb8888) {
+                (2, 0)
+            } else {
+                (0, 2)
+            };
+            for y in (0..height).step_by(step) {
+                for px in data[y * stride..][..width * 4].chunks_exact(4).step_by(step) {
+                    f(Pixel::Rgb([px[r], px[1], px[b]]));
+                }
+            }
+        }
+        DrmFourcc::Yuyv => {
+            // Y0, U, Y1, V pairs share chroma, so rows of odd widths end with a complete pair
+            let row_len = width.div_ceil(2) * 4;
+            let data = plane(planes, 0, plane_len(height, stride, row_len))?;
+            for y in (0..height).step_by(step) {
+                for (index, pair) in data[y * stride..][..row_len].chunks_exact(4).enumerate() {
+                    for x in [index * 2, index * 2 + 1] {
+                        if x < width && x % step == 0 {
+                            f(Pixel::Yuv([pair[(x % 2) * 2], pair[1], pair[3]]));
+                        }
+                    }
+                }
+            }
+        }
+        DrmFourcc::Nv12 | DrmFourcc::Nv21 => {
+            let luma = plane(planes, 0, plane_len(height, stride, width))?;
+            let chroma = plane(planes, 1, plane_len(height.div_ceil(2), stride, width.div_ceil(2) * 2))?;
+            let (u, v) = if fourcc == DrmFourcc::Nv12 { (0, 1) } else { (1, 0) };
+            for y in (0..height).step_by(step) {
+                for x in (0..width).step_by(step) {
+                    let uv = &chroma[(y / 2) * stride + (x / 2) * 2..];
+                    f(Pixel::Yuv([luma[y * stride + x], uv[u], uv[v]]));
+                }
+            }
+        }
+        DrmFourcc::Yuv420 => {
+            let chroma_stride = stride / 2;
+            let luma = plane(planes, 0, plane_len(height, stride, width))?;
+            let cb = plane(
+                planes,
+                1,
+                plane_len(height.div_ceil(2), chroma_stride, width.div_ceil(2)),
+            )?;
+            let cr = plane(
+                planes,
+                2,
+                plane_len(height.div_ceil(2), chroma_stride, width.div_ceil(2)),
+            )?;
+            for y in (0..height).step_by(step) {
+                for x in (0..width).step_by(step) {
+                    let offset = (y / 2) * chroma_stride + x / 2;
+                    f(Pixel::Yuv([luma[y * stride + x], cb[offset], cr[offset]]));
+                }
+            }
+        }
+        _ => return Err(PixelError::UnsupportedFormat(format)),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use crate::{
+    camera::{Camera, SensorConfiguration},
+    geometry::Size,
+    pixel_format::{formats, ColourEncoding, PixelFormat},
+    properties,
+    stream::{StreamConfigurationRef, StreamRole},
+};
+
+/// A raw sensor mode available on [StreamRole::Raw] stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawMode {
+    pub pixel_format: PixelFormat,
+    pub size: Size,
+    /// Bits per pixel of the sensor output.
+    pub bit_depth: u32,
+    /// Whether pixels are packed in memory (i.e. MIPI CSI-2 packing), as opposed to being padded to 16 bits.
+    pub packed: bool,
+}
+
+impl RawMode {
+    /// Lists raw modes of a [StreamRole::Raw] stream configuration.
+    ///
+    /// Formats which are not recognized as raw Bayer or monochrome formats are skipped.
+    pub fn from_stream_config(cfg: &StreamConfigurationRef<'_>) -> Vec<Self> {
+        let formats = cfg.formats();
+        let mut modes = Vec::new();
+        for pixel_format in formats.pixel_formats().into_iter() {
+            let Some((bit_depth, packed)) = raw_format_info(pixel_format) else {
+                continue;
+            };
+            for size in formats.sizes(pixel_format) {
+                modes.push(Self {
+                    pixel_format,
+                    size,
+                    bit_depth,
+                    packed,
+                });
+            }
+        }
+        modes
+    }
+
+    /// Lists raw modes of a camera by generating a [StreamRole::Raw] configuration.
+    pub fn enumerate(cam: &Camera<'_>) -> Vec<Self> {
+        cam.generate_configuration(&[StreamRole::Raw])
+            .and_then(|config| Some(Self::from_stream_config(config.get(0)?.value())))
+            .unwrap_or_default()
+    }
+
+    /// Applies pixel format and size of the mode to a raw stream configuration.
+    pub fn apply(&self, cfg: &mut StreamConfigurationRef<'_>) {
+        cfg.set_pixel_format(self.pixel_format);
+        cfg.set_size(self.size);
+    }
+
+    /// Returns sensor configuration, which forces this mode regardless of the other streams.
+    ///
+    /// Apply it with [set_sensor_configuration()].
+    ///
+    /// [set_sensor_configuration()]: crate::camera::CameraConfiguration::set_sensor_configuration
+    pub fn sensor_configuration(&self) -> SensorConfiguration {
+        SensorConfiguration::new(self.size, self.bit_depth)
+    }
+
+    /// Returns `true` if the mode covers the whole pixel array, either natively or with 2x2 binning.
+    pub fn is_full_fov(&self, pixel_array: Size) -> bool {
+        // Allow some slack for sensors cropping a few lines for alignment
+        let covers = |w: u32, h: u32| w * 100 >= pixel_array.width * 98 && h * 100 >= pixel_array.height * 98;
+        covers(self.size.width, self.size.height) || covers(self.size.width * 2, self.size.height * 2)
+    }
+
+    fn pixels(&self) -> u64 {
+        self.size.width as u64 * self.size.height as u64
+    }
+}
+
+/// Monochrome sensor formats, which libcamera describes with YUV colour encoding.
+const MONO_FORMATS: &[PixelFormat] = &[
+    formats::R8,
+    formats::R10,
+    formats::R12,
+    formats::R16,
+    formats::R10_CSI2P,
+    formats::R12_CSI2P,
+];
+
+/// Returns `(bit depth, packed)` of raw Bayer and monochrome formats, such as `SRGGB10_CSI2P` or `R12`.
+///
+/// Compressed formats (i.e. PiSP) are skipped, as their layout does not tell the bit depth of the sensor.
+pub(crate) fn raw_format_info(pixel_format: PixelFormat) -> Option<(u32, bool)> {
+    let info = pixel_format.info()?;
+    let is_raw = info.colour_encoding == ColourEncoding::Raw || MONO_FORMATS.contains(&pixel_format);
+    let is_compressed = [
+        formats::PISP_FORMAT_MOD_COMPRESS_MODE1,
+        formats::PISP_FORMAT_MOD_COMPRESS_MODE2,
+    ]
+    .contains(&pixel_format.modifier());
+    (is_raw && !is_compressed).then_some((info.bits_per_pixel, info.packed))
+}
+
+/// Raw mode selection preference of [RawModeSelector].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawModePreference {
+    /// Prefer the mode with the most pixels.
+    MaxResolution,
+    /// Prefer the mode with the fewest pixels, which sensors read out the fastest.
+    MaxFps,
+}
+
+/// Selects a raw mode by criteria.
+///
+/// ```no_run
+/// # use libcamera::raw_mode::{RawMode, RawModePreference, RawModeSelector};
+/// # fn example(cam: &libcamera::camera::Camera<'_>) {
+/// let mode = RawModeSelector::new(RawModePreference::MaxFps)
+///     .bit_depth(10)
+///     .full_fov(true)
+///     .select_for(cam);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawModeSelector {
+    preference: RawModePreference,
+    bit_depth: Option<u32>,
+    min_size: Option<Size>,
+    full_fov: bool,
+}
+
+impl RawModeSelector {
+    pub fn new(preference: RawModePreference) -> Self {
+        Self {
+            preference,
+            bit_depth: None,
+            min_size: None,
+            full_fov: false,
+        }
+    }
+
+    /// Only accept modes with the given bit depth.
+    pub fn bit_depth(mut self, bit_depth: u32) -> Self {
+        self.bit_depth = Some(bit_depth);
+        self
+    }
+
+    /// Only accept modes at least as large as `size`.
+    pub fn min_size(mut self, size: Size) -> Self {
+        self.min_size = Some(size);
+        self
+    }
+
+    /// Only accept modes covering the full field of view, see [RawMode::is_full_fov()].
+    pub fn full_fov(mut self, enabled: bool) -> Self {
+        self.full_fov = enabled;
+        self
+    }
+
+    /// Selects the best matching mode.
+    ///
+    /// `pixel_array` is only required for [RawModeSelector::full_fov()] criteria, modes are rejected without it.
+    pub fn select(&self, modes: &[RawMode], pixel_array: Option<Size>) -> Option<RawMode> {
+        let candidates = modes.iter().filter(|m| {
+            self.bit_depth.is_none_or(|b| m.bit_depth == b)
+                && self
+                    .min_size
+                    .is_none_or(|s| m.size.width >= s.width && m.size.height >= s.height)
+                && (!self.full_fov || pixel_array.is_some_and(|pa| m.is_full_fov(pa)))
+        });
+
+        // Prefer packed formats on equal size as they need less memory bandwidth
+        match self.preference {
+            RawModePreference::MaxResolution => candidates.max_by_key(|m| (m.pixels(), m.packed)),
+            RawModePreference::MaxFps => candidates.min_by_key(|m| (m.pixels(), !m.packed)),
+        }
+        .copied()
+    }
+
+    /// Enumerates raw modes of the camera and selects the best matching one.
+    pub fn select_for(&self, cam: &Camera<'_>) -> Option<RawMode> {
+        let pixel_array = cam.properties().get::<properties::PixelArraySize>().ok().map(|s| *s);
+        self.select(&RawMode::enumerate(cam), pixel_array)
+    }
+}
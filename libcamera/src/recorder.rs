@@ -0,0 +1,316 @@
+//! Recording of a single stream into a writer.
+//!
+//! [VideoRecorder] runs the capture loop that video applications otherwise write themselves: it queues requests,
+//! encodes completed frames with a [FrameEncoder], reuses the requests and stops the camera when recording ends. The
+//! default [JpegEncoder] writes concatenated JPEG images (an `MJPEG` elementary stream), passing `MJPEG` frames of UVC
+//! cameras through without re-encoding.
+
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+use crate::{
+    camera::ActiveCamera,
+    control::{ControlEntry, ControlList},
+    controls::FrameDurationLimits,
+    error::Error as CameraError,
+    framebuffer::AsFrameBuffer,
+    framebuffer_allocator::{FrameBuffer, FrameBufferAllocator},
+    framebuffer_map::{MemoryMappedFrameBuffer, MemoryMappedFrameBufferError},
+    jpeg::JpegEncoder,
+    request::{Request, ReuseFlag},
+    stream::{Stream, StreamConfiguration, StreamConfigurationRef, StreamRole},
+};
+
+#[derive(Debug, Error)]
+pub enum RecorderError {
+    #[error(transparent)]
+    Camera(#[from] CameraError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Camera could not generate configuration for role {0:?}")]
+    UnsupportedRole(StreamRole),
+    #[error("Camera configuration is invalid")]
+    InvalidConfiguration,
+    #[error(transparent)]
+    Map(#[from] MemoryMappedFrameBufferError),
+    #[error("Timed out waiting for a completed request")]
+    Timeout,
+    #[error("Camera stopped delivering requests")]
+    Disconnected,
+}
+
+/// Encodes frames of a recorded stream and writes them to the output.
+pub trait FrameEncoder {
+    /// Encodes a frame given by its plane data, limited to the bytes used.
+    fn encode(&mut self, planes: &[&[u8]], out: &mut dyn Write) -> io::Result<()>;
+
+    /// Writes any buffered data when recording ends.
+    fn finish(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        out.flush()
+    }
+}
+
+impl FrameEncoder for JpegEncoder {
+    fn encode(&mut self, planes: &[&[u8]], out: &mut dyn Write) -> io::Result<()> {
+        let data = JpegEncoder::encode(self, planes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        out.write_all(&data)
+    }
+}
+
+/// Stops a running [VideoRecorder::record()] from another thread, i.e. a signal handler.
+#[derive(Debug, Clone, Default)]
+pub struct StopHandle(Arc<AtomicBool>);
+
+impl StopHandle {
+    /// Requests recording to stop after the frame being processed.
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Summary of a finished recording.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecordingStats {
+    /// Frames encoded and written to the output.
+    pub frames_written: u64,
+    /// Frames dropped to keep the requested frame rate.
+    pub frames_skipped: u64,
+    /// Frames completed with an error status, i.e. due to a buffer underrun.
+    pub frames_failed: u64,
+    /// Duration between the start of capture and the last written frame.
+    pub duration: Duration,
+}
+
+/// Records a single stream of an [ActiveCamera] into a writer.
+pub struct VideoRecorder<'d, W: Write> {
+    cam: ActiveCamera<'d>,
+    out: W,
+    encoder: Box<dyn FrameEncoder + 'd>,
+    config: StreamConfiguration,
+    stream: Stream,
+    _alloc: FrameBufferAllocator,
+    /// Requests which are not queued while recording is stopped.
+    idle: Vec<Request>,
+    rx: mpsc::Receiver<Request>,
+    stop: StopHandle,
+    frame_rate: Option<f64>,
+    max_frames: Option<u64>,
+    max_duration: Option<Duration>,
+    frame_timeout: Duration,
+}
+
+impl<'d, W: Write> VideoRecorder<'d, W> {
+    /// Configures a stream of the given role with pipeline defaults and allocates its buffers.
+    pub fn new(cam: ActiveCamera<'d>, role: StreamRole, out: W) -> Result<Self, RecorderError> {
+        Self::with_config(cam, role, out, |_| {})
+    }
+
+    /// Configures a stream of the given role, adjusted by `f` before validation, and allocates its buffers.
+    ///
+    /// Encoder defaults to [JpegEncoder] for the applied configuration, see [VideoRecorder::set_encoder()].
+    pub fn with_config(
+        mut cam: ActiveCamera<'d>,
+        role: StreamRole,
+        out: W,
+        f: impl FnOnce(&mut StreamConfigurationRef<'_>),
+    ) -> Result<Self, RecorderError> {
+        let mut cfgs = cam
+            .generate_configuration(&[role])
+            .ok_or(RecorderError::UnsupportedRole(role))?;
+        f(&mut cfgs.get_mut(0).ok_or(RecorderError::UnsupportedRole(role))?);
+        if cfgs.validate().is_invalid() {
+            return Err(RecorderError::InvalidConfiguration);
+        }
+        let applied = cam.configure(&mut cfgs)?;
+        let (stream, config) = applied
+            .iter()
+            .next()
+            .map(|(stream, cfg)| (stream, *cfg))
+            .ok_or(RecorderError::InvalidConfiguration)?;
+
+        let mut alloc = FrameBufferAllocator::new(&cam);
+        let mut idle = Vec::new();
+        for (cookie, buffer) in alloc.alloc(&stream)?.into_iter().enumerate() {
+            let mut req = cam
+                .create_request(Some(cookie as u64))
+                .ok_or_else(|| io::Error::from_raw_os_error(libc::ENOMEM))?;
+            req.add_buffer(&stream, MemoryMappedFrameBuffer::new(buffer)?)?;
+            idle.push(req);
+        }
+
+        let (tx, rx) = mpsc::channel();
+        cam.on_request_completed(move |req| {
+            let _ = tx.send(req);
+        });
+
+        Ok(Self {
+            cam,
+            out,
+            encoder: Box::new(JpegEncoder::new(config.pixel_format, config.size, config.stride)),
+            config,
+            stream,
+            _alloc: alloc,
+            idle,
+            rx,
+            stop: StopHandle::default(),
+            frame_rate: None,
+            max_frames: None,
+            max_duration: None,
+            frame_timeout: Duration::from_secs(2),
+        })
+    }
+
+    /// Returns configuration of the recorded stream.
+    pub fn config(&self) -> &StreamConfiguration {
+        &self.config
+    }
+
+    /// Replaces the encoder, i.e. with one wrapping an external video codec.
+    pub fn set_encoder(&mut self, encoder: impl FrameEncoder + 'd) {
+        self.encoder = Box::new(encoder);
+    }
+
+    /// Limits the recorded frame rate.
+    ///
+    /// Frame duration of the camera is limited accordingly if it supports `FrameDurationLimits`, and frames arriving
+    /// faster than requested are skipped, so the output has a steady rate either way.
+    pub fn set_frame_rate(&mut self, fps: Option<f64>) {
+        self.frame_rate = fps.filter(|fps| *fps > 0.0);
+    }
+
+    /// Stops recording after `frames` frames were written.
+    pub fn set_max_frames(&mut self, frames: Option<u64>) {
+        self.max_frames = frames;
+    }
+
+    /// Stops recording once the camera captured for `duration`.
+    pub fn set_max_duration(&mut self, duration: Option<Duration>) {
+        self.max_duration = duration;
+    }
+
+    /// Sets the maximum time to wait for each frame before failing with [RecorderError::Timeout].
+    pub fn set_frame_timeout(&mut self, timeout: Duration) {
+        self.frame_timeout = timeout;
+    }
+
+    /// Returns a handle, which stops [VideoRecorder::record()] from another thread.
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop.clone()
+    }
+
+    /// Starts the camera and records until stopped by [StopHandle], a frame or duration limit is reached, or an error
+    /// occurs.
+    ///
+    /// Camera is stopped and the encoder finished before returning, including on errors. Recording can be started
+    /// again afterwards.
+    pub fn record(&mut self) -> Result<RecordingStats, RecorderError> {
+        self.stop.0.store(false, Ordering::Relaxed);
+
+        let mut controls = ControlList::new();
+        let interval = self.frame_rate.map(|fps| 1e9 / fps);
+        if let Some(interval) = interval {
+            if self.cam.controls().get(FrameDurationLimits::ID).is_some() {
+                let micros = (interval / 1e3) as i64;
+                let _ = controls.set(FrameDurationLimits([micros, micros]));
+            }
+        }
+        self.cam.start(Some(&controls))?;
+
+        let mut stats = RecordingStats::default();
+        let result = self.run(interval, &mut stats);
+
+        let stopped = self.cam.stop();
+        while let Ok(mut req) = self.rx.try_recv() {
+            req.reuse(ReuseFlag::REUSE_BUFFERS);
+            self.idle.push(req);
+        }
+        let finished = self.encoder.finish(&mut self.out);
+
+        result?;
+        stopped?;
+        finished?;
+        Ok(stats)
+    }
+
+    fn run(&mut self, interval: Option<f64>, stats: &mut RecordingStats) -> Result<(), RecorderError> {
+        for req in self.idle.drain(..) {
+            self.cam.queue_request(req)?;
+        }
+
+        let started = Instant::now();
+        // Timestamp of the first and last written frame in nanoseconds
+        let mut first = None;
+        let mut last: Option<u64> = None;
+        loop {
+            if self.stop.is_stopped()
+                || self.max_frames.is_some_and(|max| stats.frames_written >= max)
+                || self.max_duration.is_some_and(|max| started.elapsed() >= max)
+            {
+                return Ok(());
+            }
+
+            let mut req = self.rx.recv_timeout(self.frame_timeout).map_err(|e| match e {
+                mpsc::RecvTimeoutError::Timeout => RecorderError::Timeout,
+                mpsc::RecvTimeoutError::Disconnected => RecorderError::Disconnected,
+            })?;
+
+            let fb: &MemoryMappedFrameBuffer<FrameBuffer> = req.buffer(&self.stream).unwrap();
+            let timestamp = match fb.metadata() {
+                Some(metadata) if metadata.is_success() => Some(metadata.timestamp()),
+                _ => None,
+            };
+            match timestamp {
+                None => stats.frames_failed += 1,
+                // Allow 10% jitter, so that frames captured at exactly the requested rate are not skipped
+                Some(ts)
+                    if interval
+                        .zip(last)
+                        .is_some_and(|(i, l)| (ts.saturating_sub(l) as f64) < i * 0.9) =>
+                {
+                    stats.frames_skipped += 1
+                }
+                Some(ts) => {
+                    let guard = fb.sync()?;
+                    self.encoder.encode(&guard.data_used(), &mut self.out)?;
+                    drop(guard);
+
+                    stats.frames_written += 1;
+                    last = Some(ts);
+                    stats.duration = Duration::from_nanos(ts.saturating_sub(*first.get_or_insert(ts)));
+                }
+            }
+
+            req.reuse(ReuseFlag::REUSE_BUFFERS);
+            self.cam.queue_request(req)?;
+        }
+    }
+
+    pub fn camera(&self) -> &ActiveCamera<'d> {
+        &self.cam
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.out
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    /// Returns the writer. Camera is released, as buffers are freed along with the recorder.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
@@ -4,8 +4,32 @@ use std::{any::Any, collections::HashMap, io, ptr::NonNull};
 
 use bitflags::bitflags;
 use libcamera_sys::*;
+use thiserror::Error;
 
-use crate::{control::ControlList, framebuffer::AsFrameBuffer, stream::Stream};
+use crate::{
+    camera::ActiveCamera,
+    control::{ControlError, ControlList, ControlValidationError},
+    error::Error as CameraError,
+    framebuffer::AsFrameBuffer,
+    stream::Stream,
+};
+
+/// Reason why [Request::clone_template()] could not create a request.
+#[derive(Debug, Error)]
+pub enum RequestTemplateError {
+    #[error("Camera could not create a request")]
+    CreateFailed,
+    #[error("Template has buffers of a stream from a previous configuration")]
+    StaleStream,
+    #[error("Template controls are not accepted by the camera: {0:?}")]
+    InvalidControls(Vec<ControlValidationError>),
+    #[error(transparent)]
+    Control(#[from] ControlError),
+    #[error(transparent)]
+    Camera(#[from] CameraError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
 
 /// Status of [Request]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -31,8 +55,31 @@ impl TryFrom<libcamera_request_status_t> for RequestStatus {
     }
 }
 
+/// Queueing state of a [Request], tracked on the Rust side independently of [RequestStatus].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum QueueState {
+    /// Request was created or reused and can be queued.
+    #[default]
+    Idle,
+    /// Request was queued and did not complete yet.
+    InFlight,
+    /// Request completed or was cancelled and has to be reused before queueing it again.
+    Completed,
+}
+
+impl QueueState {
+    /// Fails with [CameraError::AlreadyQueued] unless the request can be queued.
+    pub(crate) fn check(self) -> Result<(), CameraError> {
+        match self {
+            Self::Idle => Ok(()),
+            Self::InFlight | Self::Completed => Err(CameraError::AlreadyQueued),
+        }
+    }
+}
+
 bitflags! {
     /// Flags to control the behaviour of [Request::reuse()].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub struct ReuseFlag: u32 {
         /// Reuse the buffers that were previously added by [Request::add_buffer()].
         const REUSE_BUFFERS = 1 << 0;
@@ -45,10 +92,16 @@ bitflags! {
 /// and scheduled for execution by [ActiveCamera::queue_request()](crate::camera::ActiveCamera::queue_request).
 /// Completed requests are returned by request completed callback (see
 /// [ActiveCamera::on_request_completed()](crate::camera::ActiveCamera::on_request_completed)) and can (should) be
-/// reused by calling [ActiveCamera::queue_request()](crate::camera::ActiveCamera::queue_request) again.
+/// reused by resetting them with [Request::reuse()] and queueing them again. Queueing a completed request without
+/// [Request::reuse()] fails with [Error::AlreadyQueued](crate::Error::AlreadyQueued).
 pub struct Request {
     pub(crate) ptr: NonNull<libcamera_request_t>,
     buffers: HashMap<Stream, Box<dyn Any + 'static>>,
+    /// Flags of the last [Request::reuse()] since the request was queued.
+    reused: Option<ReuseFlag>,
+    /// Cookie set by [Request::set_cookie()], which overrides the one given to libcamera on creation.
+    cookie: Option<u64>,
+    queue_state: QueueState,
 }
 
 impl Request {
@@ -56,6 +109,9 @@ impl Request {
         Self {
             ptr,
             buffers: Default::default(),
+            reused: None,
+            cookie: None,
+            queue_state: QueueState::Idle,
         }
     }
 
@@ -84,11 +140,11 @@ impl Request {
     ///
     /// Buffers can only be attached once. To access framebuffer after executing request use [Self::buffer()] or
     /// [Self::buffer_mut()].
-    pub fn add_buffer<T: AsFrameBuffer + Any>(&mut self, stream: &Stream, buffer: T) -> io::Result<()> {
+    pub fn add_buffer<T: AsFrameBuffer + Any>(&mut self, stream: &Stream, buffer: T) -> Result<(), CameraError> {
         let ret =
             unsafe { libcamera_request_add_buffer(self.ptr.as_ptr(), stream.ptr.as_ptr(), buffer.ptr().as_ptr()) };
         if ret < 0 {
-            Err(io::Error::from_raw_os_error(ret))
+            Err(CameraError::from_ret(ret, |errno| CameraError::BufferFailed { errno }))
         } else {
             self.buffers.insert(*stream, Box::new(buffer));
             Ok(())
@@ -109,17 +165,73 @@ impl Request {
         self.buffers.get_mut(stream).and_then(|b| b.downcast_mut())
     }
 
-    /// Returns auto-incrementing sequence number of the capture
+    /// Creates a new request on `cam` with the same controls as `self` and a buffer attached to each stream `self` has
+    /// a buffer for, i.e. to resubmit the same settings repeatedly during burst capture.
+    ///
+    /// A framebuffer can only be attached to one request, so `buffer` is called for every stream to provide a fresh
+    /// one. The template is checked against the current state of the camera: all streams must belong to the applied
+    /// configuration (see [ActiveCamera::is_stream_valid()]) and all controls must pass
+    /// [Camera::validate_controls()](crate::camera::Camera::validate_controls).
+    pub fn clone_template<T: AsFrameBuffer + Any>(
+        &self,
+        cam: &mut ActiveCamera<'_>,
+        cookie: Option<u64>,
+        mut buffer: impl FnMut(&Stream) -> io::Result<T>,
+    ) -> Result<Request, RequestTemplateError> {
+        if self.streams().any(|stream| !cam.is_stream_valid(stream)) {
+            return Err(RequestTemplateError::StaleStream);
+        }
+        let errors = cam.validate_controls(self.controls());
+        if !errors.is_empty() {
+            return Err(RequestTemplateError::InvalidControls(errors));
+        }
+
+        let mut req = cam.create_request(cookie).ok_or(RequestTemplateError::CreateFailed)?;
+        for (id, value) in self.controls() {
+            let value = value.map_err(|error| ControlError::Unreadable { id, error })?;
+            req.controls_mut().set_raw(id, value)?;
+        }
+        for stream in self.streams() {
+            req.add_buffer(stream, buffer(stream)?)?;
+        }
+        Ok(req)
+    }
+
+    /// Returns streams with attached buffers.
+    pub(crate) fn streams(&self) -> impl Iterator<Item = &Stream> {
+        self.buffers.keys()
+    }
+
+    /// Returns the stream, which `buffer` was attached to with [Self::add_buffer()].
+    pub(crate) fn find_stream(&self, buffer: *const libcamera_framebuffer_t) -> Option<Stream> {
+        self.buffers.keys().copied().find(|stream| {
+            unsafe { libcamera_request_find_buffer(self.ptr.as_ptr(), stream.ptr.as_ptr()) }.cast_const() == buffer
+        })
+    }
+
+    /// Returns auto-incrementing sequence number of the capture.
+    ///
+    /// Assigned by the pipeline, so it is only meaningful once the request has completed.
     pub fn sequence(&self) -> u32 {
         unsafe { libcamera_request_sequence(self.ptr.as_ptr()) }
     }
 
     /// Returns request identifier that was provided in
-    /// [ActiveCamera::create_request()](crate::camera::ActiveCamera::create_request).
+    /// [ActiveCamera::create_request()](crate::camera::ActiveCamera::create_request) or set by [Self::set_cookie()].
     ///
     /// Returns zero if cookie was not provided.
     pub fn cookie(&self) -> u64 {
-        unsafe { libcamera_request_cookie(self.ptr.as_ptr()) }
+        self.cookie
+            .unwrap_or_else(|| unsafe { libcamera_request_cookie(self.ptr.as_ptr()) })
+    }
+
+    /// Sets request identifier returned by [Self::cookie()], i.e. to tag a reused request with the application state
+    /// it is queued for.
+    ///
+    /// libcamera only accepts a cookie on creation, so the new value is kept on the Rust side and persists across
+    /// [Self::reuse()].
+    pub fn set_cookie(&mut self, cookie: u64) {
+        self.cookie = Some(cookie);
     }
 
     /// Capture request status
@@ -135,6 +247,21 @@ impl Request {
     /// via [Self::add_buffer()] by setting flags to [ReuseFlag::REUSE_BUFFERS].
     pub fn reuse(&mut self, flags: ReuseFlag) {
         unsafe { libcamera_request_reuse(self.ptr.as_ptr(), flags.bits()) }
+        self.reused = Some(flags);
+        self.queue_state = QueueState::Idle;
+    }
+
+    /// Returns flags of the last [Self::reuse()] call and clears them, used to notify observers when queued.
+    pub(crate) fn take_reused(&mut self) -> Option<ReuseFlag> {
+        self.reused.take()
+    }
+
+    pub(crate) fn queue_state(&self) -> QueueState {
+        self.queue_state
+    }
+
+    pub(crate) fn set_queue_state(&mut self, state: QueueState) {
+        self.queue_state = state;
     }
 }
 
@@ -155,3 +282,20 @@ impl Drop for Request {
 }
 
 unsafe impl Send for Request {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_is_queueable_only_when_idle() {
+        let mut state = QueueState::default();
+        assert!(state.check().is_ok());
+
+        state = QueueState::InFlight;
+        assert!(matches!(state.check(), Err(CameraError::AlreadyQueued)));
+
+        state = QueueState::Completed;
+        assert!(matches!(state.check(), Err(CameraError::AlreadyQueued)));
+    }
+}
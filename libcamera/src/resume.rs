@@ -0,0 +1,56 @@
+use std::{io, time::Duration};
+
+/// Detects system suspend/resume cycles.
+///
+/// `CLOCK_BOOTTIME` keeps counting while the system is suspended, whereas `CLOCK_MONOTONIC` does not. Growing
+/// difference between the two between consecutive [ResumeDetector::poll()] calls means the system was suspended in
+/// the meantime. Poll it periodically (i.e. on each completed request or on a capture timeout) and call
+/// [ActiveCamera::notify_resumed()](crate::camera::ActiveCamera::notify_resumed) once resume is detected.
+#[derive(Debug, Clone)]
+pub struct ResumeDetector {
+    offset: Duration,
+    threshold: Duration,
+}
+
+impl ResumeDetector {
+    /// Creates detector which ignores suspends shorter than 500ms.
+    pub fn new() -> io::Result<Self> {
+        Self::with_threshold(Duration::from_millis(500))
+    }
+
+    /// Creates detector which ignores suspends shorter than `threshold`.
+    pub fn with_threshold(threshold: Duration) -> io::Result<Self> {
+        Ok(Self {
+            offset: Self::suspended_offset()?,
+            threshold,
+        })
+    }
+
+    /// Returns time spent in suspend since the previous call, if it exceeds the threshold.
+    pub fn poll(&mut self) -> io::Result<Option<Duration>> {
+        let offset = Self::suspended_offset()?;
+        let suspended = offset.saturating_sub(self.offset);
+        self.offset = offset;
+
+        if suspended >= self.threshold {
+            Ok(Some(suspended))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Total time the system has spent in suspend since boot.
+    fn suspended_offset() -> io::Result<Duration> {
+        Ok(clock_time(libc::CLOCK_BOOTTIME)?.saturating_sub(clock_time(libc::CLOCK_MONOTONIC)?))
+    }
+}
+
+pub(crate) fn clock_time(clock: libc::clockid_t) -> io::Result<Duration> {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    let ret = unsafe { libc::clock_gettime(clock, &mut ts) };
+    if ret < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(Duration::new(ts.tv_sec as _, ts.tv_nsec as _))
+    }
+}
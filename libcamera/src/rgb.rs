@@ -0,0 +1,140 @@
+use thiserror::Error;
+
+use crate::{
+    framebuffer::AsFrameBuffer,
+    framebuffer_map::MemoryMappedFrameBuffer,
+    geometry::Size,
+    pixel_format::{formats, PixelFormat},
+    pixels::{self, for_each_pixel, Pixel},
+    stream::StreamConfigurationRef,
+    yuv::{RgbLayout, YuvConverter},
+};
+
+#[derive(Debug, Error)]
+pub enum RgbError {
+    /// Pixel format can not be converted to RGB
+    #[error("Unsupported pixel format {0:?}")]
+    UnsupportedFormat(PixelFormat),
+    /// Frame does not contain the planes required by the pixel format
+    #[error("Expected {expected} planes, found {found}")]
+    MissingPlanes { expected: usize, found: usize },
+    /// Plane data is shorter than implied by the frame size and stride
+    #[error("Plane {index} is {len} bytes long, but at least {expected} bytes are required")]
+    PlaneTooShort { index: usize, len: usize, expected: usize },
+    /// Output buffer is shorter than implied by the frame size and output stride
+    #[error("Output is {len} bytes long, but at least {expected} bytes are required")]
+    OutputTooShort { len: usize, expected: usize },
+    /// Compressed frame could not be decoded
+    #[error("Decoding failed: {0}")]
+    Decode(String),
+}
+
+/// A packed 8-bit RGB image.
+#[derive(Debug, Clone)]
+pub struct RgbImage {
+    pub width: u32,
+    pub height: u32,
+    /// Pixel data in R, G, B order without any padding between rows.
+    pub data: Vec<u8>,
+}
+
+/// Converts frames of a single stream to [RgbImage].
+///
+/// Supports packed RGB (`RGB888`, `BGR888`, `XRGB8888`, `XBGR8888`, `ARGB8888`, `ABGR8888`), `YUYV`, `NV12`, `NV21`
+/// and `YUV420` formats. `MJPEG` frames are decoded when `jpeg-decode` feature is enabled, which makes it possible to
+/// process frames of UVC cameras that only provide MJPEG at higher resolutions in the same way as uncompressed ones.
+#[derive(Debug, Clone, Copy)]
+pub struct RgbConverter {
+    format: PixelFormat,
+    size: Size,
+    stride: u32,
+}
+
+impl RgbConverter {
+    pub fn new(format: PixelFormat, size: Size, stride: u32) -> Self {
+        Self { format, size, stride }
+    }
+
+    /// Creates converter for frames produced by the given (validated) stream configuration.
+    pub fn from_stream_config(cfg: &StreamConfigurationRef<'_>) -> Self {
+        Self::new(cfg.get_pixel_format(), cfg.get_size(), cfg.get_stride())
+    }
+
+    /// Returns `true` if frames of the configured pixel format can be converted.
+    pub fn is_supported(&self) -> bool {
+        if self.format == formats::MJPEG {
+            return cfg!(feature = "jpeg-decode");
+        }
+
+        pixels::is_supported(self.format)
+    }
+
+    /// Converts memory mapped framebuffer into RGB.
+    ///
+    /// Only the bytes used by the frame are passed to the decoder if framebuffer metadata is available.
+    pub fn convert_buffer<T: AsFrameBuffer>(&self, fb: &MemoryMappedFrameBuffer<T>) -> Result<RgbImage, RgbError> {
+        self.convert(&fb.data_used())
+    }
+
+    /// Converts frame planes into RGB.
+    pub fn convert(&self, planes: &[&[u8]]) -> Result<RgbImage, RgbError> {
+        if self.format == formats::MJPEG {
+            return self.decode_jpeg(planes);
+        }
+
+        let yuv = YuvConverter::new(self.format, self.size, self.stride, RgbLayout::Rgb);
+        let out = if yuv.is_supported() {
+            yuv.convert(planes)?
+        } else {
+            let mut out = Vec::with_capacity(self.size.width as usize * self.size.height as usize * 3);
+            for_each_pixel(planes, self.format, self.size, self.stride, 1, |px| match px {
+                Pixel::Rgb(rgb) => out.extend(rgb),
+                // YUV formats are handled by the converter above
+                Pixel::Yuv(_) => unreachable!(),
+            })?;
+            out
+        };
+
+        Ok(RgbImage {
+            width: self.size.width,
+            height: self.size.height,
+            data: out,
+        })
+    }
+
+    #[cfg(feature = "jpeg-decode")]
+    fn decode_jpeg(&self, planes: &[&[u8]]) -> Result<RgbImage, RgbError> {
+        use zune_jpeg::{
+            zune_core::{colorspace::ColorSpace, options::DecoderOptions},
+            JpegDecoder,
+        };
+
+        let data = planes
+            .first()
+            .ok_or(RgbError::MissingPlanes { expected: 1, found: 0 })?;
+        let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGB);
+        let mut decoder = JpegDecoder::new_with_options(*data, options);
+        let pixels = decoder.decode().map_err(|e| RgbError::Decode(e.to_string()))?;
+        let (width, height) = decoder
+            .dimensions()
+            .ok_or_else(|| RgbError::Decode("missing image dimensions".to_string()))?;
+
+        Ok(RgbImage {
+            width: width as _,
+            height: height as _,
+            data: pixels,
+        })
+    }
+
+    #[cfg(not(feature = "jpeg-decode"))]
+    fn decode_jpeg(&self, _planes: &[&[u8]]) -> Result<RgbImage, RgbError> {
+        Err(RgbError::UnsupportedFormat(self.format))
+    }
+}
+
+impl<T: AsFrameBuffer> MemoryMappedFrameBuffer<T> {
+    /// Converts framebuffer contents into RGB, see [RgbConverter].
+    pub fn rgb(&self, converter: &RgbConverter) -> Result<RgbImage, RgbError> {
+        converter.convert_buffer(self)
+    }
+}
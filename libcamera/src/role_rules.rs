@@ -0,0 +1,131 @@
+//! Checks of stream configurations against the constraints implied by their [StreamRole].
+//!
+//! libcamera only reports [CameraConfigurationStatus::Invalid](crate::camera::CameraConfigurationStatus::Invalid)
+//! or silently adjusts contradictory settings, i.e. a processed format requested on a raw stream. [RoleRules] reports
+//! such settings before validation, with the stream and the reason.
+
+use thiserror::Error;
+
+use crate::{
+    camera::CameraConfiguration,
+    color_space::ColorSpace,
+    geometry::Size,
+    pixel_format::{self, ColourEncoding, PixelFormat},
+    stream::{StreamConfigurationRef, StreamRole},
+};
+
+/// Setting of a stream which contradicts its [StreamRole].
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RoleWarning {
+    #[error("Stream {index} has raw role, but processed format {format:?}")]
+    ProcessedFormatOnRaw { index: usize, format: PixelFormat },
+    #[error("Stream {index} has {role:?} role, but raw format {format:?}")]
+    RawFormatOnProcessed {
+        index: usize,
+        role: StreamRole,
+        format: PixelFormat,
+    },
+    /// Raw streams can not be scaled, so their size has to match one of the sensor modes.
+    #[error("Raw stream {index} size {size:?} is not a sensor mode, expected one of {modes:?}")]
+    RawNotSensorMode { index: usize, size: Size, modes: Vec<Size> },
+    #[error("Stream {index} has {role:?} role, but color space {color_space:?} does not match its encoding")]
+    ColorSpaceMismatch {
+        index: usize,
+        role: StreamRole,
+        color_space: ColorSpace,
+    },
+    /// Still capture streams are usually configured for the maximum resolution.
+    #[error("Still capture stream {index} size {size:?} is below the maximum {max:?}")]
+    StillBelowMaximum { index: usize, size: Size, max: Size },
+}
+
+impl RoleWarning {
+    /// Returns index of the stream within the camera configuration.
+    pub fn index(&self) -> usize {
+        match self {
+            Self::ProcessedFormatOnRaw { index, .. }
+            | Self::RawFormatOnProcessed { index, .. }
+            | Self::RawNotSensorMode { index, .. }
+            | Self::ColorSpaceMismatch { index, .. }
+            | Self::StillBelowMaximum { index, .. } => *index,
+        }
+    }
+
+    /// Returns `true` for settings which make validation fail or get adjusted, `false` for mere preferences such as
+    /// [RoleWarning::StillBelowMaximum].
+    pub fn is_contradiction(&self) -> bool {
+        !matches!(self, Self::StillBelowMaximum { .. })
+    }
+}
+
+/// Checks a [CameraConfiguration] against the roles it was generated for.
+///
+/// [CameraConfiguration] does not remember the roles, so they have to be passed in the same order as to
+/// [Camera::generate_configuration()](crate::camera::Camera::generate_configuration).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleRules {
+    roles: Vec<StreamRole>,
+}
+
+impl RoleRules {
+    pub fn new(roles: &[StreamRole]) -> Self {
+        Self { roles: roles.to_vec() }
+    }
+
+    /// Returns roles of the streams, in configuration order.
+    pub fn roles(&self) -> &[StreamRole] {
+        &self.roles
+    }
+
+    /// Returns warnings for all streams of the configuration, empty if no contradictions were found.
+    ///
+    /// Streams without a role, i.e. added beyond the roles passed to [RoleRules::new()], are not checked.
+    pub fn check(&self, config: &CameraConfiguration) -> Vec<RoleWarning> {
+        self.roles
+            .iter()
+            .enumerate()
+            .filter_map(|(index, role)| Some((index, *role, config.get(index)?)))
+            .flat_map(|(index, role, cfg)| Self::check_stream(index, role, &cfg))
+            .collect()
+    }
+
+    /// Returns warnings for a single stream configuration.
+    pub fn check_stream(index: usize, role: StreamRole, cfg: &StreamConfigurationRef<'_>) -> Vec<RoleWarning> {
+        let mut warnings = Vec::new();
+        let format = cfg.get_pixel_format();
+        let size = cfg.get_size();
+        let is_raw = pixel_format::info(format).map(|info| info.colour_encoding == ColourEncoding::Raw);
+
+        match (role, is_raw) {
+            (StreamRole::Raw, Some(false)) => warnings.push(RoleWarning::ProcessedFormatOnRaw { index, format }),
+            (StreamRole::Raw, _) => {
+                let modes = cfg.formats().sizes(format);
+                if !modes.is_empty() && !modes.contains(&size) {
+                    warnings.push(RoleWarning::RawNotSensorMode { index, size, modes });
+                }
+            }
+            (_, Some(true)) => warnings.push(RoleWarning::RawFormatOnProcessed { index, role, format }),
+            _ => {}
+        }
+
+        if let Some(color_space) = cfg.get_color_space() {
+            let raw_stream = role == StreamRole::Raw || is_raw == Some(true);
+            if (color_space == ColorSpace::RAW) != raw_stream {
+                warnings.push(RoleWarning::ColorSpaceMismatch {
+                    index,
+                    role,
+                    color_space,
+                });
+            }
+        }
+
+        if role == StreamRole::StillCapture {
+            let max = cfg.formats().range(format).max;
+            if max.width > 0 && max.height > 0 && (size.width < max.width || size.height < max.height) {
+                warnings.push(RoleWarning::StillBelowMaximum { index, size, max });
+            }
+        }
+
+        warnings
+    }
+}
@@ -0,0 +1,237 @@
+//! Helpers for Raspberry Pi specific controls.
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::{
+    camera::CameraConfiguration,
+    control::ControlList,
+    controls::ControlId,
+    geometry::Rectangle,
+    pixel_format::{formats, ColourEncoding, PixelFormat},
+    stream::Stream,
+};
+
+/// Orders per-stream crop rectangles as expected by the `rpi::ScalerCrops` control.
+///
+/// The Pi 5 pipeline handler applies `ScalerCrops` entries to the output streams in the order they appear in the
+/// camera configuration, raw streams are not counted. Streams without an entry in `crops` use `default` (i.e. the
+/// current `ScalerCrop` value or [ScalerCropMaximum](crate::properties::ScalerCropMaximum)).
+///
+/// Configuration must be applied by [ActiveCamera::configure()](crate::camera::ActiveCamera::configure), otherwise
+/// streams are not available and the result is empty.
+pub fn scaler_crops(
+    config: &CameraConfiguration,
+    crops: &HashMap<Stream, Rectangle>,
+    default: Rectangle,
+) -> Vec<Rectangle> {
+    (0..config.len())
+        .filter_map(|i| config.get(i))
+        .filter(|cfg| !is_raw(cfg.get_pixel_format()))
+        .filter_map(|cfg| cfg.stream())
+        .map(|stream| crops.get(&stream).copied().unwrap_or(default))
+        .collect()
+}
+
+/// Sets `rpi::ScalerCrops` control using crops ordered by [scaler_crops()].
+///
+/// Only available when built against a libcamera version which defines the control.
+#[cfg(libcamera_has_scaler_crops)]
+pub fn set_scaler_crops(
+    list: &mut ControlList,
+    config: &CameraConfiguration,
+    crops: &HashMap<Stream, Rectangle>,
+    default: Rectangle,
+) -> Result<(), crate::control::ControlError> {
+    list.set_raw(
+        ControlId::ScalerCrops as u32,
+        scaler_crops(config, crops, default).into(),
+    )
+}
+
+/// Detects raw (Bayer or PiSP compressed) formats, including mono PiSP compressed ones which are not counted as
+/// output streams either.
+fn is_raw(format: PixelFormat) -> bool {
+    format
+        .info()
+        .is_some_and(|info| info.colour_encoding == ColourEncoding::Raw)
+        || format == formats::MONO_PISP_COMP1
+}
+
+/// Number of histograms in [Bcm2835Stats], as `NUM_HISTOGRAMS` in `bcm2835-isp.h`.
+pub const BCM2835_NUM_HISTOGRAMS: usize = 2;
+/// Number of bins per histogram channel, as `NUM_HISTOGRAM_BINS` in `bcm2835-isp.h`.
+pub const BCM2835_NUM_HISTOGRAM_BINS: usize = 128;
+/// AWB region grid size (columns, rows), as `DEFAULT_AWB_REGIONS_X`/`DEFAULT_AWB_REGIONS_Y` in `bcm2835-isp.h`.
+pub const BCM2835_AWB_GRID: (usize, usize) = (16, 12);
+/// Number of AWB regions, as `AWB_REGIONS` in `bcm2835-isp.h`.
+pub const BCM2835_AWB_REGIONS: usize = BCM2835_AWB_GRID.0 * BCM2835_AWB_GRID.1;
+/// Number of floating regions, as `FLOATING_REGIONS` in `bcm2835-isp.h`.
+pub const BCM2835_FLOATING_REGIONS: usize = 16;
+/// Number of AGC regions, as `AGC_REGIONS` in `bcm2835-isp.h`.
+pub const BCM2835_AGC_REGIONS: usize = 16;
+/// Number of focus regions, as `FOCUS_REGIONS` in `bcm2835-isp.h`.
+pub const BCM2835_FOCUS_REGIONS: usize = 12;
+
+const REGION_SIZE: usize = 32;
+const FOCUS_SIZE: usize = 48;
+
+/// Size of `struct bcm2835_isp_stats` in bytes.
+pub const BCM2835_STATS_SIZE: usize = 8
+    + BCM2835_NUM_HISTOGRAMS * 3 * BCM2835_NUM_HISTOGRAM_BINS * 4
+    + (BCM2835_AWB_REGIONS + BCM2835_FLOATING_REGIONS + BCM2835_AGC_REGIONS) * REGION_SIZE
+    + BCM2835_FOCUS_REGIONS * FOCUS_SIZE;
+
+#[derive(Debug, Error)]
+pub enum Bcm2835StatsError {
+    #[error("Bcm2835StatsOutput control is not available in this libcamera version")]
+    Unavailable,
+    #[error("Bcm2835StatsOutput not found in metadata, enable it with StatsOutputEnable")]
+    NotFound,
+    #[error("Statistics blob is too short, expected {expected} bytes, found {found}")]
+    TooShort { expected: usize, found: usize },
+}
+
+/// Per-channel histogram, `struct bcm2835_isp_stats_hist`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bcm2835Histogram {
+    pub r: [u32; BCM2835_NUM_HISTOGRAM_BINS],
+    pub g: [u32; BCM2835_NUM_HISTOGRAM_BINS],
+    pub b: [u32; BCM2835_NUM_HISTOGRAM_BINS],
+}
+
+/// Pixel sums of a region, `struct bcm2835_isp_stats_region`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bcm2835RegionStats {
+    /// Number of pixels accumulated into the sums.
+    pub counted: u32,
+    /// Number of pixels excluded, i.e. saturated ones.
+    pub not_counted: u32,
+    pub r_sum: u64,
+    pub g_sum: u64,
+    pub b_sum: u64,
+}
+
+impl Bcm2835RegionStats {
+    /// Returns mean `(r, g, b)` of counted pixels, [None] if no pixels were counted.
+    pub fn mean(&self) -> Option<(f64, f64, f64)> {
+        let n = self.counted as f64;
+        (self.counted > 0).then(|| (self.r_sum as f64 / n, self.g_sum as f64 / n, self.b_sum as f64 / n))
+    }
+}
+
+/// Contrast measurements of a focus region, `struct bcm2835_isp_stats_focus`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bcm2835FocusStats {
+    /// Contrast sums, indexed by `[filter][row/column]`.
+    pub contrast_val: [[u64; 2]; 2],
+    /// Number of values accumulated into `contrast_val`.
+    pub contrast_val_num: [[u32; 2]; 2],
+}
+
+/// ISP statistics reported by the Raspberry Pi VC4 pipeline in the `Bcm2835StatsOutput` control, as defined by
+/// `struct bcm2835_isp_stats` in `bcm2835-isp.h`.
+///
+/// Enable the control with `StatsOutputEnable` to have it reported in request metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bcm2835Stats {
+    pub version: u32,
+    pub size: u32,
+    pub hist: Vec<Bcm2835Histogram>,
+    /// AWB regions in row-major order of [BCM2835_AWB_GRID].
+    pub awb_stats: Vec<Bcm2835RegionStats>,
+    pub floating_stats: Vec<Bcm2835RegionStats>,
+    pub agc_stats: Vec<Bcm2835RegionStats>,
+    pub focus_stats: Vec<Bcm2835FocusStats>,
+}
+
+impl Bcm2835Stats {
+    /// Parses a statistics blob in native (little-endian) byte order.
+    ///
+    /// Trailing bytes are ignored, so that blobs of newer firmware extending the structure still parse.
+    pub fn parse(data: &[u8]) -> Result<Self, Bcm2835StatsError> {
+        if data.len() < BCM2835_STATS_SIZE {
+            return Err(Bcm2835StatsError::TooShort {
+                expected: BCM2835_STATS_SIZE,
+                found: data.len(),
+            });
+        }
+
+        let mut r = Reader { data, pos: 0 };
+        let version = r.u32();
+        let size = r.u32();
+        let hist = (0..BCM2835_NUM_HISTOGRAMS)
+            .map(|_| Bcm2835Histogram {
+                r: core::array::from_fn(|_| r.u32()),
+                g: core::array::from_fn(|_| r.u32()),
+                b: core::array::from_fn(|_| r.u32()),
+            })
+            .collect();
+        let mut regions = |n: usize| (0..n).map(|_| r.region()).collect::<Vec<_>>();
+        let awb_stats = regions(BCM2835_AWB_REGIONS);
+        let floating_stats = regions(BCM2835_FLOATING_REGIONS);
+        let agc_stats = regions(BCM2835_AGC_REGIONS);
+        let focus_stats = (0..BCM2835_FOCUS_REGIONS)
+            .map(|_| Bcm2835FocusStats {
+                contrast_val: [[r.u64(), r.u64()], [r.u64(), r.u64()]],
+                contrast_val_num: [[r.u32(), r.u32()], [r.u32(), r.u32()]],
+            })
+            .collect();
+
+        Ok(Self {
+            version,
+            size,
+            hist,
+            awb_stats,
+            floating_stats,
+            agc_stats,
+            focus_stats,
+        })
+    }
+
+    /// Parses statistics from request metadata.
+    pub fn from_metadata(metadata: &ControlList) -> Result<Self, Bcm2835StatsError> {
+        let id = ControlId::from_name("Bcm2835StatsOutput").ok_or(Bcm2835StatsError::Unavailable)?;
+        let val = metadata.get_ref(id as u32).map_err(|_| Bcm2835StatsError::NotFound)?;
+        Self::parse(val.as_bytes().ok_or(Bcm2835StatsError::NotFound)?)
+    }
+
+    /// Returns AWB region at `(x, y)` of [BCM2835_AWB_GRID].
+    pub fn awb_region(&self, x: usize, y: usize) -> Option<&Bcm2835RegionStats> {
+        if x >= BCM2835_AWB_GRID.0 {
+            return None;
+        }
+        self.awb_stats.get(y * BCM2835_AWB_GRID.0 + x)
+    }
+}
+
+/// Reads native-endian fields in order, bounds are checked once by [Bcm2835Stats::parse()].
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn u32(&mut self) -> u32 {
+        let v = u32::from_ne_bytes(self.data[self.pos..self.pos + 4].try_into().unwrap());
+        self.pos += 4;
+        v
+    }
+
+    fn u64(&mut self) -> u64 {
+        let v = u64::from_ne_bytes(self.data[self.pos..self.pos + 8].try_into().unwrap());
+        self.pos += 8;
+        v
+    }
+
+    fn region(&mut self) -> Bcm2835RegionStats {
+        Bcm2835RegionStats {
+            counted: self.u32(),
+            not_counted: self.u32(),
+            r_sum: self.u64(),
+            g_sum: self.u64(),
+            b_sum: self.u64(),
+        }
+    }
+}
@@ -0,0 +1,1046 @@
+use std::{
+    io,
+    sync::{mpsc, Arc},
+    time::Duration,
+};
+
+use thiserror::Error;
+
+#[cfg(feature = "awb")]
+use crate::{
+    awb::{AwbError, FrameStatistics, GreyWorldAwb},
+    controls::ColourGains,
+};
+use crate::{
+    camera::{ActiveCamera, Camera, CameraConfiguration},
+    control::{ControlEntry, ControlError, ControlList, ControlListSnapshot},
+    control_compare::Tolerance,
+    control_value::ControlValue,
+    controls::{FrameDuration, FrameDurationLimits, SensorTimestamp},
+    error::Error as CameraError,
+    framebuffer::AsFrameBuffer,
+    framebuffer_allocator::{FrameBuffer, FrameBufferAllocator},
+    framebuffer_map::{
+        FrameLayout, MapPool, MemoryMappedFrameBuffer, MemoryMappedFrameBufferError, PlaneValidationError,
+    },
+    geometry::Size,
+    pixel_format::PixelFormat,
+    request::{Request, ReuseFlag},
+    resume::clock_time,
+    rgb::{RgbConverter, RgbError, RgbImage},
+    stream::{Stream, StreamConfiguration, StreamConfigurationRef, StreamRole},
+};
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error(transparent)]
+    Camera(#[from] CameraError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Camera could not generate configuration for roles {0:?}")]
+    UnsupportedRoles(Vec<StreamRole>),
+    #[error("Stream role {0:?} is requested more than once")]
+    DuplicateRole(StreamRole),
+    #[error("Camera configuration is invalid")]
+    InvalidConfiguration,
+    #[error(transparent)]
+    Map(#[from] MemoryMappedFrameBufferError),
+    #[error("Timed out waiting for a completed request")]
+    Timeout,
+    #[error("Camera stopped delivering requests")]
+    Disconnected,
+    #[error("Camera does not support {0} control")]
+    UnsupportedControl(&'static str),
+    #[error(transparent)]
+    Control(#[from] ControlError),
+    #[error("Invalid frame of stream {role:?}: {error}")]
+    InvalidFrame {
+        role: StreamRole,
+        error: PlaneValidationError,
+    },
+    #[error(transparent)]
+    Decode(#[from] RgbError),
+    #[cfg(feature = "awb")]
+    #[error(transparent)]
+    Awb(#[from] AwbError),
+}
+
+/// A map of values associated with configured streams, keyed by their [StreamRole].
+///
+/// Each role appears at most once. Lookups are linear, which is faster than hashing for the handful of streams a
+/// camera supports.
+#[derive(Clone)]
+pub struct StreamMap<T> {
+    entries: Vec<(StreamRole, Stream, T)>,
+}
+
+impl<T> StreamMap<T> {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Inserts value for a stream, replacing and returning the previous value of the same role.
+    pub fn insert(&mut self, role: StreamRole, stream: Stream, value: T) -> Option<T> {
+        match self.entries.iter_mut().find(|(r, _, _)| *r == role) {
+            Some(entry) => Some(core::mem::replace(entry, (role, stream, value)).2),
+            None => {
+                self.entries.push((role, stream, value));
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, role: StreamRole) -> Option<&T> {
+        self.entries.iter().find(|(r, _, _)| *r == role).map(|(_, _, v)| v)
+    }
+
+    pub fn get_mut(&mut self, role: StreamRole) -> Option<&mut T> {
+        self.entries.iter_mut().find(|(r, _, _)| *r == role).map(|(_, _, v)| v)
+    }
+
+    /// Returns value associated with a stream handle, i.e. the one a completed buffer belongs to.
+    pub fn get_by_stream(&self, stream: &Stream) -> Option<&T> {
+        self.entries.iter().find(|(_, s, _)| s == stream).map(|(_, _, v)| v)
+    }
+
+    /// Returns stream handle of a role.
+    pub fn stream(&self, role: StreamRole) -> Option<Stream> {
+        self.entries.iter().find(|(r, _, _)| *r == role).map(|(_, s, _)| *s)
+    }
+
+    /// Returns role of a stream handle.
+    pub fn role(&self, stream: &Stream) -> Option<StreamRole> {
+        self.entries.iter().find(|(_, s, _)| s == stream).map(|(r, _, _)| *r)
+    }
+
+    pub fn contains(&self, role: StreamRole) -> bool {
+        self.get(role).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over `(role, stream, value)` in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (StreamRole, Stream, &T)> {
+        self.entries.iter().map(|(r, s, v)| (*r, *s, v))
+    }
+
+    /// Creates a map with the same streams and values computed by `f`.
+    pub fn map<U>(&self, mut f: impl FnMut(StreamRole, Stream, &T) -> U) -> StreamMap<U> {
+        StreamMap {
+            entries: self.entries.iter().map(|(r, s, v)| (*r, *s, f(*r, *s, v))).collect(),
+        }
+    }
+}
+
+impl<T> Default for StreamMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for StreamMap<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.entries.iter().map(|(r, _, v)| (r, v)))
+            .finish()
+    }
+}
+
+/// Operation retried by [RetryPolicy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryOperation {
+    Acquire,
+    Configure,
+    Start,
+}
+
+/// Reported to [CaptureSessionBuilder::on_retry()] before each retry.
+#[derive(Debug)]
+pub struct RetryEvent<'e> {
+    pub operation: RetryOperation,
+    /// Number of the failed attempt, starting from 1.
+    pub attempt: u32,
+    pub error: &'e CameraError,
+    /// Delay before the next attempt.
+    pub delay: Duration,
+}
+
+/// Retry policy for transient `EBUSY` errors, which media pipelines occasionally return right after another process
+/// released the camera.
+///
+/// Delay between attempts starts at `backoff` and is doubled after every attempt, up to `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub attempts: u32,
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Policy that fails on the first error.
+    pub const NONE: Self = Self {
+        attempts: 1,
+        backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+    };
+
+    pub fn new(attempts: u32, backoff: Duration) -> Self {
+        Self {
+            attempts,
+            backoff,
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+
+    fn is_transient(e: &CameraError) -> bool {
+        matches!(e, CameraError::Busy)
+    }
+
+    fn run<T>(
+        &self,
+        operation: RetryOperation,
+        on_retry: &mut Option<RetryFn<'_>>,
+        mut f: impl FnMut() -> Result<T, CameraError>,
+    ) -> Result<T, CameraError> {
+        let mut delay = self.backoff;
+        let mut attempt = 1;
+        loop {
+            match f() {
+                Err(error) if attempt < self.attempts && Self::is_transient(&error) => {
+                    if let Some(cb) = on_retry {
+                        cb(&RetryEvent {
+                            operation,
+                            attempt,
+                            error: &error,
+                            delay,
+                        });
+                    }
+                    std::thread::sleep(delay);
+                    delay = (delay * 2).min(self.max_backoff.max(self.backoff));
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+type StreamConfigureFn<'a> = Box<dyn FnMut(&mut StreamConfigurationRef<'_>) + 'a>;
+type RetryFn<'a> = Box<dyn FnMut(&RetryEvent<'_>) + 'a>;
+type FrameRateFn<'a> = Box<dyn FnMut(&FrameRateEvent) + 'a>;
+
+/// Number of frames the pipeline is given to reach a new frame rate range before it is considered not honored.
+const FRAME_RATE_SETTLE_FRAMES: u32 = 8;
+
+/// Notification about a frame rate range set with [CaptureSession::set_frame_rate_range()].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameRateEvent {
+    /// Requested range exceeds camera limits and was clamped.
+    Clamped { requested: (f64, f64), applied: (f64, f64) },
+    /// Frame rate stayed outside of the range after it was applied, i.e. because exposure time is longer than the
+    /// frame duration allows.
+    NotHonored { requested: (f64, f64), actual: f64 },
+}
+
+/// Frame rate range requested at runtime, see [CaptureSession::set_frame_rate_range()].
+#[derive(Debug, Default)]
+struct FrameRateState {
+    /// Requested `FrameDurationLimits` in microseconds.
+    limits: Option<[i64; 2]>,
+    /// Limits need to be set on the next queued request.
+    pending: bool,
+    /// Cookie of the request carrying the limits.
+    cookie: Option<u64>,
+    /// Frames left until the limits are considered not honored.
+    settle: Option<u32>,
+}
+
+fn fps_range(limits: [i64; 2]) -> (f64, f64) {
+    (1e6 / limits[1] as f64, 1e6 / limits[0] as f64)
+}
+
+/// Buffers owned by the pipeline at any time: one being filled and one queued, so that the next frame is not dropped.
+const PIPELINE_BUFFERS: u32 = 2;
+
+/// Returns number of buffers per stream needed to capture at `target_fps` without drops, while the application holds
+/// each completed request for `consumer_latency` before queueing it back.
+///
+/// Too few buffers starve the pipeline, which then drops frames whenever the application is slow. Extra buffers beyond
+/// the recommendation do not improve throughput, but completed frames wait longer in the queue if the application
+/// falls behind, which adds latency and memory use.
+pub fn recommended_buffer_count(target_fps: f64, consumer_latency: Duration) -> u32 {
+    // The application holds at least the request it is processing
+    let held = (consumer_latency.as_secs_f64() * target_fps.max(0.0)).ceil() as u32;
+    PIPELINE_BUFFERS + held.max(1)
+}
+
+/// Configures a [CaptureSession].
+pub struct CaptureSessionBuilder<'a> {
+    roles: Vec<StreamRole>,
+    buffer_count: Option<u32>,
+    configure: Vec<(StreamRole, StreamConfigureFn<'a>)>,
+    retry: RetryPolicy,
+    on_retry: Option<RetryFn<'a>>,
+    map_threads: Option<usize>,
+}
+
+impl<'a> CaptureSessionBuilder<'a> {
+    /// Creates builder for a session with one stream per role.
+    pub fn new(roles: &[StreamRole]) -> Self {
+        Self {
+            roles: roles.to_vec(),
+            buffer_count: None,
+            configure: Vec::new(),
+            retry: RetryPolicy::NONE,
+            on_retry: None,
+            map_threads: None,
+        }
+    }
+
+    /// Requests number of buffers for all streams, see [StreamConfigurationRef::set_buffer_count()].
+    ///
+    /// Pipeline defaults are used otherwise. [CaptureSessionBuilder::configure_stream()] may still override it per
+    /// stream.
+    pub fn buffer_count(mut self, count: u32) -> Self {
+        self.buffer_count = Some(count);
+        self
+    }
+
+    /// Picks number of buffers for all streams from a latency budget, see [recommended_buffer_count()].
+    pub fn latency_budget(self, target_fps: f64, consumer_latency: Duration) -> Self {
+        self.buffer_count(recommended_buffer_count(target_fps, consumer_latency))
+    }
+
+    /// Adjusts generated configuration of the stream with the given role before it is validated.
+    pub fn configure_stream(mut self, role: StreamRole, f: impl FnMut(&mut StreamConfigurationRef<'_>) + 'a) -> Self {
+        self.configure.push((role, Box::new(f)));
+        self
+    }
+
+    /// Retries acquire, configure and start (see [CaptureSession::start()]) on transient `EBUSY` errors.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Sets a callback, which is called before each retry.
+    pub fn on_retry(mut self, cb: impl FnMut(&RetryEvent<'_>) + 'a) -> Self {
+        self.on_retry = Some(Box::new(cb));
+        self
+    }
+
+    /// Memory maps buffers on a [MapPool] with the given number of threads instead of the building thread.
+    ///
+    /// Buffers are mapped in parallel when the session is built and unmapped by the pool when it is dropped, so that
+    /// neither blocks the thread handling completed requests. Useful for large RAW buffers.
+    pub fn map_threads(mut self, threads: usize) -> Self {
+        self.map_threads = Some(threads);
+        self
+    }
+
+    /// Acquires the camera, applies configuration and allocates buffers for all streams.
+    ///
+    /// One request is created for each buffer set, limited by the stream with the lowest buffer count. Fails with
+    /// [SessionError::DuplicateRole] if a role is given more than once, as streams are keyed by their role.
+    pub fn build<'d>(mut self, cam: &'d Camera<'_>) -> Result<CaptureSession<'d>, SessionError>
+    where
+        'a: 'd,
+    {
+        let duplicate = (1..self.roles.len()).find(|i| self.roles[..*i].contains(&self.roles[*i]));
+        if let Some(index) = duplicate {
+            return Err(SessionError::DuplicateRole(self.roles[index]));
+        }
+        let mut active = self
+            .retry
+            .run(RetryOperation::Acquire, &mut self.on_retry, || cam.acquire())?;
+
+        let mut config = active
+            .generate_configuration(&self.roles)
+            .ok_or_else(|| SessionError::UnsupportedRoles(self.roles.clone()))?;
+        if let Some(count) = self.buffer_count {
+            for index in 0..config.len() {
+                if let Some(mut cfg) = config.get_mut(index) {
+                    cfg.set_buffer_count(count);
+                }
+            }
+        }
+        for (role, f) in self.configure.iter_mut() {
+            if let Some(index) = self.roles.iter().position(|r| r == role) {
+                if let Some(mut cfg) = config.get_mut(index) {
+                    f(&mut cfg);
+                }
+            }
+        }
+        if config.validate().is_invalid() {
+            return Err(SessionError::InvalidConfiguration);
+        }
+        self.retry.run(RetryOperation::Configure, &mut self.on_retry, || {
+            active.configure(&mut config)
+        })?;
+
+        let mut streams = StreamMap::new();
+        for (index, role) in self.roles.iter().enumerate() {
+            let stream = config
+                .get(index)
+                .and_then(|cfg| cfg.stream())
+                .ok_or(SessionError::InvalidConfiguration)?;
+            streams.insert(*role, stream, index);
+        }
+
+        let map_pool = self.map_threads.map(MapPool::new).transpose()?;
+        let mut alloc = FrameBufferAllocator::new(&active);
+        let mut buffers = Vec::new();
+        for (_, stream, _) in streams.iter() {
+            let allocated = alloc.alloc(&stream)?;
+            buffers.push(match &map_pool {
+                Some(pool) => pool.map_all(allocated)?,
+                None => allocated
+                    .into_iter()
+                    .map(MemoryMappedFrameBuffer::new)
+                    .collect::<Result<Vec<_>, _>>()?,
+            });
+        }
+
+        let count = buffers.iter().map(|b| b.len()).min().unwrap_or(0);
+        let mut buffers = buffers.into_iter().map(|b| b.into_iter()).collect::<Vec<_>>();
+        let mut idle = Vec::with_capacity(count);
+        for cookie in 0..count {
+            let mut req = active
+                .create_request(Some(cookie as u64))
+                .ok_or_else(|| io::Error::from_raw_os_error(libc::ENOMEM))?;
+            for ((_, stream, _), buffers) in streams.iter().zip(buffers.iter_mut()) {
+                req.add_buffer(&stream, buffers.next().unwrap())?;
+            }
+            idle.push(req);
+        }
+
+        let (tx, rx) = mpsc::channel();
+        active.on_request_completed(move |req| {
+            let _ = tx.send(req);
+        });
+
+        Ok(CaptureSession {
+            cam: active,
+            config,
+            streams: Arc::new(streams),
+            _alloc: alloc,
+            idle,
+            rx,
+            retry: self.retry,
+            on_retry: self.on_retry,
+            template: None,
+            frame_rate: FrameRateState::default(),
+            on_frame_rate: None,
+            #[cfg(feature = "awb")]
+            awb: None,
+            _map_pool: map_pool,
+        })
+    }
+}
+
+/// A configured camera with allocated buffers, which hands out completed requests by their stream roles.
+///
+/// Created by [CaptureSessionBuilder].
+pub struct CaptureSession<'d> {
+    cam: ActiveCamera<'d>,
+    config: CameraConfiguration,
+    streams: Arc<StreamMap<usize>>,
+    _alloc: FrameBufferAllocator,
+    /// Requests which are not queued, i.e. before [CaptureSession::start()].
+    idle: Vec<Request>,
+    rx: mpsc::Receiver<Request>,
+    retry: RetryPolicy,
+    on_retry: Option<RetryFn<'d>>,
+    /// Controls set on every queued request.
+    template: Option<ControlListSnapshot>,
+    frame_rate: FrameRateState,
+    on_frame_rate: Option<FrameRateFn<'d>>,
+    /// Software AWB and the stream role it gathers statistics from.
+    #[cfg(feature = "awb")]
+    awb: Option<(StreamRole, GreyWorldAwb)>,
+    /// Pool unmapping the buffers, see [CaptureSessionBuilder::map_threads()].
+    _map_pool: Option<MapPool>,
+}
+
+impl<'d> CaptureSession<'d> {
+    pub fn camera(&self) -> &ActiveCamera<'d> {
+        &self.cam
+    }
+
+    pub fn camera_mut(&mut self) -> &mut ActiveCamera<'d> {
+        &mut self.cam
+    }
+
+    /// Returns applied camera configuration.
+    pub fn config(&self) -> &CameraConfiguration {
+        &self.config
+    }
+
+    /// Returns configured streams with their index within [CaptureSession::config()].
+    pub fn streams(&self) -> &StreamMap<usize> {
+        &self.streams
+    }
+
+    /// Starts the camera and queues all idle requests.
+    ///
+    /// Requests that could not be queued stay idle and are queued again on the next start.
+    pub fn start(&mut self, controls: Option<&ControlList>) -> Result<(), SessionError> {
+        let cam = &mut self.cam;
+        self.retry
+            .run(RetryOperation::Start, &mut self.on_retry, || cam.start(controls))?;
+        while let Some(req) = self.idle.pop() {
+            self.queue_idle(req)?;
+        }
+        Ok(())
+    }
+
+    /// Stops the camera. Requests that were not completed become idle and are queued again on the next start.
+    pub fn stop(&mut self) -> Result<(), SessionError> {
+        self.cam.stop()?;
+        while let Ok(mut req) = self.rx.try_recv() {
+            req.reuse(ReuseFlag::REUSE_BUFFERS);
+            self.idle.push(req);
+        }
+        Ok(())
+    }
+
+    /// Sets controls applied to every request queued from now on, or clears them with [None].
+    ///
+    /// Frame rate range set with [CaptureSession::set_frame_rate_range()] takes precedence over the template.
+    pub fn set_request_template(&mut self, template: Option<ControlListSnapshot>) {
+        self.template = template;
+    }
+
+    pub fn request_template(&self) -> Option<&ControlListSnapshot> {
+        self.template.as_ref()
+    }
+
+    /// Limits frame rate to a range in frames per second, i.e. to lower power usage on thermal throttling.
+    ///
+    /// Range is clamped to the camera limits and set as `FrameDurationLimits` on the next queued request, so it
+    /// takes effect within a few frames without reconfiguration. Outcome is reported to the
+    /// [CaptureSession::on_frame_rate()] callback.
+    pub fn set_frame_rate_range(&mut self, min_fps: f64, max_fps: f64) -> Result<(), SessionError> {
+        if !(min_fps > 0.0 && min_fps <= max_fps) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid frame rate range").into());
+        }
+        let info = self
+            .cam
+            .controls()
+            .get(FrameDurationLimits::ID)
+            .ok_or(SessionError::UnsupportedControl("FrameDurationLimits"))?;
+
+        let requested = [(1e6 / max_fps) as i64, (1e6 / min_fps) as i64];
+        let value = ControlValue::from(FrameDurationLimits(requested));
+        let limits = match info.clamp(&value) {
+            Some(clamped) => {
+                let limits = *FrameDurationLimits::try_from(clamped).map_err(|_| SessionError::InvalidConfiguration)?;
+                self.notify_frame_rate(FrameRateEvent::Clamped {
+                    requested: (min_fps, max_fps),
+                    applied: fps_range(limits),
+                });
+                limits
+            }
+            None => requested,
+        };
+
+        self.frame_rate = FrameRateState {
+            limits: Some(limits),
+            pending: true,
+            ..Default::default()
+        };
+        Ok(())
+    }
+
+    /// Returns frame rate range set with [CaptureSession::set_frame_rate_range()] after clamping.
+    pub fn frame_rate_range(&self) -> Option<(f64, f64)> {
+        self.frame_rate.limits.map(fps_range)
+    }
+
+    /// Sets a callback for [FrameRateEvent]s.
+    pub fn on_frame_rate(&mut self, cb: impl FnMut(&FrameRateEvent) + 'd) {
+        self.on_frame_rate = Some(Box::new(cb));
+    }
+
+    fn notify_frame_rate(&mut self, event: FrameRateEvent) {
+        if let Some(cb) = self.on_frame_rate.as_mut() {
+            cb(&event);
+        }
+    }
+
+    /// Runs software AWB on frames of `role` and sets its gains on every request queued from now on.
+    ///
+    /// Meant for pipelines without an AWB algorithm, see [awb](crate::awb). Gains are updated from every successfully
+    /// captured frame returned by [CaptureSession::next_request()] and take precedence over `ColourGains` of the
+    /// request template.
+    #[cfg(feature = "awb")]
+    pub fn enable_software_awb(&mut self, role: StreamRole, awb: GreyWorldAwb) -> Result<(), SessionError> {
+        let cfg = self
+            .streams
+            .get(role)
+            .and_then(|i| self.config.get(*i))
+            .ok_or_else(|| SessionError::UnsupportedRoles(vec![role]))?;
+        if !FrameStatistics::is_supported(cfg.get_pixel_format()) {
+            return Err(AwbError::UnsupportedFormat(cfg.get_pixel_format()).into());
+        }
+        if self.cam.controls().get(ColourGains::ID).is_none() {
+            return Err(SessionError::UnsupportedControl("ColourGains"));
+        }
+        self.awb = Some((role, awb));
+        Ok(())
+    }
+
+    /// Stops software AWB and returns it. Gains already set on queued requests stay in effect.
+    #[cfg(feature = "awb")]
+    pub fn disable_software_awb(&mut self) -> Option<GreyWorldAwb> {
+        self.awb.take().map(|(_, awb)| awb)
+    }
+
+    /// Returns software AWB enabled with [CaptureSession::enable_software_awb()].
+    #[cfg(feature = "awb")]
+    pub fn software_awb(&self) -> Option<&GreyWorldAwb> {
+        self.awb.as_ref().map(|(_, awb)| awb)
+    }
+
+    /// Sets request template, software AWB gains and pending frame rate controls on a request about to be queued.
+    fn apply_controls(&mut self, req: &mut Request) -> Result<(), SessionError> {
+        if let Some(template) = self.template.as_ref() {
+            template.apply_to(req.controls_mut())?;
+        }
+        #[cfg(feature = "awb")]
+        if let Some((_, awb)) = self.awb.as_ref() {
+            awb.apply(req.controls_mut())?;
+        }
+        self.apply_frame_rate(req)
+    }
+
+    /// Updates software AWB from the frame of its stream role, if it was captured successfully.
+    #[cfg(feature = "awb")]
+    fn update_awb(&mut self, req: &Request) -> Result<(), SessionError> {
+        let Some((role, awb)) = self.awb.as_mut() else {
+            return Ok(());
+        };
+        let (Some(stream), Some(cfg)) = (
+            self.streams.stream(*role),
+            self.streams.get(*role).and_then(|i| self.config.get(*i)),
+        ) else {
+            return Ok(());
+        };
+        let Some(fb) = req
+            .buffer::<MemoryMappedFrameBuffer<FrameBuffer>>(&stream)
+            .filter(|fb| fb.metadata().is_some_and(|m| m.is_success()))
+        else {
+            return Ok(());
+        };
+        match awb.process_frame(
+            &fb.data_used(),
+            cfg.get_pixel_format(),
+            cfg.get_size(),
+            cfg.get_stride(),
+        ) {
+            // gains are kept for frames without colour information, i.e. when the lens is covered
+            Ok(_) | Err(AwbError::NoValidPixels) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn apply_frame_rate(&mut self, req: &mut Request) -> Result<(), SessionError> {
+        if let (true, Some(limits)) = (self.frame_rate.pending, self.frame_rate.limits) {
+            req.controls_mut()
+                .set(FrameDurationLimits(limits))
+                .map_err(|_| SessionError::UnsupportedControl("FrameDurationLimits"))?;
+            self.frame_rate.pending = false;
+            self.frame_rate.cookie = Some(req.cookie());
+        }
+        Ok(())
+    }
+
+    fn check_frame_rate(&mut self, req: &Request) {
+        let Some(limits) = self.frame_rate.limits else {
+            return;
+        };
+        if self.frame_rate.cookie.is_some_and(|c| c == req.cookie()) {
+            self.frame_rate.cookie = None;
+            self.frame_rate.settle = Some(FRAME_RATE_SETTLE_FRAMES);
+        }
+        let Some(settle) = self.frame_rate.settle else {
+            return;
+        };
+        let Ok(duration) = req.metadata().get::<FrameDuration>() else {
+            return;
+        };
+
+        // Allow 1% error, as durations are quantized to sensor lines
+        let tolerance = Tolerance::relative(0.01);
+        let within = (*duration >= limits[0] || tolerance.eq_f64(*duration as f64, limits[0] as f64))
+            && (*duration <= limits[1] || tolerance.eq_f64(*duration as f64, limits[1] as f64));
+        if within {
+            self.frame_rate.settle = None;
+        } else if settle > 1 {
+            self.frame_rate.settle = Some(settle - 1);
+        } else {
+            self.frame_rate.settle = None;
+            self.notify_frame_rate(FrameRateEvent::NotHonored {
+                requested: fps_range(limits),
+                actual: 1e6 / *duration as f64,
+            });
+        }
+    }
+
+    /// Waits for the next completed request.
+    pub fn next_request(&mut self, timeout: Duration) -> Result<CompletedRequest, SessionError> {
+        let request = self.rx.recv_timeout(timeout).map_err(|e| match e {
+            mpsc::RecvTimeoutError::Timeout => SessionError::Timeout,
+            mpsc::RecvTimeoutError::Disconnected => SessionError::Disconnected,
+        })?;
+
+        self.check_frame_rate(&request);
+
+        let completed = CompletedRequest {
+            request,
+            streams: self.streams.clone(),
+        };
+        if let Err(e) = self.process(&completed) {
+            self.queue(completed)?;
+            return Err(e);
+        }
+        Ok(completed)
+    }
+
+    /// Waits for a completed request like [CaptureSession::next_request()], but returns only the most recent one.
+    ///
+    /// Requests that completed earlier and were not consumed yet are queued again right away, so consumers that only
+    /// ever want the newest frame (i.e. UI previews) do not fall behind after a processing hiccup.
+    pub fn latest_frame(&mut self, timeout: Duration) -> Result<LatestFrame, SessionError> {
+        let mut request = self.next_request(timeout)?;
+        let mut skipped = 0;
+        while let Ok(newer) = self.rx.try_recv() {
+            self.check_frame_rate(&newer);
+            let older = std::mem::replace(
+                &mut request,
+                CompletedRequest {
+                    request: newer,
+                    streams: self.streams.clone(),
+                },
+            );
+            self.queue(older)?;
+            skipped += 1;
+        }
+        if skipped > 0 {
+            if let Err(e) = self.process(&request) {
+                self.queue(request)?;
+                return Err(e);
+            }
+        }
+        Ok(LatestFrame {
+            age: request.age(),
+            request,
+            skipped,
+        })
+    }
+
+    /// Updates software AWB and validates frames of a request about to be handed out.
+    fn process(&mut self, completed: &CompletedRequest) -> Result<(), SessionError> {
+        #[cfg(feature = "awb")]
+        self.update_awb(&completed.request)?;
+        self.validate(completed)
+    }
+
+    /// Validates plane sizes of successfully captured frames, see [MemoryMappedFrameBuffer::validate()].
+    ///
+    /// Called by [CaptureSession::next_request()], which queues the request again and returns the error, so layout
+    /// mismatches fail early instead of producing corrupted images.
+    pub fn validate(&self, completed: &CompletedRequest) -> Result<(), SessionError> {
+        for frame in completed.frames() {
+            if !frame.buffer().metadata().is_some_and(|m| m.is_success()) {
+                continue;
+            }
+            let Some(cfg) = self.streams.get(frame.stream_role()).and_then(|i| self.config.get(*i)) else {
+                continue;
+            };
+            frame
+                .buffer()
+                .validate(&FrameLayout::from_stream_config(&cfg))
+                .map_err(|error| SessionError::InvalidFrame {
+                    role: frame.stream_role(),
+                    error,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Captures a single frame of a stream role and converts it to RGB.
+    ///
+    /// Starts the camera if it is not running and discards the first `warmup_frames` frames, which gives automatic
+    /// exposure and white balance time to converge. The request is queued again afterwards, so the session can keep
+    /// capturing.
+    pub fn capture_still(
+        &mut self,
+        role: StreamRole,
+        warmup_frames: u32,
+        timeout: Duration,
+    ) -> Result<StillFrame, SessionError> {
+        let config = self
+            .streams
+            .get(role)
+            .and_then(|i| self.config.get(*i))
+            .map(|cfg| cfg.snapshot())
+            .ok_or_else(|| SessionError::UnsupportedRoles(vec![role]))?;
+        let converter = RgbConverter::new(config.pixel_format, config.size, config.stride);
+
+        if !self.cam.is_running() {
+            self.start(None)?;
+        }
+
+        let mut skipped = 0;
+        loop {
+            let completed = self.next_request(timeout)?;
+            let frame = completed
+                .frame(role)
+                .filter(|f| f.buffer().metadata().is_some_and(|m| m.is_success()));
+            let Some(frame) = frame.filter(|_| skipped >= warmup_frames) else {
+                skipped += 1;
+                self.queue(completed)?;
+                continue;
+            };
+
+            let image = converter.convert_buffer(frame.buffer());
+            let metadata = completed.metadata_snapshot();
+            self.queue(completed)?;
+            return Ok(StillFrame {
+                image: image?,
+                metadata: metadata?,
+                config,
+            });
+        }
+    }
+
+    /// Reuses completed request with the same buffers and queues it again.
+    ///
+    /// A request that could not be queued becomes idle and is queued again on the next start.
+    pub fn queue(&mut self, completed: CompletedRequest) -> Result<(), SessionError> {
+        let mut req = completed.request;
+        req.reuse(ReuseFlag::REUSE_BUFFERS);
+        if self.cam.is_running() {
+            self.queue_idle(req)?;
+        } else {
+            self.idle.push(req);
+        }
+        Ok(())
+    }
+
+    /// Applies controls and queues a request, keeping it idle on failure.
+    fn queue_idle(&mut self, mut req: Request) -> Result<(), SessionError> {
+        if let Err(e) = self.apply_controls(&mut req) {
+            self.idle.push(req);
+            return Err(e);
+        }
+        self.cam.try_queue_request(req).map_err(|(e, req)| {
+            self.idle.push(*req);
+            e.into()
+        })
+    }
+}
+
+/// A request completed by [CaptureSession], with its buffers accessible by stream role.
+pub struct CompletedRequest {
+    request: Request,
+    streams: Arc<StreamMap<usize>>,
+}
+
+impl CompletedRequest {
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+
+    /// Returns request metadata, see [Request::metadata()].
+    pub fn metadata(&self) -> &ControlList {
+        self.request.metadata()
+    }
+
+    /// Returns the frame of a stream role, if it was configured.
+    pub fn frame(&self, role: StreamRole) -> Option<Frame<'_>> {
+        let stream = self.streams.stream(role)?;
+        Some(Frame {
+            role,
+            stream,
+            buffer: self.request.buffer(&stream)?,
+        })
+    }
+
+    /// Iterates over frames of all configured streams.
+    pub fn frames(&self) -> impl Iterator<Item = Frame<'_>> {
+        self.streams.iter().filter_map(|(role, stream, _)| {
+            Some(Frame {
+                role,
+                stream,
+                buffer: self.request.buffer(&stream)?,
+            })
+        })
+    }
+
+    /// Returns an owned copy of request metadata, which can be sent to other threads, see [ControlList::snapshot()].
+    pub fn metadata_snapshot(&self) -> Result<ControlListSnapshot, ControlError> {
+        self.request.metadata().snapshot()
+    }
+
+    pub fn into_request(self) -> Request {
+        self.request
+    }
+
+    /// Returns time elapsed since the start of exposure, based on [SensorTimestamp] metadata.
+    ///
+    /// Returns [None] if the pipeline does not report sensor timestamps.
+    pub fn age(&self) -> Option<Duration> {
+        let timestamp = *self.metadata().get::<SensorTimestamp>().ok()?;
+        let now = clock_time(libc::CLOCK_MONOTONIC).ok()?;
+        Some(now.saturating_sub(Duration::from_nanos(timestamp.max(0) as u64)))
+    }
+}
+
+/// The most recent completed request, see [CaptureSession::latest_frame()].
+pub struct LatestFrame {
+    pub request: CompletedRequest,
+    /// Age of the frame, see [CompletedRequest::age()].
+    pub age: Option<Duration>,
+    /// Number of older completed requests that were queued again without being returned.
+    pub skipped: u32,
+}
+
+/// A buffer of a single stream within [CompletedRequest].
+pub struct Frame<'a> {
+    role: StreamRole,
+    stream: Stream,
+    buffer: &'a MemoryMappedFrameBuffer<FrameBuffer>,
+}
+
+impl<'a> Frame<'a> {
+    /// Role of the stream this frame belongs to.
+    pub fn stream_role(&self) -> StreamRole {
+        self.role
+    }
+
+    pub fn stream(&self) -> Stream {
+        self.stream
+    }
+
+    pub fn buffer(&self) -> &'a MemoryMappedFrameBuffer<FrameBuffer> {
+        self.buffer
+    }
+}
+
+/// A frame captured by [capture_still()] or [CaptureSession::capture_still()].
+#[derive(Debug, Clone)]
+pub struct StillFrame {
+    pub image: RgbImage,
+    /// Metadata of the request which captured the frame.
+    pub metadata: ControlListSnapshot,
+    /// Configuration of the stream which produced the frame.
+    pub config: StreamConfiguration,
+}
+
+/// Options for [capture_still()].
+#[derive(Debug, Clone)]
+pub struct StillOptions {
+    size: Option<Size>,
+    pixel_format: Option<PixelFormat>,
+    controls: Option<ControlListSnapshot>,
+    warmup_frames: u32,
+    timeout: Duration,
+    retry: RetryPolicy,
+}
+
+impl StillOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests frame size. Pipeline adjusts it to the closest supported size.
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Requests pixel format instead of the pipeline default, i.e. to avoid a conversion [RgbConverter] does not
+    /// support.
+    pub fn pixel_format(mut self, pixel_format: PixelFormat) -> Self {
+        self.pixel_format = Some(pixel_format);
+        self
+    }
+
+    /// Sets controls applied to every request, i.e. a fixed exposure time.
+    pub fn controls(mut self, controls: ControlListSnapshot) -> Self {
+        self.controls = Some(controls);
+        self
+    }
+
+    /// Sets number of frames discarded before the captured one. Defaults to 5.
+    pub fn warmup_frames(mut self, frames: u32) -> Self {
+        self.warmup_frames = frames;
+        self
+    }
+
+    /// Sets the maximum time to wait for each frame. Defaults to 5 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retries acquire, configure and start on transient `EBUSY` errors, see [CaptureSessionBuilder::retry()].
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+}
+
+impl Default for StillOptions {
+    fn default() -> Self {
+        Self {
+            size: None,
+            pixel_format: None,
+            controls: None,
+            warmup_frames: 5,
+            timeout: Duration::from_secs(5),
+            retry: RetryPolicy::NONE,
+        }
+    }
+}
+
+/// Captures a single still frame, converted to RGB, together with its metadata.
+///
+/// Acquires the camera, configures a [StreamRole::StillCapture] stream, allocates buffers, captures a frame after
+/// the warmup frames and releases the camera again. Use [CaptureSession] to capture more than one frame without
+/// reconfiguring the camera every time.
+pub fn capture_still(cam: &Camera<'_>, options: &StillOptions) -> Result<StillFrame, SessionError> {
+    let role = StreamRole::StillCapture;
+    let (size, pixel_format) = (options.size, options.pixel_format);
+    let mut session = CaptureSessionBuilder::new(&[role])
+        .configure_stream(role, move |cfg| {
+            if let Some(size) = size {
+                cfg.set_size(size);
+            }
+            if let Some(pixel_format) = pixel_format {
+                cfg.set_pixel_format(pixel_format);
+            }
+        })
+        .retry(options.retry)
+        .build(cam)?;
+    session.set_request_template(options.controls.clone());
+
+    let still = session.capture_still(role, options.warmup_frames, options.timeout)?;
+    session.stop()?;
+    Ok(still)
+}
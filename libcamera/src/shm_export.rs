@@ -0,0 +1,258 @@
+//! Export of frames into a shared memory ring, enabled by the `shm-export` feature.
+//!
+//! Frames are copied into a sealed memfd, which other processes map read-only after receiving its file descriptor
+//! (i.e. over a unix socket or as `/proc/<pid>/fd/<fd>`), so they can consume frames without linking libcamera.
+//!
+//! All fields are little-endian. The ring starts with a header of [RING_HEADER_SIZE] bytes:
+//!
+//! | Offset | Type      | Field                                             |
+//! |--------|-----------|---------------------------------------------------|
+//! | 0      | `[u8; 8]` | magic, [RING_MAGIC]                               |
+//! | 8      | `u32`     | version, [RING_VERSION]                           |
+//! | 12     | `u32`     | number of slots                                   |
+//! | 16     | `u64`     | slot size in bytes, including the slot header     |
+//! | 24     | `u64`     | number of frames written, latest is in slot `(n - 1) % slots` |
+//!
+//! Followed by slots, each starting with a header of [SLOT_HEADER_SIZE] bytes and frame data right after it:
+//!
+//! | Offset | Type       | Field                                              |
+//! |--------|------------|----------------------------------------------------|
+//! | 0      | `u64`      | seqlock, odd while the slot is being written       |
+//! | 8      | `u32`      | frame sequence                                     |
+//! | 12     | `u32`      | pixel format fourcc                                |
+//! | 16     | `u64`      | pixel format modifier                              |
+//! | 24     | `u32`      | width                                              |
+//! | 28     | `u32`      | height                                             |
+//! | 32     | `u32`      | stride of the first plane                          |
+//! | 36     | `u32`      | number of planes                                   |
+//! | 40     | `u64`      | timestamp in nanoseconds                           |
+//! | 48     | `u64`      | length of frame data                               |
+//! | 56     | `[u32; 4]` | length of each plane, planes are stored back to back |
+//!
+//! Readers copy a slot and discard the copy if the seqlock changed or was odd, as the writer never waits for them.
+
+use std::{
+    ffi::CString,
+    io,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd},
+    ptr::NonNull,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    framebuffer::AsFrameBuffer, framebuffer_map::MemoryMappedFrameBuffer, geometry::Size, pixel_format::PixelFormat,
+    stream::StreamConfigurationRef,
+};
+
+pub const RING_MAGIC: [u8; 8] = *b"LCAMRING";
+pub const RING_VERSION: u32 = 1;
+pub const RING_HEADER_SIZE: usize = 64;
+pub const SLOT_HEADER_SIZE: usize = 128;
+/// Maximum number of planes described by a slot header.
+pub const MAX_PLANES: usize = 4;
+
+/// Format and timing of a frame written into [ShmRing].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShmFrameInfo {
+    pub sequence: u32,
+    pub pixel_format: PixelFormat,
+    pub size: Size,
+    pub stride: u32,
+    /// Capture time in nanoseconds.
+    pub timestamp: u64,
+}
+
+impl ShmFrameInfo {
+    /// Takes format of the stream, sequence and timestamp are zero until set.
+    pub fn from_stream_config(cfg: &StreamConfigurationRef<'_>) -> Self {
+        Self {
+            sequence: 0,
+            pixel_format: cfg.get_pixel_format(),
+            size: cfg.get_size(),
+            stride: cfg.get_stride(),
+            timestamp: 0,
+        }
+    }
+}
+
+/// Single-writer ring of frames in a sealed memfd, see [module documentation](self) for the layout.
+pub struct ShmRing {
+    fd: OwnedFd,
+    ptr: NonNull<u8>,
+    len: usize,
+    slot_count: usize,
+    slot_size: usize,
+    written: u64,
+}
+
+unsafe impl Send for ShmRing {}
+
+impl ShmRing {
+    /// Creates a ring of `slot_count` slots, each holding up to `max_frame_size` bytes of frame data.
+    ///
+    /// `name` is only used for debugging, i.e. it shows up in `/proc/<pid>/fd`.
+    pub fn new(name: &str, slot_count: usize, max_frame_size: usize) -> io::Result<Self> {
+        if slot_count == 0 || slot_count > u32::MAX as usize {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid slot count"));
+        }
+        let slot_size = (SLOT_HEADER_SIZE + max_frame_size).next_multiple_of(64);
+        let len = slot_size
+            .checked_mul(slot_count)
+            .and_then(|l| l.checked_add(RING_HEADER_SIZE))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "ring size overflows"))?;
+
+        let name = CString::new(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let fd = unsafe { libc::memfd_create(name.as_ptr(), libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        if unsafe { libc::ftruncate(fd.as_raw_fd(), len as _) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Readers can rely on the size, so they do not have to guard against SIGBUS
+        let seals = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_SEAL;
+        if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_ADD_SEALS, seals) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        let ring = Self {
+            fd,
+            ptr: NonNull::new(ptr.cast()).unwrap(),
+            len,
+            slot_count,
+            slot_size,
+            written: 0,
+        };
+        ring.write_bytes(0, &RING_MAGIC);
+        ring.write_bytes(8, &RING_VERSION.to_le_bytes());
+        ring.write_bytes(12, &(slot_count as u32).to_le_bytes());
+        ring.write_bytes(16, &(slot_size as u64).to_le_bytes());
+        Ok(ring)
+    }
+
+    /// Returns total size of the ring in bytes, which readers map.
+    pub fn size(&self) -> usize {
+        self.len
+    }
+
+    pub fn slot_count(&self) -> usize {
+        self.slot_count
+    }
+
+    /// Returns maximum number of frame data bytes per slot.
+    pub fn max_frame_size(&self) -> usize {
+        self.slot_size - SLOT_HEADER_SIZE
+    }
+
+    /// Returns number of frames written so far.
+    pub fn frames_written(&self) -> u64 {
+        self.written
+    }
+
+    /// Writes bytes used by each plane of a completed frame, taking sequence and timestamp from framebuffer metadata.
+    ///
+    /// Returns the number of frame bytes written.
+    pub fn write_frame<T: AsFrameBuffer>(
+        &mut self,
+        fb: &MemoryMappedFrameBuffer<T>,
+        info: &ShmFrameInfo,
+    ) -> io::Result<usize> {
+        let mut info = *info;
+        if let Some(metadata) = fb.metadata() {
+            info.sequence = metadata.sequence();
+            info.timestamp = metadata.timestamp();
+        }
+        let _sync = fb.sync()?;
+        self.write_planes(&fb.data_used(), &info)
+    }
+
+    /// Writes a frame given as plane data into the next slot, overwriting the oldest frame.
+    ///
+    /// Fails with [io::ErrorKind::InvalidInput] if the frame does not fit into a slot or has more than [MAX_PLANES]
+    /// planes.
+    pub fn write_planes(&mut self, planes: &[&[u8]], info: &ShmFrameInfo) -> io::Result<usize> {
+        let data_len = planes.iter().map(|p| p.len()).sum::<usize>();
+        if planes.len() > MAX_PLANES || data_len > self.max_frame_size() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "frame of {data_len} bytes in {} planes does not fit into a slot of {} bytes",
+                    planes.len(),
+                    self.max_frame_size()
+                ),
+            ));
+        }
+
+        let slot = RING_HEADER_SIZE + (self.written % self.slot_count as u64) as usize * self.slot_size;
+        let seqlock = self.atomic(slot);
+        let seq = seqlock.load(Ordering::Relaxed);
+        seqlock.store(seq + 1, Ordering::Relaxed);
+        core::sync::atomic::fence(Ordering::Release);
+
+        let mut header = [0u8; SLOT_HEADER_SIZE - 8];
+        let mut put = |offset: usize, bytes: &[u8]| header[offset - 8..offset - 8 + bytes.len()].copy_from_slice(bytes);
+        put(8, &info.sequence.to_le_bytes());
+        put(12, &info.pixel_format.fourcc().to_le_bytes());
+        put(16, &info.pixel_format.modifier().to_le_bytes());
+        put(24, &info.size.width.to_le_bytes());
+        put(28, &info.size.height.to_le_bytes());
+        put(32, &info.stride.to_le_bytes());
+        put(36, &(planes.len() as u32).to_le_bytes());
+        put(40, &info.timestamp.to_le_bytes());
+        put(48, &(data_len as u64).to_le_bytes());
+        for (i, plane) in planes.iter().enumerate() {
+            put(56 + i * 4, &(plane.len() as u32).to_le_bytes());
+        }
+        self.write_bytes(slot + 8, &header);
+
+        let mut offset = slot + SLOT_HEADER_SIZE;
+        for plane in planes {
+            self.write_bytes(offset, plane);
+            offset += plane.len();
+        }
+
+        seqlock.store(seq + 2, Ordering::Release);
+        self.written += 1;
+        self.atomic(24).store(self.written, Ordering::Release);
+        Ok(data_len)
+    }
+
+    fn write_bytes(&self, offset: usize, bytes: &[u8]) {
+        assert!(offset + bytes.len() <= self.len);
+        unsafe { core::ptr::copy_nonoverlapping(bytes.as_ptr(), self.ptr.as_ptr().add(offset), bytes.len()) }
+    }
+
+    /// Returns an atomic over a `u64` field, which is little-endian on all platforms supported by libcamera.
+    fn atomic(&self, offset: usize) -> &AtomicU64 {
+        assert!(offset.is_multiple_of(8) && offset + 8 <= self.len);
+        unsafe { &*self.ptr.as_ptr().add(offset).cast::<AtomicU64>() }
+    }
+}
+
+impl AsFd for ShmRing {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl Drop for ShmRing {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr.as_ptr().cast(), self.len) };
+    }
+}
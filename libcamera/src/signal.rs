@@ -0,0 +1,90 @@
+//! Subscriptions to libcamera signals.
+//!
+//! libcamera notifies about events (completed requests and buffers, camera hotplug) through signals, which the C API
+//! exposes as `*_connect()` functions taking a callback with an opaque context pointer and returning a handle for the
+//! matching `*_disconnect()`. [SignalSubscription] owns both the context and the handle and disconnects the signal when
+//! dropped, so a callback is never invoked after it was freed.
+
+use std::{ffi::c_void, ptr::NonNull};
+
+use libcamera_sys::*;
+
+/// Disconnects a signal handler, given the handle returned by the `*_connect()` function.
+type Disconnect<'d> = Box<dyn FnOnce(NonNull<libcamera_callback_handle_t>) + 'd>;
+
+/// A connected libcamera signal, which is disconnected on drop.
+///
+/// `T` is the callback type invoked on each signal emission, i.e. `dyn FnMut(Camera<'_>) + Send` for
+/// [CameraManager::on_camera_added()](crate::camera_manager::CameraManager::on_camera_added). Callbacks are executed in
+/// the libcamera thread emitting the signal.
+#[must_use = "signal is disconnected when the subscription is dropped"]
+pub struct SignalSubscription<'d, T: ?Sized + 'd> {
+    handle: Option<NonNull<libcamera_callback_handle_t>>,
+    disconnect: Option<Disconnect<'d>>,
+    /// Callback context, double boxed so that a thin pointer to it can be passed through the C API.
+    _callback: Box<Box<T>>,
+}
+
+impl<'d, T: ?Sized + 'd> SignalSubscription<'d, T> {
+    /// Connects `callback` to a signal.
+    ///
+    /// `connect` receives the context pointer to pass to the C API and returns the connection handle. `disconnect` is
+    /// called with the handle exactly once, when the subscription is dropped or [SignalSubscription::disconnect()] is
+    /// called.
+    ///
+    /// # Safety
+    ///
+    /// The C callback must only access the context using [SignalSubscription::callback()] with the same `T`, and the
+    /// signal source must remain valid until `disconnect` is called.
+    pub(crate) unsafe fn connect(
+        callback: Box<T>,
+        connect: impl FnOnce(*mut c_void) -> *mut libcamera_callback_handle_t,
+        disconnect: impl FnOnce(NonNull<libcamera_callback_handle_t>) + 'd,
+    ) -> Self {
+        let mut callback = Box::new(callback);
+        let handle = NonNull::new(connect(callback.as_mut() as *mut Box<T> as *mut c_void));
+        Self {
+            handle,
+            disconnect: Some(Box::new(disconnect)),
+            _callback: callback,
+        }
+    }
+
+    /// Returns the callback from a context pointer passed by the C API.
+    ///
+    /// # Safety
+    ///
+    /// `data` must be the context pointer of a connected [SignalSubscription] with the same `T`. Signal emissions of a
+    /// single connection must not overlap.
+    pub(crate) unsafe fn callback<'a>(data: *mut c_void) -> &'a mut T {
+        unsafe { &mut *(data as *mut Box<T>) }
+    }
+
+    /// Returns `true` if the signal is still connected.
+    pub fn is_connected(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// Disconnects the signal, after which the callback is no longer invoked.
+    ///
+    /// Dropping the subscription has the same effect. Does nothing if already disconnected.
+    pub fn disconnect(&mut self) {
+        if let (Some(handle), Some(disconnect)) = (self.handle.take(), self.disconnect.take()) {
+            disconnect(handle);
+        }
+    }
+}
+
+impl<'d, T: ?Sized + 'd> Drop for SignalSubscription<'d, T> {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+impl<'d, T: ?Sized + 'd> core::fmt::Debug for SignalSubscription<'d, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignalSubscription")
+            .field("connected", &self.is_connected())
+            .finish()
+    }
+}
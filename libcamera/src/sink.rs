@@ -0,0 +1,139 @@
+use std::{
+    io::{self, IoSlice, Write},
+    os::fd::AsRawFd,
+};
+
+use crate::{framebuffer::AsFrameBuffer, framebuffer_map::MemoryMappedFrameBuffer};
+
+/// Writes frames into a socket (or any other file descriptor) with as few CPU copies as possible.
+///
+/// Plane data is transferred with `sendfile()` directly from the framebuffer file descriptors, which avoids copying
+/// frame data through userspace for memfd-backed buffers. Most dmabuf exporters do not support it, in which case the
+/// sink falls back to a single vectored write of the memory mapped planes for the rest of its lifetime.
+///
+/// Only bytes used by the frame are sent if framebuffer metadata is available, which keeps MJPEG frames compact.
+pub struct FrameSink<W: Write + AsRawFd> {
+    out: W,
+    sendfile: bool,
+    length_prefix: bool,
+}
+
+impl<W: Write + AsRawFd> FrameSink<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            sendfile: true,
+            length_prefix: false,
+        }
+    }
+
+    /// Prefixes each frame with its total length as a little-endian `u32`, so that the receiver can split the stream
+    /// into frames.
+    pub fn with_length_prefix(mut self, enabled: bool) -> Self {
+        self.length_prefix = enabled;
+        self
+    }
+
+    /// Returns `true` until `sendfile()` is found to be unsupported for the framebuffers.
+    pub fn uses_sendfile(&self) -> bool {
+        self.sendfile
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.out
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    /// Sends all planes of the frame one after another and returns the number of frame bytes written.
+    pub fn send_frame<T: AsFrameBuffer>(&mut self, fb: &MemoryMappedFrameBuffer<T>) -> io::Result<usize> {
+        // (fd, offset, mapped data) of each plane, limited to the bytes used
+        let planes = fb
+            .planes()
+            .into_iter()
+            .zip(fb.data_used())
+            .map(|(plane, data)| (plane.fd(), plane.offset().unwrap_or(0), data))
+            .collect::<Vec<_>>();
+        let total = planes.iter().map(|(_, _, data)| data.len()).sum::<usize>();
+
+        if self.length_prefix {
+            let len =
+                u32::try_from(total).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame too large"))?;
+            self.out.write_all(&len.to_le_bytes())?;
+        }
+
+        let mut index = 0;
+        let mut sent = 0;
+        if self.sendfile {
+            self.out.flush()?;
+            while index < planes.len() {
+                let (fd, offset, data) = planes[index];
+                match sendfile(self.out.as_raw_fd(), fd, offset + sent, data.len() - sent) {
+                    Ok(n) => {
+                        sent += n;
+                        if sent == data.len() {
+                            index += 1;
+                            sent = 0;
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) if sent == 0 && is_unsupported(&e) => {
+                        self.sendfile = false;
+                        break;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        if index < planes.len() {
+            let mut slices = Vec::with_capacity(planes.len() - index);
+            slices.push(IoSlice::new(&planes[index].2[sent..]));
+            slices.extend(planes[index + 1..].iter().map(|(_, _, data)| IoSlice::new(data)));
+            write_all_vectored(&mut self.out, &mut slices)?;
+        }
+
+        Ok(total)
+    }
+}
+
+fn sendfile(out_fd: i32, in_fd: i32, offset: usize, count: usize) -> io::Result<usize> {
+    if count == 0 {
+        return Ok(0);
+    }
+
+    let mut offset = offset as libc::off_t;
+    let ret = unsafe { libc::sendfile(out_fd, in_fd, &mut offset, count) };
+    match ret {
+        ret if ret < 0 => Err(io::Error::last_os_error()),
+        0 => Err(io::Error::new(io::ErrorKind::WriteZero, "sendfile wrote zero bytes")),
+        ret => Ok(ret as usize),
+    }
+}
+
+fn is_unsupported(e: &io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(libc::EINVAL) | Some(libc::ENOSYS) | Some(libc::EOPNOTSUPP)
+    )
+}
+
+fn write_all_vectored(out: &mut impl Write, mut slices: &mut [IoSlice<'_>]) -> io::Result<()> {
+    // Skip empty slices so that a zero length write means a closed connection
+    IoSlice::advance_slices(&mut slices, 0);
+    while !slices.is_empty() {
+        match out.write_vectored(slices) {
+            Ok(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write whole frame")),
+            Ok(n) => IoSlice::advance_slices(&mut slices, n),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
@@ -3,15 +3,16 @@ use std::{marker::PhantomData, ptr::NonNull};
 use libcamera_sys::*;
 
 use crate::{
+    color_space::ColorSpace,
     geometry::{Size, SizeRange},
-    pixel_format::{PixelFormat, PixelFormats},
+    pixel_format::{self, PixelFormat, PixelFormats},
     utils::Immutable,
 };
 
 /// Stream role hint for generating configuration.
 ///
 /// Used in [Camera::generate_configuration()](crate::camera::Camera::generate_configuration).
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum StreamRole {
     Raw,
     StillCapture,
@@ -75,6 +76,18 @@ impl<'d> StreamFormatsRef<'d> {
             .collect()
     }
 
+    /// Returns modifiers supported for a fourcc code, i.e. tiled or compressed variants offered by the pipeline.
+    ///
+    /// [DRM_FORMAT_MOD_LINEAR](crate::pixel_format::DRM_FORMAT_MOD_LINEAR) is included if the plain format is
+    /// supported as well.
+    pub fn modifiers(&self, fourcc: u32) -> Vec<u64> {
+        self.pixel_formats()
+            .into_iter()
+            .filter(|f| f.fourcc() == fourcc)
+            .map(|f| f.modifier())
+            .collect()
+    }
+
     /// Returns a [SizeRange] of supported stream sizes for a given [PixelFormat].
     pub fn range(&self, pixel_format: PixelFormat) -> SizeRange {
         SizeRange::from(unsafe { libcamera_stream_formats_range(self.ptr.as_ptr(), &pixel_format.0) })
@@ -93,13 +106,16 @@ impl<'d> core::fmt::Debug for StreamFormatsRef<'d> {
 
 pub struct StreamConfigurationRef<'d> {
     ptr: NonNull<libcamera_stream_configuration_t>,
+    /// Configuration generation of the parent [CameraConfiguration](crate::camera::CameraConfiguration).
+    generation: u64,
     _phantom: PhantomData<&'d ()>,
 }
 
 impl<'d> StreamConfigurationRef<'d> {
-    pub(crate) unsafe fn from_ptr(ptr: NonNull<libcamera_stream_configuration_t>) -> Self {
+    pub(crate) unsafe fn from_ptr(ptr: NonNull<libcamera_stream_configuration_t>, generation: u64) -> Self {
         Self {
             ptr,
+            generation,
             _phantom: Default::default(),
         }
     }
@@ -112,6 +128,20 @@ impl<'d> StreamConfigurationRef<'d> {
         unsafe { self.ptr.as_mut() }.pixel_format = pixel_format.0;
     }
 
+    /// Returns modifier of the configured pixel format, see [PixelFormat::modifier()].
+    pub fn get_modifier(&self) -> u64 {
+        self.get_pixel_format().modifier()
+    }
+
+    /// Requests a modifier for the configured fourcc, i.e. a tiled or compressed layout listed by
+    /// [StreamFormatsRef::modifiers()].
+    ///
+    /// Pipelines not supporting the modifier change the pixel format during validation.
+    pub fn set_modifier(&mut self, modifier: u64) {
+        let pixel_format = self.get_pixel_format().with_modifier(modifier);
+        self.set_pixel_format(pixel_format);
+    }
+
     pub fn get_size(&self) -> Size {
         unsafe { self.ptr.as_ref() }.size.into()
     }
@@ -120,6 +150,10 @@ impl<'d> StreamConfigurationRef<'d> {
         unsafe { self.ptr.as_mut() }.size = size.into()
     }
 
+    /// Number of bytes between the starts of two consecutive lines of the first plane.
+    ///
+    /// Filled in by [CameraConfiguration::validate()](crate::camera::CameraConfiguration::validate), zero for
+    /// compressed formats.
     pub fn get_stride(&self) -> u32 {
         unsafe { self.ptr.as_ref() }.stride
     }
@@ -128,6 +162,9 @@ impl<'d> StreamConfigurationRef<'d> {
         unsafe { self.ptr.as_mut() }.stride = stride
     }
 
+    /// Maximum number of bytes of a single frame, including all planes.
+    ///
+    /// Filled in by [CameraConfiguration::validate()](crate::camera::CameraConfiguration::validate).
     pub fn get_frame_size(&self) -> u32 {
         unsafe { self.ptr.as_ref() }.frame_size
     }
@@ -136,24 +173,79 @@ impl<'d> StreamConfigurationRef<'d> {
         unsafe { self.ptr.as_mut() }.frame_size = frame_size
     }
 
+    /// Number of buffers required by the stream, which is the depth of the request queue.
     pub fn get_buffer_count(&self) -> u32 {
         unsafe { self.ptr.as_ref() }.buffer_count
     }
 
+    /// Requests number of buffers to allocate for the stream, which is the depth of the request queue.
+    ///
+    /// More buffers tolerate longer processing delays at the cost of memory, see
+    /// [recommended_buffer_count()](crate::session::recommended_buffer_count).
+    /// [CameraConfiguration::validate()](crate::camera::CameraConfiguration::validate) adjusts it to the limits of the
+    /// pipeline.
     pub fn set_buffer_count(&mut self, buffer_count: u32) {
         unsafe { self.ptr.as_mut() }.buffer_count = buffer_count;
     }
 
+    /// Returns color space of the stream, [None] if unspecified.
+    ///
+    /// Filled in by [CameraConfiguration::validate()](crate::camera::CameraConfiguration::validate), which may also
+    /// adjust a requested color space to one supported by the pipeline.
+    pub fn get_color_space(&self) -> Option<ColorSpace> {
+        let mut color_space = libcamera_color_space_t::from(ColorSpace::RAW);
+        unsafe { libcamera_stream_configuration_get_color_space(self.ptr.as_ptr(), &mut color_space) }
+            .then(|| color_space.try_into().unwrap())
+    }
+
+    /// Returns color space of the stream, falling back to [ColorSpace::suggested_for()] if the pipeline left it
+    /// unspecified.
+    ///
+    /// Call after [CameraConfiguration::validate()](crate::camera::CameraConfiguration::validate) to get the color
+    /// space chosen by the pipeline.
+    pub fn effective_color_space(&self, role: StreamRole) -> ColorSpace {
+        self.get_color_space()
+            .unwrap_or_else(|| ColorSpace::suggested_for(self.get_pixel_format(), role))
+    }
+
+    /// Requests color space of the stream, or lets the pipeline choose when [None].
+    pub fn set_color_space(&mut self, color_space: Option<ColorSpace>) {
+        match color_space {
+            Some(color_space) => unsafe {
+                libcamera_stream_configuration_set_color_space(self.ptr.as_ptr(), &color_space.into())
+            },
+            None => unsafe { libcamera_stream_configuration_clear_color_space(self.ptr.as_ptr()) },
+        }
+    }
+
+    /// Returns an owned copy of this configuration.
+    pub fn snapshot(&self) -> StreamConfiguration {
+        StreamConfiguration::from(self)
+    }
+
+    /// Overwrites all fields of this configuration with `config`.
+    ///
+    /// The result still has to be validated with
+    /// [CameraConfiguration::validate()](crate::camera::CameraConfiguration::validate).
+    pub fn apply(&mut self, config: &StreamConfiguration) {
+        self.set_pixel_format(config.pixel_format);
+        self.set_size(config.size);
+        self.set_stride(config.stride);
+        self.set_frame_size(config.frame_size);
+        self.set_buffer_count(config.buffer_count);
+        self.set_color_space(config.color_space);
+    }
+
     /// Returns initialized [Stream] for this configuration.
     ///
     /// Stream is only available once this configuration is applied with
     /// [ActiveCamera::configure()](crate::camera::ActiveCamera::configure). It is invalidated if camera is
-    /// reconfigured.
+    /// reconfigured, see [ActiveCamera::is_stream_valid()](crate::camera::ActiveCamera::is_stream_valid).
     pub fn stream(&self) -> Option<Stream> {
         let stream = unsafe { libcamera_stream_configuration_stream(self.ptr.as_ptr()) };
-        // Stream is valid after camera->configure(), but might be invalidated after following reconfigurations.
-        // Unfortunatelly, it's hard to handle it with lifetimes so invalid StreamRef's are possible.
-        NonNull::new(stream).map(|p| unsafe { Stream::from_ptr(p) })
+        // Stream is valid after camera->configure(), but might be invalidated or reused by following reconfigurations.
+        // It's hard to handle it with lifetimes, so staleness is tracked with the configuration generation instead.
+        NonNull::new(stream).map(|p| unsafe { Stream::from_ptr(p, self.generation) })
     }
 
     /// Returns a list of available stream formats for this configuration.
@@ -164,6 +256,107 @@ impl<'d> StreamConfigurationRef<'d> {
             )
         }
     }
+
+    /// Returns size alignment required by the pipeline for the current pixel format.
+    ///
+    /// [CameraConfiguration::validate()](crate::camera::CameraConfiguration::validate) adjusts sizes, which are not a
+    /// multiple of it. Returns [None] if the pixel format is not supported by the stream.
+    pub fn alignment(&self) -> Option<Alignment> {
+        let range = self.formats().range(self.get_pixel_format());
+        (range.h_step > 0 && range.v_step > 0).then_some(Alignment {
+            width: range.h_step,
+            height: range.v_step,
+        })
+    }
+
+    /// Number of bytes by which [StreamConfigurationRef::get_stride()] exceeds the minimum stride of the pixel format.
+    ///
+    /// Returns [None] before validation, for compressed formats and for formats without a known layout, see
+    /// [pixel_format::info()].
+    pub fn stride_padding(&self) -> Option<u32> {
+        let info = pixel_format::info(self.get_pixel_format())?;
+        let min_stride = info.stride(self.get_size().width, 0, 1)?;
+        match self.get_stride() {
+            0 => None,
+            stride => Some(stride.saturating_sub(min_stride)),
+        }
+    }
+}
+
+/// Size alignment of a stream, i.e. width must be a multiple of 32 pixels.
+///
+/// Obtained from the steps of the [SizeRange] supported by the stream, see [StreamConfigurationRef::alignment()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alignment {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Alignment {
+    /// Returns `true` if size is a multiple of the alignment. Zero alignment is treated as 1, same as
+    /// [Size::aligned_to()].
+    pub fn is_aligned(&self, size: Size) -> bool {
+        size.width.is_multiple_of(self.width.max(1)) && size.height.is_multiple_of(self.height.max(1))
+    }
+
+    /// Rounds size down to the closest aligned one.
+    pub fn align_down(&self, size: Size) -> Size {
+        size.aligned_down_to(self.width, self.height)
+    }
+
+    /// Rounds size up to the closest aligned one.
+    pub fn align_up(&self, size: Size) -> Size {
+        size.aligned_to(self.width, self.height)
+    }
+}
+
+/// Reason why validation changed the requested size of a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeAdjustment {
+    /// Requested size is not a multiple of the alignment and was rounded to the closest aligned size.
+    Alignment(Alignment),
+    /// Requested size is outside of the range supported for the pixel format.
+    OutOfRange(SizeRange),
+    /// Pipeline picked a different size for another reason, i.e. the closest of the discrete supported sizes.
+    Other,
+}
+
+impl SizeAdjustment {
+    pub(crate) fn new(requested: Size, validated: Size, range: SizeRange) -> Self {
+        let in_range = (range.min.width..=range.max.width).contains(&requested.width)
+            && (range.min.height..=range.max.height).contains(&requested.height);
+        if !in_range {
+            return Self::OutOfRange(range);
+        }
+
+        let alignment = Alignment {
+            width: range.h_step,
+            height: range.v_step,
+        };
+        if alignment.width > 0
+            && alignment.height > 0
+            && requested.width.abs_diff(validated.width) < alignment.width
+            && requested.height.abs_diff(validated.height) < alignment.height
+        {
+            Self::Alignment(alignment)
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Stream configuration changed by [CameraConfiguration::validate_detailed()](
+/// crate::camera::CameraConfiguration::validate_detailed).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamAdjustment {
+    /// Index of the stream within the camera configuration.
+    pub index: usize,
+    pub requested: StreamConfiguration,
+    pub validated: StreamConfiguration,
+    /// Reason for the size change, [None] if size was kept.
+    pub size: Option<SizeAdjustment>,
+    /// Color space chosen by the pipeline, if it differs from the requested one, i.e. when none was requested.
+    pub color_space: Option<ColorSpace>,
 }
 
 impl<'d> core::fmt::Debug for StreamConfigurationRef<'d> {
@@ -174,26 +367,64 @@ impl<'d> core::fmt::Debug for StreamConfigurationRef<'d> {
             .field("stride", &self.get_stride())
             .field("frame_size", &self.get_frame_size())
             .field("buffer_count", &self.get_buffer_count())
+            .field("color_space", &self.get_color_space())
             .finish()
     }
 }
 
+/// Owned copy of a stream configuration.
+///
+/// Unlike [StreamConfigurationRef], it does not borrow the [CameraConfiguration](crate::camera::CameraConfiguration),
+/// so configuration decisions can be computed on another thread and applied later with
+/// [StreamConfigurationRef::apply()].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamConfiguration {
+    pub pixel_format: PixelFormat,
+    pub size: Size,
+    pub stride: u32,
+    pub frame_size: u32,
+    pub buffer_count: u32,
+    pub color_space: Option<ColorSpace>,
+}
+
+impl From<&StreamConfigurationRef<'_>> for StreamConfiguration {
+    fn from(config: &StreamConfigurationRef<'_>) -> Self {
+        Self {
+            pixel_format: config.get_pixel_format(),
+            size: config.get_size(),
+            stride: config.get_stride(),
+            frame_size: config.get_frame_size(),
+            buffer_count: config.get_buffer_count(),
+            color_space: config.get_color_space(),
+        }
+    }
+}
+
 /// Handle to a camera stream.
 ///
-/// Obtained from [StreamConfigurationRef::stream()] and is valid as long as camera configuration is unchanged.
-#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+/// Obtained from [StreamConfigurationRef::stream()] and is valid until the camera is configured again. Each handle
+/// records the configuration generation it was obtained in, as libcamera may reuse the same stream objects across
+/// reconfigurations, and [ActiveCamera::queue_request()](crate::camera::ActiveCamera::queue_request) rejects requests
+/// with buffers of streams from a previous generation.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub struct Stream {
     /// libcamera_stream_t is used as unique key across various libcamera structures
     /// and adding a lifetime would be really inconvenient. Dangling pointer should not
     /// cause any harm by itself as collection loopup will fail gracefully, however,
     /// it is important to never dereference this pointer to obtain libcamera_stream_configuration_t.
     pub(crate) ptr: NonNull<libcamera_stream_t>,
+    /// Configuration generation, see [ActiveCamera::is_stream_valid()](crate::camera::ActiveCamera::is_stream_valid).
+    pub(crate) generation: u64,
 }
 
 impl Stream {
-    pub(crate) unsafe fn from_ptr(ptr: NonNull<libcamera_stream_t>) -> Self {
-        Self { ptr }
+    pub(crate) unsafe fn from_ptr(ptr: NonNull<libcamera_stream_t>, generation: u64) -> Self {
+        Self { ptr, generation }
     }
 }
 
+// Safety: the pointer is an opaque key, which is never dereferenced from Rust. It is only compared and passed to
+// libcamera calls on requests and allocators, which are themselves not shared between threads, so handles can be
+// copied and shared freely.
 unsafe impl Send for Stream {}
+unsafe impl Sync for Stream {}
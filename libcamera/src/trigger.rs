@@ -0,0 +1,179 @@
+//! Correlation of external events with captured frames.
+//!
+//! Machine vision setups often need the frame that was being exposed when an external event happened, e.g. a light
+//! barrier interrupt. [FrameRing] keeps the most recent completed requests of a [CaptureSession] instead of queueing
+//! them back right away, so that once an event is timestamped with [TriggerEvent::now()], the frame whose exposure
+//! interval contains it can still be retrieved with [FrameRing::trigger()].
+
+use std::{collections::VecDeque, io, time::Duration};
+
+use crate::{
+    controls::{ExposureTime, SensorTimestamp},
+    framebuffer::AsFrameBuffer,
+    resume::clock_time,
+    session::{CaptureSession, CompletedRequest, SessionError},
+};
+
+/// An external event timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TriggerEvent {
+    /// Nanoseconds in `CLOCK_MONOTONIC` domain, which V4L2 drivers and GPIO line events use for timestamps.
+    pub timestamp: u64,
+}
+
+impl TriggerEvent {
+    /// Timestamps an event that happened right now.
+    ///
+    /// Call it as early as possible in the event handler, any latency directly shifts the matched frame.
+    pub fn now() -> io::Result<Self> {
+        Ok(Self {
+            timestamp: clock_time(libc::CLOCK_MONOTONIC)?.as_nanos() as u64,
+        })
+    }
+
+    /// Creates event from a timestamp in nanoseconds taken with `CLOCK_MONOTONIC`, i.e. by a GPIO line event.
+    pub fn from_timestamp(timestamp: u64) -> Self {
+        Self { timestamp }
+    }
+}
+
+/// Exposure interval of a frame in nanoseconds, see [TriggerEvent::timestamp].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExposureInterval {
+    /// Start of exposure of the first sensor row.
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ExposureInterval {
+    /// Reads exposure interval from request metadata.
+    ///
+    /// Falls back to the buffer timestamp and a zero exposure time if the pipeline does not report [SensorTimestamp]
+    /// or [ExposureTime] respectively.
+    pub fn from_request(req: &CompletedRequest) -> Option<Self> {
+        let metadata = req.metadata();
+        let start = match metadata.get::<SensorTimestamp>() {
+            Ok(ts) => *ts as u64,
+            Err(_) => req.frames().find_map(|f| f.buffer().metadata())?.timestamp(),
+        };
+        let exposure = metadata.get::<ExposureTime>().map(|e| *e as u64).unwrap_or(0);
+        Some(Self {
+            start,
+            end: start + exposure * 1000,
+        })
+    }
+
+    pub fn contains(&self, timestamp: u64) -> bool {
+        (self.start..=self.end).contains(&timestamp)
+    }
+
+    /// Distance of a timestamp from the interval, zero if it is contained.
+    pub fn distance(&self, timestamp: u64) -> u64 {
+        self.start
+            .saturating_sub(timestamp)
+            .max(timestamp.saturating_sub(self.end))
+    }
+}
+
+/// Keeps the most recent completed requests of a [CaptureSession] for correlation with [TriggerEvent]s.
+///
+/// Retained requests are not available to the camera, so the session needs at least `capacity + 2` buffers per
+/// stream to keep capturing without drops.
+pub struct FrameRing {
+    frames: VecDeque<(ExposureInterval, CompletedRequest)>,
+    capacity: usize,
+}
+
+impl FrameRing {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Number of retained requests.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Retains a completed request, returning the oldest one if the ring is full.
+    ///
+    /// Requests without any timestamp are returned right away.
+    pub fn push(&mut self, req: CompletedRequest) -> Option<CompletedRequest> {
+        let Some(interval) = ExposureInterval::from_request(&req) else {
+            return Some(req);
+        };
+        let evicted = if self.frames.len() >= self.capacity {
+            self.frames.pop_front().map(|(_, req)| req)
+        } else {
+            None
+        };
+        self.frames.push_back((interval, req));
+        evicted
+    }
+
+    /// Waits for the next completed request of the session, retains it and queues the evicted one back.
+    ///
+    /// Call it in the capture loop instead of [CaptureSession::next_request()].
+    pub fn poll(&mut self, session: &mut CaptureSession<'_>, timeout: Duration) -> Result<(), SessionError> {
+        let req = session.next_request(timeout)?;
+        if let Some(evicted) = self.push(req) {
+            session.queue(evicted)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the retained request, whose exposure interval contains or is nearest to the event.
+    pub fn find(&self, event: TriggerEvent) -> Option<&CompletedRequest> {
+        self.nearest(event).map(|i| &self.frames[i].1)
+    }
+
+    /// Removes the retained request nearest to the event, see [FrameRing::find()].
+    ///
+    /// Queue it back with [CaptureSession::queue()] once processed.
+    pub fn take(&mut self, event: TriggerEvent) -> Option<CompletedRequest> {
+        let index = self.nearest(event)?;
+        self.frames.remove(index).map(|(_, req)| req)
+    }
+
+    /// Polls the session until a frame with exposure ending after the event is retained, then takes the request
+    /// nearest to the event.
+    ///
+    /// Events in the future are waited for, so `timeout` applies to each poll rather than the whole call.
+    pub fn trigger(
+        &mut self,
+        session: &mut CaptureSession<'_>,
+        event: TriggerEvent,
+        timeout: Duration,
+    ) -> Result<CompletedRequest, SessionError> {
+        while self
+            .frames
+            .back()
+            .is_none_or(|(interval, _)| interval.end < event.timestamp)
+        {
+            self.poll(session, timeout)?;
+        }
+        Ok(self.take(event).unwrap())
+    }
+
+    /// Queues all retained requests back to the session.
+    pub fn drain_into(&mut self, session: &mut CaptureSession<'_>) -> Result<(), SessionError> {
+        while let Some((_, req)) = self.frames.pop_front() {
+            session.queue(req)?;
+        }
+        Ok(())
+    }
+
+    fn nearest(&self, event: TriggerEvent) -> Option<usize> {
+        self.frames
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (interval, _))| interval.distance(event.timestamp))
+            .map(|(i, _)| i)
+    }
+}
@@ -3,14 +3,26 @@ use std::{
     io,
     ops::{Deref, DerefMut},
     ptr::NonNull,
+    sync::atomic::{AtomicU64, Ordering},
 };
 
 /// Provides only an immutable reference to the contained type T.
 ///
-/// Used for FFI types to avoid having separate variants depending on mutability.
+/// Used for FFI types to avoid having separate variants depending on mutability. All read-only methods of `T` are
+/// available directly through [Deref], and `&Immutable<T>` can be passed wherever `&T` is expected thanks to deref
+/// coercion, so the wrapper rarely has to be handled explicitly:
+///
+/// ```ignore
+/// let cfg = config.get(0).unwrap();
+/// println!("{:?} {:?}", cfg.get_pixel_format(), cfg.get_size());
+/// let converter = RgbConverter::from_stream_config(&cfg);
+/// ```
+///
+/// Use [Immutable::value()] or [AsRef] in generic contexts, where deref coercion does not apply.
 pub struct Immutable<T: ?Sized>(pub(crate) T);
 
 impl<T> Immutable<T> {
+    /// Returns a reference to the contained value.
     pub fn value(&self) -> &T {
         &self.0
     }
@@ -24,12 +36,43 @@ impl<T> Deref for Immutable<T> {
     }
 }
 
+impl<T> AsRef<T> for Immutable<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Immutable<T>
+where
+    &'a T: IntoIterator,
+{
+    type Item = <&'a T as IntoIterator>::Item;
+
+    type IntoIter = <&'a T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T: PartialEq> PartialEq<T> for Immutable<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.0.eq(other)
+    }
+}
+
 impl<T: core::fmt::Debug> core::fmt::Debug for Immutable<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("Immutable").field(&self.0).finish()
     }
 }
 
+impl<T: core::fmt::Display> core::fmt::Display for Immutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 /// Trait, which allows type to be used in [UniquePtr]
 pub trait UniquePtrTarget: Sized {
     /// Allocates `Self` in the heap and returns pointer.
@@ -91,6 +134,48 @@ impl<T: UniquePtrTarget + core::fmt::Debug> core::fmt::Debug for UniquePtr<T> {
     }
 }
 
+/// Identifies a registered listener, see [ActiveCamera::add_request_completed_listener()].
+///
+/// Ids are unique across all registries, so an id of one kind of listener never removes another one.
+///
+/// [ActiveCamera::add_request_completed_listener()]: crate::camera::ActiveCamera::add_request_completed_listener
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+/// Source of listener ids, shared by all registries.
+static NEXT_LISTENER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Callbacks, which can be registered and removed independently of each other.
+pub(crate) struct Listeners<F: ?Sized> {
+    listeners: Vec<(ListenerId, Box<F>)>,
+}
+
+impl<F: ?Sized> Listeners<F> {
+    pub fn add(&mut self, listener: Box<F>) -> ListenerId {
+        let id = ListenerId(NEXT_LISTENER_ID.fetch_add(1, Ordering::Relaxed));
+        self.listeners.push((id, listener));
+        id
+    }
+
+    /// Removes a listener, returning `false` if it was not registered.
+    pub fn remove(&mut self, id: ListenerId) -> bool {
+        let len = self.listeners.len();
+        self.listeners.retain(|(i, _)| *i != id);
+        self.listeners.len() != len
+    }
+
+    /// Iterates listeners in registration order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut F> {
+        self.listeners.iter_mut().map(|(_, l)| l.as_mut())
+    }
+}
+
+impl<F: ?Sized> Default for Listeners<F> {
+    fn default() -> Self {
+        Self { listeners: Vec::new() }
+    }
+}
+
 #[inline]
 pub fn handle_result(ret: c_int) -> io::Result<()> {
     if ret < 0 {
@@ -99,3 +184,21 @@ pub fn handle_result(ret: c_int) -> io::Result<()> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listener_ids_are_unique_across_registries() {
+        let mut completed = Listeners::<dyn FnMut()>::default();
+        let mut observers = Listeners::<dyn FnMut()>::default();
+        let completed_id = completed.add(Box::new(|| {}));
+        let observer_id = observers.add(Box::new(|| {}));
+
+        assert_ne!(completed_id, observer_id);
+        assert!(!observers.remove(completed_id));
+        assert_eq!(observers.iter_mut().count(), 1);
+        assert!(completed.remove(completed_id));
+    }
+}
@@ -0,0 +1,508 @@
+//! Zero-copy import of framebuffers into Vulkan.
+//!
+//! [VulkanImageImporter] creates `VkImage`s bound to framebuffer dmabufs using `VK_EXT_external_memory_dma_buf` and
+//! `VK_EXT_image_drm_format_modifier`, so that frames can be post-processed by GPU compute shaders without copying. The
+//! device is provided by the application (i.e. from `ash` or wgpu's `as_hal()`) together with its
+//! `vkGetDeviceProcAddr`, so this module does not depend on any particular Vulkan binding. Images can be wrapped into
+//! wgpu textures with `wgpu_hal::vulkan::Device::texture_from_raw()`.
+//!
+//! Imported images are created in `VK_IMAGE_LAYOUT_UNDEFINED`. Acquire them from `VK_QUEUE_FAMILY_FOREIGN_EXT` with a
+//! queue family ownership transfer before each use to see the current frame contents.
+
+use std::{
+    ffi::{c_char, c_void},
+    io,
+    os::fd::{AsRawFd, BorrowedFd, IntoRawFd},
+    ptr,
+};
+
+use thiserror::Error;
+
+use crate::{
+    framebuffer::{AsFrameBuffer, PlaneGroup},
+    geometry::Size,
+    pixel_format::{formats, PixelFormat},
+    stream::StreamConfigurationRef,
+};
+
+pub type VkDevice = *mut c_void;
+pub type VkImage = u64;
+pub type VkDeviceMemory = u64;
+pub type VkFormat = u32;
+pub type VkImageCreateFlags = u32;
+pub type VkImageUsageFlags = u32;
+type VkResult = i32;
+type VkStructureType = u32;
+type VoidFunction = unsafe extern "system" fn();
+pub type GetDeviceProcAddrFn = unsafe extern "system" fn(VkDevice, *const c_char) -> Option<VoidFunction>;
+
+const VK_SUCCESS: VkResult = 0;
+const VK_STRUCTURE_TYPE_MEMORY_ALLOCATE_INFO: VkStructureType = 5;
+const VK_STRUCTURE_TYPE_IMAGE_CREATE_INFO: VkStructureType = 14;
+const VK_STRUCTURE_TYPE_EXTERNAL_MEMORY_IMAGE_CREATE_INFO: VkStructureType = 1000072001;
+const VK_STRUCTURE_TYPE_IMPORT_MEMORY_FD_INFO_KHR: VkStructureType = 1000074000;
+const VK_STRUCTURE_TYPE_MEMORY_FD_PROPERTIES_KHR: VkStructureType = 1000074001;
+const VK_STRUCTURE_TYPE_MEMORY_DEDICATED_ALLOCATE_INFO: VkStructureType = 1000127001;
+const VK_STRUCTURE_TYPE_IMAGE_DRM_FORMAT_MODIFIER_EXPLICIT_CREATE_INFO_EXT: VkStructureType = 1000158004;
+const VK_IMAGE_TYPE_2D: u32 = 1;
+const VK_IMAGE_TILING_DRM_FORMAT_MODIFIER_EXT: u32 = 1000158000;
+const VK_SAMPLE_COUNT_1_BIT: u32 = 1;
+const VK_SHARING_MODE_EXCLUSIVE: u32 = 0;
+const VK_IMAGE_LAYOUT_UNDEFINED: u32 = 0;
+const VK_EXTERNAL_MEMORY_HANDLE_TYPE_DMA_BUF_BIT_EXT: u32 = 0x200;
+const DRM_FORMAT_MOD_LINEAR: u64 = 0;
+const DRM_FORMAT_MOD_INVALID: u64 = 0x00ff_ffff_ffff_ffff;
+
+/// `VkImageUsageFlagBits` commonly used for post-processing.
+pub const VK_IMAGE_USAGE_TRANSFER_SRC_BIT: VkImageUsageFlags = 0x1;
+pub const VK_IMAGE_USAGE_SAMPLED_BIT: VkImageUsageFlags = 0x4;
+pub const VK_IMAGE_USAGE_STORAGE_BIT: VkImageUsageFlags = 0x8;
+/// Allows per-plane image views of multi-planar formats.
+pub const VK_IMAGE_CREATE_MUTABLE_FORMAT_BIT: VkImageCreateFlags = 0x8;
+
+#[repr(C)]
+struct VkExtent3D {
+    width: u32,
+    height: u32,
+    depth: u32,
+}
+
+#[repr(C)]
+struct VkImageCreateInfo {
+    s_type: VkStructureType,
+    p_next: *const c_void,
+    flags: VkImageCreateFlags,
+    image_type: u32,
+    format: VkFormat,
+    extent: VkExtent3D,
+    mip_levels: u32,
+    array_layers: u32,
+    samples: u32,
+    tiling: u32,
+    usage: VkImageUsageFlags,
+    sharing_mode: u32,
+    queue_family_index_count: u32,
+    p_queue_family_indices: *const u32,
+    initial_layout: u32,
+}
+
+#[repr(C)]
+struct VkExternalMemoryImageCreateInfo {
+    s_type: VkStructureType,
+    p_next: *const c_void,
+    handle_types: u32,
+}
+
+#[repr(C)]
+struct VkSubresourceLayout {
+    offset: u64,
+    size: u64,
+    row_pitch: u64,
+    array_pitch: u64,
+    depth_pitch: u64,
+}
+
+#[repr(C)]
+struct VkImageDrmFormatModifierExplicitCreateInfoEXT {
+    s_type: VkStructureType,
+    p_next: *const c_void,
+    drm_format_modifier: u64,
+    drm_format_modifier_plane_count: u32,
+    p_plane_layouts: *const VkSubresourceLayout,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct VkMemoryRequirements {
+    size: u64,
+    alignment: u64,
+    memory_type_bits: u32,
+}
+
+#[repr(C)]
+struct VkMemoryFdPropertiesKHR {
+    s_type: VkStructureType,
+    p_next: *mut c_void,
+    memory_type_bits: u32,
+}
+
+#[repr(C)]
+struct VkImportMemoryFdInfoKHR {
+    s_type: VkStructureType,
+    p_next: *const c_void,
+    handle_type: u32,
+    fd: i32,
+}
+
+#[repr(C)]
+struct VkMemoryDedicatedAllocateInfo {
+    s_type: VkStructureType,
+    p_next: *const c_void,
+    image: VkImage,
+    buffer: u64,
+}
+
+#[repr(C)]
+struct VkMemoryAllocateInfo {
+    s_type: VkStructureType,
+    p_next: *const c_void,
+    allocation_size: u64,
+    memory_type_index: u32,
+}
+
+type CreateImageFn =
+    unsafe extern "system" fn(VkDevice, *const VkImageCreateInfo, *const c_void, *mut VkImage) -> VkResult;
+type DestroyImageFn = unsafe extern "system" fn(VkDevice, VkImage, *const c_void);
+type GetImageMemoryRequirementsFn = unsafe extern "system" fn(VkDevice, VkImage, *mut VkMemoryRequirements);
+type GetMemoryFdPropertiesFn = unsafe extern "system" fn(VkDevice, u32, i32, *mut VkMemoryFdPropertiesKHR) -> VkResult;
+type AllocateMemoryFn =
+    unsafe extern "system" fn(VkDevice, *const VkMemoryAllocateInfo, *const c_void, *mut VkDeviceMemory) -> VkResult;
+type FreeMemoryFn = unsafe extern "system" fn(VkDevice, VkDeviceMemory, *const c_void);
+type BindImageMemoryFn = unsafe extern "system" fn(VkDevice, VkImage, VkDeviceMemory, u64) -> VkResult;
+
+#[derive(Debug, Error)]
+pub enum VulkanError {
+    #[error("Vulkan function {0} is not available")]
+    Unsupported(&'static str),
+    #[error("Pixel format {0:?} has no Vulkan equivalent")]
+    UnsupportedFormat(PixelFormat),
+    #[error("Framebuffer planes use {0} dmabufs, only a single dmabuf is supported")]
+    DisjointPlanes(usize),
+    #[error("Stride of plane {0} is unknown")]
+    MissingStride(usize),
+    #[error("No memory type can import the dmabuf")]
+    NoMemoryType,
+    #[error("{call} failed with VkResult {result}")]
+    Call { call: &'static str, result: VkResult },
+    #[error("Failed to duplicate dmabuf: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Returns Vulkan format with the same memory layout as a libcamera pixel format.
+///
+/// Multi-planar YUV formats map to the `VK_FORMAT_G8_B8R8_*` family, which needs a sampler YCbCr conversion for
+/// sampling, or per-plane image views (see [VK_IMAGE_CREATE_MUTABLE_FORMAT_BIT]) for compute. Formats with swapped
+/// chroma order (i.e. NV21) and packed raw formats have no equivalent.
+pub fn vk_format(pixel_format: PixelFormat) -> Option<VkFormat> {
+    VK_FORMATS
+        .iter()
+        .find_map(|(format, vk_format)| (*format == pixel_format).then_some(*vk_format))
+}
+
+// Channel order of libcamera (DRM) names is reversed compared to Vulkan, i.e. RGB888 is stored as B, G, R
+const VK_FORMATS: &[(PixelFormat, VkFormat)] = &[
+    (formats::R8, 9),
+    (formats::R16, 70),
+    (formats::RGB565, 4),
+    (formats::RGB888, 30),
+    (formats::BGR888, 23),
+    (formats::XRGB8888, 44),
+    (formats::ARGB8888, 44),
+    (formats::XBGR8888, 37),
+    (formats::ABGR8888, 37),
+    (formats::YUYV, 1000156000),
+    (formats::UYVY, 1000156001),
+    (formats::YUV420, 1000156002),
+    (formats::NV12, 1000156003),
+    (formats::YUV422, 1000156004),
+    (formats::NV16, 1000156005),
+];
+
+/// Returns the DRM format modifier to import a pixel format with.
+///
+/// libcamera reports most buffers without a modifier, which means linear layout.
+pub fn drm_modifier(pixel_format: PixelFormat) -> u64 {
+    match pixel_format.modifier() {
+        DRM_FORMAT_MOD_INVALID => DRM_FORMAT_MOD_LINEAR,
+        modifier => modifier,
+    }
+}
+
+/// Description of a dmabuf backed image to import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VulkanImageDesc {
+    pub size: Size,
+    pub pixel_format: PixelFormat,
+    /// Plane layout with strides filled in, see [PlaneGroup::set_strides()].
+    pub planes: Vec<PlaneGroup>,
+    pub usage: VkImageUsageFlags,
+    pub flags: VkImageCreateFlags,
+}
+
+impl VulkanImageDesc {
+    /// Describes a framebuffer of a stream with the given configuration.
+    pub fn new(fb: &impl AsFrameBuffer, cfg: &StreamConfigurationRef<'_>, usage: VkImageUsageFlags) -> Self {
+        let pixel_format = cfg.get_pixel_format();
+        let mut planes = fb.plane_groups();
+        PlaneGroup::set_strides(&mut planes, pixel_format, cfg.get_stride());
+        Self {
+            size: cfg.get_size(),
+            pixel_format,
+            planes,
+            usage,
+            flags: 0,
+        }
+    }
+
+    fn format(&self) -> Result<VkFormat, VulkanError> {
+        vk_format(self.pixel_format).ok_or(VulkanError::UnsupportedFormat(self.pixel_format))
+    }
+
+    fn plane_layouts(&self) -> Result<(i32, Vec<VkSubresourceLayout>), VulkanError> {
+        let [group] = self.planes.as_slice() else {
+            return Err(VulkanError::DisjointPlanes(self.planes.len()));
+        };
+        let layouts = group
+            .planes
+            .iter()
+            .map(|plane| {
+                Ok(VkSubresourceLayout {
+                    offset: plane.offset as _,
+                    // Must be zero for explicit modifier layouts, the size is derived from the other fields
+                    size: 0,
+                    row_pitch: plane.stride.ok_or(VulkanError::MissingStride(plane.index))? as _,
+                    array_pitch: 0,
+                    depth_pitch: 0,
+                })
+            })
+            .collect::<Result<Vec<_>, VulkanError>>()?;
+        Ok((group.fd, layouts))
+    }
+}
+
+/// Creates `VkImage`s from dmabufs on a given Vulkan device.
+///
+/// The device must have `VK_KHR_external_memory_fd`, `VK_EXT_external_memory_dma_buf` and
+/// `VK_EXT_image_drm_format_modifier` enabled.
+#[derive(Debug)]
+pub struct VulkanImageImporter {
+    device: VkDevice,
+    create_image: CreateImageFn,
+    destroy_image: DestroyImageFn,
+    get_image_memory_requirements: GetImageMemoryRequirementsFn,
+    get_memory_fd_properties: GetMemoryFdPropertiesFn,
+    allocate_memory: AllocateMemoryFn,
+    free_memory: FreeMemoryFn,
+    bind_image_memory: BindImageMemoryFn,
+}
+
+impl VulkanImageImporter {
+    /// Creates importer for a Vulkan device.
+    ///
+    /// # Safety
+    ///
+    /// `device` must be a valid `VkDevice` with the extensions above enabled, `get_device_proc_addr` must be its
+    /// `vkGetDeviceProcAddr`, and the device must outlive the importer and all images created by it.
+    pub unsafe fn new(device: VkDevice, get_device_proc_addr: GetDeviceProcAddrFn) -> Result<Self, VulkanError> {
+        let load = |name: &'static std::ffi::CStr| {
+            get_device_proc_addr(device, name.as_ptr()).ok_or(VulkanError::Unsupported(name.to_str().unwrap()))
+        };
+
+        Ok(Self {
+            device,
+            create_image: core::mem::transmute::<VoidFunction, CreateImageFn>(load(c"vkCreateImage")?),
+            destroy_image: core::mem::transmute::<VoidFunction, DestroyImageFn>(load(c"vkDestroyImage")?),
+            get_image_memory_requirements: core::mem::transmute::<VoidFunction, GetImageMemoryRequirementsFn>(load(
+                c"vkGetImageMemoryRequirements",
+            )?),
+            get_memory_fd_properties: core::mem::transmute::<VoidFunction, GetMemoryFdPropertiesFn>(load(
+                c"vkGetMemoryFdPropertiesKHR",
+            )?),
+            allocate_memory: core::mem::transmute::<VoidFunction, AllocateMemoryFn>(load(c"vkAllocateMemory")?),
+            free_memory: core::mem::transmute::<VoidFunction, FreeMemoryFn>(load(c"vkFreeMemory")?),
+            bind_image_memory: core::mem::transmute::<VoidFunction, BindImageMemoryFn>(load(c"vkBindImageMemory")?),
+        })
+    }
+
+    /// Imports an image.
+    ///
+    /// Vulkan keeps its own reference to the dmabuf, so the framebuffer may be reused by the camera while the image
+    /// exists. The image then shows the new frame contents once the buffer is filled again. Use
+    /// [VulkanImageImporter::import_framebuffer()] to import a framebuffer without `unsafe`.
+    ///
+    /// # Safety
+    ///
+    /// The file descriptor of `desc.planes` must be an open dmabuf for the duration of the call, i.e. one returned by
+    /// [AsFrameBuffer::plane_groups()] of a framebuffer that is still alive.
+    pub unsafe fn import(&self, desc: &VulkanImageDesc) -> Result<VulkanImage, VulkanError> {
+        let format = desc.format()?;
+        let (fd, layouts) = desc.plane_layouts()?;
+
+        let modifier_info = VkImageDrmFormatModifierExplicitCreateInfoEXT {
+            s_type: VK_STRUCTURE_TYPE_IMAGE_DRM_FORMAT_MODIFIER_EXPLICIT_CREATE_INFO_EXT,
+            p_next: ptr::null(),
+            drm_format_modifier: drm_modifier(desc.pixel_format),
+            drm_format_modifier_plane_count: layouts.len() as _,
+            p_plane_layouts: layouts.as_ptr(),
+        };
+        let external_info = VkExternalMemoryImageCreateInfo {
+            s_type: VK_STRUCTURE_TYPE_EXTERNAL_MEMORY_IMAGE_CREATE_INFO,
+            p_next: ptr::addr_of!(modifier_info).cast(),
+            handle_types: VK_EXTERNAL_MEMORY_HANDLE_TYPE_DMA_BUF_BIT_EXT,
+        };
+        let create_info = VkImageCreateInfo {
+            s_type: VK_STRUCTURE_TYPE_IMAGE_CREATE_INFO,
+            p_next: ptr::addr_of!(external_info).cast(),
+            flags: desc.flags,
+            image_type: VK_IMAGE_TYPE_2D,
+            format,
+            extent: VkExtent3D {
+                width: desc.size.width,
+                height: desc.size.height,
+                depth: 1,
+            },
+            mip_levels: 1,
+            array_layers: 1,
+            samples: VK_SAMPLE_COUNT_1_BIT,
+            tiling: VK_IMAGE_TILING_DRM_FORMAT_MODIFIER_EXT,
+            usage: desc.usage,
+            sharing_mode: VK_SHARING_MODE_EXCLUSIVE,
+            queue_family_index_count: 0,
+            p_queue_family_indices: ptr::null(),
+            initial_layout: VK_IMAGE_LAYOUT_UNDEFINED,
+        };
+
+        let mut image = 0;
+        let ret = unsafe { (self.create_image)(self.device, &create_info, ptr::null(), &mut image) };
+        if ret != VK_SUCCESS {
+            return Err(VulkanError::Call {
+                call: "vkCreateImage",
+                result: ret,
+            });
+        }
+
+        // Cleans up on errors from here on
+        let mut out = VulkanImage {
+            device: self.device,
+            image,
+            memory: 0,
+            format,
+            destroy_image: self.destroy_image,
+            free_memory: self.free_memory,
+        };
+        out.memory = self.import_memory(image, fd)?;
+
+        let ret = unsafe { (self.bind_image_memory)(self.device, image, out.memory, 0) };
+        if ret != VK_SUCCESS {
+            return Err(VulkanError::Call {
+                call: "vkBindImageMemory",
+                result: ret,
+            });
+        }
+
+        Ok(out)
+    }
+
+    /// Imports a framebuffer of a stream with the given configuration, see [VulkanImageDesc::new()].
+    pub fn import_framebuffer(
+        &self,
+        fb: &impl AsFrameBuffer,
+        cfg: &StreamConfigurationRef<'_>,
+        usage: VkImageUsageFlags,
+    ) -> Result<VulkanImage, VulkanError> {
+        // Safety: planes of `fb` are backed by its dmabufs, which stay open while `fb` is borrowed
+        unsafe { self.import(&VulkanImageDesc::new(fb, cfg, usage)) }
+    }
+
+    fn import_memory(&self, image: VkImage, fd: i32) -> Result<VkDeviceMemory, VulkanError> {
+        let mut requirements = VkMemoryRequirements::default();
+        unsafe { (self.get_image_memory_requirements)(self.device, image, &mut requirements) };
+
+        let mut fd_properties = VkMemoryFdPropertiesKHR {
+            s_type: VK_STRUCTURE_TYPE_MEMORY_FD_PROPERTIES_KHR,
+            p_next: ptr::null_mut(),
+            memory_type_bits: 0,
+        };
+        let ret = unsafe {
+            (self.get_memory_fd_properties)(
+                self.device,
+                VK_EXTERNAL_MEMORY_HANDLE_TYPE_DMA_BUF_BIT_EXT,
+                fd,
+                &mut fd_properties,
+            )
+        };
+        if ret != VK_SUCCESS {
+            return Err(VulkanError::Call {
+                call: "vkGetMemoryFdPropertiesKHR",
+                result: ret,
+            });
+        }
+
+        let memory_types = requirements.memory_type_bits & fd_properties.memory_type_bits;
+        if memory_types == 0 {
+            return Err(VulkanError::NoMemoryType);
+        }
+
+        // Vulkan takes ownership of the imported file descriptor on success only. `fd` is open as required by
+        // VulkanImageImporter::import().
+        let owned_fd = unsafe { BorrowedFd::borrow_raw(fd) }.try_clone_to_owned()?;
+        let dedicated_info = VkMemoryDedicatedAllocateInfo {
+            s_type: VK_STRUCTURE_TYPE_MEMORY_DEDICATED_ALLOCATE_INFO,
+            p_next: ptr::null(),
+            image,
+            buffer: 0,
+        };
+        let import_info = VkImportMemoryFdInfoKHR {
+            s_type: VK_STRUCTURE_TYPE_IMPORT_MEMORY_FD_INFO_KHR,
+            p_next: ptr::addr_of!(dedicated_info).cast(),
+            handle_type: VK_EXTERNAL_MEMORY_HANDLE_TYPE_DMA_BUF_BIT_EXT,
+            fd: owned_fd.as_raw_fd(),
+        };
+        let allocate_info = VkMemoryAllocateInfo {
+            s_type: VK_STRUCTURE_TYPE_MEMORY_ALLOCATE_INFO,
+            p_next: ptr::addr_of!(import_info).cast(),
+            allocation_size: requirements.size,
+            memory_type_index: memory_types.trailing_zeros(),
+        };
+
+        let mut memory = 0;
+        let ret = unsafe { (self.allocate_memory)(self.device, &allocate_info, ptr::null(), &mut memory) };
+        if ret != VK_SUCCESS {
+            return Err(VulkanError::Call {
+                call: "vkAllocateMemory",
+                result: ret,
+            });
+        }
+        let _ = owned_fd.into_raw_fd();
+
+        Ok(memory)
+    }
+}
+
+/// An imported `VkImage` with its dedicated memory, both destroyed on drop.
+#[derive(Debug)]
+pub struct VulkanImage {
+    device: VkDevice,
+    image: VkImage,
+    memory: VkDeviceMemory,
+    format: VkFormat,
+    destroy_image: DestroyImageFn,
+    free_memory: FreeMemoryFn,
+}
+
+impl VulkanImage {
+    /// Returns raw image handle.
+    pub fn handle(&self) -> VkImage {
+        self.image
+    }
+
+    /// Returns raw handle of the imported memory.
+    pub fn memory(&self) -> VkDeviceMemory {
+        self.memory
+    }
+
+    pub fn format(&self) -> VkFormat {
+        self.format
+    }
+}
+
+impl Drop for VulkanImage {
+    fn drop(&mut self) {
+        unsafe {
+            (self.destroy_image)(self.device, self.image, ptr::null());
+            if self.memory != 0 {
+                (self.free_memory)(self.device, self.memory, ptr::null());
+            }
+        }
+    }
+}
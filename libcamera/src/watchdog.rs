@@ -0,0 +1,149 @@
+//! Detection of requests that do not complete in time.
+//!
+//! Misconfigured or stalled pipelines often stop completing requests without reporting any error. [RequestWatchdog]
+//! is a [RequestObserver] which tracks queued requests and reports each one exceeding the timeout once, from its own
+//! thread, so no external timer is needed.
+
+use std::{
+    collections::HashMap,
+    io,
+    sync::{mpsc, Arc, Condvar, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::{observer::RequestObserver, request::Request};
+
+/// A queued request which did not complete within the timeout of [RequestWatchdog].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestTimeout {
+    /// Cookie of the request, see [Request::cookie()].
+    pub cookie: u64,
+    /// Time since the request was queued.
+    pub elapsed: Duration,
+}
+
+struct PendingRequest {
+    cookie: u64,
+    queued: Instant,
+    reported: bool,
+}
+
+#[derive(Default)]
+struct WatchdogState {
+    /// Queued requests by their pointer.
+    pending: HashMap<usize, PendingRequest>,
+    stop: bool,
+}
+
+type Shared = Arc<(Mutex<WatchdogState>, Condvar)>;
+
+/// Reports requests that are not completed within a timeout.
+///
+/// Register it with [ActiveCamera::add_request_observer()](crate::camera::ActiveCamera::add_request_observer). The
+/// watchdog thread is stopped once the observer is removed or the camera is dropped.
+pub struct RequestWatchdog {
+    shared: Shared,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RequestWatchdog {
+    /// Creates a watchdog, which calls `on_timeout` in the watchdog thread for each request queued for longer than
+    /// `timeout`.
+    pub fn new(timeout: Duration, on_timeout: impl FnMut(&RequestTimeout) + Send + 'static) -> io::Result<Self> {
+        let shared = Shared::default();
+        let thread = thread::Builder::new().name("libcamera-watchdog".into()).spawn({
+            let shared = shared.clone();
+            move || Self::run(&shared, timeout, on_timeout)
+        })?;
+        Ok(Self {
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    /// Creates a watchdog, which sends timed out requests to the returned channel.
+    pub fn channel(timeout: Duration) -> io::Result<(Self, mpsc::Receiver<RequestTimeout>)> {
+        let (tx, rx) = mpsc::channel();
+        let watchdog = Self::new(timeout, move |t| {
+            let _ = tx.send(*t);
+        })?;
+        Ok((watchdog, rx))
+    }
+
+    fn run(shared: &Shared, timeout: Duration, mut on_timeout: impl FnMut(&RequestTimeout)) {
+        let (state, cond) = &**shared;
+        let mut guard = state.lock().unwrap();
+        loop {
+            if guard.stop {
+                return;
+            }
+
+            let now = Instant::now();
+            let mut expired = Vec::new();
+            let mut next_deadline = None::<Instant>;
+            for req in guard.pending.values_mut().filter(|req| !req.reported) {
+                let deadline = req.queued + timeout;
+                if deadline <= now {
+                    req.reported = true;
+                    expired.push(RequestTimeout {
+                        cookie: req.cookie,
+                        elapsed: now - req.queued,
+                    });
+                } else {
+                    next_deadline = Some(next_deadline.map_or(deadline, |d| d.min(deadline)));
+                }
+            }
+
+            if !expired.is_empty() {
+                // callback is called unlocked, so that it does not delay queueing and completion of requests
+                drop(guard);
+                for t in expired.iter() {
+                    on_timeout(t);
+                }
+                guard = state.lock().unwrap();
+                continue;
+            }
+
+            guard = match next_deadline {
+                Some(deadline) => cond.wait_timeout(guard, deadline - now).unwrap().0,
+                None => cond.wait(guard).unwrap(),
+            };
+        }
+    }
+}
+
+impl RequestObserver for RequestWatchdog {
+    fn on_queue(&mut self, req: &Request) {
+        let (state, cond) = &*self.shared;
+        state.lock().unwrap().pending.insert(
+            req.ptr.as_ptr() as usize,
+            PendingRequest {
+                cookie: req.cookie(),
+                queued: Instant::now(),
+                reported: false,
+            },
+        );
+        cond.notify_one();
+    }
+
+    fn on_complete(&mut self, req: &Request) {
+        let (state, _) = &*self.shared;
+        state.lock().unwrap().pending.remove(&(req.ptr.as_ptr() as usize));
+    }
+
+    fn on_queue_failed(&mut self, req: &Request) {
+        self.on_complete(req);
+    }
+}
+
+impl Drop for RequestWatchdog {
+    fn drop(&mut self) {
+        let (state, cond) = &*self.shared;
+        state.lock().unwrap().stop = true;
+        cond.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! Fast YUV to RGB conversion.
+//!
+//! [YuvConverter] converts `NV12`, `NV21`, `YUV420` and `YUYV` frames into packed RGB, RGBA or BGR using BT.601
+//! full-range coefficients in 6-bit fixed point. Rows are processed 8 pixels at a time with SSE2 on x86_64 and NEON on
+//! aarch64, both of which are always available on these architectures, so no runtime detection is needed. Other
+//! architectures use a scalar implementation producing identical results.
+
+use drm_fourcc::DrmFourcc;
+
+use crate::{
+    framebuffer::AsFrameBuffer, framebuffer_map::MemoryMappedFrameBuffer, geometry::Size, pixel_format::PixelFormat,
+    rgb::RgbError, stream::StreamConfigurationRef,
+};
+
+/// Fixed point coefficients, scaled by 64.
+const CR_R: i16 = 90;
+const CB_G: i16 = 22;
+const CR_G: i16 = 46;
+const CB_B: i16 = 113;
+
+/// Channel order of converted pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RgbLayout {
+    /// R, G, B
+    Rgb,
+    /// R, G, B, A with opaque alpha
+    Rgba,
+    /// B, G, R
+    Bgr,
+}
+
+impl RgbLayout {
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            Self::Rgb | Self::Bgr => 3,
+            Self::Rgba => 4,
+        }
+    }
+}
+
+/// Converts YUV frames of a single stream into packed RGB.
+#[derive(Debug, Clone, Copy)]
+pub struct YuvConverter {
+    format: PixelFormat,
+    size: Size,
+    stride: u32,
+    layout: RgbLayout,
+}
+
+impl YuvConverter {
+    pub fn new(format: PixelFormat, size: Size, stride: u32, layout: RgbLayout) -> Self {
+        Self {
+            format,
+            size,
+            stride,
+            layout,
+        }
+    }
+
+    /// Creates converter for frames produced by the given (validated) stream configuration.
+    pub fn from_stream_config(cfg: &StreamConfigurationRef<'_>, layout: RgbLayout) -> Self {
+        Self::new(cfg.get_pixel_format(), cfg.get_size(), cfg.get_stride(), layout)
+    }
+
+    /// Returns `true` if frames of the configured pixel format can be converted.
+    pub fn is_supported(&self) -> bool {
+        matches!(
+            DrmFourcc::try_from(self.format.fourcc()),
+            Ok(DrmFourcc::Nv12 | DrmFourcc::Nv21 | DrmFourcc::Yuv420 | DrmFourcc::Yuyv)
+        )
+    }
+
+    /// Number of bytes of a converted frame without padding between rows.
+    pub fn output_len(&self) -> usize {
+        self.size.width as usize * self.size.height as usize * self.layout.bytes_per_pixel()
+    }
+
+    /// Converts memory mapped framebuffer.
+    pub fn convert_buffer<T: AsFrameBuffer>(&self, fb: &MemoryMappedFrameBuffer<T>) -> Result<Vec<u8>, RgbError> {
+        self.convert(&fb.data_used())
+    }
+
+    /// Converts frame planes into a new buffer without padding between rows.
+    pub fn convert(&self, planes: &[&[u8]]) -> Result<Vec<u8>, RgbError> {
+        let mut out = vec![0; self.output_len()];
+        let out_stride = self.size.width as usize * self.layout.bytes_per_pixel();
+        self.convert_into(planes, &mut out, out_stride)?;
+        Ok(out)
+    }
+
+    /// Converts frame planes into an existing buffer with `out_stride` bytes between the starts of rows, i.e. a mapped
+    /// texture.
+    pub fn convert_into(&self, planes: &[&[u8]], out: &mut [u8], out_stride: usize) -> Result<(), RgbError> {
+        let fourcc = DrmFourcc::try_from(self.format.fourcc()).map_err(|_| RgbError::UnsupportedFormat(self.format))?;
+        let (width, height, stride) = (
+            self.size.width as usize,
+            self.size.height as usize,
+            self.stride as usize,
+        );
+        let bpp = self.layout.bytes_per_pixel();
+        if height == 0 || width == 0 {
+            return Ok(());
+        }
+
+        let plane = |index: usize, len: usize| -> Result<&[u8], RgbError> {
+            let data = planes.get(index).ok_or(RgbError::MissingPlanes {
+                expected: index + 1,
+                found: planes.len(),
+            })?;
+            if data.len() < len {
+                Err(RgbError::PlaneTooShort {
+                    index,
+                    len: data.len(),
+                    expected: len,
+                })
+            } else {
+                Ok(data)
+            }
+        };
+        // Minimum plane length for `rows` lines of `row_len` bytes
+        let plane_len = |rows: usize, stride: usize, row_len: usize| rows.saturating_sub(1) * stride + row_len;
+
+        let out_len = plane_len(height, out_stride, width * bpp);
+        if out.len() < out_len || out_stride < width * bpp {
+            return Err(RgbError::OutputTooShort {
+                len: out.len(),
+                expected: out_len,
+            });
+        }
+
+        // Chroma upsampled to full width
+        let mut u = vec![0; width];
+        let mut v = vec![0; width];
+        match fourcc {
+            DrmFourcc::Nv12 | DrmFourcc::Nv21 => {
+                let chroma_len = width.div_ceil(2) * 2;
+                let luma = plane(0, plane_len(height, stride, width))?;
+                let chroma = plane(1, plane_len(height.div_ceil(2), stride, chroma_len))?;
+                let (u_off, v_off) = if fourcc == DrmFourcc::Nv12 { (0, 1) } else { (1, 0) };
+                for y in 0..height {
+                    if y % 2 == 0 {
+                        let uv = &chroma[(y / 2) * stride..][..chroma_len];
+                        for x in 0..width {
+                            u[x] = uv[(x & !1) + u_off];
+                            v[x] = uv[(x & !1) + v_off];
+                        }
+                    }
+                    convert_row(
+                        &luma[y * stride..][..width],
+                        &u,
+                        &v,
+                        &mut out[y * out_stride..][..width * bpp],
+                        self.layout,
+                    );
+                }
+            }
+            DrmFourcc::Yuv420 => {
+                let (chroma_stride, chroma_len) = (stride / 2, width.div_ceil(2));
+                let luma = plane(0, plane_len(height, stride, width))?;
+                let cb = plane(1, plane_len(height.div_ceil(2), chroma_stride, chroma_len))?;
+                let cr = plane(2, plane_len(height.div_ceil(2), chroma_stride, chroma_len))?;
+                for y in 0..height {
+                    if y % 2 == 0 {
+                        let offset = (y / 2) * chroma_stride;
+                        for x in 0..width {
+                            u[x] = cb[offset + x / 2];
+                            v[x] = cr[offset + x / 2];
+                        }
+                    }
+                    convert_row(
+                        &luma[y * stride..][..width],
+                        &u,
+                        &v,
+                        &mut out[y * out_stride..][..width * bpp],
+                        self.layout,
+                    );
+                }
+            }
+            DrmFourcc::Yuyv => {
+                let row_len = width.div_ceil(2) * 4;
+                let data = plane(0, plane_len(height, stride, row_len))?;
+                let mut luma = vec![0; width];
+                for y in 0..height {
+                    let row = &data[y * stride..][..row_len];
+                    for x in 0..width {
+                        luma[x] = row[x * 2];
+                        u[x] = row[(x & !1) * 2 + 1];
+                        v[x] = row[(x & !1) * 2 + 3];
+                    }
+                    convert_row(&luma, &u, &v, &mut out[y * out_stride..][..width * bpp], self.layout);
+                }
+            }
+            _ => return Err(RgbError::UnsupportedFormat(self.format)),
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts a row of pixels with full width chroma.
+fn convert_row(y: &[u8], u: &[u8], v: &[u8], out: &mut [u8], layout: RgbLayout) {
+    let done = simd::convert_row(y, u, v, out, layout);
+    let bpp = layout.bytes_per_pixel();
+    for x in done..y.len() {
+        let (y, u, v) = ((y[x] as i32) << 6, u[x] as i32 - 128, v[x] as i32 - 128);
+        let [r, g, b] = [
+            y + CR_R as i32 * v,
+            y - CB_G as i32 * u - CR_G as i32 * v,
+            y + CB_B as i32 * u,
+        ]
+        .map(|c| ((c + 32) >> 6).clamp(0, 255) as u8);
+        let px = &mut out[x * bpp..][..bpp];
+        match layout {
+            RgbLayout::Rgb => px.copy_from_slice(&[r, g, b]),
+            RgbLayout::Rgba => px.copy_from_slice(&[r, g, b, 255]),
+            RgbLayout::Bgr => px.copy_from_slice(&[b, g, r]),
+        }
+    }
+}
+
+/// Converts the largest multiple of 8 pixels and returns their number.
+#[cfg(target_arch = "x86_64")]
+mod simd {
+    use core::arch::x86_64::*;
+
+    use super::{RgbLayout, CB_B, CB_G, CR_G, CR_R};
+
+    pub(super) fn convert_row(y: &[u8], u: &[u8], v: &[u8], out: &mut [u8], layout: RgbLayout) -> usize {
+        let bpp = layout.bytes_per_pixel();
+        let blocks = y.len() / 8;
+        for block in 0..blocks {
+            let x = block * 8;
+            // SAFETY: SSE2 is part of the x86_64 baseline and all loads are within the 8 pixels of this block
+            let [r, g, b] = unsafe { convert8(y[x..].as_ptr(), u[x..].as_ptr(), v[x..].as_ptr()) };
+            let px = &mut out[x * bpp..][..8 * bpp];
+            for i in 0..8 {
+                match layout {
+                    RgbLayout::Rgb => px[i * 3..][..3].copy_from_slice(&[r[i], g[i], b[i]]),
+                    RgbLayout::Rgba => px[i * 4..][..4].copy_from_slice(&[r[i], g[i], b[i], 255]),
+                    RgbLayout::Bgr => px[i * 3..][..3].copy_from_slice(&[b[i], g[i], r[i]]),
+                }
+            }
+        }
+        blocks * 8
+    }
+
+    unsafe fn convert8(y: *const u8, u: *const u8, v: *const u8) -> [[u8; 8]; 3] {
+        let zero = _mm_setzero_si128();
+        let load = |p: *const u8| _mm_unpacklo_epi8(_mm_loadl_epi64(p.cast()), zero);
+        let bias = _mm_set1_epi16(128);
+        let y = _mm_slli_epi16::<6>(load(y));
+        let u = _mm_sub_epi16(load(u), bias);
+        let v = _mm_sub_epi16(load(v), bias);
+        let mul = |a: __m128i, c: i16| _mm_mullo_epi16(a, _mm_set1_epi16(c));
+
+        let r = _mm_add_epi16(y, mul(v, CR_R));
+        let g = _mm_sub_epi16(_mm_sub_epi16(y, mul(u, CB_G)), mul(v, CR_G));
+        let b = _mm_add_epi16(y, mul(u, CB_B));
+
+        let round = _mm_set1_epi16(32);
+        [r, g, b].map(|c| {
+            let c = _mm_packus_epi16(_mm_srai_epi16::<6>(_mm_add_epi16(c, round)), zero);
+            let mut out = [0u8; 8];
+            _mm_storel_epi64(out.as_mut_ptr().cast(), c);
+            out
+        })
+    }
+}
+
+/// Converts the largest multiple of 8 pixels and returns their number.
+#[cfg(target_arch = "aarch64")]
+mod simd {
+    use core::arch::aarch64::*;
+
+    use super::{RgbLayout, CB_B, CB_G, CR_G, CR_R};
+
+    pub(super) fn convert_row(y: &[u8], u: &[u8], v: &[u8], out: &mut [u8], layout: RgbLayout) -> usize {
+        let bpp = layout.bytes_per_pixel();
+        let blocks = y.len() / 8;
+        for block in 0..blocks {
+            let x = block * 8;
+            let px = &mut out[x * bpp..][..8 * bpp];
+            // SAFETY: NEON is part of the aarch64 baseline and all accesses are within the 8 pixels of this block
+            unsafe {
+                let [r, g, b] = convert8(y[x..].as_ptr(), u[x..].as_ptr(), v[x..].as_ptr());
+                match layout {
+                    RgbLayout::Rgb => vst3_u8(px.as_mut_ptr(), uint8x8x3_t(r, g, b)),
+                    RgbLayout::Rgba => vst4_u8(px.as_mut_ptr(), uint8x8x4_t(r, g, b, vdup_n_u8(255))),
+                    RgbLayout::Bgr => vst3_u8(px.as_mut_ptr(), uint8x8x3_t(b, g, r)),
+                }
+            }
+        }
+        blocks * 8
+    }
+
+    unsafe fn convert8(y: *const u8, u: *const u8, v: *const u8) -> [uint8x8_t; 3] {
+        let load = |p: *const u8| vreinterpretq_s16_u16(vmovl_u8(vld1_u8(p)));
+        let bias = vdupq_n_s16(128);
+        let y = vshlq_n_s16::<6>(load(y));
+        let u = vsubq_s16(load(u), bias);
+        let v = vsubq_s16(load(v), bias);
+
+        let r = vaddq_s16(y, vmulq_n_s16(v, CR_R));
+        let g = vsubq_s16(vsubq_s16(y, vmulq_n_s16(u, CB_G)), vmulq_n_s16(v, CR_G));
+        let b = vaddq_s16(y, vmulq_n_s16(u, CB_B));
+
+        // Rounding shift with unsigned saturation
+        [r, g, b].map(|c| vqrshrun_n_s16::<6>(c))
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod simd {
+    use super::RgbLayout;
+
+    pub(super) fn convert_row(_y: &[u8], _u: &[u8], _v: &[u8], _out: &mut [u8], _layout: RgbLayout) -> usize {
+        0
+    }
+}
+
+impl<T: AsFrameBuffer> MemoryMappedFrameBuffer<T> {
+    /// Converts YUV framebuffer contents into packed RGB, see [YuvConverter].
+    pub fn yuv_to_rgb(&self, converter: &YuvConverter) -> Result<Vec<u8>, RgbError> {
+        converter.convert_buffer(self)
+    }
+}
@@ -0,0 +1,71 @@
+//! Requires a camera supporting two streams, i.e. the `vimc` virtual driver (`modprobe vimc`). Skipped otherwise.
+
+use std::{sync::mpsc, time::Duration};
+
+use libcamera::{
+    camera_manager::CameraManager,
+    framebuffer::AsFrameBuffer,
+    framebuffer_allocator::{FrameBuffer, FrameBufferAllocator},
+    request::RequestStatus,
+    stream::StreamRole,
+};
+
+#[test]
+fn reallocating_one_stream_keeps_other_stream_buffers() {
+    let mgr = CameraManager::new().unwrap();
+    let cameras = mgr.cameras();
+    let Some(cam) = cameras.get(0) else {
+        eprintln!("No cameras found, skipping");
+        return;
+    };
+
+    let mut cam = cam.acquire().unwrap();
+    let Some(mut cfgs) = cam.generate_configuration(&[StreamRole::ViewFinder, StreamRole::VideoRecording]) else {
+        eprintln!("Camera does not support two streams, skipping");
+        return;
+    };
+    if cfgs.validate().is_invalid() || cfgs.len() < 2 {
+        eprintln!("Camera does not support two streams, skipping");
+        return;
+    }
+    cam.configure(&mut cfgs).unwrap();
+
+    let first = cfgs.get(0).unwrap().stream().unwrap();
+    let second = cfgs.get(1).unwrap().stream().unwrap();
+    let mut alloc = FrameBufferAllocator::new(&cam);
+    let first_buffers = alloc.alloc(&first).unwrap();
+    let mut second_buffers = alloc.alloc(&second).unwrap().into_iter();
+    let second_layouts = alloc.buffers(&second);
+
+    // Buffer of the second stream stays attached to a request while the first stream is reallocated
+    let mut req = cam.create_request(None).unwrap();
+    req.add_buffer(&second, second_buffers.next().unwrap()).unwrap();
+
+    drop(first_buffers);
+    alloc.free(&first).unwrap();
+    assert!(!alloc.is_allocated(&first));
+    assert!(alloc.is_allocated(&second));
+
+    let first_buffers = alloc.alloc(&first).unwrap();
+    assert!(alloc.is_allocated(&first));
+    assert!(alloc.is_allocated(&second));
+    assert_eq!(alloc.buffers(&second), second_layouts);
+    assert_eq!(alloc.buffers_in_use(&second), second_layouts.len());
+
+    req.add_buffer(&first, first_buffers.into_iter().next().unwrap())
+        .unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    cam.on_request_completed(move |req| {
+        let _ = tx.send(req);
+    });
+    cam.start(None).unwrap();
+
+    cam.queue_request(req).unwrap();
+    let req = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(req.status(), RequestStatus::Complete);
+    let fb = req.buffer::<FrameBuffer>(&second).unwrap();
+    assert!(fb.metadata().is_some_and(|m| m.is_success()));
+
+    cam.stop().unwrap();
+}
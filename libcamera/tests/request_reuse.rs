@@ -0,0 +1,47 @@
+//! Requires a camera, i.e. the `vimc` virtual driver (`modprobe vimc`). Skipped if no camera is available.
+
+use std::{sync::mpsc, time::Duration};
+
+use libcamera::{
+    camera_manager::CameraManager, framebuffer_allocator::FrameBufferAllocator, request::ReuseFlag, stream::StreamRole,
+    Error,
+};
+
+#[test]
+fn completed_request_is_rejected_until_reused() {
+    let mgr = CameraManager::new().unwrap();
+    let cameras = mgr.cameras();
+    let Some(cam) = cameras.get(0) else {
+        eprintln!("No cameras found, skipping");
+        return;
+    };
+
+    let mut cam = cam.acquire().unwrap();
+    let mut cfgs = cam.generate_configuration(&[StreamRole::ViewFinder]).unwrap();
+    assert!(!cfgs.validate().is_invalid());
+    cam.configure(&mut cfgs).unwrap();
+
+    let stream = cfgs.get(0).unwrap().stream().unwrap();
+    let mut alloc = FrameBufferAllocator::new(&cam);
+    let buffer = alloc.alloc(&stream).unwrap().into_iter().next().unwrap();
+    let mut req = cam.create_request(None).unwrap();
+    req.add_buffer(&stream, buffer).unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    cam.on_request_completed(move |req| {
+        let _ = tx.send(req);
+    });
+    cam.start(None).unwrap();
+
+    cam.queue_request(req).unwrap();
+    let mut req = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    // Reused requests are accepted again
+    req.reuse(ReuseFlag::REUSE_BUFFERS);
+    cam.queue_request(req).unwrap();
+    let req = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    assert!(matches!(cam.queue_request(req), Err(Error::AlreadyQueued)));
+
+    cam.stop().unwrap();
+}
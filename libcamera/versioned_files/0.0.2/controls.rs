@@ -1,7 +1,10 @@
 use std::ops::{Deref, DerefMut};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 #[allow(unused_imports)]
-use crate::control::{Control, Property, ControlEntry, DynControlEntry};
+use crate::control::{
+    Control, ControlDescriptor, ControlDirection, Property, ControlEntry,
+    DynControlEntry, EnumValueDescriptor,
+};
 use crate::control_value::{ControlValue, ControlValueError};
 #[allow(unused_imports)]
 use crate::geometry::{Rectangle, Size};
@@ -386,10 +389,278 @@ pub enum ControlId {
     #[cfg(feature = "vendor_draft")]
     TestPatternMode = TEST_PATTERN_MODE,
 }
+impl ControlId {
+    /// Returns all known ids.
+    pub fn all() -> &'static [ControlId] {
+        &[
+            ControlId::AeEnable,
+            ControlId::AeLocked,
+            ControlId::AeMeteringMode,
+            ControlId::AeConstraintMode,
+            ControlId::AeExposureMode,
+            ControlId::ExposureValue,
+            ControlId::ExposureTime,
+            ControlId::AnalogueGain,
+            ControlId::Brightness,
+            ControlId::Contrast,
+            ControlId::Lux,
+            ControlId::AwbEnable,
+            ControlId::AwbMode,
+            ControlId::AwbLocked,
+            ControlId::ColourGains,
+            ControlId::ColourTemperature,
+            ControlId::Saturation,
+            ControlId::SensorBlackLevels,
+            ControlId::Sharpness,
+            ControlId::FocusFoM,
+            ControlId::ColourCorrectionMatrix,
+            ControlId::ScalerCrop,
+            ControlId::DigitalGain,
+            ControlId::FrameDuration,
+            ControlId::FrameDurationLimits,
+            ControlId::SensorTemperature,
+            ControlId::SensorTimestamp,
+            ControlId::AfMode,
+            ControlId::AfRange,
+            ControlId::AfSpeed,
+            ControlId::AfMetering,
+            ControlId::AfWindows,
+            ControlId::AfTrigger,
+            ControlId::AfPause,
+            ControlId::LensPosition,
+            ControlId::AfState,
+            ControlId::AfPauseState,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::AePrecaptureTrigger,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::NoiseReductionMode,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::ColorCorrectionAberrationMode,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::AeState,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::AwbState,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::SensorRollingShutterSkew,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::LensShadingMapMode,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::SceneFlicker,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::PipelineDepth,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::MaxLatency,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::TestPatternMode,
+        ]
+    }
+    /// Returns name of the id as used in libcamera YAML definitions, i.e. `ExposureTime`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ControlId::AeEnable => "AeEnable",
+            ControlId::AeLocked => "AeLocked",
+            ControlId::AeMeteringMode => "AeMeteringMode",
+            ControlId::AeConstraintMode => "AeConstraintMode",
+            ControlId::AeExposureMode => "AeExposureMode",
+            ControlId::ExposureValue => "ExposureValue",
+            ControlId::ExposureTime => "ExposureTime",
+            ControlId::AnalogueGain => "AnalogueGain",
+            ControlId::Brightness => "Brightness",
+            ControlId::Contrast => "Contrast",
+            ControlId::Lux => "Lux",
+            ControlId::AwbEnable => "AwbEnable",
+            ControlId::AwbMode => "AwbMode",
+            ControlId::AwbLocked => "AwbLocked",
+            ControlId::ColourGains => "ColourGains",
+            ControlId::ColourTemperature => "ColourTemperature",
+            ControlId::Saturation => "Saturation",
+            ControlId::SensorBlackLevels => "SensorBlackLevels",
+            ControlId::Sharpness => "Sharpness",
+            ControlId::FocusFoM => "FocusFoM",
+            ControlId::ColourCorrectionMatrix => "ColourCorrectionMatrix",
+            ControlId::ScalerCrop => "ScalerCrop",
+            ControlId::DigitalGain => "DigitalGain",
+            ControlId::FrameDuration => "FrameDuration",
+            ControlId::FrameDurationLimits => "FrameDurationLimits",
+            ControlId::SensorTemperature => "SensorTemperature",
+            ControlId::SensorTimestamp => "SensorTimestamp",
+            ControlId::AfMode => "AfMode",
+            ControlId::AfRange => "AfRange",
+            ControlId::AfSpeed => "AfSpeed",
+            ControlId::AfMetering => "AfMetering",
+            ControlId::AfWindows => "AfWindows",
+            ControlId::AfTrigger => "AfTrigger",
+            ControlId::AfPause => "AfPause",
+            ControlId::LensPosition => "LensPosition",
+            ControlId::AfState => "AfState",
+            ControlId::AfPauseState => "AfPauseState",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::AePrecaptureTrigger => "AePrecaptureTrigger",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::NoiseReductionMode => "NoiseReductionMode",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::ColorCorrectionAberrationMode => "ColorCorrectionAberrationMode",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::AeState => "AeState",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::AwbState => "AwbState",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::SensorRollingShutterSkew => "SensorRollingShutterSkew",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::LensShadingMapMode => "LensShadingMapMode",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::SceneFlicker => "SceneFlicker",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::PipelineDepth => "PipelineDepth",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::MaxLatency => "MaxLatency",
+            #[cfg(feature = "vendor_draft")]
+            ControlId::TestPatternMode => "TestPatternMode",
+        }
+    }
+    /// Looks up id by its name as used in libcamera YAML definitions, i.e. `ExposureTime`.
+    ///
+    /// Returns [None] if name is unknown or the corresponding vendor feature is disabled.
+    pub fn from_name(name: &str) -> Option<ControlId> {
+        match name {
+            "AeEnable" => Some(ControlId::AeEnable),
+            "AeLocked" => Some(ControlId::AeLocked),
+            "AeMeteringMode" => Some(ControlId::AeMeteringMode),
+            "AeConstraintMode" => Some(ControlId::AeConstraintMode),
+            "AeExposureMode" => Some(ControlId::AeExposureMode),
+            "ExposureValue" => Some(ControlId::ExposureValue),
+            "ExposureTime" => Some(ControlId::ExposureTime),
+            "AnalogueGain" => Some(ControlId::AnalogueGain),
+            "Brightness" => Some(ControlId::Brightness),
+            "Contrast" => Some(ControlId::Contrast),
+            "Lux" => Some(ControlId::Lux),
+            "AwbEnable" => Some(ControlId::AwbEnable),
+            "AwbMode" => Some(ControlId::AwbMode),
+            "AwbLocked" => Some(ControlId::AwbLocked),
+            "ColourGains" => Some(ControlId::ColourGains),
+            "ColourTemperature" => Some(ControlId::ColourTemperature),
+            "Saturation" => Some(ControlId::Saturation),
+            "SensorBlackLevels" => Some(ControlId::SensorBlackLevels),
+            "Sharpness" => Some(ControlId::Sharpness),
+            "FocusFoM" => Some(ControlId::FocusFoM),
+            "ColourCorrectionMatrix" => Some(ControlId::ColourCorrectionMatrix),
+            "ScalerCrop" => Some(ControlId::ScalerCrop),
+            "DigitalGain" => Some(ControlId::DigitalGain),
+            "FrameDuration" => Some(ControlId::FrameDuration),
+            "FrameDurationLimits" => Some(ControlId::FrameDurationLimits),
+            "SensorTemperature" => Some(ControlId::SensorTemperature),
+            "SensorTimestamp" => Some(ControlId::SensorTimestamp),
+            "AfMode" => Some(ControlId::AfMode),
+            "AfRange" => Some(ControlId::AfRange),
+            "AfSpeed" => Some(ControlId::AfSpeed),
+            "AfMetering" => Some(ControlId::AfMetering),
+            "AfWindows" => Some(ControlId::AfWindows),
+            "AfTrigger" => Some(ControlId::AfTrigger),
+            "AfPause" => Some(ControlId::AfPause),
+            "LensPosition" => Some(ControlId::LensPosition),
+            "AfState" => Some(ControlId::AfState),
+            "AfPauseState" => Some(ControlId::AfPauseState),
+            #[cfg(feature = "vendor_draft")]
+            "AePrecaptureTrigger" => Some(ControlId::AePrecaptureTrigger),
+            #[cfg(feature = "vendor_draft")]
+            "NoiseReductionMode" => Some(ControlId::NoiseReductionMode),
+            #[cfg(feature = "vendor_draft")]
+            "ColorCorrectionAberrationMode" => {
+                Some(ControlId::ColorCorrectionAberrationMode)
+            }
+            #[cfg(feature = "vendor_draft")]
+            "AeState" => Some(ControlId::AeState),
+            #[cfg(feature = "vendor_draft")]
+            "AwbState" => Some(ControlId::AwbState),
+            #[cfg(feature = "vendor_draft")]
+            "SensorRollingShutterSkew" => Some(ControlId::SensorRollingShutterSkew),
+            #[cfg(feature = "vendor_draft")]
+            "LensShadingMapMode" => Some(ControlId::LensShadingMapMode),
+            #[cfg(feature = "vendor_draft")]
+            "SceneFlicker" => Some(ControlId::SceneFlicker),
+            #[cfg(feature = "vendor_draft")]
+            "PipelineDepth" => Some(ControlId::PipelineDepth),
+            #[cfg(feature = "vendor_draft")]
+            "MaxLatency" => Some(ControlId::MaxLatency),
+            #[cfg(feature = "vendor_draft")]
+            "TestPatternMode" => Some(ControlId::TestPatternMode),
+            _ => None,
+        }
+    }
+    /// Iterates over `(name, id)` pairs of all known ids.
+    pub fn names() -> impl Iterator<Item = (&'static str, ControlId)> {
+        Self::all().iter().map(|id| (id.name(), *id))
+    }
+    /// Returns whether the control can be set on requests, is reported in metadata, or both.
+    pub fn direction(&self) -> ControlDirection {
+        match self {
+            ControlId::AeEnable => ControlDirection::InOut,
+            ControlId::AeLocked => ControlDirection::Out,
+            ControlId::AeMeteringMode => ControlDirection::InOut,
+            ControlId::AeConstraintMode => ControlDirection::InOut,
+            ControlId::AeExposureMode => ControlDirection::InOut,
+            ControlId::ExposureValue => ControlDirection::InOut,
+            ControlId::ExposureTime => ControlDirection::InOut,
+            ControlId::AnalogueGain => ControlDirection::InOut,
+            ControlId::Brightness => ControlDirection::InOut,
+            ControlId::Contrast => ControlDirection::InOut,
+            ControlId::Lux => ControlDirection::Out,
+            ControlId::AwbEnable => ControlDirection::InOut,
+            ControlId::AwbMode => ControlDirection::InOut,
+            ControlId::AwbLocked => ControlDirection::Out,
+            ControlId::ColourGains => ControlDirection::InOut,
+            ControlId::ColourTemperature => ControlDirection::Out,
+            ControlId::Saturation => ControlDirection::InOut,
+            ControlId::SensorBlackLevels => ControlDirection::Out,
+            ControlId::Sharpness => ControlDirection::InOut,
+            ControlId::FocusFoM => ControlDirection::Out,
+            ControlId::ColourCorrectionMatrix => ControlDirection::InOut,
+            ControlId::ScalerCrop => ControlDirection::InOut,
+            ControlId::DigitalGain => ControlDirection::InOut,
+            ControlId::FrameDuration => ControlDirection::Out,
+            ControlId::FrameDurationLimits => ControlDirection::InOut,
+            ControlId::SensorTemperature => ControlDirection::Out,
+            ControlId::SensorTimestamp => ControlDirection::Out,
+            ControlId::AfMode => ControlDirection::InOut,
+            ControlId::AfRange => ControlDirection::InOut,
+            ControlId::AfSpeed => ControlDirection::InOut,
+            ControlId::AfMetering => ControlDirection::InOut,
+            ControlId::AfWindows => ControlDirection::InOut,
+            ControlId::AfTrigger => ControlDirection::InOut,
+            ControlId::AfPause => ControlDirection::InOut,
+            ControlId::LensPosition => ControlDirection::InOut,
+            ControlId::AfState => ControlDirection::Out,
+            ControlId::AfPauseState => ControlDirection::Out,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::AePrecaptureTrigger => ControlDirection::InOut,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::NoiseReductionMode => ControlDirection::InOut,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::ColorCorrectionAberrationMode => ControlDirection::InOut,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::AeState => ControlDirection::Out,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::AwbState => ControlDirection::Out,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::SensorRollingShutterSkew => ControlDirection::Out,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::LensShadingMapMode => ControlDirection::InOut,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::SceneFlicker => ControlDirection::Out,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::PipelineDepth => ControlDirection::Out,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::MaxLatency => ControlDirection::InOut,
+            #[cfg(feature = "vendor_draft")]
+            ControlId::TestPatternMode => ControlDirection::InOut,
+        }
+    }
+}
 /// Enable or disable the AE.
 ///
 /// \sa ExposureTime AnalogueGain
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AeEnable(pub bool);
 impl Deref for AeEnable {
     type Target = bool;
@@ -416,7 +687,9 @@ impl From<AeEnable> for ControlValue {
 impl ControlEntry for AeEnable {
     const ID: u32 = ControlId::AeEnable as _;
 }
-impl Control for AeEnable {}
+impl Control for AeEnable {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Report the lock status of a running AE algorithm.
 ///
 /// If the AE algorithm is locked the value shall be set to true, if it's
@@ -425,6 +698,7 @@ impl Control for AeEnable {}
 ///
 /// \sa AeEnable
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AeLocked(pub bool);
 impl Deref for AeLocked {
     type Target = bool;
@@ -451,12 +725,15 @@ impl From<AeLocked> for ControlValue {
 impl ControlEntry for AeLocked {
     const ID: u32 = ControlId::AeLocked as _;
 }
-impl Control for AeLocked {}
+impl Control for AeLocked {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Specify a metering mode for the AE algorithm to use. The metering
 /// modes determine which parts of the image are used to determine the
 /// scene brightness. Metering modes may be platform specific and not
 /// all metering modes may be supported.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AeMeteringMode {
     /// Centre-weighted metering mode.
@@ -483,12 +760,15 @@ impl From<AeMeteringMode> for ControlValue {
 impl ControlEntry for AeMeteringMode {
     const ID: u32 = ControlId::AeMeteringMode as _;
 }
-impl Control for AeMeteringMode {}
+impl Control for AeMeteringMode {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Specify a constraint mode for the AE algorithm to use. These determine
 /// how the measured scene brightness is adjusted to reach the desired
 /// target exposure. Constraint modes may be platform specific, and not
 /// all constraint modes may be supported.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AeConstraintMode {
     /// Default constraint mode. This mode aims to balance the exposure of different parts of the image so as to reach a reasonable average level. However, highlights in the image may appear over-exposed and lowlights may appear under-exposed.
@@ -515,12 +795,15 @@ impl From<AeConstraintMode> for ControlValue {
 impl ControlEntry for AeConstraintMode {
     const ID: u32 = ControlId::AeConstraintMode as _;
 }
-impl Control for AeConstraintMode {}
+impl Control for AeConstraintMode {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Specify an exposure mode for the AE algorithm to use. These specify
 /// how the desired total exposure is divided between the shutter time
 /// and the sensor's analogue gain. The exposure modes are platform
 /// specific, and not all exposure modes may be supported.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AeExposureMode {
     /// Default exposure mode.
@@ -547,7 +830,9 @@ impl From<AeExposureMode> for ControlValue {
 impl ControlEntry for AeExposureMode {
     const ID: u32 = ControlId::AeExposureMode as _;
 }
-impl Control for AeExposureMode {}
+impl Control for AeExposureMode {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Specify an Exposure Value (EV) parameter. The EV parameter will only be
 /// applied if the AE algorithm is currently enabled.
 ///
@@ -557,6 +842,7 @@ impl Control for AeExposureMode {}
 ///
 /// \sa AeEnable
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExposureValue(pub f32);
 impl Deref for ExposureValue {
     type Target = f32;
@@ -583,7 +869,9 @@ impl From<ExposureValue> for ControlValue {
 impl ControlEntry for ExposureValue {
     const ID: u32 = ControlId::ExposureValue as _;
 }
-impl Control for ExposureValue {}
+impl Control for ExposureValue {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Exposure time (shutter speed) for the frame applied in the sensor
 /// device. This value is specified in micro-seconds.
 ///
@@ -599,6 +887,7 @@ impl Control for ExposureValue {}
 /// control of which features should be automatically adjusted shouldn't
 /// better be handled through a separate AE mode control.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExposureTime(pub i32);
 impl Deref for ExposureTime {
     type Target = i32;
@@ -625,7 +914,9 @@ impl From<ExposureTime> for ControlValue {
 impl ControlEntry for ExposureTime {
     const ID: u32 = ControlId::ExposureTime as _;
 }
-impl Control for ExposureTime {}
+impl Control for ExposureTime {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Analogue gain value applied in the sensor device.
 /// The value of the control specifies the gain multiplier applied to all
 /// colour channels. This value cannot be lower than 1.0.
@@ -642,6 +933,7 @@ impl Control for ExposureTime {}
 /// control of which features should be automatically adjusted shouldn't
 /// better be handled through a separate AE mode control.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AnalogueGain(pub f32);
 impl Deref for AnalogueGain {
     type Target = f32;
@@ -668,11 +960,14 @@ impl From<AnalogueGain> for ControlValue {
 impl ControlEntry for AnalogueGain {
     const ID: u32 = ControlId::AnalogueGain as _;
 }
-impl Control for AnalogueGain {}
+impl Control for AnalogueGain {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Specify a fixed brightness parameter. Positive values (up to 1.0)
 /// produce brighter images; negative values (up to -1.0) produce darker
 /// images and 0.0 leaves pixels unchanged.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Brightness(pub f32);
 impl Deref for Brightness {
     type Target = f32;
@@ -699,10 +994,13 @@ impl From<Brightness> for ControlValue {
 impl ControlEntry for Brightness {
     const ID: u32 = ControlId::Brightness as _;
 }
-impl Control for Brightness {}
+impl Control for Brightness {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Specify a fixed contrast parameter. Normal contrast is given by the
 /// value 1.0; larger values produce images with more contrast.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Contrast(pub f32);
 impl Deref for Contrast {
     type Target = f32;
@@ -729,10 +1027,13 @@ impl From<Contrast> for ControlValue {
 impl ControlEntry for Contrast {
     const ID: u32 = ControlId::Contrast as _;
 }
-impl Control for Contrast {}
+impl Control for Contrast {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Report an estimate of the current illuminance level in lux. The Lux
 /// control can only be returned in metadata.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Lux(pub f32);
 impl Deref for Lux {
     type Target = f32;
@@ -759,11 +1060,14 @@ impl From<Lux> for ControlValue {
 impl ControlEntry for Lux {
     const ID: u32 = ControlId::Lux as _;
 }
-impl Control for Lux {}
+impl Control for Lux {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Enable or disable the AWB.
 ///
 /// \sa ColourGains
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AwbEnable(pub bool);
 impl Deref for AwbEnable {
     type Target = bool;
@@ -790,10 +1094,13 @@ impl From<AwbEnable> for ControlValue {
 impl ControlEntry for AwbEnable {
     const ID: u32 = ControlId::AwbEnable as _;
 }
-impl Control for AwbEnable {}
+impl Control for AwbEnable {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Specify the range of illuminants to use for the AWB algorithm. The modes
 /// supported are platform specific, and not all modes may be supported.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AwbMode {
     /// Search over the whole colour temperature range.
@@ -828,7 +1135,9 @@ impl From<AwbMode> for ControlValue {
 impl ControlEntry for AwbMode {
     const ID: u32 = ControlId::AwbMode as _;
 }
-impl Control for AwbMode {}
+impl Control for AwbMode {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Report the lock status of a running AWB algorithm.
 ///
 /// If the AWB algorithm is locked the value shall be set to true, if it's
@@ -837,6 +1146,7 @@ impl Control for AwbMode {}
 ///
 /// \sa AwbEnable
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AwbLocked(pub bool);
 impl Deref for AwbLocked {
     type Target = bool;
@@ -863,13 +1173,16 @@ impl From<AwbLocked> for ControlValue {
 impl ControlEntry for AwbLocked {
     const ID: u32 = ControlId::AwbLocked as _;
 }
-impl Control for AwbLocked {}
+impl Control for AwbLocked {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Pair of gain values for the Red and Blue colour channels, in that
 /// order. ColourGains can only be applied in a Request when the AWB is
 /// disabled.
 ///
 /// \sa AwbEnable
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColourGains(pub [f32; 2]);
 impl Deref for ColourGains {
     type Target = [f32; 2];
@@ -896,9 +1209,12 @@ impl From<ColourGains> for ControlValue {
 impl ControlEntry for ColourGains {
     const ID: u32 = ControlId::ColourGains as _;
 }
-impl Control for ColourGains {}
+impl Control for ColourGains {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Report the current estimate of the colour temperature, in kelvin, for this frame. The ColourTemperature control can only be returned in metadata.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColourTemperature(pub i32);
 impl Deref for ColourTemperature {
     type Target = i32;
@@ -925,11 +1241,14 @@ impl From<ColourTemperature> for ControlValue {
 impl ControlEntry for ColourTemperature {
     const ID: u32 = ControlId::ColourTemperature as _;
 }
-impl Control for ColourTemperature {}
+impl Control for ColourTemperature {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Specify a fixed saturation parameter. Normal saturation is given by
 /// the value 1.0; larger values produce more saturated colours; 0.0
 /// produces a greyscale image.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Saturation(pub f32);
 impl Deref for Saturation {
     type Target = f32;
@@ -956,12 +1275,15 @@ impl From<Saturation> for ControlValue {
 impl ControlEntry for Saturation {
     const ID: u32 = ControlId::Saturation as _;
 }
-impl Control for Saturation {}
+impl Control for Saturation {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Reports the sensor black levels used for processing a frame, in the
 /// order R, Gr, Gb, B. These values are returned as numbers out of a 16-bit
 /// pixel range (as if pixels ranged from 0 to 65535). The SensorBlackLevels
 /// control can only be returned in metadata.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SensorBlackLevels(pub [i32; 4]);
 impl Deref for SensorBlackLevels {
     type Target = [i32; 4];
@@ -988,7 +1310,9 @@ impl From<SensorBlackLevels> for ControlValue {
 impl ControlEntry for SensorBlackLevels {
     const ID: u32 = ControlId::SensorBlackLevels as _;
 }
-impl Control for SensorBlackLevels {}
+impl Control for SensorBlackLevels {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// A value of 0.0 means no sharpening. The minimum value means
 /// minimal sharpening, and shall be 0.0 unless the camera can't
 /// disable sharpening completely. The default value shall give a
@@ -998,6 +1322,7 @@ impl Control for SensorBlackLevels {}
 /// not allowed. Note also that sharpening is not applied to raw
 /// streams.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sharpness(pub f32);
 impl Deref for Sharpness {
     type Target = f32;
@@ -1024,7 +1349,9 @@ impl From<Sharpness> for ControlValue {
 impl ControlEntry for Sharpness {
     const ID: u32 = ControlId::Sharpness as _;
 }
-impl Control for Sharpness {}
+impl Control for Sharpness {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Reports a Figure of Merit (FoM) to indicate how in-focus the frame is.
 /// A larger FocusFoM value indicates a more in-focus frame. This control
 /// depends on the IPA to gather ISP statistics from the defined focus
@@ -1033,6 +1360,7 @@ impl Control for Sharpness {}
 /// implement a focus algorithm by the application, rather an indication of
 /// how in-focus a frame is.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FocusFoM(pub i32);
 impl Deref for FocusFoM {
     type Target = i32;
@@ -1059,13 +1387,16 @@ impl From<FocusFoM> for ControlValue {
 impl ControlEntry for FocusFoM {
     const ID: u32 = ControlId::FocusFoM as _;
 }
-impl Control for FocusFoM {}
+impl Control for FocusFoM {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// The 3x3 matrix that converts camera RGB to sRGB within the
 /// imaging pipeline. This should describe the matrix that is used
 /// after pixels have been white-balanced, but before any gamma
 /// transformation. The 3x3 matrix is stored in conventional reading
 /// order in an array of 9 floating point values.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColourCorrectionMatrix(pub [[f32; 3]; 3]);
 impl Deref for ColourCorrectionMatrix {
     type Target = [[f32; 3]; 3];
@@ -1092,7 +1423,9 @@ impl From<ColourCorrectionMatrix> for ControlValue {
 impl ControlEntry for ColourCorrectionMatrix {
     const ID: u32 = ControlId::ColourCorrectionMatrix as _;
 }
-impl Control for ColourCorrectionMatrix {}
+impl Control for ColourCorrectionMatrix {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Sets the image portion that will be scaled to form the whole of
 /// the final output image. The (x,y) location of this rectangle is
 /// relative to the PixelArrayActiveAreas that is being used. The units
@@ -1103,6 +1436,7 @@ impl Control for ColourCorrectionMatrix {}
 /// maximum valid value is given by the properties::ScalerCropMaximum
 /// property, and the two can be used to implement digital zoom.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScalerCrop(pub Rectangle);
 impl Deref for ScalerCrop {
     type Target = Rectangle;
@@ -1129,7 +1463,9 @@ impl From<ScalerCrop> for ControlValue {
 impl ControlEntry for ScalerCrop {
     const ID: u32 = ControlId::ScalerCrop as _;
 }
-impl Control for ScalerCrop {}
+impl Control for ScalerCrop {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Digital gain value applied during the processing steps applied
 /// to the image as captured from the sensor.
 ///
@@ -1144,6 +1480,7 @@ impl Control for ScalerCrop {}
 /// step to respect the received gain factor and shall report
 /// their total value in the request metadata.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DigitalGain(pub f32);
 impl Deref for DigitalGain {
     type Target = f32;
@@ -1170,11 +1507,14 @@ impl From<DigitalGain> for ControlValue {
 impl ControlEntry for DigitalGain {
     const ID: u32 = ControlId::DigitalGain as _;
 }
-impl Control for DigitalGain {}
+impl Control for DigitalGain {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// The instantaneous frame duration from start of frame exposure to start
 /// of next exposure, expressed in microseconds. This control is meant to
 /// be returned in metadata.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FrameDuration(pub i64);
 impl Deref for FrameDuration {
     type Target = i64;
@@ -1201,7 +1541,9 @@ impl From<FrameDuration> for ControlValue {
 impl ControlEntry for FrameDuration {
     const ID: u32 = ControlId::FrameDuration as _;
 }
-impl Control for FrameDuration {}
+impl Control for FrameDuration {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// The minimum and maximum (in that order) frame duration,
 /// expressed in microseconds.
 ///
@@ -1234,6 +1576,7 @@ impl Control for FrameDuration {}
 /// \todo Provide an explicit definition of default control values, for
 /// this and all other controls.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FrameDurationLimits(pub [i64; 2]);
 impl Deref for FrameDurationLimits {
     type Target = [i64; 2];
@@ -1260,7 +1603,9 @@ impl From<FrameDurationLimits> for ControlValue {
 impl ControlEntry for FrameDurationLimits {
     const ID: u32 = ControlId::FrameDurationLimits as _;
 }
-impl Control for FrameDurationLimits {}
+impl Control for FrameDurationLimits {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Temperature measure from the camera sensor in Celsius. This is typically
 /// obtained by a thermal sensor present on-die or in the camera module. The
 /// range of reported temperatures is device dependent.
@@ -1268,6 +1613,7 @@ impl Control for FrameDurationLimits {}
 /// The SensorTemperature control will only be returned in metadata if a
 /// themal sensor is present.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SensorTemperature(pub f32);
 impl Deref for SensorTemperature {
     type Target = f32;
@@ -1294,7 +1640,9 @@ impl From<SensorTemperature> for ControlValue {
 impl ControlEntry for SensorTemperature {
     const ID: u32 = ControlId::SensorTemperature as _;
 }
-impl Control for SensorTemperature {}
+impl Control for SensorTemperature {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// The time when the first row of the image sensor active array is exposed.
 ///
 /// The timestamp, expressed in nanoseconds, represents a monotonically
@@ -1306,6 +1654,7 @@ impl Control for SensorTemperature {}
 /// \todo Define how the sensor timestamp has to be used in the reprocessing
 /// use case.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SensorTimestamp(pub i64);
 impl Deref for SensorTimestamp {
     type Target = i64;
@@ -1332,11 +1681,14 @@ impl From<SensorTimestamp> for ControlValue {
 impl ControlEntry for SensorTimestamp {
     const ID: u32 = ControlId::SensorTimestamp as _;
 }
-impl Control for SensorTimestamp {}
+impl Control for SensorTimestamp {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Control to set the mode of the AF (autofocus) algorithm.
 ///
 /// An implementation may choose not to implement all the modes.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AfMode {
     /// The AF algorithm is in manual mode. In this mode it will never
@@ -1394,10 +1746,13 @@ impl From<AfMode> for ControlValue {
 impl ControlEntry for AfMode {
     const ID: u32 = ControlId::AfMode as _;
 }
-impl Control for AfMode {}
+impl Control for AfMode {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Control to set the range of focus distances that is scanned. An
 /// implementation may choose not to implement all the options here.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AfRange {
     /// A wide range of focus distances is scanned, all the way from
@@ -1427,13 +1782,16 @@ impl From<AfRange> for ControlValue {
 impl ControlEntry for AfRange {
     const ID: u32 = ControlId::AfRange as _;
 }
-impl Control for AfRange {}
+impl Control for AfRange {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Control that determines whether the AF algorithm is to move the lens
 /// as quickly as possible or more steadily. For example, during video
 /// recording it may be desirable not to move the lens too abruptly, but
 /// when in a preview mode (waiting for a still capture) it may be
 /// helpful to move the lens as quickly as is reasonably possible.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AfSpeed {
     /// Move the lens at its usual speed.
@@ -1456,10 +1814,13 @@ impl From<AfSpeed> for ControlValue {
 impl ControlEntry for AfSpeed {
     const ID: u32 = ControlId::AfSpeed as _;
 }
-impl Control for AfSpeed {}
+impl Control for AfSpeed {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Instruct the AF algorithm how it should decide which parts of the image
 /// should be used to measure focus.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AfMetering {
     /// The AF algorithm should decide for itself where it will measure focus.
@@ -1482,7 +1843,9 @@ impl From<AfMetering> for ControlValue {
 impl ControlEntry for AfMetering {
     const ID: u32 = ControlId::AfMetering as _;
 }
-impl Control for AfMetering {}
+impl Control for AfMetering {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Sets the focus windows used by the AF algorithm when AfMetering is set
 /// to AfMeteringWindows. The units used are pixels within the rectangle
 /// returned by the ScalerCropMaximum property.
@@ -1505,6 +1868,7 @@ impl Control for AfMetering {}
 /// the window where the focal distance for the objects shown in that part
 /// of the image are closest to the camera.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AfWindows(pub Vec<Rectangle>);
 impl Deref for AfWindows {
     type Target = Vec<Rectangle>;
@@ -1531,12 +1895,15 @@ impl From<AfWindows> for ControlValue {
 impl ControlEntry for AfWindows {
     const ID: u32 = ControlId::AfWindows as _;
 }
-impl Control for AfWindows {}
+impl Control for AfWindows {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// This control starts an autofocus scan when AfMode is set to AfModeAuto,
 /// and can also be used to terminate a scan early.
 ///
 /// It is ignored if AfMode is set to AfModeManual or AfModeContinuous.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AfTrigger {
     /// Start an AF scan. Ignored if a scan is in progress.
@@ -1559,12 +1926,15 @@ impl From<AfTrigger> for ControlValue {
 impl ControlEntry for AfTrigger {
     const ID: u32 = ControlId::AfTrigger as _;
 }
-impl Control for AfTrigger {}
+impl Control for AfTrigger {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// This control has no effect except when in continuous autofocus mode
 /// (AfModeContinuous). It can be used to pause any lens movements while
 /// (for example) images are captured. The algorithm remains inactive
 /// until it is instructed to resume.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AfPause {
     /// Pause the continuous autofocus algorithm immediately, whether or not
@@ -1603,7 +1973,9 @@ impl From<AfPause> for ControlValue {
 impl ControlEntry for AfPause {
     const ID: u32 = ControlId::AfPause as _;
 }
-impl Control for AfPause {}
+impl Control for AfPause {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Acts as a control to instruct the lens to move to a particular position
 /// and also reports back the position of the lens for each frame.
 ///
@@ -1631,6 +2003,7 @@ impl Control for AfPause {}
 /// \todo Define a property to report the maximum and minimum positions of
 /// this lens. The minimum value will often be zero (meaning infinity).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LensPosition(pub f32);
 impl Deref for LensPosition {
     type Target = f32;
@@ -1657,7 +2030,9 @@ impl From<LensPosition> for ControlValue {
 impl ControlEntry for LensPosition {
     const ID: u32 = ControlId::LensPosition as _;
 }
-impl Control for LensPosition {}
+impl Control for LensPosition {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Reports the current state of the AF algorithm in conjunction with the
 /// reported AfMode value and (in continuous AF mode) the AfPauseState
 /// value. The possible state changes are described below, though we note
@@ -1675,6 +2050,7 @@ impl Control for LensPosition {}
 /// If the AfMode is set to AfModeContinuous then the AfState will initially
 /// report AfStateScanning.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AfState {
     /// The AF algorithm is in manual mode (AfModeManual) or in auto mode
@@ -1716,13 +2092,16 @@ impl From<AfState> for ControlValue {
 impl ControlEntry for AfState {
     const ID: u32 = ControlId::AfState as _;
 }
-impl Control for AfState {}
+impl Control for AfState {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Only applicable in continuous (AfModeContinuous) mode, this reports
 /// whether the algorithm is currently running, paused or pausing (that is,
 /// will pause as soon as any in-progress scan completes).
 ///
 /// Any change to AfMode will cause AfPauseStateRunning to be reported.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AfPauseState {
     /// Continuous AF is running and the algorithm may restart a scan
@@ -1752,7 +2131,9 @@ impl From<AfPauseState> for ControlValue {
 impl ControlEntry for AfPauseState {
     const ID: u32 = ControlId::AfPauseState as _;
 }
-impl Control for AfPauseState {}
+impl Control for AfPauseState {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Control for AE metering trigger. Currently identical to
 /// ANDROID_CONTROL_AE_PRECAPTURE_TRIGGER.
 ///
@@ -1760,6 +2141,7 @@ impl Control for AfPauseState {}
 /// when it processes this request.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AePrecaptureTrigger {
     /// The trigger is idle.
@@ -1789,13 +2171,16 @@ impl ControlEntry for AePrecaptureTrigger {
     const ID: u32 = ControlId::AePrecaptureTrigger as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for AePrecaptureTrigger {}
+impl Control for AePrecaptureTrigger {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Control to select the noise reduction algorithm mode. Currently
 /// identical to ANDROID_NOISE_REDUCTION_MODE.
 ///
 ///  Mode of operation for the noise reduction algorithm.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum NoiseReductionMode {
     /// No noise reduction is applied
@@ -1828,13 +2213,16 @@ impl ControlEntry for NoiseReductionMode {
     const ID: u32 = ControlId::NoiseReductionMode as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for NoiseReductionMode {}
+impl Control for NoiseReductionMode {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Control to select the color correction aberration mode. Currently
 /// identical to ANDROID_COLOR_CORRECTION_ABERRATION_MODE.
 ///
 ///  Mode of operation for the chromatic aberration correction algorithm.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum ColorCorrectionAberrationMode {
     /// No aberration correction is applied.
@@ -1864,13 +2252,16 @@ impl ControlEntry for ColorCorrectionAberrationMode {
     const ID: u32 = ControlId::ColorCorrectionAberrationMode as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for ColorCorrectionAberrationMode {}
+impl Control for ColorCorrectionAberrationMode {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Control to report the current AE algorithm state. Currently identical to
 /// ANDROID_CONTROL_AE_STATE.
 ///
 ///  Current state of the AE algorithm.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AeState {
     /// The AE algorithm is inactive.
@@ -1906,13 +2297,16 @@ impl ControlEntry for AeState {
     const ID: u32 = ControlId::AeState as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for AeState {}
+impl Control for AeState {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Control to report the current AWB algorithm state. Currently identical
 /// to ANDROID_CONTROL_AWB_STATE.
 ///
 ///  Current state of the AWB algorithm.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AwbState {
     /// The AWB algorithm is inactive.
@@ -1943,12 +2337,15 @@ impl ControlEntry for AwbState {
     const ID: u32 = ControlId::AwbState as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for AwbState {}
+impl Control for AwbState {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Control to report the time between the start of exposure of the first
 /// row and the start of exposure of the last row. Currently identical to
 /// ANDROID_SENSOR_ROLLING_SHUTTER_SKEW
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SensorRollingShutterSkew(pub i64);
 #[cfg(feature = "vendor_draft")]
 impl Deref for SensorRollingShutterSkew {
@@ -1981,11 +2378,14 @@ impl ControlEntry for SensorRollingShutterSkew {
     const ID: u32 = ControlId::SensorRollingShutterSkew as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for SensorRollingShutterSkew {}
+impl Control for SensorRollingShutterSkew {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Control to report if the lens shading map is available. Currently
 /// identical to ANDROID_STATISTICS_LENS_SHADING_MAP_MODE.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum LensShadingMapMode {
     /// No lens shading map mode is available.
@@ -2012,11 +2412,14 @@ impl ControlEntry for LensShadingMapMode {
     const ID: u32 = ControlId::LensShadingMapMode as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for LensShadingMapMode {}
+impl Control for LensShadingMapMode {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Control to report the detected scene light frequency. Currently
 /// identical to ANDROID_STATISTICS_SCENE_FLICKER.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum SceneFlicker {
     /// No flickering detected.
@@ -2045,7 +2448,9 @@ impl ControlEntry for SceneFlicker {
     const ID: u32 = ControlId::SceneFlicker as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for SceneFlicker {}
+impl Control for SceneFlicker {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// Specifies the number of pipeline stages the frame went through from when
 /// it was exposed to when the final completed result was available to the
 /// framework. Always less than or equal to PipelineMaxDepth. Currently
@@ -2058,6 +2463,7 @@ impl Control for SceneFlicker {}
 /// pipeline stage.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PipelineDepth(pub i32);
 #[cfg(feature = "vendor_draft")]
 impl Deref for PipelineDepth {
@@ -2090,7 +2496,9 @@ impl ControlEntry for PipelineDepth {
     const ID: u32 = ControlId::PipelineDepth as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for PipelineDepth {}
+impl Control for PipelineDepth {
+    const DIRECTION: ControlDirection = ControlDirection::Out;
+}
 /// The maximum number of frames that can occur after a request (different
 /// than the previous) has been submitted, and before the result's state
 /// becomes synchronized. A value of -1 indicates unknown latency, and 0
@@ -2098,6 +2506,7 @@ impl Control for PipelineDepth {}
 /// ANDROID_SYNC_MAX_LATENCY.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaxLatency(pub i32);
 #[cfg(feature = "vendor_draft")]
 impl Deref for MaxLatency {
@@ -2130,11 +2539,14 @@ impl ControlEntry for MaxLatency {
     const ID: u32 = ControlId::MaxLatency as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for MaxLatency {}
+impl Control for MaxLatency {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 /// Control to select the test pattern mode. Currently identical to
 /// ANDROID_SENSOR_TEST_PATTERN_MODE.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum TestPatternMode {
     /// No test pattern mode is used. The camera device returns frames from
@@ -2195,7 +2607,9 @@ impl ControlEntry for TestPatternMode {
     const ID: u32 = ControlId::TestPatternMode as _;
 }
 #[cfg(feature = "vendor_draft")]
-impl Control for TestPatternMode {}
+impl Control for TestPatternMode {
+    const DIRECTION: ControlDirection = ControlDirection::InOut;
+}
 pub fn make_dyn(
     id: ControlId,
     val: ControlValue,
@@ -2272,3 +2686,937 @@ pub fn make_dyn(
         ControlId::TestPatternMode => Ok(Box::new(TestPatternMode::try_from(val)?)),
     }
 }
+/// Returns descriptions of all known ids as data, i.e. to render control panels or validate remote requests.
+pub fn descriptor_table() -> &'static [ControlDescriptor] {
+    &[
+        ControlDescriptor {
+            id: ControlId::AeEnable as u32,
+            name: "AeEnable",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_BOOL,
+            type_name: "bool",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Enable or disable the AE.",
+        },
+        ControlDescriptor {
+            id: ControlId::AeLocked as u32,
+            name: "AeLocked",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_BOOL,
+            type_name: "bool",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[],
+            summary: "Report the lock status of a running AE algorithm.",
+        },
+        ControlDescriptor {
+            id: ControlId::AeMeteringMode as u32,
+            name: "AeMeteringMode",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "MeteringCentreWeighted",
+                    value: 0,
+                    summary: "Centre-weighted metering mode.",
+                },
+                EnumValueDescriptor {
+                    name: "MeteringSpot",
+                    value: 1,
+                    summary: "Spot metering mode.",
+                },
+                EnumValueDescriptor {
+                    name: "MeteringMatrix",
+                    value: 2,
+                    summary: "Matrix metering mode.",
+                },
+                EnumValueDescriptor {
+                    name: "MeteringCustom",
+                    value: 3,
+                    summary: "Custom metering mode.",
+                },
+            ],
+            summary: "Specify a metering mode for the AE algorithm to use. The metering modes determine which parts of the image are used to determine the scene brightness. Metering modes may be platform specific and not all metering modes may be supported.",
+        },
+        ControlDescriptor {
+            id: ControlId::AeConstraintMode as u32,
+            name: "AeConstraintMode",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "ConstraintNormal",
+                    value: 0,
+                    summary: "Default constraint mode. This mode aims to balance the exposure of different parts of the image so as to reach a reasonable average level. However, highlights in the image may appear over-exposed and lowlights may appear under-exposed.",
+                },
+                EnumValueDescriptor {
+                    name: "ConstraintHighlight",
+                    value: 1,
+                    summary: "Highlight constraint mode. This mode adjusts the exposure levels in order to try and avoid over-exposing the brightest parts (highlights) of an image. Other non-highlight parts of the image may appear under-exposed.",
+                },
+                EnumValueDescriptor {
+                    name: "ConstraintShadows",
+                    value: 2,
+                    summary: "Shadows constraint mode. This mode adjusts the exposure levels in order to try and avoid under-exposing the dark parts (shadows) of an image. Other normally exposed parts of the image may appear over-exposed.",
+                },
+                EnumValueDescriptor {
+                    name: "ConstraintCustom",
+                    value: 3,
+                    summary: "Custom constraint mode.",
+                },
+            ],
+            summary: "Specify a constraint mode for the AE algorithm to use. These determine how the measured scene brightness is adjusted to reach the desired target exposure. Constraint modes may be platform specific, and not all constraint modes may be supported.",
+        },
+        ControlDescriptor {
+            id: ControlId::AeExposureMode as u32,
+            name: "AeExposureMode",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "ExposureNormal",
+                    value: 0,
+                    summary: "Default exposure mode.",
+                },
+                EnumValueDescriptor {
+                    name: "ExposureShort",
+                    value: 1,
+                    summary: "Exposure mode allowing only short exposure times.",
+                },
+                EnumValueDescriptor {
+                    name: "ExposureLong",
+                    value: 2,
+                    summary: "Exposure mode allowing long exposure times.",
+                },
+                EnumValueDescriptor {
+                    name: "ExposureCustom",
+                    value: 3,
+                    summary: "Custom exposure mode.",
+                },
+            ],
+            summary: "Specify an exposure mode for the AE algorithm to use. These specify how the desired total exposure is divided between the shutter time and the sensor's analogue gain. The exposure modes are platform specific, and not all exposure modes may be supported.",
+        },
+        ControlDescriptor {
+            id: ControlId::ExposureValue as u32,
+            name: "ExposureValue",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Specify an Exposure Value (EV) parameter. The EV parameter will only be applied if the AE algorithm is currently enabled.",
+        },
+        ControlDescriptor {
+            id: ControlId::ExposureTime as u32,
+            name: "ExposureTime",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Exposure time (shutter speed) for the frame applied in the sensor device. This value is specified in micro-seconds.",
+        },
+        ControlDescriptor {
+            id: ControlId::AnalogueGain as u32,
+            name: "AnalogueGain",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Analogue gain value applied in the sensor device. The value of the control specifies the gain multiplier applied to all colour channels. This value cannot be lower than 1.0.",
+        },
+        ControlDescriptor {
+            id: ControlId::Brightness as u32,
+            name: "Brightness",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Specify a fixed brightness parameter. Positive values (up to 1.0) produce brighter images; negative values (up to -1.0) produce darker images and 0.0 leaves pixels unchanged.",
+        },
+        ControlDescriptor {
+            id: ControlId::Contrast as u32,
+            name: "Contrast",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Specify a fixed contrast parameter. Normal contrast is given by the value 1.0; larger values produce images with more contrast.",
+        },
+        ControlDescriptor {
+            id: ControlId::Lux as u32,
+            name: "Lux",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[],
+            summary: "Report an estimate of the current illuminance level in lux. The Lux control can only be returned in metadata.",
+        },
+        ControlDescriptor {
+            id: ControlId::AwbEnable as u32,
+            name: "AwbEnable",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_BOOL,
+            type_name: "bool",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Enable or disable the AWB.",
+        },
+        ControlDescriptor {
+            id: ControlId::AwbMode as u32,
+            name: "AwbMode",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "AwbAuto",
+                    value: 0,
+                    summary: "Search over the whole colour temperature range.",
+                },
+                EnumValueDescriptor {
+                    name: "AwbIncandescent",
+                    value: 1,
+                    summary: "Incandescent AWB lamp mode.",
+                },
+                EnumValueDescriptor {
+                    name: "AwbTungsten",
+                    value: 2,
+                    summary: "Tungsten AWB lamp mode.",
+                },
+                EnumValueDescriptor {
+                    name: "AwbFluorescent",
+                    value: 3,
+                    summary: "Fluorescent AWB lamp mode.",
+                },
+                EnumValueDescriptor {
+                    name: "AwbIndoor",
+                    value: 4,
+                    summary: "Indoor AWB lighting mode.",
+                },
+                EnumValueDescriptor {
+                    name: "AwbDaylight",
+                    value: 5,
+                    summary: "Daylight AWB lighting mode.",
+                },
+                EnumValueDescriptor {
+                    name: "AwbCloudy",
+                    value: 6,
+                    summary: "Cloudy AWB lighting mode.",
+                },
+                EnumValueDescriptor {
+                    name: "AwbCustom",
+                    value: 7,
+                    summary: "Custom AWB mode.",
+                },
+            ],
+            summary: "Specify the range of illuminants to use for the AWB algorithm. The modes supported are platform specific, and not all modes may be supported.",
+        },
+        ControlDescriptor {
+            id: ControlId::AwbLocked as u32,
+            name: "AwbLocked",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_BOOL,
+            type_name: "bool",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[],
+            summary: "Report the lock status of a running AWB algorithm.",
+        },
+        ControlDescriptor {
+            id: ControlId::ColourGains as u32,
+            name: "ColourGains",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[Some(2)],
+            enumeration: &[],
+            summary: "Pair of gain values for the Red and Blue colour channels, in that order. ColourGains can only be applied in a Request when the AWB is disabled.",
+        },
+        ControlDescriptor {
+            id: ControlId::ColourTemperature as u32,
+            name: "ColourTemperature",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[],
+            summary: "Report the current estimate of the colour temperature, in kelvin, for this frame. The ColourTemperature control can only be returned in metadata.",
+        },
+        ControlDescriptor {
+            id: ControlId::Saturation as u32,
+            name: "Saturation",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Specify a fixed saturation parameter. Normal saturation is given by the value 1.0; larger values produce more saturated colours; 0.0 produces a greyscale image.",
+        },
+        ControlDescriptor {
+            id: ControlId::SensorBlackLevels as u32,
+            name: "SensorBlackLevels",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::Out,
+            size: &[Some(4)],
+            enumeration: &[],
+            summary: "Reports the sensor black levels used for processing a frame, in the order R, Gr, Gb, B. These values are returned as numbers out of a 16-bit pixel range (as if pixels ranged from 0 to 65535). The SensorBlackLevels control can only be returned in metadata.",
+        },
+        ControlDescriptor {
+            id: ControlId::Sharpness as u32,
+            name: "Sharpness",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "A value of 0.0 means no sharpening. The minimum value means minimal sharpening, and shall be 0.0 unless the camera can't disable sharpening completely. The default value shall give a \"reasonable\" level of sharpening, suitable for most use cases. The maximum value may apply extremely high levels of sharpening, higher than anyone could reasonably want. Negative values are not allowed. Note also that sharpening is not applied to raw streams.",
+        },
+        ControlDescriptor {
+            id: ControlId::FocusFoM as u32,
+            name: "FocusFoM",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[],
+            summary: "Reports a Figure of Merit (FoM) to indicate how in-focus the frame is. A larger FocusFoM value indicates a more in-focus frame. This control depends on the IPA to gather ISP statistics from the defined focus region, and combine them in a suitable way to generate a FocusFoM value. In this respect, it is not necessarily aimed at providing a way to implement a focus algorithm by the application, rather an indication of how in-focus a frame is.",
+        },
+        ControlDescriptor {
+            id: ControlId::ColourCorrectionMatrix as u32,
+            name: "ColourCorrectionMatrix",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[Some(3), Some(3)],
+            enumeration: &[],
+            summary: "The 3x3 matrix that converts camera RGB to sRGB within the imaging pipeline. This should describe the matrix that is used after pixels have been white-balanced, but before any gamma transformation. The 3x3 matrix is stored in conventional reading order in an array of 9 floating point values.",
+        },
+        ControlDescriptor {
+            id: ControlId::ScalerCrop as u32,
+            name: "ScalerCrop",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_RECTANGLE,
+            type_name: "Rectangle",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Sets the image portion that will be scaled to form the whole of the final output image. The (x,y) location of this rectangle is relative to the PixelArrayActiveAreas that is being used. The units remain native sensor pixels, even if the sensor is being used in a binning or skipping mode.",
+        },
+        ControlDescriptor {
+            id: ControlId::DigitalGain as u32,
+            name: "DigitalGain",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Digital gain value applied during the processing steps applied to the image as captured from the sensor.",
+        },
+        ControlDescriptor {
+            id: ControlId::FrameDuration as u32,
+            name: "FrameDuration",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT64,
+            type_name: "int64_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[],
+            summary: "The instantaneous frame duration from start of frame exposure to start of next exposure, expressed in microseconds. This control is meant to be returned in metadata.",
+        },
+        ControlDescriptor {
+            id: ControlId::FrameDurationLimits as u32,
+            name: "FrameDurationLimits",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT64,
+            type_name: "int64_t",
+            direction: ControlDirection::InOut,
+            size: &[Some(2)],
+            enumeration: &[],
+            summary: "The minimum and maximum (in that order) frame duration, expressed in microseconds.",
+        },
+        ControlDescriptor {
+            id: ControlId::SensorTemperature as u32,
+            name: "SensorTemperature",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[],
+            summary: "Temperature measure from the camera sensor in Celsius. This is typically obtained by a thermal sensor present on-die or in the camera module. The range of reported temperatures is device dependent.",
+        },
+        ControlDescriptor {
+            id: ControlId::SensorTimestamp as u32,
+            name: "SensorTimestamp",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT64,
+            type_name: "int64_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[],
+            summary: "The time when the first row of the image sensor active array is exposed.",
+        },
+        ControlDescriptor {
+            id: ControlId::AfMode as u32,
+            name: "AfMode",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Manual",
+                    value: 0,
+                    summary: "The AF algorithm is in manual mode. In this mode it will never perform any action nor move the lens of its own accord, but an application can specify the desired lens position using the LensPosition control.",
+                },
+                EnumValueDescriptor {
+                    name: "Auto",
+                    value: 1,
+                    summary: "The AF algorithm is in auto mode. This means that the algorithm will never move the lens or change state unless the AfTrigger control is used. The AfTrigger control can be used to initiate a focus scan, the results of which will be reported by AfState.",
+                },
+                EnumValueDescriptor {
+                    name: "Continuous",
+                    value: 2,
+                    summary: "The AF algorithm is in continuous mode. This means that the lens can re-start a scan spontaneously at any moment, without any user intervention. The AfState still reports whether the algorithm is currently scanning or not, though the application has no ability to initiate or cancel scans, nor to move the lens for itself.",
+                },
+            ],
+            summary: "Control to set the mode of the AF (autofocus) algorithm.",
+        },
+        ControlDescriptor {
+            id: ControlId::AfRange as u32,
+            name: "AfRange",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Normal",
+                    value: 0,
+                    summary: "A wide range of focus distances is scanned, all the way from infinity down to close distances, though depending on the implementation, possibly not including the very closest macro positions.",
+                },
+                EnumValueDescriptor {
+                    name: "Macro",
+                    value: 1,
+                    summary: "Only close distances are scanned.",
+                },
+                EnumValueDescriptor {
+                    name: "Full",
+                    value: 2,
+                    summary: "The full range of focus distances is scanned just as with AfRangeNormal but this time including the very closest macro positions.",
+                },
+            ],
+            summary: "Control to set the range of focus distances that is scanned. An implementation may choose not to implement all the options here.",
+        },
+        ControlDescriptor {
+            id: ControlId::AfSpeed as u32,
+            name: "AfSpeed",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Normal",
+                    value: 0,
+                    summary: "Move the lens at its usual speed.",
+                },
+                EnumValueDescriptor {
+                    name: "Fast",
+                    value: 1,
+                    summary: "Move the lens more quickly.",
+                },
+            ],
+            summary: "Control that determines whether the AF algorithm is to move the lens as quickly as possible or more steadily. For example, during video recording it may be desirable not to move the lens too abruptly, but when in a preview mode (waiting for a still capture) it may be helpful to move the lens as quickly as is reasonably possible.",
+        },
+        ControlDescriptor {
+            id: ControlId::AfMetering as u32,
+            name: "AfMetering",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Auto",
+                    value: 0,
+                    summary: "The AF algorithm should decide for itself where it will measure focus.",
+                },
+                EnumValueDescriptor {
+                    name: "Windows",
+                    value: 1,
+                    summary: "The AF algorithm should use the rectangles defined by the AfWindows control to measure focus. If no windows are specified the behaviour is platform dependent.",
+                },
+            ],
+            summary: "Instruct the AF algorithm how it should decide which parts of the image should be used to measure focus.",
+        },
+        ControlDescriptor {
+            id: ControlId::AfWindows as u32,
+            name: "AfWindows",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_RECTANGLE,
+            type_name: "Rectangle",
+            direction: ControlDirection::InOut,
+            size: &[None],
+            enumeration: &[],
+            summary: "Sets the focus windows used by the AF algorithm when AfMetering is set to AfMeteringWindows. The units used are pixels within the rectangle returned by the ScalerCropMaximum property.",
+        },
+        ControlDescriptor {
+            id: ControlId::AfTrigger as u32,
+            name: "AfTrigger",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Start",
+                    value: 0,
+                    summary: "Start an AF scan. Ignored if a scan is in progress.",
+                },
+                EnumValueDescriptor {
+                    name: "Cancel",
+                    value: 1,
+                    summary: "Cancel an AF scan. This does not cause the lens to move anywhere else. Ignored if no scan is in progress.",
+                },
+            ],
+            summary: "This control starts an autofocus scan when AfMode is set to AfModeAuto, and can also be used to terminate a scan early.",
+        },
+        ControlDescriptor {
+            id: ControlId::AfPause as u32,
+            name: "AfPause",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Immediate",
+                    value: 0,
+                    summary: "Pause the continuous autofocus algorithm immediately, whether or not any kind of scan is underway. AfPauseState will subsequently report AfPauseStatePaused. AfState may report any of AfStateScanning, AfStateFocused or AfStateFailed, depending on the algorithm's state when it received this control.",
+                },
+                EnumValueDescriptor {
+                    name: "Deferred",
+                    value: 1,
+                    summary: "This is similar to AfPauseImmediate, and if the AfState is currently reporting AfStateFocused or AfStateFailed it will remain in that state and AfPauseState will report AfPauseStatePaused.",
+                },
+                EnumValueDescriptor {
+                    name: "Resume",
+                    value: 2,
+                    summary: "Resume continuous autofocus operation. The algorithm starts again from exactly where it left off, and AfPauseState will report AfPauseStateRunning.",
+                },
+            ],
+            summary: "This control has no effect except when in continuous autofocus mode (AfModeContinuous). It can be used to pause any lens movements while (for example) images are captured. The algorithm remains inactive until it is instructed to resume.",
+        },
+        ControlDescriptor {
+            id: ControlId::LensPosition as u32,
+            name: "LensPosition",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "Acts as a control to instruct the lens to move to a particular position and also reports back the position of the lens for each frame.",
+        },
+        ControlDescriptor {
+            id: ControlId::AfState as u32,
+            name: "AfState",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Idle",
+                    value: 0,
+                    summary: "The AF algorithm is in manual mode (AfModeManual) or in auto mode (AfModeAuto) and a scan has not yet been triggered, or an in-progress scan was cancelled.",
+                },
+                EnumValueDescriptor {
+                    name: "Scanning",
+                    value: 1,
+                    summary: "The AF algorithm is in auto mode (AfModeAuto), and a scan has been started using the AfTrigger control. The scan can be cancelled by sending AfTriggerCancel at which point the algorithm will either move back to AfStateIdle or, if the scan actually completes before the cancel request is processed, to one of AfStateFocused or AfStateFailed.",
+                },
+                EnumValueDescriptor {
+                    name: "Focused",
+                    value: 2,
+                    summary: "The AF algorithm is in auto (AfModeAuto) or continuous (AfModeContinuous) mode and a scan has completed with the result that the algorithm believes the image is now in focus.",
+                },
+                EnumValueDescriptor {
+                    name: "Failed",
+                    value: 3,
+                    summary: "The AF algorithm is in auto (AfModeAuto) or continuous (AfModeContinuous) mode and a scan has completed with the result that the algorithm did not find a good focus position.",
+                },
+            ],
+            summary: "Reports the current state of the AF algorithm in conjunction with the reported AfMode value and (in continuous AF mode) the AfPauseState value. The possible state changes are described below, though we note the following state transitions that occur when the AfMode is changed.",
+        },
+        ControlDescriptor {
+            id: ControlId::AfPauseState as u32,
+            name: "AfPauseState",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Running",
+                    value: 0,
+                    summary: "Continuous AF is running and the algorithm may restart a scan spontaneously.",
+                },
+                EnumValueDescriptor {
+                    name: "Pausing",
+                    value: 1,
+                    summary: "Continuous AF has been sent an AfPauseDeferred control, and will pause as soon as any in-progress scan completes (and then report AfPauseStatePaused). No new scans will be start spontaneously until the AfPauseResume control is sent.",
+                },
+                EnumValueDescriptor {
+                    name: "Paused",
+                    value: 2,
+                    summary: "Continuous AF is paused. No further state changes or lens movements will occur until the AfPauseResume control is sent.",
+                },
+            ],
+            summary: "Only applicable in continuous (AfModeContinuous) mode, this reports whether the algorithm is currently running, paused or pausing (that is, will pause as soon as any in-progress scan completes).",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::AePrecaptureTrigger as u32,
+            name: "AePrecaptureTrigger",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Idle",
+                    value: 0,
+                    summary: "The trigger is idle.",
+                },
+                EnumValueDescriptor {
+                    name: "Start",
+                    value: 1,
+                    summary: "The pre-capture AE metering is started by the camera.",
+                },
+                EnumValueDescriptor {
+                    name: "Cancel",
+                    value: 2,
+                    summary: "The camera will cancel any active or completed metering sequence. The AE algorithm is reset to its initial state.",
+                },
+            ],
+            summary: "Control for AE metering trigger. Currently identical to ANDROID_CONTROL_AE_PRECAPTURE_TRIGGER.",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::NoiseReductionMode as u32,
+            name: "NoiseReductionMode",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Off",
+                    value: 0,
+                    summary: "No noise reduction is applied",
+                },
+                EnumValueDescriptor {
+                    name: "Fast",
+                    value: 1,
+                    summary: "Noise reduction is applied without reducing the frame rate.",
+                },
+                EnumValueDescriptor {
+                    name: "HighQuality",
+                    value: 2,
+                    summary: "High quality noise reduction at the expense of frame rate.",
+                },
+                EnumValueDescriptor {
+                    name: "Minimal",
+                    value: 3,
+                    summary: "Minimal noise reduction is applied without reducing the frame rate.",
+                },
+                EnumValueDescriptor {
+                    name: "ZSL",
+                    value: 4,
+                    summary: "Noise reduction is applied at different levels to different streams.",
+                },
+            ],
+            summary: "Control to select the noise reduction algorithm mode. Currently identical to ANDROID_NOISE_REDUCTION_MODE.",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::ColorCorrectionAberrationMode as u32,
+            name: "ColorCorrectionAberrationMode",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "ColorCorrectionAberrationOff",
+                    value: 0,
+                    summary: "No aberration correction is applied.",
+                },
+                EnumValueDescriptor {
+                    name: "ColorCorrectionAberrationFast",
+                    value: 1,
+                    summary: "Aberration correction will not slow down the frame rate.",
+                },
+                EnumValueDescriptor {
+                    name: "ColorCorrectionAberrationHighQuality",
+                    value: 2,
+                    summary: "High quality aberration correction which might reduce the frame rate.",
+                },
+            ],
+            summary: "Control to select the color correction aberration mode. Currently identical to ANDROID_COLOR_CORRECTION_ABERRATION_MODE.",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::AeState as u32,
+            name: "AeState",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Inactive",
+                    value: 0,
+                    summary: "The AE algorithm is inactive.",
+                },
+                EnumValueDescriptor {
+                    name: "Searching",
+                    value: 1,
+                    summary: "The AE algorithm has not converged yet.",
+                },
+                EnumValueDescriptor {
+                    name: "Converged",
+                    value: 2,
+                    summary: "The AE algorithm has converged.",
+                },
+                EnumValueDescriptor {
+                    name: "Locked",
+                    value: 3,
+                    summary: "The AE algorithm is locked.",
+                },
+                EnumValueDescriptor {
+                    name: "FlashRequired",
+                    value: 4,
+                    summary: "The AE algorithm would need a flash for good results",
+                },
+                EnumValueDescriptor {
+                    name: "Precapture",
+                    value: 5,
+                    summary: "The AE algorithm has started a pre-capture metering session. \\sa AePrecaptureTrigger",
+                },
+            ],
+            summary: "Control to report the current AE algorithm state. Currently identical to ANDROID_CONTROL_AE_STATE.",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::AwbState as u32,
+            name: "AwbState",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Inactive",
+                    value: 0,
+                    summary: "The AWB algorithm is inactive.",
+                },
+                EnumValueDescriptor {
+                    name: "Searching",
+                    value: 1,
+                    summary: "The AWB algorithm has not converged yet.",
+                },
+                EnumValueDescriptor {
+                    name: "AwbConverged",
+                    value: 2,
+                    summary: "The AWB algorithm has converged.",
+                },
+                EnumValueDescriptor {
+                    name: "AwbLocked",
+                    value: 3,
+                    summary: "The AWB algorithm is locked.",
+                },
+            ],
+            summary: "Control to report the current AWB algorithm state. Currently identical to ANDROID_CONTROL_AWB_STATE.",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::SensorRollingShutterSkew as u32,
+            name: "SensorRollingShutterSkew",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT64,
+            type_name: "int64_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[],
+            summary: "Control to report the time between the start of exposure of the first row and the start of exposure of the last row. Currently identical to ANDROID_SENSOR_ROLLING_SHUTTER_SKEW",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::LensShadingMapMode as u32,
+            name: "LensShadingMapMode",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Off",
+                    value: 0,
+                    summary: "No lens shading map mode is available.",
+                },
+                EnumValueDescriptor {
+                    name: "On",
+                    value: 1,
+                    summary: "The lens shading map mode is available.",
+                },
+            ],
+            summary: "Control to report if the lens shading map is available. Currently identical to ANDROID_STATISTICS_LENS_SHADING_MAP_MODE.",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::SceneFlicker as u32,
+            name: "SceneFlicker",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "SceneFickerOff",
+                    value: 0,
+                    summary: "No flickering detected.",
+                },
+                EnumValueDescriptor {
+                    name: "SceneFicker50Hz",
+                    value: 1,
+                    summary: "50Hz flickering detected.",
+                },
+                EnumValueDescriptor {
+                    name: "SceneFicker60Hz",
+                    value: 2,
+                    summary: "60Hz flickering detected.",
+                },
+            ],
+            summary: "Control to report the detected scene light frequency. Currently identical to ANDROID_STATISTICS_SCENE_FLICKER.",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::PipelineDepth as u32,
+            name: "PipelineDepth",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::Out,
+            size: &[],
+            enumeration: &[],
+            summary: "Specifies the number of pipeline stages the frame went through from when it was exposed to when the final completed result was available to the framework. Always less than or equal to PipelineMaxDepth. Currently identical to ANDROID_REQUEST_PIPELINE_DEPTH.",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::MaxLatency as u32,
+            name: "MaxLatency",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "The maximum number of frames that can occur after a request (different than the previous) has been submitted, and before the result's state becomes synchronized. A value of -1 indicates unknown latency, and 0 indicates per-frame control. Currently identical to ANDROID_SYNC_MAX_LATENCY.",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: ControlId::TestPatternMode as u32,
+            name: "TestPatternMode",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "Off",
+                    value: 0,
+                    summary: "No test pattern mode is used. The camera device returns frames from the image sensor.",
+                },
+                EnumValueDescriptor {
+                    name: "SolidColor",
+                    value: 1,
+                    summary: "Each pixel in [R, G_even, G_odd, B] is replaced by its respective color channel provided in test pattern data. \\todo Add control for test pattern data.",
+                },
+                EnumValueDescriptor {
+                    name: "ColorBars",
+                    value: 2,
+                    summary: "All pixel data is replaced with an 8-bar color pattern. The vertical bars (left-to-right) are as follows; white, yellow, cyan, green, magenta, red, blue and black. Each bar should take up 1/8 of the sensor pixel array width. When this is not possible, the bar size should be rounded down to the nearest integer and the pattern can repeat on the right side. Each bar's height must always take up the full sensor pixel array height.",
+                },
+                EnumValueDescriptor {
+                    name: "ColorBarsFadeToGray",
+                    value: 3,
+                    summary: "The test pattern is similar to TestPatternModeColorBars, except that each bar should start at its specified color at the top and fade to gray at the bottom. Furthermore each bar is further subdevided into a left and right half. The left half should have a smooth gradient, and the right half should have a quantized gradient. In particular, the right half's should consist of blocks of the same color for 1/16th active sensor pixel array width. The least significant bits in the quantized gradient should be copied from the most significant bits of the smooth gradient. The height of each bar should always be a multiple of 128. When this is not the case, the pattern should repeat at the bottom of the image.",
+                },
+                EnumValueDescriptor {
+                    name: "Pn9",
+                    value: 4,
+                    summary: "All pixel data is replaced by a pseudo-random sequence generated from a PN9 512-bit sequence (typically implemented in hardware with a linear feedback shift register). The generator should be reset at the beginning of each frame, and thus each subsequent raw frame with this test pattern should be exactly the same as the last.",
+                },
+                EnumValueDescriptor {
+                    name: "Custom1",
+                    value: 256,
+                    summary: "The first custom test pattern. All custom patterns that are available only on this camera device are at least this numeric value. All of the custom test patterns will be static (that is the raw image must not vary from frame to frame).",
+                },
+            ],
+            summary: "Control to select the test pattern mode. Currently identical to ANDROID_SENSOR_TEST_PATTERN_MODE.",
+        },
+    ]
+}
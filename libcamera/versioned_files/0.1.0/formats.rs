@@ -0,0 +1,1125 @@
+use crate::pixel_format::{ColourEncoding, PixelFormat, PixelFormatInfo, PlaneInfo};
+pub const FORMATS: &[PixelFormatInfo] = &[
+    PixelFormatInfo {
+        name: "R8",
+        format: PixelFormat::new(0x20203852, 0x0),
+        bits_per_pixel: 8,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "R10",
+        format: PixelFormat::new(0x20303152, 0x0),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "R12",
+        format: PixelFormat::new(0x20323152, 0x0),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "R16",
+        format: PixelFormat::new(0x20363152, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "RGB565",
+        format: PixelFormat::new(0x36314752, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "RGB565_BE",
+        format: PixelFormat::new(0xb6314752, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "RGB888",
+        format: PixelFormat::new(0x34324752, 0x0),
+        bits_per_pixel: 24,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 3,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "BGR888",
+        format: PixelFormat::new(0x34324742, 0x0),
+        bits_per_pixel: 24,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 3,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "XRGB8888",
+        format: PixelFormat::new(0x34325258, 0x0),
+        bits_per_pixel: 32,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "XBGR8888",
+        format: PixelFormat::new(0x34324258, 0x0),
+        bits_per_pixel: 32,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "RGBX8888",
+        format: PixelFormat::new(0x34325852, 0x0),
+        bits_per_pixel: 32,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "BGRX8888",
+        format: PixelFormat::new(0x34325842, 0x0),
+        bits_per_pixel: 32,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "ABGR8888",
+        format: PixelFormat::new(0x34324241, 0x0),
+        bits_per_pixel: 32,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "ARGB8888",
+        format: PixelFormat::new(0x34325241, 0x0),
+        bits_per_pixel: 32,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "BGRA8888",
+        format: PixelFormat::new(0x34324142, 0x0),
+        bits_per_pixel: 32,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "RGBA8888",
+        format: PixelFormat::new(0x34324152, 0x0),
+        bits_per_pixel: 32,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "RGB161616",
+        format: PixelFormat::new(0x38344752, 0x0),
+        bits_per_pixel: 48,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 6,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "BGR161616",
+        format: PixelFormat::new(0x38344742, 0x0),
+        bits_per_pixel: 48,
+        colour_encoding: ColourEncoding::Rgb,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 6,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "YUYV",
+        format: PixelFormat::new(0x56595559, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "YVYU",
+        format: PixelFormat::new(0x55595659, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "UYVY",
+        format: PixelFormat::new(0x59565955, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "VYUY",
+        format: PixelFormat::new(0x59555956, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "AVUY8888",
+        format: PixelFormat::new(0x59555641, 0x0),
+        bits_per_pixel: 32,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "XVUY8888",
+        format: PixelFormat::new(0x59555658, 0x0),
+        bits_per_pixel: 32,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "NV12",
+        format: PixelFormat::new(0x3231564e, 0x0),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 2,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "NV21",
+        format: PixelFormat::new(0x3132564e, 0x0),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 2,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "NV16",
+        format: PixelFormat::new(0x3631564e, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "NV61",
+        format: PixelFormat::new(0x3136564e, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "NV24",
+        format: PixelFormat::new(0x3432564e, 0x0),
+        bits_per_pixel: 24,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "NV42",
+        format: PixelFormat::new(0x3234564e, 0x0),
+        bits_per_pixel: 24,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "YUV420",
+        format: PixelFormat::new(0x32315559, 0x0),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 2,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 2,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "YVU420",
+        format: PixelFormat::new(0x32315659, 0x0),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 2,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 2,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "YUV422",
+        format: PixelFormat::new(0x36315559, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "YVU422",
+        format: PixelFormat::new(0x36315659, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "YUV444",
+        format: PixelFormat::new(0x34325559, 0x0),
+        bits_per_pixel: 24,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "YVU444",
+        format: PixelFormat::new(0x34325659, 0x0),
+        bits_per_pixel: 24,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "MJPEG",
+        format: PixelFormat::new(0x47504a4d, 0x0),
+        bits_per_pixel: 0,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: false,
+        pixels_per_group: 1,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 1,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "R10_CSI2P",
+        format: PixelFormat::new(0x20303152, 0xb00000000000001),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: true,
+        pixels_per_group: 4,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 5,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "R12_CSI2P",
+        format: PixelFormat::new(0x20323152, 0xb00000000000001),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Yuv,
+        packed: true,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 3,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SRGGB8",
+        format: PixelFormat::new(0x42474752, 0x0),
+        bits_per_pixel: 8,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGRBG8",
+        format: PixelFormat::new(0x47425247, 0x0),
+        bits_per_pixel: 8,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGBRG8",
+        format: PixelFormat::new(0x47524247, 0x0),
+        bits_per_pixel: 8,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SBGGR8",
+        format: PixelFormat::new(0x31384142, 0x0),
+        bits_per_pixel: 8,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 2,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SRGGB10",
+        format: PixelFormat::new(0x30314752, 0x0),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGRBG10",
+        format: PixelFormat::new(0x30314142, 0x0),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGBRG10",
+        format: PixelFormat::new(0x30314247, 0x0),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SBGGR10",
+        format: PixelFormat::new(0x30314742, 0x0),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SRGGB12",
+        format: PixelFormat::new(0x32314752, 0x0),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGRBG12",
+        format: PixelFormat::new(0x32314142, 0x0),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGBRG12",
+        format: PixelFormat::new(0x32314247, 0x0),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SBGGR12",
+        format: PixelFormat::new(0x32314742, 0x0),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SRGGB14",
+        format: PixelFormat::new(0x34314752, 0x0),
+        bits_per_pixel: 14,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGRBG14",
+        format: PixelFormat::new(0x34314142, 0x0),
+        bits_per_pixel: 14,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGBRG14",
+        format: PixelFormat::new(0x34314247, 0x0),
+        bits_per_pixel: 14,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SBGGR14",
+        format: PixelFormat::new(0x34314742, 0x0),
+        bits_per_pixel: 14,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SRGGB16",
+        format: PixelFormat::new(0x36424752, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGRBG16",
+        format: PixelFormat::new(0x36315247, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGBRG16",
+        format: PixelFormat::new(0x36314247, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SBGGR16",
+        format: PixelFormat::new(0x32525942, 0x0),
+        bits_per_pixel: 16,
+        colour_encoding: ColourEncoding::Raw,
+        packed: false,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 4,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SRGGB10_CSI2P",
+        format: PixelFormat::new(0x30314752, 0xb00000000000001),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 4,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 5,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGRBG10_CSI2P",
+        format: PixelFormat::new(0x30314142, 0xb00000000000001),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 4,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 5,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGBRG10_CSI2P",
+        format: PixelFormat::new(0x30314247, 0xb00000000000001),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 4,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 5,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SBGGR10_CSI2P",
+        format: PixelFormat::new(0x30314742, 0xb00000000000001),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 4,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 5,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SRGGB12_CSI2P",
+        format: PixelFormat::new(0x32314752, 0xb00000000000001),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 3,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGRBG12_CSI2P",
+        format: PixelFormat::new(0x32314142, 0xb00000000000001),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 3,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGBRG12_CSI2P",
+        format: PixelFormat::new(0x32314247, 0xb00000000000001),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 3,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SBGGR12_CSI2P",
+        format: PixelFormat::new(0x32314742, 0xb00000000000001),
+        bits_per_pixel: 12,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 2,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 3,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SRGGB14_CSI2P",
+        format: PixelFormat::new(0x34314752, 0xb00000000000001),
+        bits_per_pixel: 14,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 4,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 7,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGRBG14_CSI2P",
+        format: PixelFormat::new(0x34314142, 0xb00000000000001),
+        bits_per_pixel: 14,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 4,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 7,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGBRG14_CSI2P",
+        format: PixelFormat::new(0x34314247, 0xb00000000000001),
+        bits_per_pixel: 14,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 4,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 7,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SBGGR14_CSI2P",
+        format: PixelFormat::new(0x34314742, 0xb00000000000001),
+        bits_per_pixel: 14,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 4,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 7,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SRGGB10_IPU3",
+        format: PixelFormat::new(0x30314752, 0x10000000000000d),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 25,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 32,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGRBG10_IPU3",
+        format: PixelFormat::new(0x30314142, 0x10000000000000d),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 25,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 32,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SGBRG10_IPU3",
+        format: PixelFormat::new(0x30314247, 0x10000000000000d),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 25,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 32,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+    PixelFormatInfo {
+        name: "SBGGR10_IPU3",
+        format: PixelFormat::new(0x30314742, 0x10000000000000d),
+        bits_per_pixel: 10,
+        colour_encoding: ColourEncoding::Raw,
+        packed: true,
+        pixels_per_group: 25,
+        planes: &[
+            PlaneInfo {
+                bytes_per_group: 32,
+                vertical_sub_sampling: 1,
+            },
+        ],
+    },
+];
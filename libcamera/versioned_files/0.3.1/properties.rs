@@ -1,7 +1,10 @@
 use std::ops::{Deref, DerefMut};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 #[allow(unused_imports)]
-use crate::control::{Control, Property, ControlEntry, DynControlEntry};
+use crate::control::{
+    Control, ControlDescriptor, ControlDirection, Property, ControlEntry,
+    DynControlEntry, EnumValueDescriptor,
+};
 use crate::control_value::{ControlValue, ControlValueError};
 #[allow(unused_imports)]
 use crate::geometry::{Rectangle, Size};
@@ -720,8 +723,73 @@ pub enum PropertyId {
     #[cfg(feature = "vendor_draft")]
     ColorFilterArrangement = COLOR_FILTER_ARRANGEMENT,
 }
+impl PropertyId {
+    /// Returns all known ids.
+    pub fn all() -> &'static [PropertyId] {
+        &[
+            PropertyId::Location,
+            PropertyId::Rotation,
+            PropertyId::Model,
+            PropertyId::UnitCellSize,
+            PropertyId::PixelArraySize,
+            PropertyId::PixelArrayOpticalBlackRectangles,
+            PropertyId::PixelArrayActiveAreas,
+            PropertyId::ScalerCropMaximum,
+            PropertyId::SensorSensitivity,
+            PropertyId::SystemDevices,
+            #[cfg(feature = "vendor_draft")]
+            PropertyId::ColorFilterArrangement,
+        ]
+    }
+    /// Returns name of the id as used in libcamera YAML definitions, i.e. `ExposureTime`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PropertyId::Location => "Location",
+            PropertyId::Rotation => "Rotation",
+            PropertyId::Model => "Model",
+            PropertyId::UnitCellSize => "UnitCellSize",
+            PropertyId::PixelArraySize => "PixelArraySize",
+            PropertyId::PixelArrayOpticalBlackRectangles => {
+                "PixelArrayOpticalBlackRectangles"
+            }
+            PropertyId::PixelArrayActiveAreas => "PixelArrayActiveAreas",
+            PropertyId::ScalerCropMaximum => "ScalerCropMaximum",
+            PropertyId::SensorSensitivity => "SensorSensitivity",
+            PropertyId::SystemDevices => "SystemDevices",
+            #[cfg(feature = "vendor_draft")]
+            PropertyId::ColorFilterArrangement => "ColorFilterArrangement",
+        }
+    }
+    /// Looks up id by its name as used in libcamera YAML definitions, i.e. `ExposureTime`.
+    ///
+    /// Returns [None] if name is unknown or the corresponding vendor feature is disabled.
+    pub fn from_name(name: &str) -> Option<PropertyId> {
+        match name {
+            "Location" => Some(PropertyId::Location),
+            "Rotation" => Some(PropertyId::Rotation),
+            "Model" => Some(PropertyId::Model),
+            "UnitCellSize" => Some(PropertyId::UnitCellSize),
+            "PixelArraySize" => Some(PropertyId::PixelArraySize),
+            "PixelArrayOpticalBlackRectangles" => {
+                Some(PropertyId::PixelArrayOpticalBlackRectangles)
+            }
+            "PixelArrayActiveAreas" => Some(PropertyId::PixelArrayActiveAreas),
+            "ScalerCropMaximum" => Some(PropertyId::ScalerCropMaximum),
+            "SensorSensitivity" => Some(PropertyId::SensorSensitivity),
+            "SystemDevices" => Some(PropertyId::SystemDevices),
+            #[cfg(feature = "vendor_draft")]
+            "ColorFilterArrangement" => Some(PropertyId::ColorFilterArrangement),
+            _ => None,
+        }
+    }
+    /// Iterates over `(name, id)` pairs of all known ids.
+    pub fn names() -> impl Iterator<Item = (&'static str, PropertyId)> {
+        Self::all().iter().map(|id| (id.name(), *id))
+    }
+}
 /// Camera mounting location
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum Location {
     /// The camera is mounted on the front side of the device, facing the
@@ -1141,6 +1209,7 @@ impl Property for Location {}
 ///                       |                    |
 ///                       +--------------------+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rotation(pub i32);
 impl Deref for Rotation {
     type Target = i32;
@@ -1188,6 +1257,7 @@ impl Property for Rotation {}
 ///
 /// Example model names are 'ov5670', 'imx219' or 'Logitech Webcam C930e'.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Model(pub String);
 impl Deref for Model {
     type Target = String;
@@ -1225,6 +1295,7 @@ impl Property for Model {}
 /// The property can be used to calculate the physical size of the sensor's
 /// pixel array area and for calibration purposes.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnitCellSize(pub Size);
 impl Deref for UnitCellSize {
     type Target = Size;
@@ -1328,6 +1399,7 @@ impl Property for UnitCellSize {}
 /// ```text
 ///       categories (i.e. Properties::PixelArray::Size)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PixelArraySize(pub Size);
 impl Deref for PixelArraySize {
     type Target = Size;
@@ -1455,6 +1527,7 @@ impl Property for PixelArraySize {}
 /// ```text
 ///       categories (i.e. Properties::PixelArray::OpticalBlackRectangles)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PixelArrayOpticalBlackRectangles(pub Vec<Rectangle>);
 impl Deref for PixelArrayOpticalBlackRectangles {
     type Target = Vec<Rectangle>;
@@ -1560,6 +1633,7 @@ impl Property for PixelArrayOpticalBlackRectangles {}
 /// ```text
 ///       categories (i.e. Properties::PixelArray::ActiveAreas)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PixelArrayActiveAreas(pub Vec<Rectangle>);
 impl Deref for PixelArrayActiveAreas {
     type Target = Vec<Rectangle>;
@@ -1599,6 +1673,7 @@ impl Property for PixelArrayActiveAreas {}
 /// \todo Turn this property into a "maximum control value" for the
 /// ScalerCrop control once "dynamic" controls have been implemented.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScalerCropMaximum(pub Rectangle);
 impl Deref for ScalerCropMaximum {
     type Target = Rectangle;
@@ -1635,6 +1710,7 @@ impl Property for ScalerCropMaximum {}
 /// that is twice that of the full resolution mode. This value will be valid
 /// after the configure method has returned successfully.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SensorSensitivity(pub f32);
 impl Deref for SensorSensitivity {
     type Target = f32;
@@ -1668,6 +1744,7 @@ impl Property for SensorSensitivity {}
 ///
 /// Different cameras may report identical devices.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SystemDevices(pub Vec<i64>);
 impl Deref for SystemDevices {
     type Target = Vec<i64>;
@@ -1700,6 +1777,7 @@ impl Property for SystemDevices {}
 /// identical to ANDROID_SENSOR_INFO_COLOR_FILTER_ARRANGEMENT.
 #[cfg(feature = "vendor_draft")]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum ColorFilterArrangement {
     /// RGGB Bayer pattern
@@ -1761,3 +1839,177 @@ pub fn make_dyn(
         }
     }
 }
+/// Returns descriptions of all known ids as data, i.e. to render control panels or validate remote requests.
+pub fn descriptor_table() -> &'static [ControlDescriptor] {
+    &[
+        ControlDescriptor {
+            id: PropertyId::Location as u32,
+            name: "Location",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "CameraFront",
+                    value: 0,
+                    summary: "The camera is mounted on the front side of the device, facing the user",
+                },
+                EnumValueDescriptor {
+                    name: "CameraBack",
+                    value: 1,
+                    summary: "The camera is mounted on the back side of the device, facing away from the user",
+                },
+                EnumValueDescriptor {
+                    name: "CameraExternal",
+                    value: 2,
+                    summary: "The camera is attached to the device in a way that allows it to be moved freely",
+                },
+            ],
+            summary: "Camera mounting location",
+        },
+        ControlDescriptor {
+            id: PropertyId::Rotation as u32,
+            name: "Rotation",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "The camera physical mounting rotation. It is expressed as the angular difference in degrees between two reference systems, one relative to the camera module, and one defined on the external world scene to be captured when projected on the image sensor pixel array.",
+        },
+        ControlDescriptor {
+            id: PropertyId::Model as u32,
+            name: "Model",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_STRING,
+            type_name: "string",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "The model name shall to the extent possible describe the sensor. For most devices this is the model name of the sensor. While for some devices the sensor model is unavailable as the sensor or the entire camera is part of a larger unit and exposed as a black-box to the system. In such cases the model name of the smallest device that contains the camera sensor shall be used.",
+        },
+        ControlDescriptor {
+            id: PropertyId::UnitCellSize as u32,
+            name: "UnitCellSize",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_SIZE,
+            type_name: "Size",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "The pixel unit cell physical size, in nanometers.",
+        },
+        ControlDescriptor {
+            id: PropertyId::PixelArraySize as u32,
+            name: "PixelArraySize",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_SIZE,
+            type_name: "Size",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "The camera sensor pixel array readable area vertical and horizontal sizes, in pixels.",
+        },
+        ControlDescriptor {
+            id: PropertyId::PixelArrayOpticalBlackRectangles as u32,
+            name: "PixelArrayOpticalBlackRectangles",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_RECTANGLE,
+            type_name: "Rectangle",
+            direction: ControlDirection::InOut,
+            size: &[None],
+            enumeration: &[],
+            summary: "The pixel array region(s) which contain optical black pixels considered valid for calibration purposes.",
+        },
+        ControlDescriptor {
+            id: PropertyId::PixelArrayActiveAreas as u32,
+            name: "PixelArrayActiveAreas",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_RECTANGLE,
+            type_name: "Rectangle",
+            direction: ControlDirection::InOut,
+            size: &[None],
+            enumeration: &[],
+            summary: "The PixelArrayActiveAreas property defines the (possibly multiple and overlapping) portions of the camera sensor readable pixel matrix which are considered valid for image acquisition purposes.",
+        },
+        ControlDescriptor {
+            id: PropertyId::ScalerCropMaximum as u32,
+            name: "ScalerCropMaximum",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_RECTANGLE,
+            type_name: "Rectangle",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "The maximum valid rectangle for the controls::ScalerCrop control. This reflects the minimum mandatory cropping applied in the camera sensor and the rest of the pipeline. Just as the ScalerCrop control, it defines a rectangle taken from the sensor's active pixel array.",
+        },
+        ControlDescriptor {
+            id: PropertyId::SensorSensitivity as u32,
+            name: "SensorSensitivity",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_FLOAT,
+            type_name: "float",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[],
+            summary: "The relative sensitivity of the chosen sensor mode.",
+        },
+        ControlDescriptor {
+            id: PropertyId::SystemDevices as u32,
+            name: "SystemDevices",
+            vendor: "libcamera",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT64,
+            type_name: "int64_t",
+            direction: ControlDirection::InOut,
+            size: &[None],
+            enumeration: &[],
+            summary: "A list of integer values of type dev_t denoting the major and minor device numbers of the underlying devices used in the operation of this camera.",
+        },
+        #[cfg(feature = "vendor_draft")]
+        ControlDescriptor {
+            id: PropertyId::ColorFilterArrangement as u32,
+            name: "ColorFilterArrangement",
+            vendor: "draft",
+            ty: libcamera_control_type::LIBCAMERA_CONTROL_TYPE_INT32,
+            type_name: "int32_t",
+            direction: ControlDirection::InOut,
+            size: &[],
+            enumeration: &[
+                EnumValueDescriptor {
+                    name: "RGGB",
+                    value: 0,
+                    summary: "RGGB Bayer pattern",
+                },
+                EnumValueDescriptor {
+                    name: "GRBG",
+                    value: 1,
+                    summary: "GRBG Bayer pattern",
+                },
+                EnumValueDescriptor {
+                    name: "GBRG",
+                    value: 2,
+                    summary: "GBRG Bayer pattern",
+                },
+                EnumValueDescriptor {
+                    name: "BGGR",
+                    value: 3,
+                    summary: "BGGR Bayer pattern",
+                },
+                EnumValueDescriptor {
+                    name: "RGB",
+                    value: 4,
+                    summary: "Sensor is not Bayer; output has 3 16-bit values for each pixel, instead of just 1 16-bit value per pixel.",
+                },
+                EnumValueDescriptor {
+                    name: "MONO",
+                    value: 5,
+                    summary: "Sensor is not Bayer; output consists of a single colour channel.",
+                },
+            ],
+            summary: "The arrangement of color filters on sensor; represents the colors in the top-left 2x2 section of the sensor, in reading order. Currently identical to ANDROID_SENSOR_INFO_COLOR_FILTER_ARRANGEMENT.",
+        },
+    ]
+}